@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, value};
+
+/// Config file table holding the aliases.
+const TABLE: &str = "aliases";
+
+/// Short names for long parameter paths, written as `@name` wherever a path is
+/// accepted. They live in the `[aliases]` table of the config file.
+#[derive(Debug, Default)]
+pub struct Aliases {
+    file: PathBuf,
+    pub entries: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    pub fn new(file: &Path, entries: BTreeMap<String, String>) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            entries,
+        }
+    }
+
+    /// Returns `arg` with a leading `@name` replaced by the aliased path; anything
+    /// else is returned unchanged. `@db/extra` resolves to `<path of db>/extra`.
+    pub fn resolve(&self, arg: &str) -> Result<String, String> {
+        let Some(reference) = arg.strip_prefix('@') else {
+            return Ok(arg.to_string());
+        };
+
+        let (name, rest) = match reference.find('/') {
+            Some(pos) => reference.split_at(pos),
+            None => (reference, ""),
+        };
+        let path = self.entries.get(name).ok_or_else(|| {
+            format!(
+                "Unknown alias '@{}'. Type 'alias list' to see aliases.",
+                name
+            )
+        })?;
+        if rest.is_empty() {
+            return Ok(path.clone());
+        }
+        Ok(format!("{}{}", path.trim_end_matches('/'), rest))
+    }
+
+    pub fn add(&mut self, name: &str, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid alias name '{}': use letters, digits, '-' and '_'",
+                name
+            )
+            .into());
+        }
+        if !path.starts_with('/') {
+            return Err(format!("Alias target must be a path starting with '/': {}", path).into());
+        }
+
+        self.entries.insert(name.to_string(), path.to_string());
+        self.save()
+    }
+
+    /// Adds or overwrites several aliases at once, e.g. from a saved session.
+    pub fn extend(
+        &mut self,
+        entries: &BTreeMap<String, String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if entries
+            .iter()
+            .all(|(name, path)| self.entries.get(name) == Some(path))
+        {
+            return Ok(());
+        }
+        self.entries.extend(
+            entries
+                .iter()
+                .map(|(name, path)| (name.clone(), path.clone())),
+        );
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.entries.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Rewrites only the `[aliases]` table, keeping the rest of the config file,
+    /// comments included, as the user wrote it.
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let content = match std::fs::read_to_string(&self.file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let mut doc: DocumentMut = content
+            .parse()
+            .map_err(|e| format!("Invalid config file {}: {}", self.file.display(), e))?;
+
+        let mut table = Table::new();
+        for (name, path) in &self.entries {
+            table.insert(name, value(path.as_str()));
+        }
+        doc.insert(TABLE, Item::Table(table));
+
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.file, doc.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use crate::client::{ClientOptions, build_secrets_manager_client, build_ssm_client};
+use crate::secrets_manager::SecretsManagerBackend;
+use async_trait::async_trait;
+use rusoto_core::{Region, RusotoError};
+use rusoto_ssm::{
+    DeleteParameterError, DeleteParameterRequest, DeleteParameterResult, DescribeParametersError,
+    DescribeParametersRequest, DescribeParametersResult, GetParameterError, GetParameterRequest,
+    GetParameterResult, GetParametersByPathError, GetParametersByPathRequest,
+    GetParametersByPathResult, GetParametersError, GetParametersRequest, GetParametersResult,
+    ListTagsForResourceError, ListTagsForResourceRequest, ListTagsForResourceResult,
+    PutParameterError, PutParameterRequest, PutParameterResult, Ssm, SsmClient,
+};
+
+/// Where parameters are stored. Selected with `--backend`, the `backend`
+/// config key, or an `ssm://` / `sm://` scheme on the base path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackendKind {
+    /// AWS Systems Manager Parameter Store.
+    #[default]
+    Ssm,
+    /// AWS Secrets Manager; secret `prod/db` shows up as `/prod/db`.
+    SecretsManager,
+}
+
+impl std::str::FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ssm" => Ok(Self::Ssm),
+            "secretsmanager" | "sm" => Ok(Self::SecretsManager),
+            other => Err(format!(
+                "Invalid backend '{}' (expected ssm|secretsmanager)",
+                other
+            )),
+        }
+    }
+}
+
+impl BackendKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ssm => "ssm",
+            Self::SecretsManager => "secretsmanager",
+        }
+    }
+
+    pub fn scheme(self) -> &'static str {
+        match self {
+            Self::Ssm => "ssm://",
+            Self::SecretsManager => "sm://",
+        }
+    }
+
+    /// Splits `sm://prod/` into the Secrets Manager backend and `/prod/`;
+    /// paths without a scheme are returned unchanged.
+    pub fn split_scheme(path: &str) -> (Option<Self>, String) {
+        for kind in [Self::Ssm, Self::SecretsManager] {
+            if let Some(rest) = path.strip_prefix(kind.scheme()) {
+                return (Some(kind), format!("/{}", rest.trim_start_matches('/')));
+            }
+        }
+        (None, path.to_string())
+    }
+}
+
+/// The storage operations daps needs, shaped after the SSM API so every
+/// backend plugs into the same retry, caching and chunking code. Other
+/// backends translate their errors into the SSM ones, e.g. a missing secret
+/// becomes `ParameterNotFound`.
+#[async_trait]
+pub trait ParameterBackend: Send + Sync {
+    async fn get_parameter(
+        &self,
+        input: GetParameterRequest,
+    ) -> Result<GetParameterResult, RusotoError<GetParameterError>>;
+
+    /// Fetches up to 10 parameters by name; unknown names are returned in
+    /// `invalid_parameters`.
+    async fn get_parameters(
+        &self,
+        input: GetParametersRequest,
+    ) -> Result<GetParametersResult, RusotoError<GetParametersError>>;
+
+    async fn get_parameters_by_path(
+        &self,
+        input: GetParametersByPathRequest,
+    ) -> Result<GetParametersByPathResult, RusotoError<GetParametersByPathError>>;
+
+    async fn put_parameter(
+        &self,
+        input: PutParameterRequest,
+    ) -> Result<PutParameterResult, RusotoError<PutParameterError>>;
+
+    async fn delete_parameter(
+        &self,
+        input: DeleteParameterRequest,
+    ) -> Result<DeleteParameterResult, RusotoError<DeleteParameterError>>;
+
+    async fn describe_parameters(
+        &self,
+        input: DescribeParametersRequest,
+    ) -> Result<DescribeParametersResult, RusotoError<DescribeParametersError>>;
+
+    async fn list_tags_for_resource(
+        &self,
+        input: ListTagsForResourceRequest,
+    ) -> Result<ListTagsForResourceResult, RusotoError<ListTagsForResourceError>>;
+}
+
+#[async_trait]
+impl ParameterBackend for SsmClient {
+    async fn get_parameter(
+        &self,
+        input: GetParameterRequest,
+    ) -> Result<GetParameterResult, RusotoError<GetParameterError>> {
+        Ssm::get_parameter(self, input).await
+    }
+
+    async fn get_parameters(
+        &self,
+        input: GetParametersRequest,
+    ) -> Result<GetParametersResult, RusotoError<GetParametersError>> {
+        Ssm::get_parameters(self, input).await
+    }
+
+    async fn get_parameters_by_path(
+        &self,
+        input: GetParametersByPathRequest,
+    ) -> Result<GetParametersByPathResult, RusotoError<GetParametersByPathError>> {
+        Ssm::get_parameters_by_path(self, input).await
+    }
+
+    async fn put_parameter(
+        &self,
+        input: PutParameterRequest,
+    ) -> Result<PutParameterResult, RusotoError<PutParameterError>> {
+        Ssm::put_parameter(self, input).await
+    }
+
+    async fn delete_parameter(
+        &self,
+        input: DeleteParameterRequest,
+    ) -> Result<DeleteParameterResult, RusotoError<DeleteParameterError>> {
+        Ssm::delete_parameter(self, input).await
+    }
+
+    async fn describe_parameters(
+        &self,
+        input: DescribeParametersRequest,
+    ) -> Result<DescribeParametersResult, RusotoError<DescribeParametersError>> {
+        Ssm::describe_parameters(self, input).await
+    }
+
+    async fn list_tags_for_resource(
+        &self,
+        input: ListTagsForResourceRequest,
+    ) -> Result<ListTagsForResourceResult, RusotoError<ListTagsForResourceError>> {
+        Ssm::list_tags_for_resource(self, input).await
+    }
+}
+
+/// Builds the client for `kind` with the shared network settings.
+pub fn build_backend(
+    kind: BackendKind,
+    region: Region,
+    options: &ClientOptions,
+) -> Result<Box<dyn ParameterBackend>, Box<dyn std::error::Error>> {
+    Ok(match kind {
+        BackendKind::Ssm => Box::new(build_ssm_client(region, options)?),
+        BackendKind::SecretsManager => Box::new(SecretsManagerBackend::new(
+            build_secrets_manager_client(region, options)?,
+        )),
+    })
+}
@@ -0,0 +1,256 @@
+use crate::encryption::Encryption;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use subtle::ConstantTimeEq;
+
+/// Version written in the header of every cache file. Version 2 bodies are a
+/// JSON object keyed by parameter path; version 1 and headerless files hold
+/// `key: value` lines and are still read.
+pub const FORMAT_VERSION: u32 = 2;
+
+const MAGIC: &str = "#daps-cache";
+
+/// A cache file that cannot be trusted; the caller should rebuild it from AWS.
+#[derive(Debug)]
+pub enum CacheError {
+    /// The header's check value does not match the contents.
+    Tampered(PathBuf),
+    /// Written by a newer (or unknown) version of daps.
+    UnsupportedVersion(PathBuf, String),
+    /// A value could not be decrypted with the current key.
+    Undecryptable(PathBuf, String),
+    /// The file is sealed but encryption is off.
+    Sealed(PathBuf),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Tampered(path) => write!(
+                f,
+                "Cache file {} failed its integrity check (corrupted or modified)",
+                path.display()
+            ),
+            CacheError::UnsupportedVersion(path, version) => write!(
+                f,
+                "Cache file {} has unsupported format version {}",
+                path.display(),
+                version
+            ),
+            CacheError::Undecryptable(path, key) => write!(
+                f,
+                "Cannot decrypt {} in {} with the current key",
+                key,
+                path.display()
+            ),
+            CacheError::Sealed(path) => write!(
+                f,
+                "Cache file {} is sealed and cannot be read with encryption off",
+                path.display()
+            ),
+        }?;
+        write!(f, "; run 'refresh' to rebuild the cache")
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// Check value over `body`: HMAC-SHA256 when a key is available, otherwise a
+/// plain SHA-256 that only detects accidental corruption.
+fn check_value(body: &str, mac_key: Option<&[u8; 32]>) -> (&'static str, String) {
+    match mac_key {
+        Some(key) => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+            mac.update(body.as_bytes());
+            ("hmac-sha256", hex::encode(mac.finalize().into_bytes()))
+        }
+        None => ("sha256", hex::encode(Sha256::digest(body.as_bytes()))),
+    }
+}
+
+/// Writes `map` as JSON, sorted by key so the file is stable between writes.
+pub fn write_map<T: Serialize>(
+    path: &Path,
+    map: &HashMap<String, T>,
+    encryption: &Encryption,
+) -> std::io::Result<()> {
+    let sorted: BTreeMap<&String, &T> = map.iter().collect();
+    write(path, &serde_json::to_string_pretty(&sorted)?, encryption)
+}
+
+/// Reads a map written by [`write_map`]. Text files from older versions are
+/// converted line by line, with `legacy` turning the text after the first `:`
+/// into a value.
+pub fn read_map<T: DeserializeOwned>(
+    path: &Path,
+    encryption: &Encryption,
+    legacy: impl Fn(&str) -> T,
+) -> Result<HashMap<String, T>, Box<dyn std::error::Error>> {
+    let (version, body) = read(path, encryption)?;
+    if version >= 2 {
+        return serde_json::from_str(&body)
+            .map_err(|_| CacheError::Tampered(path.to_path_buf()).into());
+    }
+
+    Ok(body
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), legacy(value.trim())))
+        .collect())
+}
+
+/// What `cache verify` learns about one cache file.
+#[derive(Debug)]
+pub struct Inspection {
+    pub version: u32,
+    /// Entries as stored: values are still encrypted.
+    pub entries: BTreeMap<String, serde_json::Value>,
+    /// Line numbers of `key: value` lines without a `:`, in files from older
+    /// versions. [`read_map`] skips them.
+    pub malformed_lines: Vec<usize>,
+}
+
+/// Reads a cache file like [`read_map`], also reporting the lines of older
+/// text files it would skip.
+pub fn inspect(
+    path: &Path,
+    encryption: &Encryption,
+) -> Result<Inspection, Box<dyn std::error::Error>> {
+    let (version, body) = read(path, encryption)?;
+    if version >= 2 {
+        let entries =
+            serde_json::from_str(&body).map_err(|_| CacheError::Tampered(path.to_path_buf()))?;
+        return Ok(Inspection {
+            version,
+            entries,
+            malformed_lines: Vec::new(),
+        });
+    }
+
+    // Headerless files start with the body; the others with the header line.
+    let first_line = if version == 0 { 1 } else { 2 };
+    let mut entries = BTreeMap::new();
+    let mut malformed_lines = Vec::new();
+    for (index, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.split_once(':') {
+            Some((key, value)) => {
+                let value = serde_json::Value::String(value.trim().to_string());
+                entries.insert(key.trim().to_string(), value);
+            }
+            None => malformed_lines.push(index + first_line),
+        }
+    }
+    Ok(Inspection {
+        version,
+        entries,
+        malformed_lines,
+    })
+}
+
+/// Writes `body` to `path` behind a `#daps-cache v<N> <alg> <check> [sealed]`
+/// header line. With `seal_cache` on, the whole body is encrypted as one blob so
+/// parameter names are not readable either; the check covers the stored bytes.
+fn write(path: &Path, body: &str, encryption: &Encryption) -> std::io::Result<()> {
+    let (stored, flag) = if encryption.seal_cache && encryption.enabled {
+        (encryption.encrypt_value(body) + "\n", " sealed")
+    } else {
+        (body.to_string(), "")
+    };
+
+    let (algorithm, check) = check_value(&stored, encryption.integrity_key().as_ref());
+    let content = format!(
+        "{} v{} {} {}{}\n{}",
+        MAGIC, FORMAT_VERSION, algorithm, check, flag, stored
+    );
+    write_atomic(path, content)
+}
+
+/// Writes to a temporary file next to `path` and renames it over `path`, so an
+/// interrupted write leaves the previous file intact instead of a truncated one.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    replace_file(path, contents.as_ref(), None)
+}
+
+/// [`write_atomic`] for files holding values: on Unix the temporary file is
+/// created readable by its owner only, so the contents are never exposed.
+pub fn write_private(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    replace_file(path, contents.as_ref(), Some(0o600))
+}
+
+fn replace_file(path: &Path, contents: &[u8], mode: Option<u32>) -> std::io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if let Some(mode) = mode {
+        // A leftover temporary file would keep its own mode.
+        if temporary.exists() {
+            std::fs::remove_file(&temporary)?;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(mode);
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+    }
+    options.open(&temporary)?.write_all(contents)?;
+    std::fs::rename(&temporary, path)
+}
+
+/// Reads the format version and body of a cache file, verifying its header and
+/// unsealing it if needed. Files written before the header existed are reported
+/// as version 0 and gain a header on the next write.
+fn read(path: &Path, encryption: &Encryption) -> Result<(u32, String), Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let Some(rest) = content.strip_prefix(MAGIC) else {
+        return Ok((0, content));
+    };
+
+    let (header, stored) = rest.split_once('\n').unwrap_or((rest, ""));
+    let fields: Vec<&str> = header.split_whitespace().collect();
+    let (version, algorithm, check, sealed) = match fields[..] {
+        [version, algorithm, check] => (version, algorithm, check, false),
+        [version, algorithm, check, "sealed"] => (version, algorithm, check, true),
+        _ => return Err(CacheError::Tampered(path.to_path_buf()).into()),
+    };
+    let version = match version
+        .strip_prefix('v')
+        .and_then(|v| v.parse::<u32>().ok())
+    {
+        Some(v) if (1..=FORMAT_VERSION).contains(&v) => v,
+        _ => {
+            return Err(
+                CacheError::UnsupportedVersion(path.to_path_buf(), version.to_string()).into(),
+            );
+        }
+    };
+    if sealed && !encryption.enabled {
+        return Err(CacheError::Sealed(path.to_path_buf()).into());
+    }
+
+    let (expected_algorithm, expected) = check_value(stored, encryption.integrity_key().as_ref());
+    let matches = bool::from(check.as_bytes().ct_eq(expected.as_bytes()));
+    if algorithm != expected_algorithm || !matches {
+        return Err(CacheError::Tampered(path.to_path_buf()).into());
+    }
+
+    if sealed {
+        let body = encryption
+            .try_decrypt(stored.trim())
+            .map_err(|_| CacheError::Tampered(path.to_path_buf()))?;
+        return Ok((version, body));
+    }
+    Ok((version, stored.to_string()))
+}
@@ -0,0 +1,119 @@
+use crate::commands::size::STANDARD_LIMIT;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// A value too large for one parameter is written as `/path/.chunk0..N-1`,
+/// and `/path` itself holds this prefix followed by `<count>:<sha256>`.
+/// Reads put the value back together and hide the chunk parameters.
+const MANIFEST_PREFIX: &str = "daps:chunked:v1:";
+/// Last path segment of a chunk, followed by its index.
+const CHUNK_SEGMENT: &str = ".chunk";
+/// Each chunk fits in a Standard tier parameter.
+pub const CHUNK_SIZE: usize = STANDARD_LIMIT;
+/// Upper bound on the number of chunks of one value (400 KB).
+pub const MAX_CHUNKS: usize = 100;
+
+pub fn chunk_path(path: &str, index: usize) -> String {
+    format!("{}/{}{}", path, CHUNK_SEGMENT, index)
+}
+
+/// Whether `path` is one of the `.chunkN` parameters of a chunked value.
+pub fn is_chunk_path(path: &str) -> bool {
+    path.rsplit_once('/')
+        .and_then(|(_, name)| name.strip_prefix(CHUNK_SEGMENT))
+        .is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Whether a value of this size has to be chunked.
+pub fn needs_chunking(value: &str) -> bool {
+    value.len() > CHUNK_SIZE
+}
+
+/// Splits `value` into pieces of at most `CHUNK_SIZE` bytes, never inside a
+/// UTF-8 character.
+pub fn split(value: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = value;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(CHUNK_SIZE);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+pub fn manifest(value: &str, count: usize) -> String {
+    format!("{}{}:{}", MANIFEST_PREFIX, count, digest(value))
+}
+
+/// Returns the chunk count and digest recorded in a manifest value.
+pub fn parse_manifest(value: &str) -> Option<(usize, &str)> {
+    let (count, digest) = value.strip_prefix(MANIFEST_PREFIX)?.split_once(':')?;
+    Some((count.parse().ok()?, digest))
+}
+
+/// Rebuilds the value described by `manifest` from its chunks, checking the
+/// digest so a half-written value is never returned.
+pub fn reassemble<'a>(
+    path: &str,
+    manifest: &str,
+    mut chunk: impl FnMut(&str) -> Option<&'a str>,
+) -> Result<String, String> {
+    let (count, expected) =
+        parse_manifest(manifest).ok_or_else(|| format!("{}: invalid chunk manifest", path))?;
+    let mut value = String::new();
+    for index in 0..count {
+        let chunk_path = chunk_path(path, index);
+        let part = chunk(&chunk_path)
+            .ok_or_else(|| format!("{}: chunk {} is missing", path, chunk_path))?;
+        value.push_str(part);
+    }
+    if digest(&value) != expected {
+        return Err(format!(
+            "{}: chunks do not match the manifest (written concurrently?)",
+            path
+        ));
+    }
+    Ok(value)
+}
+
+/// Replaces every manifest in `values` with the reassembled value and drops
+/// the chunk entries. Returns the paths that could not be put back together,
+/// with the reason; their manifests are left in place.
+pub fn assemble_all(values: &mut HashMap<String, String>) -> Vec<String> {
+    let manifests: Vec<String> = values
+        .iter()
+        .filter(|(_, value)| parse_manifest(value).is_some())
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut assembled = Vec::new();
+    for path in manifests {
+        match reassemble(&path, &values[&path], |chunk| {
+            values.get(chunk).map(String::as_str)
+        }) {
+            Ok(value) => assembled.push((path, value)),
+            Err(err) => errors.push(err),
+        }
+    }
+    values.extend(assembled);
+    values.retain(|path, _| !is_chunk_path(path));
+    errors
+}
+
+/// Removes the `.chunkN` entries from the parameter tree.
+pub fn hide_chunks(parameters: &mut HashMap<String, Vec<String>>) {
+    parameters.retain(|path, _| !is_chunk_path(path));
+    for children in parameters.values_mut() {
+        children.retain(|child| !is_chunk_path(&format!("/{}", child)));
+    }
+}
+
+fn digest(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
@@ -4,7 +4,15 @@
 pub enum Command {
     Exit,
     Refresh,
-    Migration,
+    /// `gc [prefix] [--dry-run]` — drops parameters deleted in AWS from the cache.
+    Gc(String),
+    /// `cache stats|verify|rebuild` — inspects and repairs the local cache files.
+    Cache(String),
+    /// `migration [--assume-plaintext]` — re-encrypts the cached values with
+    /// the current key.
+    Migration(String),
+    /// `rotate-key` — re-encrypts the local cache with a new passphrase.
+    RotateKey,
     /// Re-fetches the currently-selected parameter from AWS.
     Reload,
     /// Prints the currently-selected parameter name.
@@ -15,16 +23,126 @@ pub enum Command {
     ReloadByPaths(String),
     /// `reload-by-path <path>` — re-fetches one explicit parameter.
     ReloadByPath(String),
-    /// `set <value>` — sets the currently-selected parameter to `value`.
+    /// `reload-all [path...]` — re-fetches the listed parameters, or the last
+    /// search result, in batches and reports which values changed.
+    ReloadAll(String),
+    /// `set [<path>|#<index>] <value>` — sets a parameter, the selected one by
+    /// default, to `value`.
     Set(String),
-    /// `sel <index>` — picks a parameter from the last search result by index.
-    SelectByIndex(String),
-    /// `insert <path>:<value>:<type>` — creates a new parameter.
+    /// `set-many <prefix> <key>=<value>...` — creates or updates several
+    /// parameters under a prefix at once.
+    SetMany(String),
+    /// `sel <index|path>` — picks a parameter from the last search result by
+    /// index, or by full or uniquely matching partial path.
+    Select(String),
+    /// `insert <path>:<value>[:<type>]` or `insert <path> <value> [<type>]` —
+    /// creates a new parameter; empty starts the wizard.
     Insert(String),
-    /// `search <term>` — fuzzy-searches cached parameter keys.
+    /// `search <term> [--sort <key>]` — fuzzy-searches cached parameter keys.
     Search(String),
+    /// `import <file> [--on-conflict <strategy>]` — bulk-writes parameters from a JSON file.
+    Import(String),
+    /// `region <name>` — switches the session to another AWS region.
+    Region(String),
+    /// `profile <name>` — switches the session to another AWS profile.
+    Profile(String),
+    /// `workspace [list] | show [name] | use <name> [base-path]` — switches
+    /// profile, region, base path and cache to a configured workspace.
+    Workspace(String),
+    /// `pending` — lists changes queued while offline.
+    Pending,
+    /// `status` — shows the selection, where commands go and pending changes.
+    Status,
+    /// `push` — sends queued offline changes to AWS.
+    Push,
+    /// `discard [path]` — drops one or all queued offline changes.
+    Discard(String),
+    /// `pin [path]` — pins the current value of a parameter for drift alerts.
+    Pin(String),
+    /// `unpin <path>` — removes a pin.
+    Unpin(String),
+    /// `pins` — lists pinned parameters and their drift status.
+    Pins,
+    /// `help [command]` — lists commands or describes one.
+    Help(String),
+    /// `cheatsheet` — prints a quick reference of common workflows.
+    Cheatsheet,
+    /// `history [n]` — lists recent commands with their `!N` indices.
+    History(String),
     /// `parse-db` — parses the selected parameter's value as a Postgres connection string.
     ParseDb,
+    /// `session [save <name> | load <name> | list]` — saves or restores a session.
+    Session(String),
+    /// `recent` — lists recently viewed or changed parameters.
+    Recent,
+    /// `get <path> [| filter...]` — shows a parameter, same as typing its path;
+    /// accepts `@alias` and display filters such as `| b64d`.
+    Get(String),
+    /// `show [path] [--pager]` — prints a whole value, which listings cut short.
+    Show(String),
+    /// `scope [prefix]` — restricts the session to a prefix.
+    Scope(String),
+    /// `unscope` — lifts the restriction set by `scope`.
+    Unscope,
+    /// `alias [add <name> <path> | rm <name> | list]` — manages path aliases.
+    Alias(String),
+    /// `macro record|stop|cancel|play|show|rm|list` — records and replays command sequences.
+    Macro(String),
+    /// `lint [prefix]` — checks parameters against naming and hygiene rules.
+    Lint(String),
+    /// `age [prefix]` — lists parameters by last change, oldest first.
+    Age(String),
+    /// `size [prefix]` — lists value sizes and subtree totals.
+    Size(String),
+    /// `count [prefix]` — summarizes the parameters under a prefix.
+    Count(String),
+    /// `list [prefix] [--remote] [filters]` — lists parameters with their type.
+    List(String),
+    /// `notify setup|listen` — EventBridge change notifications and a live change feed.
+    Notify(String),
+    /// `appconfig apps | show | versions | get ...` — read-only AWS AppConfig views.
+    AppConfig(String),
+    /// `latest-ami <os> [--arm64]` — the current AMI ID from AWS public parameters.
+    LatestAmi(String),
+    /// `stat [path]` — shows a parameter's type, version and last change.
+    Stat(String),
+    /// `set-binary <path> <file>` — stores a file's bytes base64-encoded.
+    SetBinary(String),
+    /// `get-binary <path> <file>` — writes a base64 value back out as bytes.
+    GetBinary(String),
+    /// `sync <source> <dest> [...]` — reconciles parameters between two endpoints.
+    Sync(String),
+    /// `snapshot create|list|diff|restore|rm|auto [...]` — saves and restores
+    /// the parameters under a prefix.
+    Snapshot(String),
+    /// `export <format> <prefix> [...]` — renders parameters for other tools.
+    Export(String),
+    /// `deps [path]` — prints the parameters a value references as `${/path}`.
+    Deps(String),
+    /// `render-resolved [path]` — a value with its `${/path}` references substituted.
+    RenderResolved(String),
+    /// `render <template-file> [--output <file>]` — fills `{{/path}}` placeholders.
+    Render(String),
+    /// `stats [reset]` — API call and cache statistics for the session.
+    Stats(String),
+    /// `redact [on|off]` — masks every displayed value and turns off the clipboard.
+    Redact(String),
+    /// `delete [path] [--yes]` — deletes a parameter after confirmation.
+    Delete(String),
+    /// `cp <src> <dst> [--yes]` — copies a parameter with its type, description,
+    /// tags and KMS key.
+    Copy(String),
+    /// `mv <src> <dst> [--yes]` — copies a parameter, then deletes the source.
+    Move(String),
+    /// `cmp [path] [--clipboard | --file <file>]` — checks a stored value against other content.
+    Cmp(String),
+    /// `paste [path] [--yes]` — sets a parameter to the clipboard contents.
+    Paste(String),
+    /// `replace <prefix> --find <old> --with <new> [...]` — bulk edits values under a prefix.
+    Replace(String),
+    /// `mv-tree <old-prefix> <new-prefix> [--dry-run] [--yes]` — moves a whole
+    /// subtree, resumably.
+    MoveTree(String),
     /// Anything else is treated as a path to navigate / display.
     Navigate(String),
 }
@@ -43,41 +161,518 @@ impl Command {
         match keyword {
             "exit" => Command::Exit,
             "refresh" => Command::Refresh,
-            "migration" => Command::Migration,
+            "gc" => Command::Gc(rest.to_string()),
+            "cache" => Command::Cache(rest.to_string()),
+            "migration" => Command::Migration(rest.to_string()),
+            "rotate-key" => Command::RotateKey,
             "reload" => Command::Reload,
             "reloads" => Command::ReloadSelected,
             "reload-by-paths" => Command::ReloadByPaths(rest.to_string()),
             "reload-by-path" => Command::ReloadByPath(rest.to_string()),
+            "reload-all" => Command::ReloadAll(rest.to_string()),
             "set" => Command::Set(rest.to_string()),
+            "set-many" => Command::SetMany(rest.to_string()),
             // "select" (no arg) → show current selection; "sel <n>" → pick by index
             "select" => Command::ShowSelected,
-            "sel" => Command::SelectByIndex(rest.to_string()),
+            "sel" => Command::Select(rest.to_string()),
             "insert" => Command::Insert(rest.to_string()),
             "search" => Command::Search(rest.to_string()),
+            "import" => Command::Import(rest.to_string()),
+            "region" => Command::Region(rest.to_string()),
+            "profile" => Command::Profile(rest.to_string()),
+            "pending" => Command::Pending,
+            "status" => Command::Status,
+            "push" => Command::Push,
+            "discard" => Command::Discard(rest.to_string()),
+            "pin" => Command::Pin(rest.to_string()),
+            "unpin" => Command::Unpin(rest.to_string()),
+            "pins" => Command::Pins,
+            "help" => Command::Help(rest.to_string()),
+            "cheatsheet" => Command::Cheatsheet,
+            "history" => Command::History(rest.to_string()),
             "parse-db" => Command::ParseDb,
+            "get" => Command::Get(rest.to_string()),
+            "show" => Command::Show(rest.to_string()),
+            "recent" => Command::Recent,
+            "session" => Command::Session(rest.to_string()),
+            "workspace" => Command::Workspace(rest.to_string()),
+            "scope" => Command::Scope(rest.to_string()),
+            "unscope" => Command::Unscope,
+            "alias" => Command::Alias(rest.to_string()),
+            "macro" => Command::Macro(rest.to_string()),
+            "lint" => Command::Lint(rest.to_string()),
+            "age" => Command::Age(rest.to_string()),
+            "size" => Command::Size(rest.to_string()),
+            "count" => Command::Count(rest.to_string()),
+            "list" => Command::List(rest.to_string()),
+            "appconfig" => Command::AppConfig(rest.to_string()),
+            "notify" => Command::Notify(rest.to_string()),
+            "stat" => Command::Stat(rest.to_string()),
+            "latest-ami" => Command::LatestAmi(rest.to_string()),
+            "set-binary" => Command::SetBinary(rest.to_string()),
+            "get-binary" => Command::GetBinary(rest.to_string()),
+            "sync" => Command::Sync(rest.to_string()),
+            "snapshot" => Command::Snapshot(rest.to_string()),
+            "export" => Command::Export(rest.to_string()),
+            "render" => Command::Render(rest.to_string()),
+            "deps" => Command::Deps(rest.to_string()),
+            "render-resolved" => Command::RenderResolved(rest.to_string()),
+            "stats" => Command::Stats(rest.to_string()),
+            "redact" => Command::Redact(rest.to_string()),
+            "delete" => Command::Delete(rest.to_string()),
+            "cp" => Command::Copy(rest.to_string()),
+            "mv" => Command::Move(rest.to_string()),
+            "mv-tree" => Command::MoveTree(rest.to_string()),
+            "replace" => Command::Replace(rest.to_string()),
+            "paste" => Command::Paste(rest.to_string()),
+            "cmp" => Command::Cmp(rest.to_string()),
             _ => Command::Navigate(line.to_string()),
         }
     }
 
     /// Keyword strings exposed to the completer / highlighter.
     pub fn keywords() -> Vec<String> {
-        vec![
-            "exit",
-            "refresh",
-            "reload",
-            "reloads",
-            "set",
-            "select",
-            "sel",
-            "reload-by-path",
-            "reload-by-paths",
-            "insert",
-            "search",
-            "migration",
-            "parse-db",
-        ]
-        .into_iter()
-        .map(String::from)
-        .collect()
+        COMMANDS.iter().map(|spec| spec.name.to_string()).collect()
     }
 }
+
+/// Groups commands in `help` output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Category {
+    Navigation,
+    Editing,
+    Cache,
+    Session,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Navigation,
+        Category::Editing,
+        Category::Cache,
+        Category::Session,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Navigation => "Navigation",
+            Category::Editing => "Editing",
+            Category::Cache => "Cache",
+            Category::Session => "Session",
+        }
+    }
+}
+
+/// Static description of a REPL command, used for help, the cheat sheet and completion.
+#[derive(Debug)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub summary: &'static str,
+    pub category: Category,
+}
+
+impl CommandSpec {
+    pub fn find(name: &str) -> Option<&'static CommandSpec> {
+        COMMANDS.iter().find(|spec| spec.name == name)
+    }
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "search",
+        usage: "search <term> [--sort relevance|name|modified|length]",
+        summary: "Fuzzy-search cached parameter names; results are numbered for `sel` and listed with type, value and age.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "sel",
+        usage: "sel <index|path|-N|@alias>",
+        summary: "Select a parameter by search result index, full or partial path, recent entry (`-1` is the latest) or alias.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "recent",
+        usage: "recent",
+        summary: "List recently viewed or changed parameters; `sel -1` re-selects the latest.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "select",
+        usage: "select",
+        summary: "Show the currently selected parameter.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "get",
+        usage: "get <path> [| b64d | json | lines | trim]",
+        summary: "Show the values under a path and copy them, like typing the path itself; filters after '|' transform what is shown.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "show",
+        usage: "show [path] [| filter...] [--pager]",
+        summary: "Print the whole value of a parameter (the selected one by default), which get and search cut to value_width; Alt-V types it.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "latest-ami",
+        usage: "latest-ami <os> [--arm64]",
+        summary: "Look up the current AMI ID for al2023, al2, ubuntu, ubuntu-22.04 or windows.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "scope",
+        usage: "scope [prefix]",
+        summary: "Restrict navigation, completion, search and every read or write to a prefix.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "unscope",
+        usage: "unscope",
+        summary: "Lift the restriction set by `scope`.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "alias",
+        usage: "alias [add <name> <path> | rm <name> | list]",
+        summary: "Manage short names for paths; use `@name` anywhere a path is accepted.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "macro",
+        usage: "macro [record <name> | stop | cancel | play <name> | show <name> | rm <name> | list]",
+        summary: "Record the commands you run as a named macro and replay them later.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "parse-db",
+        usage: "parse-db",
+        summary: "Split the selected value as a `key=value;...` connection string and copy the fields.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "set",
+        usage: "set [<path>|#<index>] <value> [--no-validate] [--yes]",
+        summary: "Update a parameter (the selected one by default, or a search result by index) in AWS and in the cache.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "paste",
+        usage: "paste [path] [--yes] [--no-validate]",
+        summary: "Set a parameter (the selected one by default) to the clipboard contents, after a masked preview.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "cmp",
+        usage: "cmp [path] [--clipboard | --file <file>]",
+        summary: "Check a parameter's stored value against the clipboard or a file and show what differs.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "set-many",
+        usage: "set-many <prefix> <key>=<value>... [--from-file <file>] [--type <type>] [--dry-run] [--yes] [--no-validate]",
+        summary: "Create or update several parameters under a prefix, after one combined preview.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "insert",
+        usage: "insert [<path>:<value>[:<type>] | <path> <value> [<type>]] [--no-validate] [--yes] [--data-type <type>] [--allowed-pattern <regex>]",
+        summary: "Create a new parameter (type: String, StringList or SecureString); with no arguments, asks for each field.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "set-binary",
+        usage: "set-binary <path> <file>",
+        summary: "Store a file's bytes base64-encoded (new parameters are SecureString).",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "get-binary",
+        usage: "get-binary <path> <file>",
+        summary: "Decode a base64 value and write the bytes to a file.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "import",
+        usage: "import [csv] <file> [--on-conflict skip|overwrite|prompt|newer] [--dry-run]",
+        summary: "Write every entry of a JSON or CSV file, resolving existing keys with the chosen strategy; --dry-run shows the diff.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "lint",
+        usage: "lint [prefix]",
+        summary: "Check parameters for SecureString use, naming, depth, descriptions and rotation age.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "age",
+        usage: "age [prefix]",
+        summary: "List parameters by last change, oldest first, highlighting ones past the rotation threshold.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "size",
+        usage: "size [prefix]",
+        summary: "List cached value sizes, largest first, with subtree totals and 4 KB / 8 KB limit warnings.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "count",
+        usage: "count [prefix]",
+        summary: "Count parameters by type and tier, with total value size, deepest path and latest changes.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "list",
+        usage: "list [prefix] [--remote] [--type <type>] [--key-id <key>] [--tier <tier>]",
+        summary: "List parameters with their type; --remote reads metadata from AWS with server-side filters, without values.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "appconfig",
+        usage: "appconfig apps | show <app> | versions <app> <profile> | get <app> <profile> [<version> | --env <env>]",
+        summary: "Browse AWS AppConfig applications and read hosted or deployed configuration (read-only).",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "notify",
+        usage: "notify setup <prefix> --sns-arn <arn> [--name <rule>] [--dry-run] | notify listen [prefix] [--interval <secs>] [--for <secs>]",
+        summary: "Send Parameter Store changes under a prefix to SNS via EventBridge, or watch changes live.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "stat",
+        usage: "stat [path]",
+        summary: "Show the type, version, last change and value size of a parameter, from the cache.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "sync",
+        usage: "sync <source> <dest> [--prefix <path>] [--delete-extra] [--dry-run] [--yes]",
+        summary: "Make dest match source across backends, regions or profiles (`sm://prod/?region=eu-west-1&profile=b`), after showing the plan.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "snapshot",
+        usage: "snapshot create <name> [prefix] | snapshot list | snapshot diff <name> | snapshot restore <name> [--delete-extra] [--dry-run] [--yes] | snapshot rm <name> | snapshot auto [on|off]",
+        summary: "Save the parameters under a prefix locally, compare them with AWS later and restore them after a bad change.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "export",
+        usage: "export k8s-secret|k8s-configmap|terraform|csv|dotenv <prefix> [--name <name>] [--namespace <ns>] [--output <file>]",
+        summary: "Render cached parameters under prefix as a Kubernetes Secret or ConfigMap, as Terraform resources with import commands, as CSV or as a .env file.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "render",
+        usage: "render <template-file> [--output <file>]",
+        summary: "Fill `{{/path}}`, `{{/path#key}}`, `{{/path | default \"x\"}}` and `{{/path | env}}` placeholders with parameter values.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "deps",
+        usage: "deps [path]",
+        summary: "Show the tree of parameters a value references as `${/other/path}`, marking missing ones and cycles.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "render-resolved",
+        usage: "render-resolved [path]",
+        summary: "Show a value with its `${/other/path}` references substituted recursively; fails on cycles.",
+        category: Category::Navigation,
+    },
+    CommandSpec {
+        name: "delete",
+        usage: "delete [path] [--yes]",
+        summary: "Delete a parameter (the selected one by default) after confirmation; protected paths need the name typed back.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "cp",
+        usage: "cp <src> <dst> [--yes]",
+        summary: "Copy a parameter to a new path, keeping its type, description, tags and KMS key.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "mv",
+        usage: "mv <src> <dst> [--yes]",
+        summary: "Rename a parameter: copy it like `cp`, then delete the source.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "mv-tree",
+        usage: "mv-tree <old-prefix> <new-prefix> [--dry-run] [--yes]",
+        summary: "Move every parameter under a prefix to another prefix; an interrupted move resumes when run again.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "replace",
+        usage: "replace <prefix> --find <old> --with <new> [--regex] [--dry-run] [--yes]",
+        summary: "Replace text in every value under a prefix, after previewing the diffs.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "pending",
+        usage: "pending",
+        summary: "List changes queued while offline.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "push",
+        usage: "push",
+        summary: "Send queued offline changes to AWS.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "discard",
+        usage: "discard [path]",
+        summary: "Drop one queued change, or all of them.",
+        category: Category::Editing,
+    },
+    CommandSpec {
+        name: "refresh",
+        usage: "refresh",
+        summary: "Reload every parameter under the base path from AWS, dropping deleted ones from the cache and resolving conflicts with queued changes.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "gc",
+        usage: "gc [prefix] [--dry-run]",
+        summary: "Remove parameters that no longer exist in AWS from the cache and completions.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "cache",
+        usage: "cache stats | cache verify | cache rebuild",
+        summary: "Show what the store directory holds, check every cache file, or rebuild the base path's cache from AWS.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "reload",
+        usage: "reload",
+        summary: "Re-fetch the selected parameter from AWS.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "reloads",
+        usage: "reloads",
+        summary: "Re-fetch everything under the selected path.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "reload-by-path",
+        usage: "reload-by-path <path>",
+        summary: "Re-fetch one parameter by its full path.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "reload-by-paths",
+        usage: "reload-by-paths <prefix>",
+        summary: "Re-fetch everything under a path prefix.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "reload-all",
+        usage: "reload-all [path...]",
+        summary: "Re-fetch the last search result, or the given parameters, and show which values changed.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "migration",
+        usage: "migration [--assume-plaintext]",
+        summary: "Re-encrypt the cached values with the current key, upgrading older caches.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "rotate-key",
+        usage: "rotate-key",
+        summary: "Re-encrypt the whole local cache with a new passphrase.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "pin",
+        usage: "pin [path]",
+        summary: "Pin the current value of a parameter and warn whenever it drifts.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "unpin",
+        usage: "unpin <path>",
+        summary: "Remove a pin.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "pins",
+        usage: "pins",
+        summary: "List pinned parameters and their drift status.",
+        category: Category::Cache,
+    },
+    CommandSpec {
+        name: "status",
+        usage: "status",
+        summary: "Show the selected parameter and its cache age, region, profile and pending changes.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "region",
+        usage: "region [name]",
+        summary: "Show or switch the AWS region (reloads parameters).",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "profile",
+        usage: "profile [name]",
+        summary: "Show or switch the AWS profile (reloads parameters).",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "workspace",
+        usage: "workspace [list] | workspace show [name] | workspace use <name> [base-path]",
+        summary: "Switch profile, region, base path and cache together to a workspace from the config.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "help",
+        usage: "help [command]",
+        summary: "List commands, or show details for one command.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "cheatsheet",
+        usage: "cheatsheet",
+        summary: "Print a quick reference of common workflows.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "session",
+        usage: "session [save <name> | load <name> | list]",
+        summary: "Save the base path, selection, last search and aliases, or restore them later.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "history",
+        usage: "history [n]",
+        summary: "List the last n commands (default 20); `!N` re-runs entry N, `!!` the last one.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "stats",
+        usage: "stats [reset]",
+        summary: "Show API calls, latency, retries, throttling and value cache hit rate for this session.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "redact",
+        usage: "redact [on|off]",
+        summary: "Mask every value on screen and keep values off the clipboard, e.g. while screen sharing.",
+        category: Category::Session,
+    },
+    CommandSpec {
+        name: "exit",
+        usage: "exit",
+        summary: "Quit daps (CTRL-D and CTRL-C work too).",
+        category: Category::Session,
+    },
+];
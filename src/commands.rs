@@ -1,30 +1,84 @@
+pub mod age;
+pub mod alias;
+pub mod appconfig;
+pub mod binary;
+pub mod cache;
+pub mod compare;
+pub mod copy;
+pub mod count;
+pub mod delete;
+pub mod deps;
+pub mod export;
+pub mod gc;
+pub mod help;
+pub mod history;
+pub mod import;
 pub mod insert;
+pub mod lint;
+pub mod list;
+pub mod macros;
 pub mod migration;
+pub mod move_tree;
+pub mod notify;
 pub mod parse_db;
+pub mod paste;
+pub mod pending;
+pub mod pin;
+pub mod public;
+pub mod recent;
+pub mod redact;
 pub mod refresh;
 pub mod reload;
+pub mod reload_all;
 pub mod reload_by_paths;
+pub mod render;
+pub mod replace;
+pub mod rotate_key;
+pub mod scope;
 pub mod search;
 pub mod select;
+pub mod session;
 pub mod set;
+pub mod set_many;
+pub mod show;
+pub mod size;
+pub mod snapshot;
+pub mod stat;
+pub mod stats;
+pub mod status;
+pub mod switch;
+pub mod sync;
+pub mod workspace;
 
+use crate::completer::ParameterCompleter;
 use crate::cpboard::Cpboard;
+use crate::output;
+use serde_json::json;
 
+/// Prints a command's resulting value and copies it to the clipboard, unless
+/// values are redacted. A failed command is passed back to the caller.
 pub async fn handle_command_result<'a>(
     result: Result<String, Box<dyn std::error::Error>>,
     cpboard: &mut Cpboard<'a>,
-) {
-    match result {
-        Ok(value) => {
-            use colored::Colorize;
-            println!("Result value: {}", value.red());
-            match cpboard.set_clipboard_content(&value) {
-                Ok(_) => println!("Copied to clipboard: {}", value.red()),
-                Err(err) => println!("Error copying to clipboard: {}", err),
-            }
-        }
-        Err(err) => {
-            println!("Error executing command: {}", err);
-        }
+    completer: &ParameterCompleter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let value = result.map_err(|err| format!("Error executing command: {}", err))?;
+
+    if completer.output.is_json() {
+        let copied = !completer.redact && matches!(cpboard.set_clipboard_content(&value), Ok(true));
+        output::print_json(json!({ "value": completer.shown(&value), "copied": copied }));
+        return Ok(());
+    }
+
+    use colored::Colorize;
+    println!("Result value: {}", completer.shown(&value).red());
+    if completer.redact {
+        return Ok(());
+    }
+    match cpboard.set_clipboard_content(&value) {
+        Ok(true) if !completer.quiet => println!("Copied to clipboard: {}", value.red()),
+        Ok(_) => {}
+        Err(err) => println!("Error copying to clipboard: {}", err),
     }
+    Ok(())
 }
@@ -0,0 +1,91 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_date;
+use colored::Colorize;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Handles `age [prefix]`: lists parameters under `prefix` (the base path by
+/// default), least recently changed first, highlighting those older than the
+/// configured threshold. Offline it works from the change dates in the cache.
+pub async fn age(
+    helper: &ParamStoreHelper,
+    prefix: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let prefix = if prefix.is_empty() {
+        completer.root()
+    } else {
+        prefix
+    };
+
+    // Offline, the change dates recorded in the cache are used.
+    let mut parameters = if completer.offline {
+        let mut cached = completer.cached_metadata(prefix);
+        let total = cached.len();
+        cached.retain(|param| param.last_modified_date.is_some());
+        if cached.len() < total {
+            eprintln!(
+                "{} parameter(s) have no cached change date; run 'refresh' online to record them",
+                total - cached.len()
+            );
+        }
+        cached
+    } else {
+        completer.describe_parameters(prefix).await?
+    };
+    parameters.sort_by(|a, b| {
+        a.last_modified_date
+            .unwrap_or_default()
+            .total_cmp(&b.last_modified_date.unwrap_or_default())
+    });
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs_f64();
+    let threshold = completer.age_threshold_days;
+    let mut stale = 0;
+    for param in &parameters {
+        let path = param.name.as_deref().unwrap_or_default();
+        let param_type = param.type_.as_deref().unwrap_or("-");
+        let modified = param.last_modified_date.unwrap_or_default();
+        let age_days = ((now - modified) / 86_400.0).floor().max(0.0) as u64;
+        let is_stale = age_days > threshold;
+        if is_stale {
+            stale += 1;
+        }
+
+        if completer.output.is_json() {
+            output::print_json(json!({
+                "path": path,
+                "type": param_type,
+                "last_modified": format_date(modified),
+                "last_modified_user": param.last_modified_user,
+                "age_days": age_days,
+                "stale": is_stale,
+            }));
+            continue;
+        }
+
+        let line = format!(
+            "{}  {:>6}  {:<12}  {}",
+            format_date(modified),
+            format!("{}d", age_days),
+            param_type,
+            path
+        );
+        if is_stale {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    if !completer.output.is_json() {
+        println!(
+            "{} of {} parameter(s) not changed in more than {} days",
+            stale,
+            parameters.len(),
+            threshold
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `alias add <name> <path>`, `alias rm <name>` and `alias [list]`.
+/// Aliases are saved to the `[aliases]` table of the config file.
+pub fn alias(helper: &mut ParamStoreHelper, args: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut parts = args.split_whitespace();
+    let aliases = &mut helper.completer.aliases;
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, ..) | (Some("list"), None, _) => {
+            if helper.completer.output.is_json() {
+                for (name, path) in &aliases.entries {
+                    output::print_json(json!({ "alias": name, "path": path }));
+                }
+            } else if aliases.entries.is_empty() {
+                println!("No aliases. Use 'alias add <name> <path>' to add one.");
+            } else {
+                for (name, path) in &aliases.entries {
+                    println!("  {:<20} {}", format!("@{}", name).cyan(), path.green());
+                }
+            }
+        }
+        (Some("add"), Some(name), Some(path)) => {
+            let name = name.trim_start_matches('@');
+            aliases.add(name, path)?;
+            println!(
+                "Added alias {} -> {}",
+                format!("@{}", name).cyan(),
+                path.green()
+            );
+        }
+        (Some("rm"), Some(name), None) => {
+            let name = name.trim_start_matches('@');
+            if aliases.remove(name)? {
+                println!("Removed alias {}", format!("@{}", name).cyan());
+            } else {
+                return Err(format!("No alias named '@{}'", name).into());
+            }
+        }
+        _ => {
+            return Err("Usage: alias add <name> <path> | alias rm <name> | alias list".into());
+        }
+    }
+    Ok(())
+}
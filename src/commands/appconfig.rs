@@ -0,0 +1,434 @@
+use crate::client::build_appconfig_client;
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use colored::Colorize;
+use rusoto_appconfig::{
+    AppConfig, AppConfigClient, GetConfigurationRequest, GetHostedConfigurationVersionRequest,
+    HostedConfigurationVersionSummary, ListApplicationsRequest, ListConfigurationProfilesRequest,
+    ListEnvironmentsRequest, ListHostedConfigurationVersionsRequest,
+};
+use serde_json::json;
+
+const USAGE: &str = "Usage: appconfig apps | appconfig show <app> | appconfig versions <app> <profile> | appconfig get <app> <profile> [<version> | --env <env>]";
+
+/// Identifies deployed-configuration reads in AppConfig's metrics.
+const CLIENT_ID: &str = "daps";
+
+/// What `appconfig` was asked for. Applications, environments and profiles
+/// are given by name or ID.
+#[derive(Debug, PartialEq)]
+pub enum AppConfigArgs {
+    Apps,
+    Show {
+        app: String,
+    },
+    Versions {
+        app: String,
+        profile: String,
+    },
+    /// A hosted version (the latest by default), or with `env` what is
+    /// deployed to that environment.
+    Get {
+        app: String,
+        profile: String,
+        version: Option<i64>,
+        env: Option<String>,
+    },
+}
+
+impl AppConfigArgs {
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut words = args.split_whitespace();
+        let action = words.next().unwrap_or_default();
+        let mut env = None;
+        let mut positional = Vec::new();
+        while let Some(word) = words.next() {
+            match word {
+                "--env" => env = Some(words.next().ok_or(USAGE)?.to_string()),
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option {}. {}", flag, USAGE));
+                }
+                _ => positional.push(word.to_string()),
+            }
+        }
+        if env.is_some() && action != "get" {
+            return Err(format!("--env only applies to 'appconfig get'. {}", USAGE));
+        }
+        match (action, positional.as_slice()) {
+            ("apps", []) => Ok(AppConfigArgs::Apps),
+            ("show", [app]) => Ok(AppConfigArgs::Show { app: app.clone() }),
+            ("versions", [app, profile]) => Ok(AppConfigArgs::Versions {
+                app: app.clone(),
+                profile: profile.clone(),
+            }),
+            ("get", [app, profile, rest @ ..]) if rest.len() <= 1 => {
+                let version = match rest.first() {
+                    Some(_) if env.is_some() => {
+                        return Err(format!("Give a version or --env, not both. {}", USAGE));
+                    }
+                    Some(version) => Some(
+                        version
+                            .parse()
+                            .map_err(|_| format!("Invalid version '{}'. {}", version, USAGE))?,
+                    ),
+                    None => None,
+                };
+                Ok(AppConfigArgs::Get {
+                    app: app.clone(),
+                    profile: profile.clone(),
+                    version,
+                    env,
+                })
+            }
+            _ => Err(USAGE.to_string()),
+        }
+    }
+}
+
+/// An application, environment or configuration profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Named {
+    pub id: String,
+    pub name: String,
+    /// Location of a profile's content, or an environment's state.
+    pub detail: Option<String>,
+}
+
+/// Finds `wanted` among `items` by name, or by ID.
+pub fn find<'a>(items: &'a [Named], wanted: &str, kind: &str) -> Result<&'a Named, String> {
+    items
+        .iter()
+        .find(|item| item.name == wanted)
+        .or_else(|| items.iter().find(|item| item.id == wanted))
+        .ok_or_else(|| {
+            let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+            format!(
+                "No AppConfig {} named '{}'; found: {}",
+                kind,
+                wanted,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )
+        })
+}
+
+/// Configuration content as text: JSON is pretty-printed, other text is kept
+/// as it is, and anything that is not UTF-8 is shown base64-encoded.
+pub fn render_content(content: &[u8], content_type: Option<&str>) -> String {
+    let Ok(text) = std::str::from_utf8(content) else {
+        return BASE64.encode(content);
+    };
+    if content_type.is_some_and(|t| t.contains("json"))
+        && let Ok(value) = serde_json::from_str::<serde_json::Value>(text)
+        && let Ok(pretty) = serde_json::to_string_pretty(&value)
+    {
+        return pretty;
+    }
+    text.to_string()
+}
+
+async fn applications(
+    completer: &ParameterCompleter,
+    client: &AppConfigClient,
+) -> Result<Vec<Named>, Box<dyn std::error::Error>> {
+    let mut request = ListApplicationsRequest::default();
+    let mut found = Vec::new();
+    loop {
+        let page = completer
+            .call("ListApplications", || {
+                client.list_applications(request.clone())
+            })
+            .await?;
+        found.extend(
+            page.items
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|app| {
+                    Some(Named {
+                        id: app.id?,
+                        name: app.name?,
+                        detail: app.description,
+                    })
+                }),
+        );
+        match page.next_token {
+            Some(token) => request.next_token = Some(token),
+            None => return Ok(found),
+        }
+    }
+}
+
+async fn environments(
+    completer: &ParameterCompleter,
+    client: &AppConfigClient,
+    app_id: &str,
+) -> Result<Vec<Named>, Box<dyn std::error::Error>> {
+    let mut request = ListEnvironmentsRequest {
+        application_id: app_id.to_string(),
+        ..Default::default()
+    };
+    let mut found = Vec::new();
+    loop {
+        let page = completer
+            .call("ListEnvironments", || {
+                client.list_environments(request.clone())
+            })
+            .await?;
+        found.extend(
+            page.items
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|env| {
+                    Some(Named {
+                        id: env.id?,
+                        name: env.name?,
+                        detail: env.state,
+                    })
+                }),
+        );
+        match page.next_token {
+            Some(token) => request.next_token = Some(token),
+            None => return Ok(found),
+        }
+    }
+}
+
+async fn profiles(
+    completer: &ParameterCompleter,
+    client: &AppConfigClient,
+    app_id: &str,
+) -> Result<Vec<Named>, Box<dyn std::error::Error>> {
+    let mut request = ListConfigurationProfilesRequest {
+        application_id: app_id.to_string(),
+        ..Default::default()
+    };
+    let mut found = Vec::new();
+    loop {
+        let page = completer
+            .call("ListConfigurationProfiles", || {
+                client.list_configuration_profiles(request.clone())
+            })
+            .await?;
+        found.extend(
+            page.items
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|profile| {
+                    Some(Named {
+                        id: profile.id?,
+                        name: profile.name?,
+                        detail: profile.location_uri,
+                    })
+                }),
+        );
+        match page.next_token {
+            Some(token) => request.next_token = Some(token),
+            None => return Ok(found),
+        }
+    }
+}
+
+/// Hosted versions of a profile, newest first.
+async fn hosted_versions(
+    completer: &ParameterCompleter,
+    client: &AppConfigClient,
+    app_id: &str,
+    profile_id: &str,
+) -> Result<Vec<HostedConfigurationVersionSummary>, Box<dyn std::error::Error>> {
+    let mut request = ListHostedConfigurationVersionsRequest {
+        application_id: app_id.to_string(),
+        configuration_profile_id: profile_id.to_string(),
+        ..Default::default()
+    };
+    let mut found = Vec::new();
+    loop {
+        let page = completer
+            .call("ListHostedConfigurationVersions", || {
+                client.list_hosted_configuration_versions(request.clone())
+            })
+            .await?;
+        found.extend(page.items.unwrap_or_default());
+        match page.next_token {
+            Some(token) => request.next_token = Some(token),
+            None => break,
+        }
+    }
+    found.sort_by_key(|version| std::cmp::Reverse(version.version_number));
+    Ok(found)
+}
+
+/// Handles `appconfig ...`: read-only views of AWS AppConfig, so feature flags
+/// and configuration kept there are visible next to the parameters.
+/// `apps` lists applications, `show` an application's environments and
+/// configuration profiles, and `versions` a profile's hosted versions.
+/// `get` returns a hosted version's content, the latest by default, or with
+/// `--env` the configuration deployed to that environment.
+pub async fn appconfig(
+    helper: &ParamStoreHelper,
+    args: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let args = AppConfigArgs::parse(args)?;
+    let completer = &helper.completer;
+    if completer.offline {
+        return Err("appconfig is not available in offline mode".into());
+    }
+    let client = build_appconfig_client(completer.region.clone(), &completer.client_options)?;
+    let json = completer.output.is_json();
+
+    let print_named = |kind: &str, items: &[Named]| {
+        for item in items {
+            if json {
+                output::print_json(json!({
+                    "kind": kind,
+                    "name": item.name,
+                    "id": item.id,
+                    "detail": item.detail,
+                }));
+            } else {
+                println!(
+                    "  {:<32} {:<10} {}",
+                    item.name.green(),
+                    item.id,
+                    item.detail.as_deref().unwrap_or_default().dimmed()
+                );
+            }
+        }
+    };
+
+    match args {
+        AppConfigArgs::Apps => {
+            let apps = applications(completer, &client).await?;
+            if !json {
+                println!("{} application(s)", apps.len());
+            }
+            print_named("application", &apps);
+            Ok(None)
+        }
+        AppConfigArgs::Show { app } => {
+            let apps = applications(completer, &client).await?;
+            let app = find(&apps, &app, "application")?;
+            let envs = environments(completer, &client, &app.id).await?;
+            let profiles = profiles(completer, &client, &app.id).await?;
+            if !json {
+                println!("{} ({})", app.name.bold(), app.id);
+                println!("Environments:");
+            }
+            print_named("environment", &envs);
+            if !json {
+                println!("Configuration profiles:");
+            }
+            print_named("profile", &profiles);
+            Ok(None)
+        }
+        AppConfigArgs::Versions { app, profile } => {
+            let apps = applications(completer, &client).await?;
+            let app = find(&apps, &app, "application")?;
+            let profiles = profiles(completer, &client, &app.id).await?;
+            let profile = find(&profiles, &profile, "configuration profile")?;
+            let versions = hosted_versions(completer, &client, &app.id, &profile.id).await?;
+            for version in &versions {
+                if json {
+                    output::print_json(json!({
+                        "version": version.version_number,
+                        "content_type": version.content_type,
+                        "description": version.description,
+                    }));
+                } else {
+                    println!(
+                        "  {:>5}  {:<20} {}",
+                        version
+                            .version_number
+                            .unwrap_or_default()
+                            .to_string()
+                            .yellow(),
+                        version.content_type.as_deref().unwrap_or("-"),
+                        version.description.as_deref().unwrap_or_default().dimmed()
+                    );
+                }
+            }
+            if !json {
+                println!(
+                    "{} hosted version(s) of {}/{}",
+                    versions.len(),
+                    app.name,
+                    profile.name
+                );
+            }
+            Ok(None)
+        }
+        AppConfigArgs::Get {
+            app,
+            profile,
+            env: Some(env),
+            ..
+        } => {
+            let request = GetConfigurationRequest {
+                application: app,
+                environment: env,
+                configuration: profile,
+                client_id: CLIENT_ID.to_string(),
+                client_configuration_version: None,
+            };
+            let deployed = completer
+                .call("GetConfiguration", || {
+                    client.get_configuration(request.clone())
+                })
+                .await?;
+            completer.progress(&format!(
+                "Deployed configuration version {}",
+                deployed.configuration_version.as_deref().unwrap_or("-")
+            ));
+            let content = deployed.content.unwrap_or_default();
+            Ok(Some(render_content(
+                &content,
+                deployed.content_type.as_deref(),
+            )))
+        }
+        AppConfigArgs::Get {
+            app,
+            profile,
+            version,
+            env: None,
+        } => {
+            let apps = applications(completer, &client).await?;
+            let app = find(&apps, &app, "application")?;
+            let profiles = profiles(completer, &client, &app.id).await?;
+            let profile = find(&profiles, &profile, "configuration profile")?;
+            let version = match version {
+                Some(version) => version,
+                None => hosted_versions(completer, &client, &app.id, &profile.id)
+                    .await?
+                    .first()
+                    .and_then(|latest| latest.version_number)
+                    .ok_or_else(|| {
+                        format!("{}/{} has no hosted versions", app.name, profile.name)
+                    })?,
+            };
+            let request = GetHostedConfigurationVersionRequest {
+                application_id: app.id.clone(),
+                configuration_profile_id: profile.id.clone(),
+                version_number: version,
+            };
+            let hosted = completer
+                .call("GetHostedConfigurationVersion", || {
+                    client.get_hosted_configuration_version(request.clone())
+                })
+                .await?;
+            completer.progress(&format!(
+                "{}/{} hosted version {}",
+                app.name, profile.name, version
+            ));
+            let content = hosted.content.unwrap_or_default();
+            Ok(Some(render_content(
+                &content,
+                hosted.content_type.as_deref(),
+            )))
+        }
+    }
+}
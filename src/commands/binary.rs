@@ -0,0 +1,125 @@
+use crate::commands::size::check_value_size;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use colored::Colorize;
+use serde_json::json;
+use std::io::Write;
+
+/// Splits `<path|@alias> <file>`; the file name may contain spaces.
+fn parse_args(
+    helper: &ParamStoreHelper,
+    args: &str,
+    usage: &str,
+) -> Result<(String, String), String> {
+    let (path, file) = args
+        .split_once(' ')
+        .map(|(path, file)| (path, file.trim()))
+        .filter(|(_, file)| !file.is_empty())
+        .ok_or_else(|| format!("Usage: {}", usage))?;
+    let path = helper.completer.aliases.resolve(path)?;
+    if !path.starts_with('/') {
+        return Err(format!("Usage: {}", usage));
+    }
+    Ok((path, file.to_string()))
+}
+
+/// Handles `set-binary <path> <file>`: stores the file's bytes base64-encoded.
+/// An existing parameter keeps its type; a new one is created as `SecureString`,
+/// since binary blobs are usually keystores or keys. Returns the path.
+pub async fn set_binary(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (path, file) = parse_args(helper, args, "set-binary <path> <file>")?;
+    let (path, file) = (path.as_str(), file.as_str());
+    let bytes = std::fs::read(file).map_err(|e| format!("Cannot read {}: {}", file, e))?;
+    let value = BASE64.encode(&bytes);
+    let completer = &mut helper.completer;
+    check_value_size(path, &value, completer.chunking)?;
+
+    let exists = completer.values.contains_key(path);
+    if completer.offline {
+        let param_type = (!exists).then(|| "SecureString".to_string());
+        completer.queue_change(path, &value, param_type)?;
+        println!(
+            "Offline: queued {} bytes for {} (run 'push' when back online)",
+            bytes.len(),
+            path
+        );
+        return Ok(path.to_string());
+    }
+
+    if exists {
+        completer.change_value(path, value).await?;
+    } else {
+        completer
+            .set_parameter(path, value.clone(), Some("SecureString".to_string()))
+            .await?;
+        completer.update_all(path, value).await?;
+    }
+    if completer.output.is_json() {
+        output::print_json(json!({ "path": path, "file": file, "bytes": bytes.len() }));
+    } else {
+        println!(
+            "Stored {} ({} bytes, base64-encoded) in {}",
+            file,
+            bytes.len(),
+            path.green()
+        );
+    }
+    Ok(path.to_string())
+}
+
+/// Handles `get-binary <path> <file>`: decodes the base64 value of `path` and
+/// writes the bytes to `file`, readable by the owner only on Unix. The value is
+/// fetched from AWS when it is not cached.
+pub async fn get_binary(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (path, file) = parse_args(helper, args, "get-binary <path> <file>")?;
+    let (path, file) = (path.as_str(), file.as_str());
+    let completer = &mut helper.completer;
+    let value = match completer.cached_value(path) {
+        Some(value) => {
+            completer.stats.record_cache(1, 0);
+            value
+        }
+        None => {
+            completer.stats.record_cache(0, 1);
+            completer.get_set_value(path).await?
+        }
+    };
+    if value.is_empty() {
+        return Err(format!("No value found for {}", path).into());
+    }
+    let bytes = BASE64
+        .decode(value.trim())
+        .map_err(|e| format!("{} does not hold a base64 value: {}", path, e))?;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(file)
+        .and_then(|mut out| out.write_all(&bytes))
+        .map_err(|e| format!("Cannot write {}: {}", file, e))?;
+
+    if completer.output.is_json() {
+        output::print_json(json!({ "path": path, "file": file, "bytes": bytes.len() }));
+    } else {
+        println!(
+            "Wrote {} bytes from {} to {}",
+            bytes.len(),
+            path.green(),
+            file
+        );
+    }
+    Ok(path.to_string())
+}
@@ -0,0 +1,247 @@
+use crate::cache_file::{self, FORMAT_VERSION};
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_timestamp;
+use colored::Colorize;
+use serde_json::json;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+const USAGE: &str = "Usage: cache stats | cache verify | cache rebuild";
+
+/// Handles `cache stats|verify|rebuild`: shows what the store directory
+/// holds, checks every cache file of the backend, and rebuilds the current
+/// base path's files from AWS.
+pub async fn cache(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match args.trim() {
+        "" | "stats" => stats(helper),
+        "verify" => verify(helper),
+        "rebuild" => rebuild(helper).await,
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn stats(helper: &ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let store = &completer.store;
+    let root = format!("{}/", completer.base_path.trim_end_matches('/'));
+    let files = [
+        ("parameters", store.parameters(&completer.base_path)),
+        ("values", store.values(&completer.base_path)),
+        ("types", store.types(&completer.base_path)),
+    ];
+    let (store_files, store_bytes) = directory_size(store.root());
+    let parameters = completer.parameters_under(&root).len();
+    let (values, resident) = (completer.values.len(), completer.values.resident_len());
+    let pending = completer.pending.changes.len();
+
+    if completer.output.is_json() {
+        for (kind, file) in &files {
+            let (bytes, modified) = file_info(file);
+            output::print_json(json!({
+                "file": file.display().to_string(),
+                "kind": kind,
+                "entries": entry_count(helper, file),
+                "bytes": bytes,
+                "modified": modified,
+            }));
+        }
+        output::print_json(json!({
+            "store": store.root().display().to_string(),
+            "store_files": store_files,
+            "store_bytes": store_bytes,
+            "base_path": completer.base_path,
+            "parameters": parameters,
+            "values": values,
+            "resident_values": resident,
+            "pending": pending,
+        }));
+        return Ok(());
+    }
+
+    println!(
+        "Store {} ({} files, {} bytes)",
+        store.root().display().to_string().cyan(),
+        store_files,
+        store_bytes
+    );
+    println!("Cache of {}:", completer.base_path.green());
+    for (kind, file) in &files {
+        let (bytes, modified) = file_info(file);
+        match entry_count(helper, file) {
+            Some(entries) => println!(
+                "  {:<11} {:>6} entries {:>9} bytes  {}",
+                kind,
+                entries,
+                bytes,
+                modified.as_deref().unwrap_or("-").dimmed()
+            ),
+            None if bytes == 0 => println!("  {:<11} {}", kind, "not written".dimmed()),
+            None => println!("  {:<11} {}", kind, "unreadable; see 'cache verify'".red()),
+        }
+    }
+    println!(
+        "In memory: {} parameter(s), {} value(s) ({} resident), {} pending change(s)",
+        parameters, values, resident, pending
+    );
+    Ok(())
+}
+
+/// Checks every cache file of the backend: its header and check value, that
+/// its body parses, and that each cached value decrypts with the current key.
+fn verify(helper: &ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let files = completer.store.cache_files();
+    if files.is_empty() {
+        println!("No cache files in {}", completer.store.root().display());
+        return Ok(());
+    }
+
+    let json = completer.output.is_json();
+    let mut broken = 0;
+    for file in &files {
+        let name = file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut problems = Vec::new();
+        let mut entries = 0;
+        let mut older = false;
+        match cache_file::inspect(file, &completer.encryption) {
+            Err(err) => problems.push(err.to_string()),
+            Ok(inspection) => {
+                entries = inspection.entries.len();
+                older = inspection.version < FORMAT_VERSION;
+                for line in inspection.malformed_lines {
+                    problems.push(format!("line {} is not a 'key: value' line", line));
+                }
+                if is_values_file(&name) {
+                    for (key, value) in &inspection.entries {
+                        let decrypted = value
+                            .as_str()
+                            .ok_or("not a string")
+                            .and_then(|value| completer.encryption.try_decrypt(value));
+                        if let Err(reason) = decrypted {
+                            problems.push(format!("cannot decrypt {} ({})", key, reason));
+                        }
+                    }
+                }
+            }
+        }
+        if !problems.is_empty() {
+            broken += 1;
+        }
+
+        if json {
+            output::print_json(json!({
+                "file": name,
+                "entries": entries,
+                "older_format": older,
+                "problems": problems,
+            }));
+        } else if problems.is_empty() {
+            let note = if older { " (older format)" } else { "" };
+            println!(
+                "  {} {:<40} {} entries{}",
+                "ok".green(),
+                name,
+                entries,
+                note.dimmed()
+            );
+        } else {
+            println!("  {} {}", "FAIL".red(), name);
+            for problem in &problems {
+                println!("       {}", problem);
+            }
+        }
+    }
+
+    if broken > 0 {
+        return Err(format!(
+            "{} of {} cache file(s) have problems; run 'cache rebuild' for the base path they belong to",
+            broken,
+            files.len()
+        )
+        .into());
+    }
+    if !json {
+        println!("All {} cache file(s) are intact", files.len());
+    }
+    Ok(())
+}
+
+/// Deletes the current base path's cache files and fetches everything again.
+async fn rebuild(helper: &mut ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    if completer.offline {
+        return Err("cache rebuild fetches from AWS and is not available offline".into());
+    }
+    let base_path = completer.base_path.clone();
+    for file in [
+        completer.store.parameters(&base_path),
+        completer.store.values(&base_path),
+        completer.store.types(&base_path),
+    ] {
+        match std::fs::remove_file(&file) {
+            Ok(()) => log::info!("Removed {}", file.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Cannot remove {}: {}", file.display(), e).into()),
+        }
+    }
+
+    let refresh = completer.refresh;
+    completer.refresh = true;
+    let loaded = completer.load_parameters().await;
+    completer.refresh = refresh;
+    loaded?;
+    println!(
+        "Rebuilt the cache of {} ({} parameter(s))",
+        base_path.green(),
+        completer.values.len()
+    );
+    Ok(())
+}
+
+fn is_values_file(name: &str) -> bool {
+    name.starts_with("values_") || name.starts_with("sm_values_")
+}
+
+/// Number of entries of a readable cache file, `None` when it is missing or
+/// does not pass its checks.
+fn entry_count(helper: &ParamStoreHelper, file: &Path) -> Option<usize> {
+    cache_file::inspect(file, &helper.completer.encryption)
+        .ok()
+        .map(|inspection| inspection.entries.len())
+}
+
+/// Size in bytes and last modification time (UTC) of a file; 0 and `None`
+/// when it does not exist.
+fn file_info(file: &Path) -> (u64, Option<String>) {
+    let Ok(metadata) = std::fs::metadata(file) else {
+        return (0, None);
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|age| format_timestamp(age.as_secs_f64()));
+    (metadata.len(), modified)
+}
+
+/// Number and total size of the files in `dir` and its subdirectories.
+fn directory_size(dir: &Path) -> (usize, u64) {
+    let mut total = (0, 0);
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let (files, bytes) = directory_size(&path);
+            total = (total.0 + files, total.1 + bytes);
+        } else if let Ok(metadata) = entry.metadata() {
+            total = (total.0 + 1, total.1 + metadata.len());
+        }
+    }
+    total
+}
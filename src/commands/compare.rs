@@ -0,0 +1,185 @@
+use crate::cpboard::Cpboard;
+use crate::helper::ParamStoreHelper;
+use crate::utils::split_change;
+use colored::Colorize;
+
+const USAGE: &str = "Usage: cmp [path] [--clipboard | --file <file>]";
+
+/// Characters of unchanged text shown on each side of a difference.
+const CONTEXT: usize = 20;
+/// Differing lines listed before the rest are summarised.
+const MAX_LINES: usize = 10;
+
+/// How two values compare.
+#[derive(Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    /// Equal once trailing line breaks are ignored, as files usually end with one.
+    EqualIgnoringLineBreak,
+    /// What differs, one line of report each.
+    Different(Vec<String>),
+}
+
+/// Compares the stored value with `other`. Secrets are compared without
+/// showing either value: only lengths and where they first differ.
+pub fn compare_values(stored: &str, other: &str, secret: bool) -> Comparison {
+    if stored == other {
+        return Comparison::Equal;
+    }
+    if stored.trim_end_matches(['\r', '\n']) == other.trim_end_matches(['\r', '\n']) {
+        return Comparison::EqualIgnoringLineBreak;
+    }
+    if secret {
+        let first = stored
+            .chars()
+            .zip(other.chars())
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| stored.chars().count().min(other.chars().count()));
+        return Comparison::Different(vec![format!(
+            "stored {} characters, compared {}; first difference at character {}",
+            stored.chars().count(),
+            other.chars().count(),
+            first + 1
+        )]);
+    }
+
+    let (stored_lines, other_lines): (Vec<&str>, Vec<&str>) =
+        (stored.lines().collect(), other.lines().collect());
+    if stored_lines.len() <= 1 && other_lines.len() <= 1 {
+        return Comparison::Different(inline_diff(stored, other));
+    }
+    let mut report = Vec::new();
+    let count = stored_lines.len().max(other_lines.len());
+    let differing: Vec<usize> = (0..count)
+        .filter(|&i| stored_lines.get(i) != other_lines.get(i))
+        .collect();
+    for &i in differing.iter().take(MAX_LINES) {
+        report.push(format!("line {}:", i + 1));
+        match stored_lines.get(i) {
+            Some(line) => report.push(format!("  {} {}", "-".red(), line)),
+            None => report.push(format!("  {} (no line)", "-".red())),
+        }
+        match other_lines.get(i) {
+            Some(line) => report.push(format!("  {} {}", "+".green(), line)),
+            None => report.push(format!("  {} (no line)", "+".green())),
+        }
+    }
+    if differing.len() > MAX_LINES {
+        report.push(format!(
+            "... and {} more differing line(s)",
+            differing.len() - MAX_LINES
+        ));
+    }
+    Comparison::Different(report)
+}
+
+/// The differing middle of two one-line values, with a little unchanged text
+/// around it.
+fn inline_diff(stored: &str, other: &str) -> Vec<String> {
+    let (prefix, removed, added, suffix) = split_change(stored, other);
+    let skip = prefix.chars().count().saturating_sub(CONTEXT);
+    let before: String = prefix.chars().skip(skip).collect();
+    let before = if skip > 0 {
+        format!("…{}", before)
+    } else {
+        before
+    };
+    let mut after: String = suffix.chars().take(CONTEXT).collect();
+    if suffix.chars().count() > CONTEXT {
+        after.push('…');
+    }
+    vec![
+        format!(
+            "  {} {}{}{}",
+            "-".red(),
+            before,
+            removed.red().bold(),
+            after
+        ),
+        format!(
+            "  {} {}{}{}",
+            "+".green(),
+            before,
+            added.green().bold(),
+            after
+        ),
+    ]
+}
+
+/// Handles `cmp [path] [--clipboard | --file <file>]`: checks the stored value
+/// of a parameter (the selected one by default) against the clipboard (the
+/// default) or a file, e.g. to confirm a rotation went through. Online the
+/// value is fetched fresh from AWS. Reports a match, or what differs, in
+/// which case the command fails so scripts can rely on it.
+pub async fn compare<'a>(
+    helper: &mut ParamStoreHelper,
+    cpboard: &mut Cpboard<'a>,
+    args: &str,
+    selected: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut words = args.split_whitespace();
+    let (mut path, mut file) = (None, None);
+    while let Some(word) = words.next() {
+        match word {
+            "--clipboard" => {}
+            "--file" => file = Some(words.next().ok_or(USAGE)?),
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ if path.is_none() => path = Some(helper.completer.aliases.resolve(word)?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let path = match path {
+        Some(path) => path,
+        None if selected.is_empty() => return Err(USAGE.into()),
+        None => selected.to_string(),
+    };
+    let completer = &mut helper.completer;
+    if !completer.is_parameter(&path) {
+        return Err(format!("{} is not a known parameter", path).into());
+    }
+
+    let (other, source) = match file {
+        Some(file) => (
+            std::fs::read_to_string(file).map_err(|e| format!("Cannot read {}: {}", file, e))?,
+            file.to_string(),
+        ),
+        None => (
+            cpboard
+                .get_clipboard_content()
+                .map_err(|e| format!("Cannot read the clipboard: {}", e))?,
+            "the clipboard".to_string(),
+        ),
+    };
+    let stored = if completer.offline {
+        completer
+            .cached_value(&path)
+            .ok_or_else(|| format!("No cached value for {}", path))?
+    } else {
+        completer.get_set_value(&path).await?
+    };
+
+    let secret = completer.redact || completer.is_sensitive(&path);
+    match compare_values(&stored, &other, secret) {
+        Comparison::Equal => {
+            println!("{} {} matches {}", "✓".green(), path.green(), source);
+        }
+        Comparison::EqualIgnoringLineBreak => {
+            println!(
+                "{} {} matches {} (ignoring a trailing line break)",
+                "✓".green(),
+                path.green(),
+                source
+            );
+        }
+        Comparison::Different(report) => {
+            println!("{} {} differs from {}", "✗".red(), path.green(), source);
+            for line in report {
+                println!("{}", line);
+            }
+            return Err(format!("{} does not match {}", path, source).into());
+        }
+    }
+    Ok(path)
+}
@@ -0,0 +1,83 @@
+use crate::completer::NewParameter;
+use crate::helper::ParamStoreHelper;
+use crate::names::check_name;
+use crate::utils::take_flag;
+use colored::Colorize;
+
+/// Handles `cp <src> <dst> [--yes]` and, with `remove_source`, `mv`: creates
+/// `dst` with the value, type, description, tags and KMS key of `src`, then
+/// for `mv` deletes `src` (and moves its pin). `dst` must not exist yet.
+/// Protected paths need their name typed back, or `--yes`. Returns the
+/// resolved source and destination paths.
+pub async fn copy(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    remove_source: bool,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let command = if remove_source { "mv" } else { "cp" };
+    let (args, yes) = take_flag(args.trim(), "--yes");
+    let completer = &mut helper.completer;
+    let (src, dst) = match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [src, dst] => (
+            completer.aliases.resolve(src)?,
+            completer.aliases.resolve(dst)?,
+        ),
+        _ => return Err(format!("Usage: {} <src> <dst> [--yes]", command).into()),
+    };
+    if completer.offline {
+        return Err(format!("{} is not available in offline mode", command).into());
+    }
+    if !completer.is_parameter(&src) {
+        return Err(format!("{} is not a known parameter", src).into());
+    }
+    if completer.is_parameter(&dst) {
+        return Err(format!("{} already exists; delete it first", dst).into());
+    }
+    check_name(&dst)?;
+    completer.protected.confirm(&dst, "create", yes)?;
+    if remove_source {
+        completer.protected.confirm(&src, "move", yes)?;
+    }
+
+    let value = completer.get_set_value(&src).await?;
+    let metadata = completer
+        .describe_parameters(&src)
+        .await?
+        .into_iter()
+        .find(|metadata| metadata.name.as_deref() == Some(src.as_str()));
+    let param_type = metadata
+        .as_ref()
+        .and_then(|metadata| metadata.type_.clone())
+        .or_else(|| completer.types.get(&src).cloned())
+        .unwrap_or_else(|| "String".to_string());
+    let new = NewParameter {
+        path: dst.clone(),
+        value,
+        key_id: metadata
+            .as_ref()
+            .and_then(|metadata| metadata.key_id.clone())
+            .filter(|_| param_type == "SecureString"),
+        description: metadata
+            .as_ref()
+            .and_then(|metadata| metadata.description.clone()),
+        allowed_pattern: metadata
+            .as_ref()
+            .and_then(|metadata| metadata.allowed_pattern.clone()),
+        data_type: metadata.and_then(|metadata| metadata.data_type),
+        tags: completer.parameter_tags(&src).await?,
+        param_type,
+    };
+    completer.create_parameter(&new).await?;
+
+    if !remove_source {
+        println!("Copied {} to {}", src, dst.green());
+        return Ok((src, dst));
+    }
+    completer.delete_parameter(&src).await?;
+    if completer.pins.unpin(&src) {
+        completer.pins.pin(&dst, &new.value);
+        completer.pins.save()?;
+    }
+    println!("Moved {} to {}", src, dst.green());
+    Ok((src, dst))
+}
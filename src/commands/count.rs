@@ -0,0 +1,173 @@
+use crate::chunking;
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_date;
+use colored::Colorize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// How many recently modified parameters `count` lists.
+const RECENT: usize = 5;
+
+/// What `count` reports about the parameters under a prefix.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub parameters: usize,
+    pub by_type: BTreeMap<String, usize>,
+    /// Empty offline, where tiers are not known.
+    pub by_tier: BTreeMap<String, usize>,
+    /// Total size of the cached values.
+    pub value_bytes: usize,
+    /// Parameters whose value is not cached, so not in `value_bytes`.
+    pub uncached: usize,
+    pub deepest: Option<String>,
+    /// Path and change time, newest first.
+    pub recent: Vec<(String, f64)>,
+}
+
+/// One parameter as `summarize` sees it.
+struct Entry {
+    name: String,
+    param_type: Option<String>,
+    tier: Option<String>,
+    modified: Option<f64>,
+}
+
+/// Gathers the `count` figures for `prefix`. Online the type, tier and change
+/// date come from DescribeParameters; offline the cached metadata is used
+/// instead, which has no tiers.
+pub async fn summarize(
+    completer: &ParameterCompleter,
+    prefix: &str,
+) -> Result<Summary, Box<dyn std::error::Error>> {
+    let listed = if completer.offline {
+        completer.cached_metadata(prefix)
+    } else {
+        completer.describe_parameters(prefix).await?
+    };
+    let mut entries: Vec<Entry> = listed
+        .into_iter()
+        .filter_map(|metadata| {
+            Some(Entry {
+                name: metadata.name?,
+                param_type: metadata.type_,
+                tier: metadata.tier,
+                modified: metadata.last_modified_date,
+            })
+        })
+        .filter(|entry| !chunking::is_chunk_path(&entry.name))
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut summary = Summary {
+        parameters: entries.len(),
+        ..Default::default()
+    };
+    for entry in &entries {
+        let param_type = entry.param_type.as_deref().unwrap_or("unknown");
+        *summary.by_type.entry(param_type.to_string()).or_default() += 1;
+        if let Some(tier) = &entry.tier {
+            *summary.by_tier.entry(tier.clone()).or_default() += 1;
+        }
+        let depth = entry.name.matches('/').count();
+        if summary
+            .deepest
+            .as_ref()
+            .is_none_or(|deepest| depth > deepest.matches('/').count())
+        {
+            summary.deepest = Some(entry.name.clone());
+        }
+    }
+
+    let paths: Vec<String> = entries.iter().map(|entry| entry.name.clone()).collect();
+    let values = completer.cached_values(&paths);
+    summary.value_bytes = values.values().map(String::len).sum();
+    summary.uncached = paths.len() - values.len();
+
+    let mut dated: Vec<(String, f64)> = entries
+        .into_iter()
+        .filter_map(|entry| Some((entry.name, entry.modified?)))
+        .collect();
+    dated.sort_by(|a, b| b.1.total_cmp(&a.1));
+    dated.truncate(RECENT);
+    summary.recent = dated;
+    Ok(summary)
+}
+
+/// Handles `count [prefix]`: how many parameters live under `prefix` (the base
+/// path by default), by type and tier, the size of their values, the deepest
+/// path and the most recently changed entries.
+pub async fn count(
+    helper: &ParamStoreHelper,
+    prefix: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let prefix = if prefix.is_empty() {
+        completer.root()
+    } else {
+        prefix
+    };
+    let summary = summarize(completer, prefix).await?;
+
+    if completer.output.is_json() {
+        let recent: Vec<_> = summary
+            .recent
+            .iter()
+            .map(
+                |(path, modified)| json!({ "path": path, "last_modified": format_date(*modified) }),
+            )
+            .collect();
+        output::print_json(json!({
+            "prefix": prefix,
+            "parameters": summary.parameters,
+            "by_type": summary.by_type,
+            "by_tier": summary.by_tier,
+            "value_bytes": summary.value_bytes,
+            "uncached": summary.uncached,
+            "deepest": summary.deepest,
+            "recent": recent,
+        }));
+        return Ok(());
+    }
+
+    let breakdown = |counts: &BTreeMap<String, usize>| {
+        counts
+            .iter()
+            .map(|(name, count)| format!("{} {}", name, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    println!(
+        "{} parameter(s) under {}",
+        summary.parameters.to_string().bold(),
+        prefix.green()
+    );
+    if summary.parameters == 0 {
+        return Ok(());
+    }
+    println!("  By type:  {}", breakdown(&summary.by_type));
+    if !summary.by_tier.is_empty() {
+        println!("  By tier:  {}", breakdown(&summary.by_tier));
+    }
+    let uncached = if summary.uncached > 0 {
+        format!(" ({} value(s) not cached)", summary.uncached)
+    } else {
+        String::new()
+    };
+    println!("  Values:   {} bytes{}", summary.value_bytes, uncached);
+    if let Some(deepest) = &summary.deepest {
+        println!(
+            "  Deepest:  {} ({} levels)",
+            deepest,
+            deepest.matches('/').count()
+        );
+    }
+    if !summary.recent.is_empty() {
+        println!("  Recently modified:");
+        for (path, modified) in &summary.recent {
+            println!("    {}  {}", format_date(*modified), path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::helper::ParamStoreHelper;
+use crate::utils::{prompt_line, take_flag};
+use colored::Colorize;
+use std::io::IsTerminal;
+
+/// Handles `delete [path] [--yes]`: deletes a parameter, the selected one when
+/// no path is given, from AWS and the cache. Asks `[y/N]` first, or for the
+/// name to be typed back on protected paths; `--yes` skips both and is needed
+/// without a terminal.
+pub async fn delete(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    selected: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (args, yes) = take_flag(args.trim(), "--yes");
+    let completer = &mut helper.completer;
+    let path = match args {
+        "" => selected.to_string(),
+        path => completer.aliases.resolve(path)?,
+    };
+    if path.is_empty() {
+        return Err("No parameter given or selected. Usage: delete [path] [--yes]".into());
+    }
+    if completer.offline {
+        return Err("delete is not available in offline mode".into());
+    }
+    if !completer.is_parameter(&path) {
+        return Err(format!("{} is not a known parameter", path).into());
+    }
+
+    if completer.protected.matching(&path).is_some() {
+        completer.protected.confirm(&path, "delete", yes)?;
+    } else if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err(format!("Pass --yes to delete {} without a prompt", path).into());
+        }
+        if !prompt_line(&format!("Delete {}? [y/N]: ", path))?.eq_ignore_ascii_case("y") {
+            return Err("Delete cancelled".into());
+        }
+    }
+
+    completer.delete_parameter(&path).await?;
+    println!("Deleted {}", path.red());
+    Ok(path)
+}
@@ -0,0 +1,200 @@
+use crate::helper::ParamStoreHelper;
+use colored::Colorize;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// `${/path}` or `${@alias}` inside a value.
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{([/@][^}\s]*)\}").expect("valid reference pattern"));
+
+/// The values of a parameter and everything it references, directly or not.
+/// `None` marks a referenced parameter that does not exist.
+pub type Values = HashMap<String, Option<String>>;
+
+/// The paths `value` references as `${/path}`, in order, without repeats.
+/// Aliases are left as written.
+pub fn references(value: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for capture in REFERENCE.captures_iter(value) {
+        let path = capture[1].to_string();
+        if !found.contains(&path) {
+            found.push(path);
+        }
+    }
+    found
+}
+
+/// Fetches the value of `root` and of every parameter it leads to through
+/// references. Values come from the cache, or from AWS when missing.
+async fn collect(
+    helper: &mut ParamStoreHelper,
+    root: &str,
+) -> Result<Values, Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    let mut values = Values::new();
+    let mut queue = vec![root.to_string()];
+    while !queue.is_empty() {
+        for path in &queue {
+            completer.check_scope(path)?;
+        }
+        completer.hydrate(&queue).await?;
+        let mut next = Vec::new();
+        for path in queue {
+            let value = completer.cached_value(&path);
+            if let Some(value) = &value {
+                for reference in references(value) {
+                    let reference = completer.aliases.resolve(&reference)?;
+                    if !values.contains_key(&reference) && !next.contains(&reference) {
+                        next.push(reference);
+                    }
+                }
+            }
+            values.insert(path, value);
+        }
+        next.retain(|path| !values.contains_key(path));
+        queue = next;
+    }
+    Ok(values)
+}
+
+/// The dependency tree of `root` as lines to print. Parameters that do not
+/// exist and references back into the current branch are marked.
+pub fn dependency_tree(
+    root: &str,
+    values: &Values,
+    resolve: &dyn Fn(&str) -> String,
+) -> Vec<String> {
+    let mut lines = vec![root.to_string()];
+    let mut branch = vec![root.to_string()];
+    add_children(root, values, resolve, "", &mut branch, &mut lines);
+    lines
+}
+
+fn add_children(
+    path: &str,
+    values: &Values,
+    resolve: &dyn Fn(&str) -> String,
+    indent: &str,
+    branch: &mut Vec<String>,
+    lines: &mut Vec<String>,
+) {
+    let Some(Some(value)) = values.get(path) else {
+        return;
+    };
+    let children: Vec<String> = references(value).iter().map(|r| resolve(r)).collect();
+    for (index, child) in children.iter().enumerate() {
+        let last = index + 1 == children.len();
+        let (connector, deeper) = if last {
+            ("└─ ", "   ")
+        } else {
+            ("├─ ", "│  ")
+        };
+        let marker = if branch.contains(child) {
+            " (cycle)"
+        } else if !matches!(values.get(child), Some(Some(_))) {
+            " (missing)"
+        } else {
+            ""
+        };
+        lines.push(format!("{}{}{}{}", indent, connector, child, marker));
+        if marker.is_empty() {
+            branch.push(child.clone());
+            let indent = format!("{}{}", indent, deeper);
+            add_children(child, values, resolve, &indent, branch, lines);
+            branch.pop();
+        }
+    }
+}
+
+/// The value of `path` with every `${...}` reference replaced by the
+/// referenced value, itself resolved first. Fails on a missing parameter or
+/// on a cycle, naming the chain of references.
+pub fn resolve_value(
+    path: &str,
+    values: &Values,
+    resolve: &dyn Fn(&str) -> String,
+    chain: &mut Vec<String>,
+) -> Result<String, String> {
+    if let Some(start) = chain.iter().position(|seen| seen == path) {
+        let mut cycle = chain[start..].to_vec();
+        cycle.push(path.to_string());
+        return Err(format!("Reference cycle: {}", cycle.join(" -> ")));
+    }
+    let value = match values.get(path) {
+        Some(Some(value)) => value,
+        _ if chain.is_empty() => return Err(format!("{} does not exist", path)),
+        _ => {
+            return Err(format!(
+                "{} references {}, which does not exist",
+                chain.last().map(String::as_str).unwrap_or_default(),
+                path
+            ));
+        }
+    };
+    chain.push(path.to_string());
+    let mut resolved = String::with_capacity(value.len());
+    let mut end = 0;
+    for capture in REFERENCE.captures_iter(value) {
+        let whole = capture.get(0).expect("capture 0 is the whole match");
+        resolved.push_str(&value[end..whole.start()]);
+        let reference = resolve(&capture[1]);
+        resolved.push_str(&resolve_value(&reference, values, resolve, chain)?);
+        end = whole.end();
+    }
+    resolved.push_str(&value[end..]);
+    chain.pop();
+    Ok(resolved)
+}
+
+fn target(helper: &ParamStoreHelper, args: &str, selected: &str) -> Result<String, String> {
+    match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [] if !selected.is_empty() => Ok(selected.to_string()),
+        [path] => helper.completer.aliases.resolve(path),
+        _ => Err("Give one parameter path, or select one first".to_string()),
+    }
+}
+
+/// Handles `deps [path]`: prints the tree of parameters the value of `path`
+/// (the selected one by default) references as `${/other/path}`.
+pub async fn deps(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    selected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = target(helper, args, selected)?;
+    let values = collect(helper, &path).await?;
+    if !matches!(values.get(&path), Some(Some(_))) {
+        return Err(format!("{} does not exist", path).into());
+    }
+    let aliases = &helper.completer.aliases;
+    let resolve = |reference: &str| aliases.resolve(reference).unwrap_or_default();
+    let lines = dependency_tree(&path, &values, &resolve);
+    if lines.len() == 1 {
+        println!("{} references no other parameters", path.green());
+        return Ok(());
+    }
+    for line in lines {
+        let line = line
+            .replace(" (cycle)", &" (cycle)".yellow().to_string())
+            .replace(" (missing)", &" (missing)".red().to_string());
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Handles `render-resolved [path]`: the value of `path` (the selected one by
+/// default) with its `${...}` references replaced, recursively. Returns the
+/// path and the resolved value.
+pub async fn render_resolved(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    selected: &str,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let path = target(helper, args, selected)?;
+    let values = collect(helper, &path).await?;
+    let aliases = &helper.completer.aliases;
+    let resolve = |reference: &str| aliases.resolve(reference).unwrap_or_default();
+    let resolved = resolve_value(&path, &values, &resolve, &mut Vec::new())?;
+    Ok((path, resolved))
+}
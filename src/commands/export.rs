@@ -0,0 +1,400 @@
+use crate::env_vars::{self, EnvFormat};
+use crate::helper::ParamStoreHelper;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use colored::Colorize;
+use rusoto_ssm::ParameterMetadata;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+const USAGE: &str = "Usage: export k8s-secret|k8s-configmap|terraform|csv|dotenv <prefix> [--name <name>] [--namespace <ns>] [--output <file>]";
+
+/// The values under the export prefix, sorted by path.
+type Values = BTreeMap<String, String>;
+
+/// Options shared by every export format.
+struct ExportArgs {
+    format: String,
+    prefix: String,
+    name: Option<String>,
+    namespace: Option<String>,
+    output: Option<String>,
+}
+
+impl ExportArgs {
+    fn parse(helper: &ParamStoreHelper, args: &str) -> Result<Self, String> {
+        let mut words = args.split_whitespace();
+        let format = words.next().ok_or(USAGE)?.to_string();
+        let mut prefix = None;
+        let (mut name, mut namespace, mut output) = (None, None, None);
+        while let Some(word) = words.next() {
+            match word {
+                "--name" => name = Some(words.next().ok_or(USAGE)?.to_string()),
+                "--namespace" => namespace = Some(words.next().ok_or(USAGE)?.to_string()),
+                "--output" | "-o" => output = Some(words.next().ok_or(USAGE)?.to_string()),
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option {}. {}", flag, USAGE));
+                }
+                _ if prefix.is_none() => prefix = Some(word),
+                _ => return Err(USAGE.to_string()),
+            }
+        }
+        let prefix = helper.completer.aliases.resolve(prefix.ok_or(USAGE)?)?;
+        if !prefix.starts_with('/') {
+            return Err(USAGE.to_string());
+        }
+        Ok(ExportArgs {
+            format,
+            prefix,
+            name,
+            namespace,
+            output,
+        })
+    }
+}
+
+/// Handles `export <format> <prefix> [...]`: renders the parameters under
+/// `prefix` in a format other tools consume, to stdout or `--output`. Values
+/// missing from the cache are fetched first.
+pub async fn export(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = ExportArgs::parse(helper, args)?;
+    if helper.completer.redact && args.output.is_none() {
+        return Err("Values are redacted; pass --output <file> to export them".into());
+    }
+    let root = format!("{}/", args.prefix.trim_end_matches('/'));
+    let paths = helper.completer.parameters_under(&root);
+    helper.completer.hydrate(&paths).await?;
+    let values: Values = helper.completer.cached_values(&paths).into_iter().collect();
+    if values.is_empty() {
+        return Err(format!("No cached values to export under {}", root).into());
+    }
+
+    let (document, sensitive) = match args.format.as_str() {
+        "k8s-secret" => (kubernetes(helper, &args, &values, true)?, true),
+        "k8s-configmap" => (kubernetes(helper, &args, &values, false)?, false),
+        "terraform" => (terraform(helper, &args, &values).await?, false),
+        "csv" => (csv_table(helper, &args, &values).await?, true),
+        "dotenv" => {
+            let mapping = &helper.completer.env_mapping;
+            let document = env_vars::render(&values, &root, EnvFormat::Dotenv, mapping)?;
+            (document, true)
+        }
+        other => return Err(format!("Unknown export format '{}'. {}", other, USAGE).into()),
+    };
+    write_output(&document, args.output.as_deref(), sensitive)
+}
+
+/// Builds a Kubernetes `Secret` (every value, base64-encoded) or `ConfigMap`
+/// (plain `String`/`StringList` values only) manifest. Keys are the paths
+/// relative to the prefix, with characters Kubernetes rejects replaced by `_`:
+/// `/prod/app/db/url` under `/prod/app` becomes `db_url`.
+fn kubernetes(
+    helper: &ParamStoreHelper,
+    args: &ExportArgs,
+    values: &Values,
+    secret: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let name = args
+        .name
+        .as_deref()
+        .ok_or_else(|| format!("--name is required. {}", USAGE))?;
+    check_object_name(name)?;
+    if let Some(namespace) = &args.namespace {
+        check_object_name(namespace)?;
+    }
+
+    let root = format!("{}/", args.prefix.trim_end_matches('/'));
+    let mut data: BTreeMap<String, (&str, &str)> = BTreeMap::new();
+    let mut skipped = 0;
+    for (path, value) in values {
+        let Some(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        if !secret && completer.is_sensitive(path) {
+            skipped += 1;
+            continue;
+        }
+        let key = kubernetes_key(relative);
+        if let Some((other, _)) = data.insert(key.clone(), (path, value)) {
+            return Err(format!("{} and {} both map to the key '{}'", other, path, key).into());
+        }
+    }
+    if data.is_empty() && skipped > 0 {
+        return Err(format!(
+            "All {} parameter(s) under {} are secret; use 'export k8s-secret' instead",
+            skipped, root
+        )
+        .into());
+    }
+    if skipped > 0 {
+        eprintln!(
+            "{}",
+            format!(
+                "Skipped {} secret parameter(s); use 'export k8s-secret' for those",
+                skipped
+            )
+            .yellow()
+        );
+    }
+
+    let mut document = format!(
+        "apiVersion: v1\nkind: {}\nmetadata:\n  name: {}\n",
+        if secret { "Secret" } else { "ConfigMap" },
+        name
+    );
+    if let Some(namespace) = &args.namespace {
+        document.push_str(&format!("  namespace: {}\n", namespace));
+    }
+    if secret {
+        document.push_str("type: Opaque\n");
+    }
+    document.push_str("data:\n");
+    for (key, (_, value)) in &data {
+        // JSON strings are valid double-quoted YAML scalars.
+        let value = if secret {
+            BASE64.encode(value.as_bytes())
+        } else {
+            serde_json::to_string(value)?
+        };
+        document.push_str(&format!("  {}: {}\n", key, value));
+    }
+    Ok(document)
+}
+
+/// Builds `aws_ssm_parameter` resources for the parameters under the prefix,
+/// followed by the `terraform import` commands that adopt the existing ones.
+/// Secret values are never written out: they become sensitive variables.
+/// Types and descriptions come from AWS when online, from the cache otherwise.
+async fn terraform(
+    helper: &ParamStoreHelper,
+    args: &ExportArgs,
+    values: &Values,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let root = format!("{}/", args.prefix.trim_end_matches('/'));
+    let described: HashMap<String, ParameterMetadata> = if completer.offline {
+        HashMap::new()
+    } else {
+        completer
+            .describe_parameters(&root)
+            .await?
+            .into_iter()
+            .filter_map(|meta| Some((meta.name.clone()?, meta)))
+            .collect()
+    };
+
+    let mut resources: BTreeMap<String, &str> = BTreeMap::new();
+    for path in values.keys() {
+        let Some(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        let resource = terraform_name(relative);
+        if let Some(other) = resources.insert(resource.clone(), path) {
+            return Err(format!(
+                "{} and {} both map to the resource name '{}'",
+                other, path, resource
+            )
+            .into());
+        }
+    }
+    if resources.is_empty() {
+        return Err(format!("No cached values to export under {}", root).into());
+    }
+
+    let mut document = String::new();
+    let mut variables = String::new();
+    for (resource, path) in &resources {
+        let meta = described.get(*path);
+        let param_type = meta
+            .and_then(|meta| meta.type_.clone())
+            .or_else(|| completer.types.get(*path).cloned())
+            .unwrap_or_else(|| "SecureString".to_string());
+        document.push_str(&format!(
+            "resource \"aws_ssm_parameter\" \"{}\" {{\n  name = {}\n  type = \"{}\"\n",
+            resource,
+            hcl_string(path),
+            param_type
+        ));
+        if completer.is_sensitive(path) {
+            document.push_str(&format!("  value = var.{}\n", resource));
+            variables.push_str(&format!(
+                "variable \"{}\" {{\n  description = {}\n  type = string\n  sensitive = true\n}}\n\n",
+                resource,
+                hcl_string(&format!("Value of {}", path))
+            ));
+        } else {
+            document.push_str(&format!("  value = {}\n", hcl_string(&values[*path])));
+        }
+        if let Some(meta) = meta {
+            if let Some(description) = meta.description.as_deref().filter(|d| !d.is_empty()) {
+                document.push_str(&format!("  description = {}\n", hcl_string(description)));
+            }
+            if let Some(tier) = meta.tier.as_deref().filter(|tier| *tier != "Standard") {
+                document.push_str(&format!("  tier = \"{}\"\n", tier));
+            }
+        }
+        document.push_str("}\n\n");
+    }
+    document.push_str(&variables);
+    document.push_str("# Adopt the existing parameters before the first apply:\n");
+    for (resource, path) in &resources {
+        document.push_str(&format!(
+            "# terraform import aws_ssm_parameter.{} {}\n",
+            resource, path
+        ));
+    }
+    Ok(document)
+}
+
+/// Builds a spreadsheet of the parameters under the prefix with the columns
+/// `import csv` reads back: name, type, value, description and tags. Secret
+/// values are included. Descriptions and tags are only known online.
+async fn csv_table(
+    helper: &ParamStoreHelper,
+    args: &ExportArgs,
+    values: &Values,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let root = format!("{}/", args.prefix.trim_end_matches('/'));
+    let described: HashMap<String, ParameterMetadata> = if completer.offline {
+        HashMap::new()
+    } else {
+        completer
+            .describe_parameters(&root)
+            .await?
+            .into_iter()
+            .filter_map(|meta| Some((meta.name.clone()?, meta)))
+            .collect()
+    };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["name", "type", "value", "description", "tags"])?;
+    for (path, value) in values {
+        let meta = described.get(path);
+        let param_type = meta
+            .and_then(|meta| meta.type_.clone())
+            .or_else(|| completer.types.get(path).cloned())
+            .unwrap_or_default();
+        let description = meta
+            .and_then(|meta| meta.description.clone())
+            .unwrap_or_default();
+        let tags = if completer.offline {
+            String::new()
+        } else {
+            completer
+                .parameter_tags(path)
+                .await?
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writer.write_record([path.as_str(), &param_type, value, &description, &tags])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// Terraform identifiers may contain letters, digits, `_` and `-`, and must
+/// not start with a digit or `-`.
+fn terraform_name(relative: &str) -> String {
+    let name: String = relative
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name
+    } else {
+        format!("_{}", name)
+    }
+}
+
+/// Quotes `value` as an HCL string, escaping template sequences too.
+fn hcl_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                quoted.push(c);
+                quoted.push(c);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Kubernetes data keys may only contain alphanumerics, `-`, `_` and `.`.
+fn kubernetes_key(relative: &str) -> String {
+    relative
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Object names and namespaces must be lowercase DNS subdomains.
+fn check_object_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 253
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'))
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid Kubernetes name '{}': use lowercase letters, digits, '-' and '.'",
+            name
+        ))
+    }
+}
+
+/// Prints `document`, or writes it to `file`; files holding secret values are
+/// readable by the owner only on Unix.
+pub fn write_output(
+    document: &str,
+    file: Option<&str>,
+    sensitive: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(file) = file else {
+        print!("{}", document);
+        return Ok(());
+    };
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if sensitive {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(file)
+        .and_then(|mut out| out.write_all(document.as_bytes()))
+        .map_err(|e| format!("Cannot write {}: {}", file, e))?;
+    println!("Wrote {}", file);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+use std::collections::HashSet;
+
+const USAGE: &str = "Usage: gc [prefix] [--dry-run]";
+
+/// Handles `gc [prefix] [--dry-run]`: removes from the cache the parameters
+/// under `prefix` (the scope or base path by default) that no longer exist in
+/// AWS. Only names are fetched, with DescribeParameters. Paths with queued
+/// offline writes are kept.
+pub async fn gc(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    if completer.offline {
+        return Err("gc asks AWS which parameters exist and is not available offline".into());
+    }
+    let mut prefix = None;
+    let mut dry_run = false;
+    for word in args.split_whitespace() {
+        match word {
+            "--dry-run" => dry_run = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ if prefix.is_none() => prefix = Some(completer.aliases.resolve(word)?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let prefix = prefix.unwrap_or_else(|| completer.root().to_string());
+    if !prefix.starts_with('/') {
+        return Err(USAGE.into());
+    }
+    let root = format!("{}/", prefix.trim_end_matches('/'));
+
+    completer.progress(&format!("Listing the parameters under {}", root));
+    let existing: HashSet<String> = completer
+        .describe_parameters(&root)
+        .await?
+        .into_iter()
+        .filter_map(|parameter| parameter.name)
+        .collect();
+    let queued: HashSet<&str> = completer
+        .pending
+        .changes
+        .iter()
+        .map(|change| change.path.as_str())
+        .collect();
+    let stale: Vec<String> = completer
+        .parameters_under(&root)
+        .into_iter()
+        .filter(|path| !existing.contains(path) && !queued.contains(path.as_str()))
+        .collect();
+
+    if !dry_run {
+        completer.forget(&stale)?;
+    }
+    report_removed(completer, &stale, &root, dry_run);
+    Ok(())
+}
+
+/// Lists the parameters dropped from the cache because they were deleted in
+/// AWS, by `gc` or by a `refresh`.
+pub fn report_removed(
+    completer: &ParameterCompleter,
+    removed: &[String],
+    root: &str,
+    dry_run: bool,
+) {
+    if completer.output.is_json() {
+        for path in removed {
+            output::print_json(json!({ "removed": path, "dry_run": dry_run }));
+        }
+        return;
+    }
+    if removed.is_empty() {
+        completer.progress(&format!("No deleted parameters cached under {}", root));
+        return;
+    }
+    for path in removed {
+        println!("  {} {}", "-".red(), path);
+    }
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!(
+        "{} {} parameter(s) deleted in AWS from the cache",
+        verb,
+        removed.len()
+    );
+}
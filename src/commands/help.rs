@@ -0,0 +1,141 @@
+use crate::command::{COMMANDS, Category, CommandSpec};
+use colored::Colorize;
+
+/// Common workflows shown by `cheatsheet`: a title and the commands involved,
+/// each with a short note. Usage strings come from the command metadata.
+const WORKFLOWS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Find and update a value",
+        &[
+            ("search", "find the key"),
+            ("sel", "pick it from the results"),
+            ("set", "write the new value"),
+        ],
+    ),
+    (
+        "Look up a value",
+        &[(
+            "search",
+            "or type a path and press Tab; Enter copies the value",
+        )],
+    ),
+    (
+        "Create parameters",
+        &[
+            ("insert", "one at a time"),
+            ("import", "many at once from JSON"),
+        ],
+    ),
+    (
+        "Keep the cache fresh",
+        &[
+            ("reload", "just the selected key"),
+            ("refresh", "everything under the base path"),
+        ],
+    ),
+    (
+        "Work offline",
+        &[
+            ("pending", "review queued changes"),
+            ("push", "apply them once back online"),
+        ],
+    ),
+    (
+        "Guard critical keys",
+        &[
+            ("pin", "remember the expected value"),
+            ("pins", "check for drift"),
+        ],
+    ),
+];
+
+/// Width used to wrap help text, from `$COLUMNS` when available.
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(100)
+        .max(40)
+}
+
+/// Word-wraps `text` to `width`, indenting continuation lines by `indent`.
+fn wrap(text: &str, width: usize, indent: usize) -> String {
+    let available = width.saturating_sub(indent).max(20);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > available {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines.join(&format!("\n{}", " ".repeat(indent)))
+}
+
+/// Handles `help [command]`.
+pub fn help(topic: &str) {
+    if !topic.is_empty() {
+        match CommandSpec::find(topic) {
+            Some(spec) => {
+                println!("{}", spec.usage.cyan().bold());
+                println!("  {}", wrap(spec.summary, terminal_width(), 2));
+            }
+            None => println!("Unknown command '{}'. Type 'help' for a list.", topic),
+        }
+        return;
+    }
+
+    let usage_width = COMMANDS
+        .iter()
+        .map(|spec| spec.usage.len())
+        .max()
+        .unwrap_or(0);
+    let usage_width = usage_width.min(36);
+    let indent = usage_width + 4;
+
+    for category in Category::ALL {
+        println!("{}", category.title().bold().underline());
+        for spec in COMMANDS.iter().filter(|spec| spec.category == category) {
+            let summary = wrap(spec.summary, terminal_width(), indent);
+            if spec.usage.len() > usage_width {
+                println!("  {}", spec.usage.cyan());
+                println!("{}{}", " ".repeat(indent), summary);
+            } else {
+                println!(
+                    "  {:<width$}  {}",
+                    spec.usage.cyan(),
+                    summary,
+                    width = usage_width
+                );
+            }
+        }
+        println!();
+    }
+    println!(
+        "Anything else is treated as a parameter path. Type '{}' for common workflows.",
+        "cheatsheet".yellow()
+    );
+}
+
+/// Handles `cheatsheet`: a compact, colorized quick reference of common workflows.
+pub fn cheatsheet() {
+    println!("{}", "daps quick reference".bold().underline());
+    for (title, steps) in WORKFLOWS {
+        println!("{}", title.yellow().bold());
+        for (index, (name, note)) in steps.iter().enumerate() {
+            let usage = CommandSpec::find(name).map_or(*name, |spec| spec.usage);
+            let arrow = if index == 0 { "  " } else { "→ " };
+            let usage = format!("{:<44}", usage);
+            println!("  {}{} {}", arrow.dimmed(), usage.cyan(), note.dimmed());
+        }
+    }
+    println!("Type '{}' for every command.", "help".yellow());
+}
@@ -0,0 +1,32 @@
+use colored::Colorize;
+use rustyline::history::History;
+
+/// Default number of entries shown by `history`.
+const DEFAULT_COUNT: usize = 20;
+
+/// Handles `history [n]`: prints the last `n` entries with the indices `!N` accepts.
+/// With `redact`, values typed after `set` and `insert` are masked.
+pub fn list_history(
+    history: &History,
+    count: &str,
+    redact: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = if count.is_empty() {
+        DEFAULT_COUNT
+    } else {
+        count
+            .parse()
+            .map_err(|_| format!("Invalid count '{}'; usage: history [n]", count))?
+    };
+
+    let start = history.len().saturating_sub(count);
+    for (index, entry) in history.iter().enumerate().skip(start) {
+        let entry = if redact {
+            crate::history::redact(entry)
+        } else {
+            entry.clone()
+        };
+        println!("{:>5}  {}", (index + 1).to_string().yellow(), entry);
+    }
+    Ok(())
+}
@@ -0,0 +1,477 @@
+use crate::commands::insert::parse_tags;
+use crate::completer::NewParameter;
+use crate::helper::ParamStoreHelper;
+use crate::progress::{Checkpoint, ProgressBar};
+use crate::shutdown::Cancellation;
+use crate::utils::prompt_line;
+use colored::Colorize;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::time::UNIX_EPOCH;
+
+/// What to do when an imported key already exists in Parameter Store.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictStrategy {
+    Skip,
+    Overwrite,
+    Prompt,
+    /// Overwrite only when the imported entry is newer than the remote one.
+    Newer,
+}
+
+impl std::str::FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "prompt" => Ok(Self::Prompt),
+            "newer" => Ok(Self::Newer),
+            other => Err(format!(
+                "Unknown conflict strategy '{}' (expected skip|overwrite|prompt|newer)",
+                other
+            )),
+        }
+    }
+}
+
+/// One entry of an import file.
+#[derive(Debug, Deserialize)]
+pub struct ImportEntry {
+    pub name: String,
+    pub value: String,
+    #[serde(rename = "type", default)]
+    pub param_type: Option<String>,
+    /// Seconds since the Unix epoch; falls back to the file's modification time.
+    #[serde(default)]
+    pub last_modified: Option<f64>,
+    /// Only applied when the parameter is created.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Only applied when the parameter is created.
+    #[serde(skip)]
+    pub tags: Vec<(String, String)>,
+}
+
+/// Import files are either a JSON array of entries or a flat `{ "name": "value" }` object.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ImportFile {
+    Entries(Vec<ImportEntry>),
+    Flat(BTreeMap<String, String>),
+}
+
+/// Per-key result shown in the import report.
+#[derive(Debug, PartialEq)]
+pub enum ImportOutcome {
+    Created,
+    Overwritten,
+    Unchanged,
+    Skipped(&'static str),
+    Failed(String),
+}
+
+const USAGE: &str =
+    "Usage: import [csv] <file> [--on-conflict skip|overwrite|prompt|newer] [--dry-run]";
+
+/// Options of the `import` command.
+#[derive(Debug, PartialEq)]
+pub struct ImportArgs {
+    pub file: String,
+    pub strategy: ConflictStrategy,
+    /// Read a spreadsheet export instead of JSON; implied by a `.csv` file.
+    pub csv: bool,
+    /// Print what would change without writing anything.
+    pub dry_run: bool,
+}
+
+/// Parses `import [csv] <file> [--on-conflict skip|overwrite|prompt|newer] [--dry-run]`.
+pub fn parse_import_args(raw: &str) -> Result<ImportArgs, String> {
+    let mut file = None;
+    let mut strategy = ConflictStrategy::Skip;
+    let (mut csv, mut dry_run) = (false, false);
+    let mut tokens = raw.split_whitespace().peekable();
+    if tokens.peek() == Some(&"csv") {
+        csv = true;
+        tokens.next();
+    }
+
+    while let Some(token) = tokens.next() {
+        if let Some(value) = token.strip_prefix("--on-conflict=") {
+            strategy = value.parse()?;
+        } else if token == "--on-conflict" {
+            strategy = tokens
+                .next()
+                .ok_or("--on-conflict requires a value")?
+                .parse()?;
+        } else if token == "--dry-run" {
+            dry_run = true;
+        } else if file.is_none() {
+            file = Some(token.to_string());
+        } else {
+            return Err(format!("Unexpected argument '{}'", token));
+        }
+    }
+
+    let file = file.ok_or(USAGE)?;
+    csv |= file.to_lowercase().ends_with(".csv");
+    Ok(ImportArgs {
+        file,
+        strategy,
+        csv,
+        dry_run,
+    })
+}
+
+/// Reads a CSV file with a header row. `name` and `value` are required
+/// columns; `type`, `description` and `tags` (`key=value, key=value`) are
+/// optional, and empty cells mean "not set".
+fn read_csv_entries(content: &str) -> Result<Vec<ImportEntry>, Box<dyn std::error::Error>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+    let name_column = column("name").ok_or("The CSV file has no 'name' column")?;
+    let value_column = column("value").ok_or("The CSV file has no 'value' column")?;
+    let (type_column, description_column, tags_column) =
+        (column("type"), column("description"), column("tags"));
+
+    let mut entries = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        // Line 1 is the header.
+        let line = index + 2;
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .map(str::trim)
+                .filter(|cell| !cell.is_empty())
+                .map(str::to_string)
+        };
+        let name = cell(Some(name_column)).ok_or(format!("Line {}: the name is empty", line))?;
+        let tags = parse_tags(&cell(tags_column).unwrap_or_default())
+            .map_err(|e| format!("Line {}: {}", line, e))?;
+        entries.push(ImportEntry {
+            name,
+            // Values keep their surrounding whitespace.
+            value: record.get(value_column).unwrap_or_default().to_string(),
+            param_type: cell(type_column),
+            last_modified: None,
+            description: cell(description_column),
+            tags,
+        });
+    }
+    Ok(entries)
+}
+
+fn read_entries(file: &str, csv: bool) -> Result<Vec<ImportEntry>, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(file)?;
+    let file_mtime = std::fs::metadata(file)?
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs_f64());
+
+    let mut entries = if csv {
+        read_csv_entries(&content)?
+    } else {
+        match serde_json::from_str::<ImportFile>(&content)? {
+            ImportFile::Entries(entries) => entries,
+            ImportFile::Flat(map) => map
+                .into_iter()
+                .map(|(name, value)| ImportEntry {
+                    name,
+                    value,
+                    param_type: None,
+                    last_modified: None,
+                    description: None,
+                    tags: Vec::new(),
+                })
+                .collect(),
+        }
+    };
+
+    for entry in &mut entries {
+        entry.last_modified = entry.last_modified.or(file_mtime);
+    }
+    Ok(entries)
+}
+
+/// Handles `import [csv] <file> [--on-conflict ...] [--dry-run]`.
+/// Writes every entry of a JSON or CSV file to Parameter Store, resolving keys
+/// that already exist according to the chosen strategy, and prints a per-key
+/// report. With `--dry-run` the report shows what would change, with the old
+/// and new values of plain parameters, and nothing is written. Otherwise
+/// finished entries are saved to a checkpoint, so an import stopped by Ctrl-C
+/// or a crash skips them when run again.
+pub async fn import(
+    helper: &mut ParamStoreHelper,
+    raw: &str,
+) -> Result<Vec<(String, ImportOutcome)>, Box<dyn std::error::Error>> {
+    let args = parse_import_args(raw)?;
+    if args.strategy == ConflictStrategy::Prompt && !args.dry_run && !std::io::stdin().is_terminal()
+    {
+        return Err(
+            "--on-conflict prompt needs a terminal; use skip, overwrite or newer instead".into(),
+        );
+    }
+    let entries = read_entries(&args.file, args.csv)?;
+    println!(
+        "{} {} parameters from {} (on conflict: {:?})",
+        if args.dry_run {
+            "Dry run of importing"
+        } else {
+            "Importing"
+        },
+        entries.len(),
+        args.file,
+        args.strategy
+    );
+
+    if args.dry_run {
+        let mut report = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let outcome = import_entry(helper, &entry, args.strategy, true)
+                .await
+                .unwrap_or_else(|e| ImportOutcome::Failed(e.to_string()));
+            report.push((entry.name, outcome));
+        }
+        print_report(&report, true);
+        return Ok(report);
+    }
+
+    let checkpoint_file = helper.completer.store.checkpoint("import");
+    let mut checkpoint = Checkpoint::resume(
+        &checkpoint_file,
+        &format!(
+            "import {}{} --on-conflict {:?}",
+            if args.csv { "csv " } else { "" },
+            args.file,
+            args.strategy
+        ),
+    )?;
+    if !checkpoint.done.is_empty() {
+        println!(
+            "Resuming: {} of {} entries were imported before the interruption",
+            checkpoint.done.len(),
+            entries.len()
+        );
+    }
+    // Prompts would be drawn over by the bar.
+    let mut bar = (args.strategy != ConflictStrategy::Prompt)
+        .then(|| ProgressBar::new("Importing", entries.len(), &helper.completer));
+    let cancel = Cancellation::begin();
+
+    let total = entries.len();
+    let mut report = Vec::with_capacity(total);
+    for entry in entries {
+        if checkpoint.done.contains(&entry.name) {
+            report.push((entry.name, ImportOutcome::Skipped("imported before resume")));
+            if let Some(bar) = &mut bar {
+                bar.inc();
+            }
+            continue;
+        }
+        if cancel.requested() {
+            checkpoint.save(&checkpoint_file)?;
+            drop(bar);
+            print_report(&report, false);
+            return Err(format!(
+                "Import stopped after {} of {} entries; run the same import again to resume",
+                report.len(),
+                total
+            )
+            .into());
+        }
+        let outcome = import_entry(helper, &entry, args.strategy, false)
+            .await
+            .unwrap_or_else(|e| ImportOutcome::Failed(e.to_string()));
+        // Failed entries are retried on resume.
+        if !matches!(outcome, ImportOutcome::Failed(_)) {
+            checkpoint.done.insert(entry.name.clone());
+            checkpoint.save(&checkpoint_file)?;
+        }
+        if let Some(bar) = &mut bar {
+            bar.inc();
+        }
+        report.push((entry.name, outcome));
+    }
+    drop(bar);
+    Checkpoint::finish(&checkpoint_file)?;
+
+    print_report(&report, false);
+    Ok(report)
+}
+
+async fn import_entry(
+    helper: &mut ParamStoreHelper,
+    entry: &ImportEntry,
+    strategy: ConflictStrategy,
+    dry_run: bool,
+) -> Result<ImportOutcome, Box<dyn std::error::Error>> {
+    let existing = helper.completer.fetch_parameter(&entry.name).await?;
+    let type_changes = |param: &rusoto_ssm::Parameter| {
+        entry
+            .param_type
+            .as_ref()
+            .is_some_and(|new| param.type_.as_ref() != Some(new))
+    };
+
+    let outcome = match &existing {
+        None => ImportOutcome::Created,
+        Some(param)
+            if param.value.as_deref() == Some(entry.value.as_str()) && !type_changes(param) =>
+        {
+            return Ok(ImportOutcome::Unchanged);
+        }
+        Some(param) => match strategy {
+            ConflictStrategy::Skip => return Ok(ImportOutcome::Skipped("exists")),
+            ConflictStrategy::Overwrite => ImportOutcome::Overwritten,
+            ConflictStrategy::Newer => {
+                let remote = param.last_modified_date.unwrap_or(0.0);
+                match entry.last_modified {
+                    Some(local) if local > remote => ImportOutcome::Overwritten,
+                    _ => return Ok(ImportOutcome::Skipped("remote is newer")),
+                }
+            }
+            ConflictStrategy::Prompt if dry_run => ImportOutcome::Overwritten,
+            ConflictStrategy::Prompt => {
+                let answer = prompt_line(&format!(
+                    "{} already exists. Overwrite? [y/N] ",
+                    entry.name.yellow()
+                ))?;
+                if answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") {
+                    ImportOutcome::Overwritten
+                } else {
+                    return Ok(ImportOutcome::Skipped("declined"));
+                }
+            }
+        },
+    };
+
+    // Keep the remote type on overwrite unless the file specifies one.
+    let param_type = entry
+        .param_type
+        .clone()
+        .or_else(|| existing.as_ref().and_then(|p| p.type_.clone()))
+        .unwrap_or_else(|| "String".to_string());
+    if dry_run {
+        print_change(
+            entry,
+            existing.as_ref(),
+            &param_type,
+            helper.completer.redact,
+        );
+        return Ok(outcome);
+    }
+
+    if existing.is_none() {
+        let new = NewParameter {
+            path: entry.name.clone(),
+            value: entry.value.clone(),
+            param_type,
+            key_id: None,
+            description: entry.description.clone(),
+            tags: entry.tags.clone(),
+            allowed_pattern: None,
+            data_type: None,
+        };
+        helper.completer.create_parameter(&new).await?;
+    } else {
+        helper
+            .completer
+            .set_parameter(&entry.name, entry.value.clone(), Some(param_type))
+            .await?;
+        helper
+            .completer
+            .cache_value(&entry.name, entry.value.clone())
+            .await;
+    }
+
+    Ok(outcome)
+}
+
+/// Dry-run detail for one entry that would be written. Secret values are
+/// never shown.
+fn print_change(
+    entry: &ImportEntry,
+    existing: Option<&rusoto_ssm::Parameter>,
+    param_type: &str,
+    redact: bool,
+) {
+    let secret =
+        |param_type: &str| redact || (param_type != "String" && param_type != "StringList");
+    let Some(param) = existing else {
+        let value = if secret(param_type) {
+            "********".to_string()
+        } else {
+            entry.value.clone()
+        };
+        println!(
+            "{} {} [{}] = {}",
+            "+".green(),
+            entry.name,
+            param_type,
+            value
+        );
+        if let Some(description) = &entry.description {
+            println!("    description: {}", description);
+        }
+        if !entry.tags.is_empty() {
+            let tags: Vec<String> = entry
+                .tags
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            println!("    tags: {}", tags.join(", "));
+        }
+        return;
+    };
+
+    let old_type = param.type_.as_deref().unwrap_or("String");
+    println!("{} {}", "~".yellow(), entry.name);
+    if old_type != param_type {
+        println!("    type: {} -> {}", old_type, param_type);
+    }
+    let old_value = param.value.as_deref().unwrap_or_default();
+    if old_value != entry.value {
+        if secret(old_type) || secret(param_type) {
+            println!("    value: changed (secret)");
+        } else {
+            println!("    value: {} -> {}", old_value.red(), entry.value.green());
+        }
+    }
+}
+
+fn print_report(report: &[(String, ImportOutcome)], dry_run: bool) {
+    for (name, outcome) in report {
+        let label = match outcome {
+            ImportOutcome::Created => "created".green().to_string(),
+            ImportOutcome::Overwritten => "overwritten".yellow().to_string(),
+            ImportOutcome::Unchanged => "unchanged".dimmed().to_string(),
+            ImportOutcome::Skipped(reason) => format!("skipped ({})", reason).cyan().to_string(),
+            ImportOutcome::Failed(err) => format!("failed: {}", err).red().to_string(),
+        };
+        println!("  {:<60} {}", name, label);
+    }
+
+    let count = |f: fn(&ImportOutcome) -> bool| report.iter().filter(|(_, o)| f(o)).count();
+    println!(
+        "{}: {} created, {} overwritten, {} unchanged, {} skipped, {} failed",
+        if dry_run {
+            "Dry run, nothing written; would be"
+        } else {
+            "Import finished"
+        },
+        count(|o| matches!(o, ImportOutcome::Created)),
+        count(|o| matches!(o, ImportOutcome::Overwritten)),
+        count(|o| matches!(o, ImportOutcome::Unchanged)),
+        count(|o| matches!(o, ImportOutcome::Skipped(_))),
+        count(|o| matches!(o, ImportOutcome::Failed(_))),
+    );
+}
@@ -1,37 +1,451 @@
+use crate::commands::size::check_value_size;
+use crate::completer::NewParameter;
+use crate::generate::Generator;
 use crate::helper::ParamStoreHelper;
+use crate::names::check_name;
+use crate::policy::{check_allowed_pattern, check_data_type};
+use crate::utils::{prompt_hidden, split_words, take_flag};
+use crate::vocabulary::{DATA_TYPES, PARAMETER_TYPES};
+use colored::Colorize;
+use rustyline::Editor;
+use rustyline::error::ReadlineError;
+use std::io::IsTerminal;
 
-/// Handles the `insert <path>:<value>:<type>` command.
-/// `raw` is the already-parsed argument (everything after "insert "), format: `/path:value:Type`.
+const USAGE: &str = "Usage: insert <path>:<value>[:<type>] or insert <path> <value> [<type>]";
+
+/// An `insert` line taken apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertArgs {
+    pub path: String,
+    pub value: String,
+    pub param_type: Option<String>,
+}
+
+impl InsertArgs {
+    /// Parses either grammar:
+    ///
+    /// - `/path:value[:Type]`, where `\:` is a literal colon; the value may
+    ///   contain spaces, and unescaped colons inside it only work when a type
+    ///   follows
+    /// - `/path <value> [Type]`, split like a shell line, so values with
+    ///   colons or spaces can be quoted:
+    ///   `insert /p "postgres://u:p@h:5432/db" SecureString`
+    ///
+    /// The colon form is used when the first word contains a colon.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+        let first = raw.split_whitespace().next().ok_or(USAGE)?;
+        let (path, value, param_type) = if split_colons(first).len() > 1 {
+            let mut fields = split_colons(raw);
+            let path = fields.remove(0);
+            let param_type = if fields.len() > 1 { fields.pop() } else { None };
+            (path, fields.join(":"), param_type)
+        } else {
+            let mut words = split_words(raw)?.into_iter();
+            match (words.next(), words.next(), words.next(), words.next()) {
+                (Some(path), Some(value), param_type, None) => (path, value, param_type),
+                (_, None, _, _) => return Err(format!("Missing value. {}", USAGE)),
+                _ => {
+                    return Err(format!(
+                        "Too many words; quote values that contain spaces. {}",
+                        USAGE
+                    ));
+                }
+            }
+        };
+        let param_type = param_type.filter(|t| !t.is_empty());
+        if let Some(param_type) = &param_type
+            && !PARAMETER_TYPES.contains(&param_type.as_str())
+        {
+            return Err(format!(
+                "Unknown parameter type '{}' (expected {}). If the value contains ':', quote it or escape ':' as '\\:'",
+                param_type,
+                PARAMETER_TYPES.join(", ")
+            ));
+        }
+        Ok(Self {
+            path,
+            value,
+            param_type,
+        })
+    }
+}
+
+/// Splits on colons not preceded by a backslash, unescaping `\:`.
+fn split_colons(text: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&':') => {
+                chars.next();
+                fields.last_mut().unwrap().push(':');
+            }
+            ':' => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+/// The path an `insert` line creates, with its flags and any generator left
+/// out; `None` when the line does not parse.
+pub fn inserted_path(raw: &str) -> Option<String> {
+    let (raw, _) = take_allowed_pattern(raw);
+    let (raw, _) = take_data_type(raw).ok()?;
+    let (raw, _) = take_flag(&raw, "--yes");
+    let (raw, _) = take_flag(raw, "--no-validate");
+    if let Some(pos) = raw.find(" --generate") {
+        let target = raw[..pos].trim();
+        return Some(target.split(':').next().unwrap_or(target).to_string());
+    }
+    InsertArgs::parse(raw).ok().map(|args| args.path)
+}
+
+/// Handles the `insert` command with arguments (see `InsertArgs::parse`), or
+/// `/path[:Type] --generate...` to create the parameter with a generated value
+/// (`SecureString` unless a type is given). Without a type the configured
+/// default type is used; `type_policy` rules pick or enforce the type under
+/// their paths. Typed values for password/secret paths must meet the secret
+/// policy unless `--no-validate` is given, and protected paths need their name
+/// typed back or `--yes`. A trailing `--allowed-pattern <regex>` or a
+/// `--data-type <type>` creates the parameter with that `AllowedPattern` or
+/// data type; it must not exist yet.
 /// Creates a new parameter in AWS SSM and adds it to the local cache.
+/// In offline mode the insert is queued instead.
 pub async fn insert_value(
     helper: &mut ParamStoreHelper,
     raw: &str,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    println!("Inserting parameter: {}", raw);
-    let path_and_value = raw.to_string();
+    let (raw, allowed_pattern) = take_allowed_pattern(raw);
+    let (raw, data_type) = take_data_type(raw)?;
+    let (raw, yes) = take_flag(&raw, "--yes");
+    let (raw, no_validate) = take_flag(raw, "--no-validate");
 
-    // Format: /path/to/parameter:value:Type
-    let index = path_and_value.find(':').ok_or("Invalid format")?;
-    let last_index = path_and_value.rfind(':').ok_or("Invalid format")?;
-
-    let param_type = if last_index != index {
-        Some(path_and_value[last_index + 1..].to_string())
+    let (path, value, param_type) = if let Some(pos) = raw.find(" --generate") {
+        let generator = Generator::parse(&raw[pos + 1..])?.ok_or("Invalid generator")?;
+        let target = raw[..pos].trim();
+        let (path, param_type) = match target.split_once(':') {
+            Some((path, param_type)) => (path, Some(param_type)),
+            None => (target, None),
+        };
+        let param_type = helper
+            .completer
+            .type_policy
+            .resolve(path, param_type, "SecureString")?;
+        (path.to_string(), generator.generate(), param_type)
     } else {
-        None
+        let args = InsertArgs::parse(raw)?;
+        let param_type = helper.completer.type_policy.resolve(
+            &args.path,
+            args.param_type.as_deref(),
+            helper.completer.type_policy.default_type(),
+        )?;
+
+        // `insert /path::SecureString` asks for the value without echo.
+        let value = if args.value.is_empty() && param_type == "SecureString" {
+            prompt_hidden(&format!("Value for {} (hidden): ", args.path))?
+        } else {
+            args.value
+        };
+        if !no_validate {
+            helper.completer.secret_policy.check(&args.path, &value)?;
+        }
+        (args.path, value, param_type)
     };
 
-    let path = &path_and_value[..index];
-    let value = &path_and_value[index + 1..last_index];
+    check_name(&path)?;
+    check_value_size(&path, &value, helper.completer.chunking)?;
+    helper.completer.protected.confirm(&path, "insert", yes)?;
+    helper
+        .completer
+        .progress(&format!("Inserting parameter: {}", path));
+
+    if allowed_pattern.is_some() || data_type.is_some() {
+        if helper.completer.offline {
+            return Err(
+                "--allowed-pattern and --data-type need AWS access and cannot be queued offline"
+                    .into(),
+            );
+        }
+        let new = NewParameter {
+            path,
+            value,
+            param_type,
+            key_id: None,
+            description: None,
+            tags: Vec::new(),
+            allowed_pattern,
+            data_type,
+        };
+        helper.completer.create_parameter(&new).await?;
+        println!("Created {}", new.path.green());
+        return Ok(new.value);
+    }
+
+    if helper.completer.offline {
+        helper
+            .completer
+            .queue_change(&path, &value, Some(param_type))?;
+        println!(
+            "Offline: queued insert of {} (run 'push' when back online)",
+            path
+        );
+        return Ok(value);
+    }
 
     helper
         .completer
-        .set_parameter(path, value.to_string(), param_type)
+        .set_parameter(&path, value.clone(), Some(param_type))
         .await?;
+    helper.completer.update_all(&path, value.clone()).await?;
+
+    let shown = helper.completer.shown(&value);
     helper
         .completer
-        .update_all(path, value.to_string())
-        .await?;
+        .progress(&format!("Inserted value: {}", shown));
+    Ok(value)
+}
+
+/// Handles `insert` with no arguments: asks for each field in turn, shows a
+/// summary and creates the parameter once confirmed. Values may contain any
+/// character, and `SecureString` values are read without echo. Returns the new
+/// path, or `None` if the user declined at the summary.
+pub async fn insert_wizard(
+    rl: &mut Editor<ParamStoreHelper>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if !std::io::stdin().is_terminal() {
+        return Err(
+            "insert without arguments needs a terminal; use insert <path>:<value>:<type>".into(),
+        );
+    }
+    if rl.helper().is_some_and(|helper| helper.completer.offline) {
+        return Err(
+            "The insert wizard needs AWS access; use insert <path>:<value>:<type> to queue offline"
+                .into(),
+        );
+    }
+
+    let path = ask(rl, "Path (Tab completes): ")?;
+    if !path.starts_with('/') {
+        return Err(format!("Path must start with '/': {}", path).into());
+    }
+    check_name(&path)?;
+    if rl
+        .helper()
+        .is_some_and(|helper| helper.completer.values.contains_key(&path))
+    {
+        return Err(format!(
+            "{} already exists; select it and use 'set' to change it",
+            path
+        )
+        .into());
+    }
+    if let Some(helper) = rl.helper() {
+        helper.completer.protected.confirm(&path, "insert", false)?;
+    }
+
+    let default_type = match rl.helper() {
+        Some(helper) => {
+            let policy = &helper.completer.type_policy;
+            policy.resolve(&path, None, policy.default_type())?
+        }
+        None => PARAMETER_TYPES[0].to_string(),
+    };
+    let param_type = ask_type(rl, &default_type)?;
+    if let Some(helper) = rl.helper() {
+        helper
+            .completer
+            .type_policy
+            .resolve(&path, Some(&param_type), &param_type)?;
+    }
+    let secure = param_type == "SecureString";
+    let value = if secure {
+        prompt_hidden("Value (hidden): ")?
+    } else {
+        ask(rl, "Value: ")?
+    };
+    if let Some(helper) = rl.helper() {
+        helper.completer.secret_policy.check(&path, &value)?;
+    }
+    let key_id = if secure {
+        optional(ask(rl, "KMS key id or alias (blank for alias/aws/ssm): ")?)
+    } else {
+        None
+    };
+    let description = optional(ask(rl, "Description (optional): ")?);
+    let tags = parse_tags(&ask(rl, "Tags as key=value, comma separated (optional): ")?)?;
+    let allowed_pattern = optional(ask(
+        rl,
+        "Allowed pattern, a regular expression later values must match (optional): ",
+    )?);
+    if let Some(pattern) = &allowed_pattern {
+        check_allowed_pattern(&path, &value, pattern)?;
+    }
+    let data_type = if param_type == "String" {
+        optional(ask(
+            rl,
+            "Data type, text or aws:ec2:image for AMI ids (blank for text): ",
+        )?)
+    } else {
+        None
+    };
+    if let Some(data_type) = &data_type {
+        check_data_type(&path, &value, data_type)?;
+    }
+
+    let new = NewParameter {
+        path,
+        value,
+        param_type,
+        key_id,
+        description,
+        tags,
+        allowed_pattern,
+        data_type,
+    };
+    let chunking = rl.helper().is_some_and(|helper| helper.completer.chunking);
+    check_value_size(&new.path, &new.value, chunking)?;
+    let redact = rl.helper().is_some_and(|helper| helper.completer.redact);
+    print_summary(&new, redact);
+    if !ask(rl, "Create this parameter? [y/N]: ")?.eq_ignore_ascii_case("y") {
+        println!("Insert cancelled");
+        return Ok(None);
+    }
+
+    let helper = rl.helper_mut().ok_or("REPL helper is not set")?;
+    helper.completer.create_parameter(&new).await?;
+    println!("Created {}", new.path.green());
+    Ok(Some(new.path))
+}
+
+/// Reads one trimmed line; CTRL-C or CTRL-D cancels the wizard.
+fn ask(
+    rl: &mut Editor<ParamStoreHelper>,
+    prompt: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match rl.readline(prompt) {
+        Ok(line) => Ok(line.trim().to_string()),
+        Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => Err("Insert cancelled".into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Offers the parameter types by number; a blank answer picks `default`.
+fn ask_type(
+    rl: &mut Editor<ParamStoreHelper>,
+    default: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    for (index, name) in PARAMETER_TYPES.iter().enumerate() {
+        println!("  {}) {}", index + 1, name);
+    }
+    let default_number = PARAMETER_TYPES
+        .iter()
+        .position(|name| *name == default)
+        .unwrap_or(0)
+        + 1;
+    let answer = ask(rl, &format!("Type [{}]: ", default_number))?;
+    if answer.is_empty() {
+        return Ok(default.to_string());
+    }
+    answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| PARAMETER_TYPES.get(i))
+        .or_else(|| {
+            PARAMETER_TYPES
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(&answer))
+        })
+        .map(|name| name.to_string())
+        .ok_or_else(|| format!("Unknown parameter type '{}'", answer).into())
+}
+
+/// Takes a trailing `--allowed-pattern <regex>` off an `insert` line. The
+/// pattern is the rest of the line, so backslashes stay as typed; one pair of
+/// surrounding quotes is removed.
+fn take_allowed_pattern(raw: &str) -> (&str, Option<String>) {
+    let Some((rest, pattern)) = raw.split_once("--allowed-pattern ") else {
+        return (raw, None);
+    };
+    let pattern = pattern.trim();
+    let unquoted = ['\'', '"'].iter().find_map(|quote| {
+        pattern
+            .strip_prefix(*quote)
+            .and_then(|pattern| pattern.strip_suffix(*quote))
+    });
+    (
+        rest.trim_end(),
+        Some(unquoted.unwrap_or(pattern).to_string()),
+    )
+}
+
+/// Takes `--data-type <type>` off an `insert` line.
+fn take_data_type(raw: &str) -> Result<(String, Option<String>), String> {
+    let Some((before, after)) = raw.split_once("--data-type") else {
+        return Ok((raw.to_string(), None));
+    };
+    let after = after.trim_start();
+    let (data_type, rest) = after.split_once(char::is_whitespace).unwrap_or((after, ""));
+    if data_type.is_empty() {
+        return Err(format!(
+            "--data-type requires a value ({})",
+            DATA_TYPES.join(", ")
+        ));
+    }
+    let raw = format!("{} {}", before.trim_end(), rest.trim_start());
+    Ok((raw.trim().to_string(), Some(data_type.to_string())))
+}
+
+fn optional(answer: String) -> Option<String> {
+    (!answer.is_empty()).then_some(answer)
+}
+
+/// Parses `env=prod, team=payments`.
+pub fn parse_tags(raw: &str) -> Result<Vec<(String, String)>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| {
+            tag.split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .filter(|(key, _)| !key.is_empty())
+                .ok_or_else(|| format!("Invalid tag '{}': expected key=value", tag))
+        })
+        .collect()
+}
+
+fn print_summary(new: &NewParameter, redact: bool) {
+    let value = if redact || new.param_type == "SecureString" {
+        "********".to_string()
+    } else {
+        new.value.clone()
+    };
+    let tags = new
+        .tags
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(", ");
 
-    println!("Inserted value: {}", value);
-    Ok(value.to_string())
+    println!("{}", "New parameter".bold());
+    println!("  {:<12} {}", "Path:".cyan(), new.path.green());
+    println!("  {:<12} {}", "Type:".cyan(), new.param_type);
+    println!("  {:<12} {}", "Value:".cyan(), value);
+    if let Some(key_id) = &new.key_id {
+        println!("  {:<12} {}", "KMS key:".cyan(), key_id);
+    }
+    if let Some(description) = &new.description {
+        println!("  {:<12} {}", "Description:".cyan(), description);
+    }
+    if let Some(pattern) = &new.allowed_pattern {
+        println!("  {:<12} {}", "Pattern:".cyan(), pattern);
+    }
+    if let Some(data_type) = &new.data_type {
+        println!("  {:<12} {}", "Data type:".cyan(), data_type);
+    }
+    if !tags.is_empty() {
+        println!("  {:<12} {}", "Tags:".cyan(), tags);
+    }
 }
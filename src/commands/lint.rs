@@ -0,0 +1,74 @@
+use crate::helper::ParamStoreHelper;
+use crate::lint::Severity;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `lint [prefix]`: checks every parameter under `prefix` (the base
+/// path by default) against the `[lint]` rules and prints a report. Fails when
+/// any error-level finding is reported, so scripts can gate on it.
+pub async fn lint(
+    helper: &ParamStoreHelper,
+    prefix: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    if completer.offline {
+        return Err("lint reads parameter metadata from AWS and is not available offline".into());
+    }
+    let prefix = if prefix.is_empty() {
+        completer.root()
+    } else {
+        prefix
+    };
+
+    completer.progress(&format!("Linting parameters under {}", prefix));
+    let parameters = completer.describe_parameters(prefix).await?;
+    let findings = completer.lint.check(&parameters);
+
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Warning)
+        .count();
+
+    if completer.output.is_json() {
+        for finding in &findings {
+            output::print_json(json!({
+                "path": finding.path,
+                "rule": finding.rule,
+                "severity": finding.severity.as_str(),
+                "message": finding.message,
+            }));
+        }
+    } else {
+        for finding in &findings {
+            let severity = match finding.severity {
+                Severity::Error => "error".red().bold(),
+                Severity::Warning => "warning".yellow(),
+                Severity::Info => "info".dimmed(),
+            };
+            println!(
+                "{:<8} {:<12} {} {}",
+                severity,
+                finding.rule,
+                finding.path.green(),
+                finding.message
+            );
+        }
+        println!(
+            "{} parameter(s) checked: {} error(s), {} warning(s), {} info",
+            parameters.len(),
+            errors,
+            warnings,
+            findings.len() - errors - warnings
+        );
+    }
+
+    if errors > 0 {
+        return Err(format!("lint found {} error(s)", errors).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::backend::BackendKind;
+use crate::chunking;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_date;
+use crate::vocabulary::PARAMETER_TYPES;
+use colored::Colorize;
+use serde_json::json;
+
+const USAGE: &str =
+    "Usage: list [prefix] [--remote] [--type <type>] [--key-id <key>] [--tier <tier>]";
+
+/// What `list` was asked for.
+#[derive(Debug, Default, PartialEq)]
+pub struct ListArgs {
+    pub prefix: String,
+    pub remote: bool,
+    /// Server-side filters as `(key, value)`, e.g. `("Tier", "Advanced")`.
+    pub filters: Vec<(&'static str, String)>,
+}
+
+impl ListArgs {
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut parsed = ListArgs::default();
+        let mut words = args.split_whitespace();
+        while let Some(word) = words.next() {
+            let key = match word {
+                "--remote" => {
+                    parsed.remote = true;
+                    continue;
+                }
+                "--type" => "Type",
+                "--key-id" => "KeyId",
+                "--tier" => "Tier",
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option {}. {}", flag, USAGE));
+                }
+                prefix if parsed.prefix.is_empty() => {
+                    parsed.prefix = prefix.to_string();
+                    continue;
+                }
+                _ => return Err(USAGE.to_string()),
+            };
+            let value = words
+                .next()
+                .ok_or_else(|| format!("{} needs a value. {}", word, USAGE))?;
+            match key {
+                "Type" if !PARAMETER_TYPES.contains(&value) => {
+                    return Err(format!(
+                        "Unknown parameter type '{}'; expected one of {}",
+                        value,
+                        PARAMETER_TYPES.join(", ")
+                    ));
+                }
+                "Tier" if !["Standard", "Advanced", "Intelligent-Tiering"].contains(&value) => {
+                    return Err(format!(
+                        "Unknown tier '{}'; expected Standard, Advanced or Intelligent-Tiering",
+                        value
+                    ));
+                }
+                _ => {}
+            }
+            parsed.filters.push((key, value.to_string()));
+        }
+        if !parsed.filters.is_empty() && !parsed.remote {
+            return Err(format!(
+                "--type, --key-id and --tier need --remote. {}",
+                USAGE
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Handles `list [prefix] [--remote] [--type T] [--key-id K] [--tier T]`:
+/// lists the parameters under `prefix` (the base path by default) with their
+/// type. Without `--remote` the cached tree is listed. With it, names and
+/// metadata come from DescribeParameters, filtered on the server, so no
+/// value is downloaded or decrypted.
+pub async fn list(helper: &ParamStoreHelper, args: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let args = ListArgs::parse(args)?;
+    let prefix = if args.prefix.is_empty() {
+        completer.root().to_string()
+    } else {
+        completer.aliases.resolve(&args.prefix)?
+    };
+
+    if !args.remote {
+        let mut paths: Vec<&String> = completer
+            .values
+            .keys()
+            .filter(|path| path.starts_with(&prefix) && completer.in_scope(path))
+            .collect();
+        paths.sort();
+        for path in &paths {
+            let param_type = completer.types.get(*path).map_or("-", String::as_str);
+            if completer.output.is_json() {
+                output::print_json(json!({ "path": path, "type": param_type }));
+            } else {
+                println!("{:<12}  {}", param_type, path.green());
+            }
+        }
+        if !completer.output.is_json() {
+            println!("{} parameter(s) under {}", paths.len(), prefix);
+        }
+        return Ok(());
+    }
+
+    if completer.offline {
+        return Err("list --remote is not available in offline mode".into());
+    }
+    if completer.backend_kind == BackendKind::SecretsManager && !args.filters.is_empty() {
+        return Err("--type, --key-id and --tier only apply to Parameter Store".into());
+    }
+    let filters: Vec<(&str, &str)> = args
+        .filters
+        .iter()
+        .map(|(key, value)| (*key, value.as_str()))
+        .collect();
+    let parameters = completer.describe_filtered(&prefix, &filters).await?;
+    let mut listed = 0;
+    for param in &parameters {
+        let path = param.name.as_deref().unwrap_or_default();
+        if chunking::is_chunk_path(path) {
+            continue;
+        }
+        listed += 1;
+        let param_type = param.type_.as_deref().unwrap_or("-");
+        let tier = param.tier.as_deref().unwrap_or("-");
+        let modified = param.last_modified_date.map(format_date);
+        if completer.output.is_json() {
+            output::print_json(json!({
+                "path": path,
+                "type": param_type,
+                "tier": tier,
+                "key_id": param.key_id,
+                "version": param.version,
+                "last_modified": modified,
+            }));
+            continue;
+        }
+        println!(
+            "{:<12}  {:<8}  {:<10}  {}",
+            param_type,
+            tier,
+            modified.as_deref().unwrap_or("-"),
+            path.green()
+        );
+    }
+    if !completer.output.is_json() {
+        println!("{} parameter(s) under {}", listed, prefix);
+    }
+    Ok(())
+}
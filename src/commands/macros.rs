@@ -0,0 +1,103 @@
+use crate::helper::ParamStoreHelper;
+use crate::macros::check_name;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+const USAGE: &str = "Usage: macro record <name> | macro stop | macro cancel | macro play <name> | macro show <name> | macro rm <name> | macro list";
+
+/// A macro being recorded: the command lines that succeeded since
+/// `macro record`.
+#[derive(Debug)]
+pub struct Recording {
+    pub name: String,
+    pub lines: Vec<String>,
+}
+
+/// Handles the `macro` subcommands. `record` starts collecting the commands
+/// that follow and `stop` saves them to the `[macros]` table of the config
+/// file. `play` returns the commands of a saved macro for the caller to run.
+pub fn manage_macro(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    recording: &mut Option<Recording>,
+) -> Result<Option<Vec<String>>, Box<dyn std::error::Error>> {
+    let macros = &mut helper.completer.macros;
+    let mut parts = args.split_whitespace();
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, ..) | (Some("list"), None, _) => {
+            if helper.completer.output.is_json() {
+                for (name, lines) in &macros.entries {
+                    output::print_json(json!({ "macro": name, "commands": lines }));
+                }
+            } else if macros.entries.is_empty() {
+                println!("No macros. Use 'macro record <name>' to record one.");
+            } else {
+                for (name, lines) in &macros.entries {
+                    println!("  {:<20} {} command(s)", name.cyan(), lines.len());
+                }
+            }
+        }
+        (Some("show"), Some(name), None) => {
+            let lines = macros
+                .entries
+                .get(name)
+                .ok_or_else(|| format!("No macro named '{}'", name))?;
+            for (index, line) in lines.iter().enumerate() {
+                println!("  {:>3}  {}", index + 1, line);
+            }
+        }
+        (Some("record"), Some(name), None) => {
+            if let Some(current) = recording {
+                return Err(format!(
+                    "Already recording '{}'; type 'macro stop' or 'macro cancel' first",
+                    current.name
+                )
+                .into());
+            }
+            check_name(name)?;
+            if macros.entries.contains_key(name) {
+                println!(
+                    "Macro '{}' exists and will be replaced on 'macro stop'",
+                    name
+                );
+            }
+            *recording = Some(Recording {
+                name: name.to_string(),
+                lines: Vec::new(),
+            });
+            println!(
+                "Recording '{}'; commands that succeed are added. Type '{}' to save it.",
+                name.cyan(),
+                "macro stop".yellow()
+            );
+        }
+        (Some("stop"), None, _) => {
+            let current = recording.take().ok_or("Not recording a macro")?;
+            let count = current.lines.len();
+            macros.add(&current.name, current.lines)?;
+            println!("Saved macro {} ({} command(s))", current.name.cyan(), count);
+        }
+        (Some("cancel"), None, _) => {
+            let current = recording.take().ok_or("Not recording a macro")?;
+            println!("Discarded the recording of '{}'", current.name);
+        }
+        (Some("play"), Some(name), None) => {
+            let lines = macros
+                .entries
+                .get(name)
+                .ok_or_else(|| format!("No macro named '{}'", name))?;
+            return Ok(Some(lines.clone()));
+        }
+        (Some("rm"), Some(name), None) => {
+            if macros.remove(name)? {
+                println!("Removed macro {}", name.cyan());
+            } else {
+                return Err(format!("No macro named '{}'", name).into());
+            }
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(None)
+}
@@ -1,9 +1,20 @@
 use crate::helper::ParamStoreHelper;
+use crate::utils::take_flag;
 
-/// Handles the `migration` command.
+/// Handles the `migration [--assume-plaintext]` command.
 /// Re-encrypts all locally cached parameter values with the current encryption key.
-pub async fn migration(helper: &mut ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
-    helper.completer.migrate_encryption().await?;
+pub async fn migration(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (rest, assume_plaintext) = take_flag(args, "--assume-plaintext");
+    if !rest.trim().is_empty() {
+        return Err("Usage: migration [--assume-plaintext]".into());
+    }
+    helper
+        .completer
+        .migrate_encryption(assume_plaintext)
+        .await?;
     println!("Migration completed");
     Ok(())
 }
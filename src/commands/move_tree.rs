@@ -0,0 +1,240 @@
+use crate::chunking;
+use crate::completer::NewParameter;
+use crate::helper::ParamStoreHelper;
+use crate::names::check_name;
+use crate::progress::{Checkpoint, ProgressBar};
+use crate::shutdown::Cancellation;
+use crate::utils::prompt_line;
+use colored::Colorize;
+use std::io::IsTerminal;
+
+const USAGE: &str = "Usage: mv-tree <old-prefix> <new-prefix> [--dry-run] [--yes]";
+
+/// Checkpoint record of a parameter copied to the new prefix, by relative path.
+fn copied(relative: &str) -> String {
+    format!("copy:{}", relative)
+}
+
+/// Checkpoint record of an original deleted from the old prefix.
+fn deleted(relative: &str) -> String {
+    format!("delete:{}", relative)
+}
+
+/// Handles `mv-tree <old-prefix> <new-prefix> [--dry-run] [--yes]`: re-creates
+/// every parameter under the old prefix at the same relative path under the
+/// new one, keeping type, description, tags and KMS key, then deletes the
+/// originals. Nothing is deleted until every copy exists. Progress is saved
+/// in the store directory after each step, so after a failure or Ctrl-C the
+/// same command picks up where it stopped.
+pub async fn move_tree(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    let (mut dry_run, mut yes) = (false, false);
+    let mut positional = Vec::new();
+    for word in args.split_whitespace() {
+        match word {
+            "--dry-run" => dry_run = true,
+            "--yes" => yes = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ => positional.push(completer.aliases.resolve(word)?),
+        }
+    }
+    let [from, to] = &positional[..] else {
+        return Err(USAGE.into());
+    };
+    let from = format!("{}/", from.trim_end_matches('/'));
+    let to = format!("{}/", to.trim_end_matches('/'));
+    if !from.starts_with('/') || !to.starts_with('/') || from == "/" {
+        return Err(USAGE.into());
+    }
+    if to.starts_with(&from) || from.starts_with(&to) {
+        return Err(format!("{} and {} overlap", from, to).into());
+    }
+    if completer.offline {
+        return Err("mv-tree is not available in offline mode".into());
+    }
+
+    let checkpoint_file = completer.store.checkpoint("mv_tree");
+    // The checkpoint is saved before the first copy, so a copy may exist
+    // even when it records nothing yet.
+    let resumed = checkpoint_file.exists();
+    let mut checkpoint = Checkpoint::resume(&checkpoint_file, &format!("mv-tree {} {}", from, to))?;
+
+    let listed = completer.describe_parameters(&from).await?;
+    let chunks: Vec<String> = listed
+        .iter()
+        .filter_map(|metadata| metadata.name.clone())
+        .filter(|name| chunking::is_chunk_path(name))
+        .collect();
+    let sources: Vec<_> = listed
+        .into_iter()
+        .filter(|metadata| {
+            metadata
+                .name
+                .as_deref()
+                .is_some_and(|name| !chunking::is_chunk_path(name))
+        })
+        .collect();
+    let copied_before = checkpoint
+        .done
+        .iter()
+        .filter(|record| record.starts_with("copy:"))
+        .count();
+    if sources.is_empty() && copied_before == 0 {
+        return Err(format!("No parameters under {}", from).into());
+    }
+
+    let mut protected = Vec::new();
+    for metadata in &sources {
+        let name = metadata.name.as_deref().unwrap_or_default();
+        let relative = &name[from.len()..];
+        let target = format!("{}{}", to, relative);
+        check_name(&target)?;
+        if !resumed && completer.is_parameter(&target) {
+            return Err(format!("{} already exists; nothing was moved", target).into());
+        }
+        for path in [name, target.as_str()] {
+            if let Some(pattern) = completer.protected.matching(path) {
+                protected.push(format!("{} ('{}')", path, pattern));
+            }
+        }
+        let marker = if checkpoint.done.contains(&copied(relative)) {
+            "✓".green()
+        } else {
+            "→".yellow()
+        };
+        println!("  {} {} {}", marker, name, target);
+    }
+    println!(
+        "{} parameter(s) to move from {} to {}{}",
+        sources.len(),
+        from,
+        to,
+        if copied_before == 0 {
+            String::new()
+        } else {
+            format!(" ({} already copied)", copied_before)
+        }
+    );
+    if dry_run {
+        return Ok(());
+    }
+    if !protected.is_empty() && !yes {
+        return Err(format!(
+            "Protected paths are involved: {}; pass --yes to move them",
+            protected.join(", ")
+        )
+        .into());
+    }
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass --yes to run mv-tree without a prompt".into());
+        }
+        if !prompt_line("Move these parameters? [y/N]: ")?.eq_ignore_ascii_case("y") {
+            println!("mv-tree cancelled");
+            return Ok(());
+        }
+    }
+    checkpoint.save(&checkpoint_file)?;
+
+    let resume_hint = |err: Box<dyn std::error::Error>| -> Box<dyn std::error::Error> {
+        format!(
+            "{}; run the same mv-tree again to resume where it stopped",
+            err
+        )
+        .into()
+    };
+    let originals: Vec<String> = sources
+        .iter()
+        .filter_map(|metadata| metadata.name.clone())
+        .chain(chunks)
+        .collect();
+    let mut bar = ProgressBar::new("Moving", sources.len() + originals.len(), completer);
+    bar.resume_at(checkpoint.done.len());
+    let cancel = Cancellation::begin();
+    let stopped = || -> Box<dyn std::error::Error> { resume_hint("mv-tree stopped".into()) };
+    for metadata in &sources {
+        let name = metadata.name.clone().unwrap_or_default();
+        let relative = name[from.len()..].to_string();
+        if checkpoint.done.contains(&copied(&relative)) {
+            continue;
+        }
+        if cancel.requested() {
+            return Err(stopped());
+        }
+        let target = format!("{}{}", to, relative);
+        let value = completer
+            .get_set_value(&name)
+            .await
+            .map_err(|e| resume_hint(e.into()))?;
+        // A copy written just before an interruption is kept if it matches.
+        if resumed && let Some(existing) = completer.fetch_parameter(&target).await? {
+            if existing.value.as_deref() != Some(value.as_str()) {
+                return Err(format!("{} already exists with another value", target).into());
+            }
+            checkpoint.done.insert(copied(&relative));
+            checkpoint.save(&checkpoint_file)?;
+            bar.inc();
+            continue;
+        }
+        let param_type = metadata
+            .type_
+            .clone()
+            .unwrap_or_else(|| "String".to_string());
+        let new = NewParameter {
+            path: target,
+            value,
+            key_id: metadata
+                .key_id
+                .clone()
+                .filter(|_| param_type == "SecureString"),
+            description: metadata.description.clone(),
+            allowed_pattern: metadata.allowed_pattern.clone(),
+            data_type: metadata.data_type.clone(),
+            tags: completer.parameter_tags(&name).await.map_err(resume_hint)?,
+            param_type,
+        };
+        completer
+            .create_parameter(&new)
+            .await
+            .map_err(resume_hint)?;
+        checkpoint.done.insert(copied(&relative));
+        checkpoint.save(&checkpoint_file)?;
+        bar.note(&format!("Copied {}", new.path));
+        bar.inc();
+    }
+
+    for name in originals {
+        let relative = name[from.len()..].to_string();
+        if checkpoint.done.contains(&deleted(&relative)) {
+            continue;
+        }
+        if cancel.requested() {
+            return Err(stopped());
+        }
+        completer
+            .delete_parameter(&name)
+            .await
+            .map_err(resume_hint)?;
+        checkpoint.done.insert(deleted(&relative));
+        checkpoint.save(&checkpoint_file)?;
+        bar.inc();
+    }
+    drop(bar);
+    Checkpoint::finish(&checkpoint_file)?;
+    println!(
+        "Moved {} parameter(s) from {} to {}",
+        checkpoint
+            .done
+            .iter()
+            .filter(|record| record.starts_with("copy:"))
+            .count(),
+        from,
+        to.green()
+    );
+    Ok(())
+}
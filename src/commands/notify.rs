@@ -0,0 +1,393 @@
+use crate::backend::BackendKind;
+use crate::client::build_events_client;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_timestamp;
+use colored::Colorize;
+use ratatui::crossterm::event::{self, Event, KeyCode};
+use rusoto_events::{EventBridge, PutRuleRequest, PutTargetsRequest, Target};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const USAGE: &str = "Usage: notify setup <prefix> --sns-arn <arn> [--name <rule>] [--dry-run] | notify listen [prefix] [--interval <secs>] [--for <secs>]";
+
+/// Seconds between polls when `--interval` is not given.
+const DEFAULT_INTERVAL: u64 = 30;
+/// Shortest allowed polling interval, to stay clear of throttling.
+const MIN_INTERVAL: u64 = 5;
+
+/// The EventBridge pattern matching Parameter Store change events for
+/// parameters under `prefix`.
+pub fn event_pattern(prefix: &str) -> serde_json::Value {
+    json!({
+        "source": ["aws.ssm"],
+        "detail-type": ["Parameter Store Change"],
+        "detail": { "name": [{ "prefix": prefix }] }
+    })
+}
+
+/// A rule name derived from `prefix`: `daps-` and the path with characters
+/// EventBridge does not allow turned into `-`, at most 64 characters.
+pub fn rule_name(prefix: &str) -> String {
+    let path: String = prefix
+        .trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = if path.is_empty() {
+        "daps-all".to_string()
+    } else {
+        format!("daps-{}", path)
+    };
+    name.chars().take(64).collect()
+}
+
+/// The version and last change of a parameter, as seen by one poll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seen {
+    pub version: i64,
+    pub modified: Option<f64>,
+    pub user: Option<String>,
+}
+
+/// A change found between two polls.
+#[derive(Debug, PartialEq)]
+pub enum ChangeEvent {
+    Created(String, Seen),
+    Updated(String, i64, Seen),
+    Deleted(String),
+}
+
+/// What changed between two polls of the same prefix, ordered by path.
+pub fn diff_polls(
+    before: &BTreeMap<String, Seen>,
+    after: &BTreeMap<String, Seen>,
+) -> Vec<ChangeEvent> {
+    let mut events = Vec::new();
+    for (path, seen) in after {
+        match before.get(path) {
+            None => events.push(ChangeEvent::Created(path.clone(), seen.clone())),
+            Some(old) if old.version != seen.version => events.push(ChangeEvent::Updated(
+                path.clone(),
+                old.version,
+                seen.clone(),
+            )),
+            Some(_) => {}
+        }
+    }
+    events.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .map(|path| ChangeEvent::Deleted(path.clone())),
+    );
+    events.sort_by(|a, b| event_path(a).cmp(event_path(b)));
+    events
+}
+
+fn event_path(event: &ChangeEvent) -> &str {
+    match event {
+        ChangeEvent::Created(path, _)
+        | ChangeEvent::Updated(path, _, _)
+        | ChangeEvent::Deleted(path) => path,
+    }
+}
+
+/// Reads the version and last change of every parameter under `prefix`,
+/// without values.
+pub async fn poll(
+    helper: &ParamStoreHelper,
+    prefix: &str,
+) -> Result<BTreeMap<String, Seen>, Box<dyn std::error::Error>> {
+    let described = helper.completer.describe_parameters(prefix).await?;
+    Ok(described
+        .into_iter()
+        .filter_map(|param| {
+            let seen = Seen {
+                version: param.version.unwrap_or_default(),
+                modified: param.last_modified_date,
+                user: param.last_modified_user,
+            };
+            Some((param.name?, seen))
+        })
+        .collect())
+}
+
+fn check_available(helper: &ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    if helper.completer.offline {
+        return Err("Change notifications need AWS access; they are not available offline".into());
+    }
+    if helper.completer.backend_kind == BackendKind::SecretsManager {
+        return Err("Change notifications are only available for Parameter Store".into());
+    }
+    Ok(())
+}
+
+/// Handles `notify setup` and `notify listen`.
+pub async fn notify(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut words = args.split_whitespace();
+    match words.next() {
+        Some("setup") => setup(helper, words.collect()).await,
+        Some("listen") => listen(helper, words.collect()).await,
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// `notify setup <prefix> --sns-arn <arn> [--name <rule>] [--dry-run]`:
+/// creates or updates an EventBridge rule sending Parameter Store change
+/// events under `prefix` to an SNS topic. With `--dry-run` it only prints the
+/// rule and the equivalent AWS CLI commands.
+async fn setup(
+    helper: &mut ParamStoreHelper,
+    words: Vec<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut prefix, mut sns_arn, mut name, mut dry_run) = (None, None, None, false);
+    let mut words = words.into_iter();
+    while let Some(word) = words.next() {
+        match word {
+            "--sns-arn" => sns_arn = Some(words.next().ok_or(USAGE)?.to_string()),
+            "--name" => name = Some(words.next().ok_or(USAGE)?.to_string()),
+            "--dry-run" => dry_run = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ if prefix.is_none() => prefix = Some(helper.completer.aliases.resolve(word)?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let (Some(prefix), Some(sns_arn)) = (prefix, sns_arn) else {
+        return Err(USAGE.into());
+    };
+    if !sns_arn.starts_with("arn:") || !sns_arn.contains(":sns:") {
+        return Err(format!("Not an SNS topic ARN: {}", sns_arn).into());
+    }
+    let prefix = helper.completer.scoped(&prefix)?.to_string();
+    let name = name.unwrap_or_else(|| rule_name(&prefix));
+    let pattern = event_pattern(&prefix).to_string();
+
+    if dry_run {
+        println!("EventBridge rule {}:", name.cyan());
+        println!("  {}", pattern);
+        println!("Equivalent AWS CLI commands:");
+        println!(
+            "  aws events put-rule --name {} --event-pattern '{}'",
+            name, pattern
+        );
+        println!(
+            "  aws events put-targets --rule {} --targets Id=sns,Arn={}",
+            name, sns_arn
+        );
+    } else {
+        check_available(helper)?;
+        let completer = &helper.completer;
+        let client = build_events_client(completer.region.clone(), &completer.client_options)?;
+        let request = PutRuleRequest {
+            name: name.clone(),
+            event_pattern: Some(pattern),
+            description: Some(format!("Parameter Store changes under {} (daps)", prefix)),
+            state: Some("ENABLED".to_string()),
+            ..Default::default()
+        };
+        let rule = completer
+            .call("PutRule", || client.put_rule(request.clone()))
+            .await?;
+        let request = PutTargetsRequest {
+            rule: name.clone(),
+            targets: vec![Target {
+                id: "sns".to_string(),
+                arn: sns_arn.clone(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let targets = completer
+            .call("PutTargets", || client.put_targets(request.clone()))
+            .await?;
+        if targets.failed_entry_count.unwrap_or(0) > 0 {
+            let reasons: Vec<String> = targets
+                .failed_entries
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|entry| entry.error_message)
+                .collect();
+            return Err(format!(
+                "Rule {} was created but its SNS target was not: {}",
+                name,
+                reasons.join("; ")
+            )
+            .into());
+        }
+        println!(
+            "Rule {} sends changes under {} to {}",
+            rule.rule_arn.unwrap_or(name).cyan(),
+            prefix.green(),
+            sns_arn
+        );
+    }
+    println!(
+        "The topic's access policy must allow {} to call sns:Publish.",
+        "events.amazonaws.com".yellow()
+    );
+    Ok(())
+}
+
+/// `notify listen [prefix] [--interval <secs>] [--for <secs>]`: polls the
+/// versions under `prefix` (the base path by default) and prints each
+/// creation, update and deletion as it is seen, until Enter is pressed or
+/// `--for` seconds have passed. Values are never shown.
+async fn listen(
+    helper: &mut ParamStoreHelper,
+    words: Vec<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut prefix, mut interval, mut limit) = (None, DEFAULT_INTERVAL, None);
+    let mut words = words.into_iter();
+    while let Some(word) = words.next() {
+        match word {
+            "--interval" => {
+                interval = words
+                    .next()
+                    .and_then(|secs| secs.parse().ok())
+                    .ok_or(USAGE)?;
+            }
+            "--for" => {
+                limit = Some(
+                    words
+                        .next()
+                        .and_then(|secs| secs.parse().ok())
+                        .map(Duration::from_secs)
+                        .ok_or(USAGE)?,
+                );
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ if prefix.is_none() => prefix = Some(helper.completer.aliases.resolve(word)?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    if interval < MIN_INTERVAL {
+        return Err(format!("--interval must be at least {} seconds", MIN_INTERVAL).into());
+    }
+    check_available(helper)?;
+    let prefix = prefix.unwrap_or_else(|| helper.completer.base_path.clone());
+    let prefix = helper.completer.scoped(&prefix)?.to_string();
+
+    let interactive = std::io::stdin().is_terminal();
+    let started = Instant::now();
+    let mut known = poll(helper, &prefix).await?;
+    if !helper.completer.output.is_json() {
+        println!(
+            "Watching {} parameter(s) under {} every {}s{}",
+            known.len(),
+            prefix.green(),
+            interval,
+            if interactive {
+                "; press Enter to stop"
+            } else {
+                ""
+            }
+        );
+    }
+
+    loop {
+        let remaining = match limit {
+            Some(limit) => match limit.checked_sub(started.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => remaining,
+                _ => break,
+            },
+            None => Duration::MAX,
+        };
+        let wait = Duration::from_secs(interval).min(remaining);
+        if interactive {
+            if stop_requested(wait)? {
+                break;
+            }
+        } else {
+            tokio::time::sleep(wait).await;
+        }
+        if limit.is_some_and(|limit| started.elapsed() >= limit) {
+            break;
+        }
+
+        let current = poll(helper, &prefix).await?;
+        for event in diff_polls(&known, &current) {
+            print_event(helper, &event);
+        }
+        known = current;
+    }
+    Ok(())
+}
+
+/// Waits up to `wait` for Enter. The terminal stays in line mode, so other
+/// keys only arrive with the Enter that follows them.
+fn stop_requested(wait: Duration) -> Result<bool, Box<dyn std::error::Error>> {
+    let deadline = std::time::Instant::now() + wait;
+    loop {
+        let left = deadline.saturating_duration_since(std::time::Instant::now());
+        if left.is_zero() || !event::poll(left)? {
+            return Ok(false);
+        }
+        if let Event::Key(key) = event::read()?
+            && key.code == KeyCode::Enter
+        {
+            return Ok(true);
+        }
+    }
+}
+
+fn print_event(helper: &ParamStoreHelper, event: &ChangeEvent) {
+    let when = |seen: &Seen| seen.modified.map(format_timestamp).unwrap_or_default();
+    let by = |seen: &Seen| {
+        seen.user
+            .as_deref()
+            .map(|user| format!(" by {}", user))
+            .unwrap_or_default()
+    };
+    if helper.completer.output.is_json() {
+        let record = match event {
+            ChangeEvent::Created(path, seen) => json!({
+                "event": "created", "path": path, "version": seen.version,
+                "modified": seen.modified, "user": seen.user,
+            }),
+            ChangeEvent::Updated(path, old, seen) => json!({
+                "event": "updated", "path": path, "previous_version": old,
+                "version": seen.version, "modified": seen.modified, "user": seen.user,
+            }),
+            ChangeEvent::Deleted(path) => json!({ "event": "deleted", "path": path }),
+        };
+        output::print_json(record);
+        return;
+    }
+    match event {
+        ChangeEvent::Created(path, seen) => println!(
+            "{} {} {} (v{}){}",
+            when(seen),
+            "+".green(),
+            path.green(),
+            seen.version,
+            by(seen)
+        ),
+        ChangeEvent::Updated(path, old, seen) => println!(
+            "{} {} {} (v{} → v{}){}",
+            when(seen),
+            "~".yellow(),
+            path.green(),
+            old,
+            seen.version,
+            by(seen)
+        ),
+        ChangeEvent::Deleted(path) => println!("{} {}", "-".red(), path.green()),
+    }
+}
@@ -1,3 +1,4 @@
+use crate::completer::ParameterCompleter;
 use crate::cpboard::Cpboard;
 use colored::Colorize;
 
@@ -7,7 +8,14 @@ use colored::Colorize;
 ///
 /// `param_key`  – the SSM parameter path (shown in the header)
 /// `raw`        – the raw connection string value from the cache
-pub fn parse_db<'a>(param_key: &str, raw: &str, cpboard: &mut Cpboard<'a>) {
+/// `completer`  – `quiet` skips the "copied" confirmation; `redact` masks the
+///                fields and skips the clipboard
+pub fn parse_db<'a>(
+    param_key: &str,
+    raw: &str,
+    cpboard: &mut Cpboard<'a>,
+    completer: &ParameterCompleter,
+) {
     let raw = raw.trim().trim_matches(|c| c == '"' || c == '\'');
 
     if raw.is_empty() {
@@ -47,14 +55,22 @@ pub fn parse_db<'a>(param_key: &str, raw: &str, cpboard: &mut Cpboard<'a>) {
 
     let mut clipboard_content = String::new();
     for (key, value) in &pairs {
-        println!("  {:<12} {}", format!("{}:", key).cyan().bold(), value.yellow());
+        println!(
+            "  {:<12} {}",
+            format!("{}:", key).cyan().bold(),
+            completer.shown(value).yellow()
+        );
         clipboard_content.push_str(&format!("{}: {}\n", key, value));
     }
 
     println!("{}", "─────────────────────────────────────────────".dimmed());
 
+    if completer.redact {
+        return;
+    }
     match cpboard.set_clipboard_content(&clipboard_content) {
-        Ok(_) => println!("{}", "✓ Copied to clipboard".green()),
+        Ok(true) if !completer.quiet => println!("{}", "✓ Copied to clipboard".green()),
+        Ok(_) => {}
         Err(err) => println!("Error copying to clipboard: {}", err),
     }
 }
@@ -0,0 +1,113 @@
+use crate::commands::size::check_value_size;
+use crate::completer::REDACTED;
+use crate::cpboard::Cpboard;
+use crate::helper::ParamStoreHelper;
+use crate::utils::prompt_line;
+use colored::Colorize;
+use std::io::IsTerminal;
+
+const USAGE: &str = "Usage: paste [path] [--yes] [--no-validate]";
+
+/// Shortest value whose ends are shown in the preview; shorter ones are fully
+/// masked.
+const PREVIEW_MIN: usize = 12;
+
+/// A pasted value as it is previewed: its first four and last two characters
+/// around `…`, so the right thing can be recognised without showing it.
+pub fn masked_preview(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < PREVIEW_MIN {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 2..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// Handles `paste [path] [--yes] [--no-validate]`: sets an existing parameter
+/// (the selected one by default) to what is on the clipboard, without a
+/// trailing line break. The value is shown masked, with its length, and
+/// written after a `[y/N]` confirmation unless `--yes` is given. The same
+/// size, secret policy and protected-path checks as `set` apply, and offline
+/// the change is queued. Returns the path.
+pub async fn paste<'a>(
+    helper: &mut ParamStoreHelper,
+    cpboard: &mut Cpboard<'a>,
+    args: &str,
+    selected: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (mut yes, mut no_validate) = (false, false);
+    let mut path = None;
+    for word in args.split_whitespace() {
+        match word {
+            "--yes" => yes = true,
+            "--no-validate" => no_validate = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ if path.is_none() => path = Some(helper.completer.aliases.resolve(word)?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let path = match path {
+        Some(path) => path,
+        None if selected.is_empty() => return Err(USAGE.into()),
+        None => selected.to_string(),
+    };
+    let completer = &mut helper.completer;
+    if !completer.is_parameter(&path) {
+        return Err(format!("{} does not exist; create it with 'insert' first", path).into());
+    }
+
+    let content = cpboard
+        .get_clipboard_content()
+        .map_err(|e| format!("Cannot read the clipboard: {}", e))?;
+    let value = content.trim_end_matches(['\r', '\n']);
+    if value.is_empty() {
+        return Err("The clipboard is empty".into());
+    }
+    check_value_size(&path, value, completer.chunking)?;
+    if !no_validate {
+        completer.secret_policy.check(&path, value)?;
+    }
+
+    let preview = if completer.redact {
+        REDACTED.to_string()
+    } else {
+        masked_preview(value)
+    };
+    let lines = value.lines().count();
+    println!(
+        "Paste into {}: {} ({} characters{})",
+        path.green(),
+        preview,
+        value.chars().count(),
+        if lines > 1 {
+            format!(", {} lines", lines)
+        } else {
+            String::new()
+        }
+    );
+    if completer.protected.matching(&path).is_some() {
+        completer.protected.confirm(&path, "paste into", yes)?;
+    } else if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass --yes to paste without a prompt".into());
+        }
+        if !prompt_line("Set it to the clipboard contents? [y/N]: ")?.eq_ignore_ascii_case("y") {
+            return Err(format!("Paste cancelled; {} was left unchanged", path).into());
+        }
+    }
+
+    if completer.offline {
+        completer.queue_change(&path, value, None)?;
+        println!(
+            "Offline: queued change for {} (run 'push' when back online)",
+            path
+        );
+        return Ok(path);
+    }
+    completer.change_value(&path, value.to_string()).await?;
+    println!("Pasted into {}", path.green());
+    Ok(path)
+}
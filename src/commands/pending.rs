@@ -0,0 +1,219 @@
+use crate::commands::set::print_change;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::pending::PendingChange;
+use crate::utils::prompt_line;
+use colored::Colorize;
+use serde_json::json;
+use std::io::IsTerminal;
+
+/// Handles the `pending` command: lists writes queued while offline.
+pub fn list_pending(helper: &ParamStoreHelper) {
+    let pending = &helper.completer.pending;
+    if helper.completer.output.is_json() {
+        for (index, change) in pending.changes.iter().enumerate() {
+            output::print_json(json!({
+                "index": index,
+                "path": change.path,
+                "value": helper.completer.shown(&change.value),
+                "type": change.param_type,
+            }));
+        }
+        return;
+    }
+
+    if pending.is_empty() {
+        println!("No pending changes");
+        return;
+    }
+
+    println!("{} pending change(s):", pending.len());
+    for (index, change) in pending.changes.iter().enumerate() {
+        let kind = match &change.param_type {
+            Some(t) => format!("insert ({})", t),
+            None => "set".to_string(),
+        };
+        println!(
+            "{}: {} {} -> {}",
+            index.to_string().yellow(),
+            kind.cyan(),
+            change.path.green(),
+            helper.completer.shown(&change.value).red()
+        );
+    }
+}
+
+/// Handles the `push` command: sends queued changes to AWS in the order they
+/// were made. Successful changes are removed from the queue; failures stay queued.
+pub async fn push_pending(helper: &mut ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    if helper.completer.offline {
+        return Err("Cannot push while in offline mode; restart daps without --offline".into());
+    }
+
+    let changes = helper.completer.pending.changes.clone();
+    if changes.is_empty() {
+        println!("No pending changes");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for change in changes {
+        // A queued insert carries its type; a queued set keeps the remote type.
+        let result = match &change.param_type {
+            Some(_) => push_insert(helper, &change).await,
+            None => helper
+                .completer
+                .change_value(&change.path, change.value.clone())
+                .await
+                .map(|_| ()),
+        };
+
+        match result {
+            Ok(()) => {
+                helper.completer.pending.remove(&change.path);
+                println!("{} {}", "pushed".green(), change.path);
+            }
+            Err(e) => {
+                failed += 1;
+                println!("{} {}: {}", "failed".red(), change.path, e);
+            }
+        }
+    }
+
+    helper
+        .completer
+        .pending
+        .save(&helper.completer.encryption)?;
+    if failed > 0 {
+        return Err(format!("{} change(s) could not be pushed and remain queued", failed).into());
+    }
+    println!("All pending changes pushed");
+    Ok(())
+}
+
+async fn push_insert(
+    helper: &mut ParamStoreHelper,
+    change: &PendingChange,
+) -> Result<(), Box<dyn std::error::Error>> {
+    helper
+        .completer
+        .set_parameter(
+            &change.path,
+            change.value.clone(),
+            change.param_type.clone(),
+        )
+        .await?;
+    helper
+        .completer
+        .cache_value(&change.path, change.value.clone())
+        .await;
+    Ok(())
+}
+
+/// Handles `discard [path]`: drops one queued change, or all of them.
+pub fn discard_pending(
+    helper: &mut ParamStoreHelper,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pending = &mut helper.completer.pending;
+    if path.is_empty() {
+        let count = pending.len();
+        pending.changes.clear();
+        pending.save(&helper.completer.encryption)?;
+        println!(
+            "Discarded {} pending change(s); restart daps to restore the cached values",
+            count
+        );
+    } else if pending.remove(path) {
+        pending.save(&helper.completer.encryption)?;
+        println!("Discarded pending change for {}", path);
+    } else {
+        println!("No pending change for {}", path);
+    }
+    Ok(())
+}
+
+/// Asks what to do with each queued change whose parameter changed in AWS
+/// since it was queued: keep the local change (which `push` then writes over
+/// AWS), take the value from AWS (dropping the local change), or first view
+/// the difference. Without a terminal, or with JSON output, conflicts are
+/// only listed and the changes stay queued as they are.
+pub async fn resolve_conflicts(
+    helper: &mut ParamStoreHelper,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let conflicts = helper.completer.pending_conflicts();
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    let completer = &mut helper.completer;
+    let interactive = std::io::stdin().is_terminal() && !completer.output.is_json();
+
+    let mut unresolved = 0;
+    for change in conflicts {
+        let path = change.path.as_str();
+        let remote_version = completer.revisions.get(path).and_then(|r| r.version);
+        if completer.output.is_json() {
+            output::print_json(json!({
+                "conflict": path,
+                "base_version": change.base_version,
+                "remote_version": remote_version,
+            }));
+        }
+        if !interactive {
+            unresolved += 1;
+            continue;
+        }
+
+        let remote = completer
+            .fetch_parameter(path)
+            .await?
+            .and_then(|parameter| parameter.value);
+        let what = match (change.base_version, remote_version) {
+            (_, None) => "was deleted in AWS".to_string(),
+            (None, Some(_)) => "was created in AWS".to_string(),
+            (Some(base), Some(now)) => format!("changed in AWS (version {} -> {})", base, now),
+        };
+        println!(
+            "{} {} {} while a local change to it is queued",
+            "conflict".yellow(),
+            path.green(),
+            what
+        );
+        loop {
+            let answer = prompt_line("  [l]keep local, [r]take remote, [d]iff, [s]kip: ")?;
+            match answer.to_lowercase().as_str() {
+                "l" | "local" => {
+                    completer.keep_local(path)?;
+                    println!("  Keeping the local change; 'push' will overwrite AWS");
+                    break;
+                }
+                "r" | "remote" => {
+                    completer.take_remote(path, remote.clone())?;
+                    println!("  Took the value from AWS; the local change was dropped");
+                    break;
+                }
+                "d" | "diff" => match &remote {
+                    Some(remote) => {
+                        let secret = completer.redact || completer.is_sensitive(path);
+                        println!("  {} AWS, {} local", "-".red(), "+".green());
+                        print_change(remote, &change.value, secret);
+                    }
+                    None => println!("  {} deleted in AWS", "-".red()),
+                },
+                "" | "s" | "skip" => {
+                    unresolved += 1;
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if unresolved > 0 && !completer.output.is_json() {
+        println!(
+            "{} queued change(s) conflict with AWS; run 'refresh' in a terminal to resolve them, or 'discard <path>' to take the AWS value",
+            unresolved
+        );
+    }
+    Ok(())
+}
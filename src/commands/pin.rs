@@ -0,0 +1,112 @@
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::pins::Drift;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `pin [path]`: records the current value of `path` (or the selected
+/// parameter) as its expected value.
+pub fn pin(
+    helper: &mut ParamStoreHelper,
+    path: &str,
+    selected: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = if path.is_empty() { selected } else { path };
+    if path.is_empty() {
+        return Err("No parameter given or selected. Usage: pin <path>".into());
+    }
+
+    let completer = &mut helper.completer;
+    let value = completer.cached_value(path).ok_or_else(|| {
+        format!(
+            "No cached value for '{}'. Try 'reload-by-path {}' first.",
+            path, path
+        )
+    })?;
+
+    completer.pins.pin(path, &value);
+    completer.pins.save()?;
+    println!("Pinned {} to its current value", path.green());
+    Ok(())
+}
+
+/// Handles `unpin <path>`.
+pub fn unpin(helper: &mut ParamStoreHelper, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let pins = &mut helper.completer.pins;
+    if pins.unpin(path) {
+        pins.save()?;
+        println!("Unpinned {}", path.green());
+    } else {
+        println!("{} is not pinned", path);
+    }
+    Ok(())
+}
+
+/// Handles `pins`: lists pinned parameters with their current drift status.
+pub fn list_pins(helper: &ParamStoreHelper) {
+    let completer = &helper.completer;
+    let json = completer.output.is_json();
+    if completer.pins.entries.is_empty() {
+        if !json {
+            println!("No pinned parameters. Use 'pin <path>' to add one.");
+        }
+        return;
+    }
+
+    for path in completer.pins.entries.keys() {
+        // `None` means the pin lies outside the loaded base path.
+        let drift = path.starts_with(&completer.base_path).then(|| {
+            completer
+                .pins
+                .check(path, completer.cached_value(path).as_deref())
+        });
+        if json {
+            let status = match drift {
+                None => "not loaded",
+                Some(None) => "ok",
+                Some(Some(Drift::Changed)) => "drifted",
+                Some(Some(Drift::Missing)) => "missing",
+            };
+            output::print_json(json!({ "path": path, "status": status }));
+            continue;
+        }
+
+        let status = match drift {
+            None => "not loaded".dimmed(),
+            Some(None) => "ok".green(),
+            Some(Some(Drift::Changed)) => "DRIFTED".red().bold(),
+            Some(Some(Drift::Missing)) => "MISSING".red().bold(),
+        };
+        println!("  {:<60} {}", path, status);
+    }
+}
+
+/// Prints a warning for every pinned parameter whose loaded value deviates from its pin.
+pub fn warn_on_drift(completer: &ParameterCompleter) {
+    let pinned: Vec<String> = completer.pins.entries.keys().cloned().collect();
+    let values = completer.cached_values(&pinned);
+    for (path, drift) in completer.pins.drifted(&completer.base_path, &values) {
+        println!(
+            "{} {}: {}",
+            "⚠ Drift:".red().bold(),
+            path.yellow(),
+            drift.reason()
+        );
+    }
+}
+
+/// Warns if a single freshly fetched parameter deviates from its pin.
+pub fn warn_on_drift_for(completer: &ParameterCompleter, path: &str) {
+    if let Some(drift) = completer
+        .pins
+        .check(path, completer.cached_value(path).as_deref())
+    {
+        println!(
+            "{} {}: {}",
+            "⚠ Drift:".red().bold(),
+            path.yellow(),
+            drift.reason()
+        );
+    }
+}
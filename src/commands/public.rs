@@ -0,0 +1,113 @@
+use crate::backend::BackendKind;
+use crate::cpboard::Cpboard;
+use crate::display_filter::{self, Filter};
+use crate::helper::ParamStoreHelper;
+use crate::names::PUBLIC_PREFIX;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+const USAGE: &str = "Usage: latest-ami <os> [--arm64], where <os> is one of al2023, al2, ubuntu, ubuntu-22.04, windows";
+
+/// The public parameter holding the latest AMI ID for `os`.
+pub fn ami_parameter(os: &str, arm64: bool) -> Result<String, String> {
+    let arch = if arm64 { "arm64" } else { "x86_64" };
+    let ubuntu = |release: &str, volume: &str| {
+        format!(
+            "canonical/ubuntu/server/{}/stable/current/{}/hvm/{}/ami-id",
+            release,
+            if arm64 { "arm64" } else { "amd64" },
+            volume
+        )
+    };
+    let relative = match os {
+        "al2023" | "amazon-linux" => {
+            format!("ami-amazon-linux-latest/al2023-ami-kernel-default-{}", arch)
+        }
+        "al2" => format!("ami-amazon-linux-latest/amzn2-ami-hvm-{}-gp2", arch),
+        "ubuntu" | "ubuntu-24.04" => ubuntu("24.04", "ebs-gp3"),
+        "ubuntu-22.04" => ubuntu("22.04", "ebs-gp2"),
+        "windows" if arm64 => return Err("Windows AMIs are x86_64 only".to_string()),
+        "windows" => "ami-windows-latest/Windows_Server-2022-English-Full-Base".to_string(),
+        "" => return Err(USAGE.to_string()),
+        other => return Err(format!("Unknown OS '{}'. {}", other, USAGE)),
+    };
+    Ok(format!("{}{}", PUBLIC_PREFIX, relative))
+}
+
+fn check_available(helper: &ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    if helper.completer.offline {
+        return Err("Public parameters need AWS access; they are not available offline".into());
+    }
+    if helper.completer.backend_kind == BackendKind::SecretsManager {
+        return Err("Public parameters are only published in Parameter Store".into());
+    }
+    Ok(())
+}
+
+/// Handles a lookup under `/aws/service/`: prints the public parameter, or
+/// the ones directly under the path, and copies them to the clipboard.
+/// Unlike other lookups it goes to AWS each time and ignores the base path
+/// and scope.
+pub async fn get_public<'a>(
+    helper: &ParamStoreHelper,
+    path: &str,
+    filters: &[Filter],
+    cpboard: &mut Cpboard<'a>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    check_available(helper)?;
+    let completer = &helper.completer;
+    let found = completer.fetch_public(path).await?;
+    if found.is_empty() {
+        return Err(format!("No public parameters at {}", path).into());
+    }
+
+    let mut clipboard_content = String::new();
+    for (name, value) in &found {
+        let value =
+            display_filter::apply(value, filters).map_err(|e| format!("{}: {}", name, e))?;
+        let value = completer.shown(&value);
+        if completer.output.is_json() {
+            output::print_json(json!({ "path": name, "value": value, "public": true }));
+        } else {
+            println!("Found value for {}: {}", name.green(), value.red());
+        }
+        clipboard_content.push_str(&format!("{}: {}\n", name, value));
+    }
+    if completer.redact || completer.output.is_json() {
+        return Ok(());
+    }
+    match cpboard.set_clipboard_content(&clipboard_content) {
+        Ok(true) if !completer.quiet => println!("Copied to clipboard:\n{}", clipboard_content),
+        Ok(_) => {}
+        Err(err) => println!("Error copying to clipboard: {}", err),
+    }
+    Ok(())
+}
+
+/// Handles `latest-ami <os> [--arm64]`: looks up the current AMI ID for a
+/// common OS from the public parameters AWS maintains.
+pub async fn latest_ami(
+    helper: &ParamStoreHelper,
+    args: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut arm64 = false;
+    let mut os = "";
+    for word in args.split_whitespace() {
+        match word {
+            "--arm64" => arm64 = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            word if os.is_empty() => os = word,
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let path = ami_parameter(os, arm64)?;
+    check_available(helper)?;
+    helper.completer.progress(&format!("Reading {}", path));
+    match helper.completer.fetch_parameter(&path).await? {
+        Some(param) => Ok(param.value.unwrap_or_default()),
+        None => Err(format!("{} is not published in this region", path).into()),
+    }
+}
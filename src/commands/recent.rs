@@ -0,0 +1,28 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `recent`: lists recently viewed or changed parameters with the
+/// `sel -N` shortcut for each.
+pub fn list_recent(helper: &ParamStoreHelper) {
+    let completer = &helper.completer;
+    if completer.output.is_json() {
+        for (index, path) in completer.recent.entries.iter().enumerate() {
+            output::print_json(json!({ "index": -(index as i64) - 1, "path": path }));
+        }
+        return;
+    }
+
+    if completer.recent.entries.is_empty() {
+        println!("No recent parameters yet.");
+        return;
+    }
+    for (index, path) in completer.recent.entries.iter().enumerate() {
+        println!(
+            "{:>5}  {}",
+            format!("-{}", index + 1).yellow(),
+            path.green()
+        );
+    }
+}
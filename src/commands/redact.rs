@@ -0,0 +1,30 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `redact [on|off]`: while on, every value shown on screen is
+/// masked and nothing is copied to the clipboard, so daps can be used while
+/// screen sharing. Without an argument, shows whether it is on.
+pub fn redact(helper: &mut ParamStoreHelper, args: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    match args.trim() {
+        "" => {}
+        "on" => completer.redact = true,
+        "off" => completer.redact = false,
+        other => {
+            return Err(format!("Unknown argument '{}'. Usage: redact [on|off]", other).into());
+        }
+    }
+    if completer.output.is_json() {
+        output::print_json(json!({ "redact": completer.redact }));
+    } else if completer.redact {
+        println!(
+            "{}: values are masked and the clipboard is off",
+            "Redaction on".yellow()
+        );
+    } else {
+        println!("Redaction off: values are shown and copied");
+    }
+    Ok(())
+}
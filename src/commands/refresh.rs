@@ -1,13 +1,37 @@
+use crate::commands::gc::report_removed;
+use crate::commands::pending::resolve_conflicts;
+use crate::commands::pin::warn_on_drift;
 use crate::helper::ParamStoreHelper;
 
 /// Handles the `refresh` command.
-/// Reloads all parameters from AWS SSM, bypassing the local cache.
+/// Reloads all parameters from AWS SSM, bypassing the local cache, and
+/// re-fetches the completion vocabulary (regions, KMS aliases). Cached
+/// parameters that no longer exist are dropped and listed, and queued changes
+/// to parameters that changed in AWS meanwhile are resolved one by one.
 pub async fn refresh(helper: &mut ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
-    helper
-        .completer
-        .load_parameters()
-        .await
-        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-    println!("Parameters refreshed");
+    let completer = &mut helper.completer;
+    let root = format!("{}/", completer.root().trim_end_matches('/'));
+    let before = completer.parameters_under(&root);
+
+    // Offline there is nothing to bypass the cache for.
+    let refresh = completer.refresh;
+    completer.refresh = !completer.offline;
+    let loaded = completer.load_parameters().await;
+    completer.refresh = refresh;
+    loaded.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+
+    completer.load_vocabulary(true).await;
+    completer.progress("Parameters refreshed");
+    if !completer.offline {
+        let removed: Vec<String> = before
+            .into_iter()
+            .filter(|path| !completer.is_parameter(path))
+            .collect();
+        if !removed.is_empty() {
+            report_removed(completer, &removed, &root, false);
+        }
+        resolve_conflicts(helper).await?;
+    }
+    warn_on_drift(&helper.completer);
     Ok(())
 }
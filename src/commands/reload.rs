@@ -1,3 +1,4 @@
+use crate::commands::pin::warn_on_drift_for;
 use crate::helper::ParamStoreHelper;
 
 /// Handles the `reload` command: re-fetches the currently selected parameter from AWS SSM.
@@ -5,9 +6,15 @@ pub async fn reload(
     helper: &mut ParamStoreHelper,
     path: &str,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    println!("Reloading parameter: {}", path);
+    helper
+        .completer
+        .progress(&format!("Reloading parameter: {}", path));
     let value = helper.completer.get_set_value(path).await?;
-    println!("Reloaded value: {}", value);
+    let shown = helper.completer.shown(&value);
+    helper
+        .completer
+        .progress(&format!("Reloaded value: {}", shown));
+    warn_on_drift_for(&helper.completer, path);
     Ok(value)
 }
 
@@ -16,8 +23,14 @@ pub async fn reload_by_path(
     helper: &mut ParamStoreHelper,
     path: &str,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    println!("Reloading parameter by path: {}", path);
+    helper
+        .completer
+        .progress(&format!("Reloading parameter by path: {}", path));
     let value = helper.completer.get_set_value(path).await?;
-    println!("Reloaded value: {}", value);
+    let shown = helper.completer.shown(&value);
+    helper
+        .completer
+        .progress(&format!("Reloaded value: {}", shown));
+    warn_on_drift_for(&helper.completer, path);
     Ok(value)
 }
@@ -0,0 +1,91 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+use std::collections::HashSet;
+
+/// What `reload-all` found, by path.
+#[derive(Debug, Default, PartialEq)]
+pub struct Reloaded {
+    /// Parameters whose value differs from the cached one.
+    pub changed: Vec<String>,
+    /// Parameters whose value is the same as the cached one.
+    pub unchanged: Vec<String>,
+    /// Parameters AWS no longer has.
+    pub missing: Vec<String>,
+}
+
+/// Handles `reload-all [path...]`: re-fetches the given parameters, or every
+/// entry of the last search result, with batched GetParameters calls and
+/// reports which values changed since they were cached.
+pub async fn reload_all(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<Reloaded, Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    if completer.offline {
+        return Err("reload-all needs AWS access and is not available offline".into());
+    }
+    let mut paths = match args.split_whitespace().collect::<Vec<_>>() {
+        words if words.is_empty() => completer.search_result.clone(),
+        words => words
+            .into_iter()
+            .map(|word| completer.aliases.resolve(word))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let mut seen = HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    if paths.is_empty() {
+        return Err("Nothing to reload; run 'search' first or name the parameters".into());
+    }
+
+    let before: Vec<Option<String>> = paths.iter().map(|p| completer.cached_value(p)).collect();
+    completer.progress(&format!("Reloading {} parameter(s)", paths.len()));
+    let fetched = completer.get_set_values_by_names(&paths).await?;
+
+    let mut reloaded = Reloaded::default();
+    for (path, old) in paths.iter().zip(before) {
+        match fetched.get(path) {
+            None => reloaded.missing.push(path.clone()),
+            Some(new) if old.as_ref() == Some(new) => reloaded.unchanged.push(path.clone()),
+            Some(new) => {
+                if completer.output.is_json() {
+                    output::print_json(json!({
+                        "path": path,
+                        "old": old.as_deref().map(|old| completer.shown(old)),
+                        "new": completer.shown(new),
+                    }));
+                } else {
+                    let old = match &old {
+                        Some(old) => completer.clipped(old),
+                        None => "(not cached)".to_string(),
+                    };
+                    println!(
+                        "{}: {} -> {}",
+                        path.green(),
+                        old.dimmed(),
+                        completer.clipped(new).red()
+                    );
+                }
+                reloaded.changed.push(path.clone());
+            }
+        }
+    }
+
+    if completer.output.is_json() {
+        for path in &reloaded.missing {
+            output::print_json(json!({ "path": path, "missing": true }));
+        }
+    } else {
+        for path in &reloaded.missing {
+            println!("{}: {}", path.green(), "not found".yellow());
+        }
+        println!(
+            "{} changed, {} unchanged, {} not found",
+            reloaded.changed.len(),
+            reloaded.unchanged.len(),
+            reloaded.missing.len()
+        );
+    }
+    Ok(reloaded)
+}
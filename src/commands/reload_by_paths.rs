@@ -3,7 +3,8 @@ use colored::Colorize;
 use std::collections::HashMap;
 
 /// Handles the `reload-by-paths <paths>` and `reloads` commands.
-/// Re-fetches all parameters under a given path prefix from AWS SSM.
+/// Re-fetches all parameters under a given path prefix from AWS SSM. When the
+/// path is a parameter rather than a prefix, it is fetched by name.
 pub async fn reload_by_paths(
     helper: &mut ParamStoreHelper,
     paths: &str,
@@ -12,14 +13,23 @@ pub async fn reload_by_paths(
         return Err("No paths provided for reload".into());
     }
 
-    println!("Reloading parameters by paths: {:?}", paths);
-    let values = helper.completer.get_set_values(paths).await?;
+    helper
+        .completer
+        .progress(&format!("Reloading parameters by paths: {:?}", paths));
+    let values = if helper.completer.is_parameter(paths) {
+        helper
+            .completer
+            .get_set_values_by_names(&[paths.to_string()])
+            .await?
+    } else {
+        helper.completer.get_set_values(paths).await?
+    };
 
     if values.is_empty() {
         println!("No parameters found for the given paths");
     } else {
         for (key, value) in &values {
-            println!("{}: {}", key.green(), value.red());
+            println!("{}: {}", key.green(), helper.completer.shown(value).red());
         }
     }
 
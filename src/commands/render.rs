@@ -0,0 +1,176 @@
+use crate::commands::export::write_output;
+use crate::helper::ParamStoreHelper;
+use crate::utils::json_field;
+use rusoto_core::RusotoError;
+use rusoto_ssm::GetParameterError;
+
+const USAGE: &str = "Usage: render <template-file> [--output <file>]";
+
+/// One `{{ ... }}` placeholder of a template.
+struct Placeholder {
+    path: String,
+    /// JSON field after `#`, as in `/prod/db#password`.
+    key: Option<String>,
+    default: Option<String>,
+    /// `| env` renders the environment variable name of the path instead of
+    /// its value.
+    env: bool,
+}
+
+impl Placeholder {
+    /// Parses the inside of `{{ ... }}`. Returns `Ok(None)` for expressions
+    /// that are not parameter paths, so other template syntax is left alone.
+    fn parse(helper: &ParamStoreHelper, expression: &str) -> Result<Option<Self>, String> {
+        let mut parts = expression.split('|').map(str::trim);
+        let target = parts.next().unwrap_or_default();
+        if !target.starts_with('/') && !target.starts_with('@') {
+            return Ok(None);
+        }
+        let (path, key) = match target.split_once('#') {
+            Some((path, key)) => (path, Some(key.to_string())),
+            None => (target, None),
+        };
+        let mut placeholder = Placeholder {
+            path: helper.completer.aliases.resolve(path)?,
+            key,
+            default: None,
+            env: false,
+        };
+        for filter in parts {
+            if filter == "env" {
+                placeholder.env = true;
+            } else if let Some(default) = filter.strip_prefix("default") {
+                let default = default.trim();
+                let unquoted = default
+                    .strip_prefix('"')
+                    .and_then(|d| d.strip_suffix('"'))
+                    .unwrap_or(default);
+                placeholder.default = Some(unquoted.to_string());
+            } else {
+                return Err(format!(
+                    "Unknown filter '{}' in {{{{{}}}}} (expected env or default \"<value>\")",
+                    filter, expression
+                ));
+            }
+        }
+        Ok(Some(placeholder))
+    }
+}
+
+/// `/prod/db-host` → `PROD_DB_HOST`.
+pub fn env_var_name(path: &str) -> String {
+    path.trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Handles `render <template-file> [--output <file>]`: replaces every
+/// `{{/path}}` placeholder with the parameter's value and prints the result,
+/// or writes it to `--output`. Values come from the cache and are fetched from
+/// AWS when missing. `{{/path | default "x"}}` falls back to `x` when the
+/// parameter does not exist, `{{/path#key}}` takes one field of a JSON value,
+/// and `{{/path | env}}` renders the path as a variable name. Placeholders
+/// that do not start with `/` or `@` are copied unchanged.
+pub async fn render(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut words = args.split_whitespace();
+    let (mut template, mut output) = (None, None);
+    while let Some(word) = words.next() {
+        match word {
+            "--output" | "-o" => output = Some(words.next().ok_or(USAGE)?),
+            _ if template.is_none() => template = Some(word),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let template = template.ok_or(USAGE)?;
+    let source = std::fs::read_to_string(template)
+        .map_err(|e| format!("Cannot read {}: {}", template, e))?;
+
+    let mut rendered = String::with_capacity(source.len());
+    let mut missing = Vec::new();
+    let mut sensitive = false;
+    let mut rest = source.as_str();
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let expression = &rest[start + 2..start + 2 + length];
+        rendered.push_str(&rest[..start]);
+        let end = start + 2 + length + 2;
+
+        let Some(placeholder) = Placeholder::parse(helper, expression.trim())? else {
+            rendered.push_str(&rest[start..end]);
+            rest = &rest[end..];
+            continue;
+        };
+        rest = &rest[end..];
+        if placeholder.env {
+            rendered.push_str(&env_var_name(&placeholder.path));
+            continue;
+        }
+
+        match lookup(helper, &placeholder.path).await? {
+            Some(value) => {
+                sensitive |= helper.completer.is_sensitive(&placeholder.path);
+                match &placeholder.key {
+                    Some(key) => rendered.push_str(
+                        &json_field(&value, key)
+                            .map_err(|e| format!("{}#{}: {}", placeholder.path, key, e))?,
+                    ),
+                    None => rendered.push_str(&value),
+                }
+            }
+            None => match placeholder.default {
+                Some(default) => rendered.push_str(&default),
+                None => missing.push(placeholder.path),
+            },
+        }
+    }
+    rendered.push_str(rest);
+
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        return Err(format!(
+            "{} has placeholders for missing parameters: {}",
+            template,
+            missing.join(", ")
+        )
+        .into());
+    }
+    if helper.completer.redact && output.is_none() {
+        return Err("Values are redacted; pass --output <file> to render them".into());
+    }
+    write_output(&rendered, output, sensitive)
+}
+
+/// The cached value of `path`, fetched from AWS when not cached. `None` when
+/// the parameter does not exist (or is not cached, offline).
+async fn lookup(
+    helper: &mut ParamStoreHelper,
+    path: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    if let Some(value) = completer.cached_value(path) {
+        completer.stats.record_cache(1, 0);
+        return Ok(Some(value));
+    }
+    completer.stats.record_cache(0, 1);
+    if completer.offline {
+        return Ok(None);
+    }
+    match completer.get_set_value(path).await {
+        Ok(value) => Ok((!value.is_empty()).then_some(value)),
+        Err(RusotoError::Service(GetParameterError::ParameterNotFound(_))) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,176 @@
+use crate::commands::set::print_change;
+use crate::commands::size::check_value_size;
+use crate::helper::ParamStoreHelper;
+use crate::utils::{prompt_line, split_words};
+use colored::Colorize;
+use regex::Regex;
+use std::io::IsTerminal;
+
+const USAGE: &str = "Usage: replace <prefix> --find <old> --with <new> [--regex] [--dry-run] [--yes] [--no-validate]";
+
+/// What `replace` was asked for. Quote `<old>` and `<new>` when they contain
+/// spaces.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReplaceArgs {
+    pub prefix: String,
+    pub find: String,
+    pub with: String,
+    pub regex: bool,
+    pub dry_run: bool,
+    pub yes: bool,
+    pub no_validate: bool,
+}
+
+impl ReplaceArgs {
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut parsed = ReplaceArgs::default();
+        let (mut find, mut with) = (None, None);
+        let mut words = split_words(args)?.into_iter();
+        while let Some(word) = words.next() {
+            match word.as_str() {
+                "--find" => find = Some(words.next().ok_or(USAGE)?),
+                "--with" => with = Some(words.next().ok_or(USAGE)?),
+                "--regex" => parsed.regex = true,
+                "--dry-run" => parsed.dry_run = true,
+                "--yes" => parsed.yes = true,
+                "--no-validate" => parsed.no_validate = true,
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option {}. {}", flag, USAGE));
+                }
+                _ if parsed.prefix.is_empty() => parsed.prefix = word,
+                _ => return Err(USAGE.to_string()),
+            }
+        }
+        match (find, with) {
+            (Some(find), Some(with)) if !parsed.prefix.is_empty() && !find.is_empty() => {
+                parsed.find = find;
+                parsed.with = with;
+                Ok(parsed)
+            }
+            _ => Err(USAGE.to_string()),
+        }
+    }
+}
+
+/// Replaces every match of `find` in `value`: literally, or with `regex` as a
+/// regular expression whose groups `with` may use as `$1` or `${name}`.
+/// Returns `None` when nothing matches.
+pub fn replace_in(value: &str, find: &str, with: &str, regex: Option<&Regex>) -> Option<String> {
+    let replaced = match regex {
+        Some(regex) if regex.is_match(value) => regex.replace_all(value, with).into_owned(),
+        Some(_) => return None,
+        None if value.contains(find) => value.replace(find, with),
+        None => return None,
+    };
+    (replaced != value).then_some(replaced)
+}
+
+/// Handles `replace <prefix> --find <old> --with <new> [--regex] [--dry-run]
+/// [--yes]`: finds the cached values under `prefix` that contain `<old>`,
+/// shows each change as a diff (secrets by length only), then writes the new
+/// values after one confirmation. Values are checked against the size limit
+/// and the secret policy (unless `--no-validate`) before anything is written.
+/// Protected paths need `--yes`. Offline, the changes are queued for `push`.
+pub async fn replace(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let args = ReplaceArgs::parse(args)?;
+    let completer = &mut helper.completer;
+    let prefix = completer.aliases.resolve(&args.prefix)?;
+    let prefix = completer.scoped(&prefix)?.to_string();
+    let regex = if args.regex {
+        Some(Regex::new(&args.find).map_err(|e| format!("Invalid --find regex: {}", e))?)
+    } else {
+        None
+    };
+
+    let paths = completer.parameters_under(&prefix);
+    completer.hydrate(&paths).await?;
+    let values = completer.cached_values(&paths);
+    let mut changes: Vec<(String, String, String)> = values
+        .into_iter()
+        .filter_map(|(path, value)| {
+            let replaced = replace_in(&value, &args.find, &args.with, regex.as_ref())?;
+            Some((path, value, replaced))
+        })
+        .collect();
+    changes.sort();
+    if changes.is_empty() {
+        println!("No cached value under {} contains '{}'", prefix, args.find);
+        return Ok(());
+    }
+
+    let mut protected = Vec::new();
+    for (path, current, replaced) in &changes {
+        check_value_size(path, replaced, completer.chunking)?;
+        if !args.no_validate {
+            completer.secret_policy.check(path, replaced)?;
+        }
+        if let Some(pattern) = completer.protected.matching(path) {
+            protected.push(format!("{} ('{}')", path, pattern));
+        }
+        println!("{} {}", "~".yellow(), path.green());
+        print_change(
+            current,
+            replaced,
+            completer.redact || completer.is_sensitive(path),
+        );
+    }
+    println!(
+        "{} parameter(s) under {} would change",
+        changes.len(),
+        prefix
+    );
+    if args.dry_run {
+        return Ok(());
+    }
+    if !protected.is_empty() && !args.yes {
+        return Err(format!(
+            "Protected paths would change: {}; pass --yes to replace them",
+            protected.join(", ")
+        )
+        .into());
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass --yes to run replace without a prompt".into());
+        }
+        if !prompt_line("Apply these changes? [y/N]: ")?.eq_ignore_ascii_case("y") {
+            println!("Replace cancelled");
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for (path, _, replaced) in &changes {
+        let result = if completer.offline {
+            completer.queue_change(path, replaced, None)
+        } else {
+            completer
+                .change_value(path, replaced.clone())
+                .await
+                .map(|_| ())
+        };
+        match result {
+            Ok(()) => completer.progress(&format!("Replaced in {}", path)),
+            Err(err) => {
+                failed += 1;
+                println!("{} {}: {}", "Failed".red(), path, err);
+            }
+        }
+    }
+    let done = changes.len() - failed;
+    if completer.offline {
+        println!(
+            "Offline: queued {} change(s) (run 'push' when back online)",
+            done
+        );
+    } else {
+        println!("Replaced in {} parameter(s)", done);
+    }
+    if failed > 0 {
+        return Err(format!("{} replacement(s) failed", failed).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::encryption::prompt_passphrase;
+use crate::helper::ParamStoreHelper;
+use colored::Colorize;
+
+/// Handles the `rotate-key` command.
+/// Prompts for a new passphrase and re-encrypts the whole local cache with it.
+pub fn rotate_key(helper: &mut ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    let secret = prompt_passphrase("New cache passphrase: ")?;
+    if secret.is_empty() {
+        return Err("The new passphrase must not be empty".into());
+    }
+    if prompt_passphrase("Repeat new passphrase: ")? != secret {
+        return Err("Passphrases do not match; the key was not changed".into());
+    }
+
+    let count = helper.completer.rotate_key(&secret)?;
+    println!("Re-encrypted {} cached value(s) with the new key", count);
+    println!(
+        "{}",
+        "Use the new passphrase as DAPS_ENCRYPTION_KEY from now on".yellow()
+    );
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `scope [prefix]`: narrows the session to `prefix`, so navigation,
+/// completion, search, listings and every read or write stay inside it.
+/// Without an argument, shows the current scope.
+pub fn scope(helper: &mut ParamStoreHelper, args: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    if args.trim().is_empty() {
+        if completer.output.is_json() {
+            output::print_json(json!({ "scope": completer.scope }));
+        } else {
+            match &completer.scope {
+                Some(scope) => println!("Scoped to {}", scope.green()),
+                None => println!("No scope set; 'scope <prefix>' sets one"),
+            }
+        }
+        return Ok(());
+    }
+
+    let resolved = completer.aliases.resolve(args.trim())?;
+    let prefix = resolved.trim_end_matches('/');
+    if !prefix.starts_with('/') {
+        return Err("Usage: scope <prefix>, e.g. scope /app/db".into());
+    }
+    if !completer.parameters.contains_key(prefix) && !completer.is_parameter(prefix) {
+        return Err(format!("{} is not a known path", prefix).into());
+    }
+
+    completer.scope = Some(prefix.to_string());
+    // Indices from an earlier search may point outside the scope.
+    completer.search_result.clear();
+    let count = completer.parameters_under(prefix).len();
+    if completer.output.is_json() {
+        output::print_json(json!({ "scope": prefix, "parameters": count }));
+    } else {
+        println!(
+            "Scoped to {} ({} parameter(s)); 'unscope' lifts it",
+            prefix.green(),
+            count
+        );
+    }
+    Ok(())
+}
+
+/// Handles `unscope`: lifts the restriction set by `scope`.
+pub fn unscope(helper: &mut ParamStoreHelper) {
+    let completer = &mut helper.completer;
+    let lifted = completer.scope.take();
+    if completer.output.is_json() {
+        output::print_json(json!({ "scope": null }));
+        return;
+    }
+    match lifted {
+        Some(scope) => println!("Scope {} lifted", scope),
+        None => println!("No scope was set"),
+    }
+}
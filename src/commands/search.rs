@@ -1,7 +1,11 @@
 use crate::helper::ParamStoreHelper;
+use crate::output::{self, Table};
+use crate::utils::format_age;
 use colored::Colorize;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Highlights all case-insensitive occurrences of `term` within `text` using green+bold.
 fn highlight_match(text: &str, term: &str) -> String {
@@ -15,79 +19,197 @@ fn highlight_match(text: &str, term: &str) -> String {
     while let Some(pos) = lower_text[start..].find(&lower_term) {
         let abs_pos = start + pos;
         result.push_str(&text[start..abs_pos]);
-        result.push_str(&format!("{}", &text[abs_pos..abs_pos + term.len()].green().bold()));
+        result.push_str(&format!(
+            "{}",
+            &text[abs_pos..abs_pos + term.len()].green().bold()
+        ));
         start = abs_pos + term.len();
     }
     result.push_str(&text[start..]);
     result
 }
 
-/// Handles the `search <term>` command.
-/// Performs fuzzy matching against all cached parameter keys and prints ranked results.
-/// Stores matched keys into `helper.completer.search_result` for later use by `sel`.
-pub fn search(helper: &mut ParamStoreHelper, search_term: &str) {
+const USAGE: &str = "Usage: search <term> [--sort relevance|name|modified|length]";
+
+/// Order of `search` results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// Best fuzzy match first.
+    #[default]
+    Relevance,
+    Name,
+    /// Most recently changed first; unknown times last.
+    Modified,
+    /// Longest value first.
+    Length,
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "relevance" => Ok(Self::Relevance),
+            "name" => Ok(Self::Name),
+            "modified" => Ok(Self::Modified),
+            "length" => Ok(Self::Length),
+            other => Err(format!(
+                "Unknown sort key '{}' (expected relevance, name, modified or length)",
+                other
+            )),
+        }
+    }
+}
+
+/// Splits `<term> [--sort <key>]`.
+pub fn parse_search_args(args: &str) -> Result<(String, SortKey), String> {
+    let mut term = Vec::new();
+    let mut sort = SortKey::default();
+    let mut words = args.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "--sort" => sort = words.next().ok_or(USAGE)?.parse()?,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE));
+            }
+            word => term.push(word),
+        }
+    }
+    if term.is_empty() {
+        return Err(format!("Please provide a search term. {}", USAGE));
+    }
+    Ok((term.join(" "), sort))
+}
+
+/// Reorders results, which come best match first, by `sort`.
+fn sort_results(helper: &ParamStoreHelper, keys: &mut [String], sort: SortKey) {
+    let completer = &helper.completer;
+    match sort {
+        SortKey::Relevance => {}
+        SortKey::Name => keys.sort(),
+        SortKey::Modified => keys.sort_by(|a, b| {
+            let modified = |key: &String| {
+                completer
+                    .revisions
+                    .get(key)
+                    .and_then(|revision| revision.last_modified)
+            };
+            modified(b)
+                .partial_cmp(&modified(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        SortKey::Length => keys.sort_by_key(|key| {
+            std::cmp::Reverse(completer.cached_value(key).map_or(0, |value| value.len()))
+        }),
+    }
+}
+
+/// Prints numbered results as a table; the index is what `sel` takes.
+fn print_results(helper: &ParamStoreHelper, keys: &[String], search_term: &str) {
+    let completer = &helper.completer;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64());
+    let mut table = Table::new(&["#", "TYPE", "NAME", "VALUE", "AGE"]);
+    for (index, key) in keys.iter().enumerate() {
+        let value = completer.cached_value(key);
+        let param_type = completer.types.get(key);
+        let modified = completer
+            .revisions
+            .get(key)
+            .and_then(|revision| revision.last_modified);
+        if completer.output.is_json() {
+            let value = value.map(|value| completer.shown(&value).to_string());
+            output::print_json(json!({
+                "index": index,
+                "path": key,
+                "type": param_type,
+                "value": value,
+                "last_modified": modified,
+            }));
+            continue;
+        }
+        let value = value.map(|value| completer.clipped(&value));
+        table.row(vec![
+            index.to_string().yellow().to_string(),
+            param_type.map_or("-", String::as_str).dimmed().to_string(),
+            highlight_match(key, search_term),
+            value
+                .as_deref()
+                .unwrap_or("<unavailable>")
+                .red()
+                .to_string(),
+            modified.map_or_else(|| "-".to_string(), |modified| format_age(modified, now)),
+        ]);
+    }
+    if !completer.output.is_json() {
+        table.print();
+    }
+}
+
+/// Handles `search <term> [--sort relevance|name|modified|length]`.
+/// Performs fuzzy matching against all known parameter keys and prints ranked
+/// results, or the 20 best sorted by name, last change or value length.
+/// Results without a cached value are fetched in one batch before printing.
+/// Stores matched keys into `helper.completer.search_result`, in the printed
+/// order, for later use by `sel`.
+pub async fn search(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (search_term, sort) = parse_search_args(args)?;
+    let search_term = search_term.as_str();
     let matcher = SkimMatcherV2::default();
+    let candidates = helper.completer.search_candidates(search_term);
 
-    let mut matches: Vec<_> = helper
-        .completer
-        .values
-        .keys()
-        .filter_map(|k| matcher.fuzzy_match(k, search_term).map(|score| (k.clone(), score)))
+    let mut matches: Vec<_> = candidates
+        .iter()
+        .filter_map(|k| {
+            matcher
+                .fuzzy_match(k, search_term)
+                .map(|score| (k.clone(), score))
+        })
         .collect();
 
-    matches.sort_by(|a, b| b.1.cmp(&a.1));
+    matches.sort_by_key(|m| std::cmp::Reverse(m.1));
 
-    let keys: Vec<String> = matches.into_iter().take(20).map(|(key, _)| key).collect();
+    let mut keys: Vec<String> = matches.into_iter().take(20).map(|(key, _)| key).collect();
 
     if keys.is_empty() {
         // Fallback: simple contains search
-        let fallback_keys: Vec<String> = helper
-            .completer
-            .values
-            .keys()
-            .filter(|k| k.to_lowercase().contains(&search_term.to_lowercase()))
-            .cloned()
-            .collect();
+        let mut fallback_keys = helper.completer.parameters_containing(search_term);
 
         if fallback_keys.is_empty() {
-            println!("No matching parameters found for '{}'", search_term);
+            if !helper.completer.output.is_json() {
+                println!("No matching parameters found for '{}'", search_term);
+            }
         } else {
-            println!(
-                "Fuzzy search found no matches, showing contains matches for '{}':",
-                search_term
-            );
-            for (index, key) in fallback_keys.iter().enumerate() {
-                let value = helper
-                    .completer
-                    .values
-                    .get(key.as_str())
-                    .map(|s| s.as_str())
-                    .unwrap_or("<unavailable>");
+            if !helper.completer.output.is_json() {
                 println!(
-                    "{}: {} -> {}",
-                    index.to_string().yellow(),
-                    highlight_match(key, search_term),
-                    value.red()
+                    "Fuzzy search found no matches, showing contains matches for '{}':",
+                    search_term
                 );
             }
+            hydrate(helper, &fallback_keys).await;
+            sort_results(helper, &mut fallback_keys, sort);
+            print_results(helper, &fallback_keys, search_term);
             helper.completer.search_result = fallback_keys;
         }
     } else {
-        println!("Fuzzy search results for '{}':", search_term);
-        for (index, key) in keys.iter().enumerate() {
-            let value = helper
-                .completer
-                .values
-                .get(key.as_str())
-                .map(|s| s.as_str())
-                .unwrap_or("<unavailable>");
-            println!(
-                "{}: {} -> {}",
-                index.to_string().yellow(),
-                highlight_match(key, search_term),
-                value.red()
-            );
+        if !helper.completer.output.is_json() {
+            println!("Fuzzy search results for '{}':", search_term);
         }
+        hydrate(helper, &keys).await;
+        sort_results(helper, &mut keys, sort);
+        print_results(helper, &keys, search_term);
         helper.completer.search_result = keys;
     }
+    Ok(())
+}
+
+/// Best effort: results that cannot be fetched are shown as unavailable.
+async fn hydrate(helper: &mut ParamStoreHelper, keys: &[String]) {
+    if let Err(err) = helper.completer.hydrate(keys).await {
+        log::warn!("Could not fetch search results: {}", err);
+    }
 }
@@ -1,27 +1,39 @@
+use crate::completer::ParameterCompleter;
 use crate::helper::ParamStoreHelper;
 use colored::Colorize;
 
-/// Handles `sel <index>` – selects a parameter from the last search results by numeric index.
-/// Returns the selected parameter path, or an error if the index is invalid.
-pub fn select_by_index(
+/// How many candidates an ambiguous `sel` lists before cutting off.
+const AMBIGUOUS_LIMIT: usize = 10;
+
+/// Handles `sel <index|path|-N|@alias>` – selects a parameter from the last
+/// search results by numeric index, a known parameter by full or partial
+/// path, a recent entry or an alias.
+/// Returns the selected parameter path, or an error if nothing or more than
+/// one parameter matches.
+pub fn select(
     helper: &mut ParamStoreHelper,
     arg: &str,
 ) -> Result<String, Box<dyn std::error::Error>> {
+    let arg = arg.trim();
     if arg.is_empty() {
         return Err("No parameter selected".into());
     }
 
-    let index = arg
-        .parse::<usize>()
-        .map_err(|_| "Argument must be a numeric index from search results")?;
-
-    let search_result = helper.completer.search_result.clone();
-
-    if index >= search_result.len() {
-        return Err("Invalid index selected".into());
-    }
-
-    let selected_param = search_result[index].clone();
+    let completer = &helper.completer;
+    let selected_param = if arg.starts_with('@') {
+        completer.aliases.resolve(arg)?
+    } else if arg.starts_with('-') {
+        completer.recent.get(arg)?.to_string()
+    } else if let Ok(index) = arg.parse::<usize>() {
+        completer
+            .search_result
+            .get(index)
+            .cloned()
+            .ok_or("Invalid index selected")?
+    } else {
+        match_path(completer, arg)?
+    };
+    completer.check_scope(&selected_param)?;
 
     helper
         .completer
@@ -31,3 +43,43 @@ pub fn select_by_index(
     println!("Selected parameter: {}", selected_param.green());
     Ok(selected_param)
 }
+
+/// The one known parameter `arg` names: the parameter itself, or the only one
+/// whose path contains it. When several do, a parameter that ends with `arg`
+/// as whole path segments wins if it is the only such one, so `db/host`
+/// picks `/app/db/host` over `/app/db/hostname`.
+fn match_path(completer: &ParameterCompleter, arg: &str) -> Result<String, String> {
+    if completer.is_parameter(arg) && completer.in_scope(arg) {
+        return Ok(arg.to_string());
+    }
+
+    let candidates = completer.parameters_containing(arg);
+    if let [only] = candidates.as_slice() {
+        return Ok(only.clone());
+    }
+
+    let suffix = format!("/{}", arg.trim_start_matches('/'));
+    let ending: Vec<&String> = candidates.iter().filter(|c| c.ends_with(&suffix)).collect();
+    if let [only] = ending.as_slice() {
+        return Ok((*only).clone());
+    }
+
+    match candidates.len() {
+        0 => Err(format!("No parameter matches '{}'", arg)),
+        count => {
+            let mut listed = candidates
+                .iter()
+                .take(AMBIGUOUS_LIMIT)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            if count > AMBIGUOUS_LIMIT {
+                listed.push_str(&format!(", and {} more", count - AMBIGUOUS_LIMIT));
+            }
+            Err(format!(
+                "'{}' matches {} parameters: {}",
+                arg, count, listed
+            ))
+        }
+    }
+}
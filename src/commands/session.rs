@@ -0,0 +1,94 @@
+use crate::helper::ParamStoreHelper;
+use crate::sessions::{self, SavedSession};
+use crate::utils::{parse_region, region_spec};
+use colored::Colorize;
+
+/// Handles `session save <name>`, `session load <name>` and `session [list]`.
+/// `selected` is the REPL's current selection, saved and restored with the rest.
+pub async fn manage_session(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    selected: &mut String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = helper.completer.store.sessions();
+    let mut parts = args.split_whitespace();
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, ..) | (Some("list"), None, _) => {
+            let names = sessions::list(&dir);
+            if names.is_empty() {
+                println!("No saved sessions. Use 'session save <name>' to save one.");
+            }
+            for name in names {
+                println!("  {}", name.cyan());
+            }
+        }
+        (Some("save"), Some(name), None) => {
+            let completer = &helper.completer;
+            let saved = SavedSession {
+                base_path: completer.base_path.clone(),
+                region: region_spec(&completer.region),
+                profile: completer.client_options.profile.clone(),
+                selected: selected.clone(),
+                search_result: completer.search_result.clone(),
+                aliases: completer.aliases.entries.clone(),
+                saved_at: 0,
+            };
+            sessions::save(&dir, name, &saved)?;
+            println!("Saved session {}", name.cyan());
+        }
+        (Some("load"), Some(name), None) => {
+            let saved = sessions::load(&dir, name)?;
+            restore(helper, &saved).await?;
+            *selected = saved.selected;
+            println!(
+                "Loaded session {} ({} in {})",
+                name.cyan(),
+                helper.completer.base_path.green(),
+                helper.completer.region.name()
+            );
+            if !selected.is_empty() {
+                println!("Selected parameter: {}", selected.green());
+            }
+        }
+        _ => {
+            return Err("Usage: session save <name> | session load <name> | session list".into());
+        }
+    }
+    Ok(())
+}
+
+/// Points the completer back at the saved region, profile and base path,
+/// reloading parameters only when one of them changed.
+async fn restore(
+    helper: &mut ParamStoreHelper,
+    saved: &SavedSession,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    let client_changed = region_spec(&completer.region) != saved.region
+        || completer.client_options.profile != saved.profile;
+    let base_changed = completer.base_path != saved.base_path;
+
+    if client_changed {
+        completer.switch_client(parse_region(&saved.region)?, saved.profile.clone())?;
+    }
+    if base_changed {
+        completer.switch_base_path(&saved.base_path);
+    }
+    if client_changed || base_changed {
+        // A different region or profile means the cached values belong to
+        // another account or region, so bypass the cache.
+        let refresh = completer.refresh;
+        completer.refresh = client_changed;
+        let result = completer.load_parameters().await;
+        completer.refresh = refresh;
+        result?;
+    }
+
+    completer.search_result = saved.search_result.clone();
+    completer
+        .metadata
+        .insert("selected".to_string(), saved.selected.clone());
+    completer.aliases.extend(&saved.aliases)?;
+    Ok(())
+}
@@ -1,15 +1,166 @@
+use crate::commands::size::check_value_size;
+use crate::generate::Generator;
 use crate::helper::ParamStoreHelper;
+use crate::names::check_name;
+use crate::utils::{prompt_hidden, prompt_line, split_change, take_flag};
+use colored::Colorize;
+use std::io::IsTerminal;
 
-/// Handles the `set <value>` command.
-/// `value` is the already-parsed argument (everything after "set ").
-/// Sets the currently selected parameter to the given value in AWS SSM and updates the local cache.
+/// Works out which parameter `set` changes and the value part of `args`:
+/// `#N <value>` is entry `N` of the last search results, `<path> <value>` (or
+/// `@alias <value>`) a known parameter, and anything else is a value for the
+/// selected parameter. A first word starting with `/` or `@` that names no
+/// known parameter is an error rather than a value.
+pub fn target<'a>(
+    helper: &ParamStoreHelper,
+    args: &'a str,
+    selected: &str,
+) -> Result<(String, &'a str), String> {
+    let (first, rest) = args.split_once(' ').unwrap_or((args, ""));
+    if let Some(index) = first.strip_prefix('#') {
+        let results = &helper.completer.search_result;
+        let index: usize = index
+            .parse()
+            .map_err(|_| format!("'{}' is not a search result index", first))?;
+        let path = results.get(index).ok_or_else(|| match results.len() {
+            0 => "No search results; run 'search' first".to_string(),
+            n => format!(
+                "Invalid index #{}: the last search has {} result(s)",
+                index, n
+            ),
+        })?;
+        return Ok((path.clone(), rest.trim_start()));
+    }
+    if first.starts_with(['/', '@']) {
+        let path = helper.completer.aliases.resolve(first)?;
+        if !helper.completer.is_parameter(&path) {
+            return Err(format!(
+                "Unknown parameter '{}'; use set <path> <value> for a value that starts with '/'",
+                first
+            ));
+        }
+        return Ok((path, rest.trim_start()));
+    }
+    if selected.is_empty() {
+        return Err("No parameter selected; use set <path> <value> or set #<index> <value>".into());
+    }
+    Ok((selected.to_string(), args))
+}
+
+/// Handles the `set [<path>|#<index>] <value>` command.
+/// `args` is the already-parsed argument (everything after "set "); the
+/// parameter is picked by `target`, the selected one by default.
+/// Sets the parameter to the given value in AWS SSM and updates the local
+/// cache, returning the path and the value.
+/// In offline mode the change is queued instead. A bare `set` on a secret
+/// parameter reads the value without echo, and `set --generate...` uses a
+/// freshly generated value (see `Generator::parse`). Typed values for
+/// password/secret paths must meet the secret policy unless `--no-validate`
+/// is given. The current value, fetched fresh unless offline, is shown above
+/// the new one first. Protected paths need their name typed back, or `--yes`;
+/// other secrets are confirmed with `[y/N]` at a terminal unless `--yes` is
+/// given.
 pub async fn set_value(
     helper: &mut ParamStoreHelper,
-    value: &str,
-    path: &str,
-) -> Result<String, Box<dyn std::error::Error>> {
-    println!("Setting parameter: {}", path);
-    let value = helper.completer.change_value(path, value.to_string()).await?;
-    println!("Set value: {}", value);
-    Ok(value)
+    args: &str,
+    selected: &str,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let (args, yes) = take_flag(args, "--yes");
+    let (args, no_validate) = take_flag(args, "--no-validate");
+    let (path, value) = target(helper, args, selected)?;
+    let path = path.as_str();
+    helper
+        .completer
+        .progress(&format!("Setting parameter: {}", path));
+    let owned;
+    let value = if let Some(generator) = Generator::parse(value)? {
+        owned = generator.generate();
+        owned.as_str()
+    } else {
+        let value = if value.is_empty() {
+            if !helper.completer.is_sensitive(path) {
+                return Err(
+                    "Usage: set <value> (a bare 'set' prompts for SecureString values)".into(),
+                );
+            }
+            owned = prompt_hidden(&format!("New value for {} (hidden): ", path))?;
+            owned.as_str()
+        } else {
+            value
+        };
+        if !no_validate {
+            helper.completer.secret_policy.check(path, value)?;
+        }
+        value
+    };
+
+    check_name(path)?;
+    check_value_size(path, value, helper.completer.chunking)?;
+    if !helper.completer.quiet && !helper.completer.output.is_json() {
+        let current = if helper.completer.offline {
+            helper.completer.cached_value(path)
+        } else {
+            Some(helper.completer.get_set_value(path).await?)
+        };
+        if let Some(current) = current {
+            let secret = helper.completer.redact || helper.completer.is_sensitive(path);
+            print_change(&current, value, secret);
+        }
+    }
+    if helper.completer.protected.matching(path).is_some() {
+        helper.completer.protected.confirm(path, "set", yes)?;
+    } else if !yes
+        && helper.completer.is_sensitive(path)
+        && std::io::stdin().is_terminal()
+        && !prompt_line(&format!("Change secret {}? [y/N]: ", path))?.eq_ignore_ascii_case("y")
+    {
+        return Err(format!("Set cancelled; {} was left unchanged", path).into());
+    }
+
+    if helper.completer.offline {
+        helper.completer.queue_change(path, value, None)?;
+        println!(
+            "Offline: queued change for {} (run 'push' when back online)",
+            path
+        );
+        return Ok((path.to_string(), value.to_string()));
+    }
+
+    let value = helper
+        .completer
+        .change_value(path, value.to_string())
+        .await?;
+    let shown = helper.completer.shown(&value);
+    helper.completer.progress(&format!("Set value: {}", shown));
+    Ok((path.to_string(), value))
+}
+
+/// Shows the current value above the new one with the part that differs
+/// highlighted, so typos and truncation are caught before anything is
+/// written. Secrets are compared by length only.
+pub fn print_change(current: &str, new: &str, secret: bool) {
+    if current == new {
+        println!("  {}", "(value unchanged)".dimmed());
+        return;
+    }
+    if secret {
+        println!("  {} {} characters", "-".red(), current.chars().count());
+        println!("  {} {} characters", "+".green(), new.chars().count());
+        return;
+    }
+    let (prefix, removed, added, suffix) = split_change(current, new);
+    println!(
+        "  {} {}{}{}",
+        "-".red(),
+        prefix,
+        removed.red().bold(),
+        suffix
+    );
+    println!(
+        "  {} {}{}{}",
+        "+".green(),
+        prefix,
+        added.green().bold(),
+        suffix
+    );
 }
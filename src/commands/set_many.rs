@@ -0,0 +1,391 @@
+use crate::commands::set::print_change;
+use crate::commands::size::check_value_size;
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::names::check_name;
+use crate::output;
+use crate::utils::{prompt_line, split_words};
+use crate::vocabulary::PARAMETER_TYPES;
+use colored::Colorize;
+use serde_json::json;
+use std::io::IsTerminal;
+
+const USAGE: &str = "Usage: set-many <prefix> <key>=<value>... [--from-file <file>] [--type <type>] [--dry-run] [--yes] [--no-validate]";
+
+/// What `set-many` was asked for. Quote values that contain spaces.
+#[derive(Debug, Default, PartialEq)]
+pub struct SetManyArgs {
+    pub prefix: String,
+    /// Keys relative to the prefix and their values, in the order given.
+    pub pairs: Vec<(String, String)>,
+    pub from_file: Option<String>,
+    /// Type of the parameters that are created; existing ones keep theirs.
+    pub param_type: Option<String>,
+    pub dry_run: bool,
+    pub yes: bool,
+    pub no_validate: bool,
+}
+
+impl SetManyArgs {
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut parsed = SetManyArgs::default();
+        let mut words = split_words(args)?.into_iter();
+        while let Some(word) = words.next() {
+            match word.as_str() {
+                "--from-file" => parsed.from_file = Some(words.next().ok_or(USAGE)?),
+                "--type" => parsed.param_type = Some(words.next().ok_or(USAGE)?),
+                "--dry-run" => parsed.dry_run = true,
+                "--yes" => parsed.yes = true,
+                "--no-validate" => parsed.no_validate = true,
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option {}. {}", flag, USAGE));
+                }
+                _ if parsed.prefix.is_empty() => parsed.prefix = word,
+                _ => parsed.pairs.push(parse_pair(&word)?),
+            }
+        }
+        if parsed.prefix.is_empty() || (parsed.pairs.is_empty() && parsed.from_file.is_none()) {
+            return Err(USAGE.to_string());
+        }
+        if let Some(param_type) = &parsed.param_type
+            && !PARAMETER_TYPES.contains(&param_type.as_str())
+        {
+            return Err(format!(
+                "Unknown type '{}' (expected {})",
+                param_type,
+                PARAMETER_TYPES.join(", ")
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Splits `key=value`; the key is a path relative to the prefix.
+fn parse_pair(word: &str) -> Result<(String, String), String> {
+    match word.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !key.starts_with('/') => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!(
+            "Expected <key>=<value> with a key relative to the prefix, got '{}'",
+            word
+        )),
+    }
+}
+
+/// Reads `key=value` pairs from a file, one or more per line and quoted like
+/// on the command line. Blank lines and lines starting with `#` are skipped.
+pub fn read_pairs(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut pairs = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        for word in split_words(line).map_err(|e| format!("Line {}: {}", index + 1, e))? {
+            pairs.push(parse_pair(&word).map_err(|e| format!("Line {}: {}", index + 1, e))?);
+        }
+    }
+    Ok(pairs)
+}
+
+/// What `set-many` does to one parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Created,
+    Updated,
+    Unchanged,
+    Failed(String),
+}
+
+/// One parameter `set-many` writes.
+struct Write {
+    path: String,
+    value: String,
+    /// The current value; `None` when the parameter is created.
+    current: Option<String>,
+    /// Type of a created parameter.
+    param_type: String,
+}
+
+/// Whether a `set-many` line types a secret value inline, so it is kept out
+/// of the history: one of its keys is, or would be created as, a secret.
+pub fn has_inline_secret(completer: &ParameterCompleter, args: &str) -> bool {
+    let Ok(args) = SetManyArgs::parse(args) else {
+        return false;
+    };
+    let Ok(prefix) = completer.aliases.resolve(&args.prefix) else {
+        return false;
+    };
+    let policy = &completer.type_policy;
+    args.pairs.iter().any(|(key, _)| {
+        let path = join(&prefix, key);
+        if completer.is_parameter(&path) {
+            completer.is_sensitive(&path)
+        } else {
+            policy
+                .resolve(&path, args.param_type.as_deref(), policy.default_type())
+                .is_ok_and(|param_type| param_type == "SecureString")
+        }
+    })
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    format!("{}/{}", prefix.trim_end_matches('/'), key)
+}
+
+/// Handles `set-many <prefix> <key>=<value>... [--from-file <file>] [--type
+/// <type>] [--dry-run] [--yes] [--no-validate]`: creates or updates several
+/// parameters under `prefix` at once. Every key is checked first (name, size,
+/// scope, type policy and, unless `--no-validate`, the secret policy), then
+/// all changes are previewed together and written after one confirmation;
+/// protected paths need `--yes`. Pairs from `--from-file` come after those on
+/// the command line. Offline, the changes are queued for `push`. Returns the
+/// outcome per parameter.
+pub async fn set_many(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<Vec<(String, Outcome)>, Box<dyn std::error::Error>> {
+    let mut args = SetManyArgs::parse(args)?;
+    if let Some(file) = &args.from_file {
+        let content =
+            std::fs::read_to_string(file).map_err(|e| format!("Cannot read {}: {}", file, e))?;
+        args.pairs
+            .extend(read_pairs(&content).map_err(|e| format!("{}: {}", file, e))?);
+    }
+    if args.pairs.is_empty() {
+        return Err(format!("No <key>=<value> pairs given. {}", USAGE).into());
+    }
+
+    let completer = &mut helper.completer;
+    let prefix = completer.aliases.resolve(&args.prefix)?;
+    if !prefix.starts_with('/') {
+        return Err(USAGE.into());
+    }
+    let mut paths: Vec<String> = Vec::new();
+    for (key, _) in &args.pairs {
+        let path = join(&prefix, key);
+        if paths.contains(&path) {
+            return Err(format!("{} is given more than once", path).into());
+        }
+        paths.push(path);
+    }
+    let existing: Vec<String> = paths
+        .iter()
+        .filter(|path| completer.is_parameter(path))
+        .cloned()
+        .collect();
+    if !completer.offline {
+        completer.hydrate(&existing).await?;
+    }
+    let current = completer.cached_values(&existing);
+
+    let mut writes = Vec::new();
+    let mut protected = Vec::new();
+    for (path, (_, value)) in paths.into_iter().zip(args.pairs) {
+        check_name(&path)?;
+        completer.check_scope(&path)?;
+        check_value_size(&path, &value, completer.chunking)?;
+        if !args.no_validate {
+            completer.secret_policy.check(&path, &value)?;
+        }
+        let current = if completer.is_parameter(&path) {
+            let value = current.get(&path).cloned();
+            Some(value.ok_or_else(|| format!("The current value of {} is not available", path))?)
+        } else {
+            None
+        };
+        let param_type = match &current {
+            Some(_) => String::new(),
+            None => completer.type_policy.resolve(
+                &path,
+                args.param_type.as_deref(),
+                completer.type_policy.default_type(),
+            )?,
+        };
+        if let Some(pattern) = completer.protected.matching(&path) {
+            protected.push(format!("{} ('{}')", path, pattern));
+        }
+        writes.push(Write {
+            path,
+            value,
+            current,
+            param_type,
+        });
+    }
+
+    if !completer.output.is_json() {
+        for write in &writes {
+            preview(completer, write);
+        }
+    }
+    let planned: Vec<(String, Outcome)> = writes
+        .iter()
+        .map(|write| (write.path.clone(), write.planned()))
+        .collect();
+    let changing = planned
+        .iter()
+        .filter(|(_, outcome)| *outcome != Outcome::Unchanged)
+        .count();
+    if args.dry_run || changing == 0 {
+        print_report(completer, &planned, args.dry_run);
+        return Ok(planned);
+    }
+    if !protected.is_empty() && !args.yes {
+        return Err(format!(
+            "Protected paths would change: {}; pass --yes to set them",
+            protected.join(", ")
+        )
+        .into());
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass --yes to run set-many without a prompt".into());
+        }
+        if !prompt_line("Apply these changes? [y/N]: ")?.eq_ignore_ascii_case("y") {
+            println!("Set-many cancelled");
+            return Ok(Vec::new());
+        }
+    }
+
+    let mut report = Vec::with_capacity(writes.len());
+    for write in writes {
+        let outcome = match apply(completer, &write).await {
+            Ok(outcome) => outcome,
+            Err(err) => Outcome::Failed(err.to_string()),
+        };
+        report.push((write.path, outcome));
+    }
+    print_report(completer, &report, false);
+    let failed = report
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+        .count();
+    if failed > 0 {
+        return Err(format!(
+            "{} of {} parameter(s) could not be set",
+            failed,
+            report.len()
+        )
+        .into());
+    }
+    Ok(report)
+}
+
+impl Write {
+    fn planned(&self) -> Outcome {
+        match &self.current {
+            None => Outcome::Created,
+            Some(current) if *current == self.value => Outcome::Unchanged,
+            Some(_) => Outcome::Updated,
+        }
+    }
+}
+
+/// Shows one write of the combined preview: new parameters with their type,
+/// changed values as a diff. Secret values are never shown.
+fn preview(completer: &ParameterCompleter, write: &Write) {
+    match &write.current {
+        None => {
+            let secret = completer.redact || write.param_type == "SecureString";
+            let shown = if secret { "********" } else { &write.value };
+            println!(
+                "{} {} [{}] = {}",
+                "+".green(),
+                write.path.green(),
+                write.param_type,
+                shown
+            );
+        }
+        Some(current) if *current == write.value => {
+            println!("{} {}", "=".dimmed(), write.path.dimmed());
+        }
+        Some(current) => {
+            println!("{} {}", "~".yellow(), write.path.green());
+            let secret = completer.redact || completer.is_sensitive(&write.path);
+            print_change(current, &write.value, secret);
+        }
+    }
+}
+
+async fn apply(
+    completer: &mut ParameterCompleter,
+    write: &Write,
+) -> Result<Outcome, Box<dyn std::error::Error>> {
+    match &write.current {
+        Some(current) if *current == write.value => Ok(Outcome::Unchanged),
+        Some(_) if completer.offline => {
+            completer.queue_change(&write.path, &write.value, None)?;
+            Ok(Outcome::Updated)
+        }
+        Some(_) => {
+            completer
+                .change_value(&write.path, write.value.clone())
+                .await?;
+            Ok(Outcome::Updated)
+        }
+        None if completer.offline => {
+            completer.queue_change(&write.path, &write.value, Some(write.param_type.clone()))?;
+            Ok(Outcome::Created)
+        }
+        None => {
+            completer
+                .set_parameter(
+                    &write.path,
+                    write.value.clone(),
+                    Some(write.param_type.clone()),
+                )
+                .await?;
+            completer
+                .update_all(&write.path, write.value.clone())
+                .await?;
+            Ok(Outcome::Created)
+        }
+    }
+}
+
+/// The per-parameter result, or with `dry_run` what would happen.
+fn print_report(completer: &ParameterCompleter, report: &[(String, Outcome)], dry_run: bool) {
+    if completer.output.is_json() {
+        for (path, outcome) in report {
+            let (result, error) = match outcome {
+                Outcome::Created => ("created", None),
+                Outcome::Updated => ("updated", None),
+                Outcome::Unchanged => ("unchanged", None),
+                Outcome::Failed(err) => ("failed", Some(err)),
+            };
+            output::print_json(json!({
+                "path": path,
+                "result": result,
+                "error": error,
+                "dry_run": dry_run,
+                "queued": completer.offline && !dry_run && error.is_none(),
+            }));
+        }
+        return;
+    }
+    for (path, outcome) in report {
+        let label = match outcome {
+            Outcome::Created => "created".green().to_string(),
+            Outcome::Updated => "updated".yellow().to_string(),
+            Outcome::Unchanged => "unchanged".dimmed().to_string(),
+            Outcome::Failed(err) => format!("failed: {}", err).red().to_string(),
+        };
+        println!("  {:<60} {}", path, label);
+    }
+    let count = |f: fn(&Outcome) -> bool| report.iter().filter(|(_, o)| f(o)).count();
+    println!(
+        "{}: {} created, {} updated, {} unchanged, {} failed",
+        if dry_run {
+            "Dry run, nothing written; would be"
+        } else if completer.offline {
+            "Offline, queued for 'push'"
+        } else {
+            "Set-many finished"
+        },
+        count(|o| matches!(o, Outcome::Created)),
+        count(|o| matches!(o, Outcome::Updated)),
+        count(|o| matches!(o, Outcome::Unchanged)),
+        count(|o| matches!(o, Outcome::Failed(_))),
+    );
+}
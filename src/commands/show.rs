@@ -0,0 +1,49 @@
+use crate::display_filter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::{page, take_flag};
+use colored::Colorize;
+use serde_json::json;
+
+const USAGE: &str = "Usage: show [path] [| filter...] [--pager]";
+
+/// Handles `show [path] [| filter...] [--pager]`: prints the whole value of a
+/// parameter (the selected one by default), which `get` and `search` cut to
+/// `value_width`. `--pager` opens it in `$PAGER` instead.
+pub async fn show(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    selected: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    let (args, pager) = take_flag(args.trim(), "--pager");
+    let (target, filters) = display_filter::split(args)?;
+    let path = match target.as_str() {
+        "" if selected.is_empty() => return Err(USAGE.into()),
+        "" => selected.to_string(),
+        target => completer.aliases.resolve(target)?,
+    };
+    if !completer.is_parameter(&path) {
+        return Err(format!("{} is not a known parameter", path).into());
+    }
+    if completer.values.is_unavailable(&path) && !completer.offline {
+        completer.get_set_value(&path).await?;
+    }
+    let value = completer.cached_value(&path).ok_or_else(|| {
+        format!(
+            "No cached value for {}. Try 'reload-by-path {}'.",
+            path, path
+        )
+    })?;
+    let value = display_filter::apply(&value, &filters).map_err(|e| format!("{}: {}", path, e))?;
+    let value = completer.shown(&value);
+
+    if completer.output.is_json() {
+        output::print_json(json!({ "path": path, "value": value }));
+    } else if pager {
+        page(&format!("{}\n", value))?;
+    } else {
+        println!("{}:\n{}", path.green(), value);
+    }
+    Ok(path)
+}
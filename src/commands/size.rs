@@ -0,0 +1,144 @@
+use crate::chunking;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use colored::Colorize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Largest value of a Standard tier parameter, in bytes.
+pub const STANDARD_LIMIT: usize = 4 * 1024;
+/// Largest value of an Advanced tier parameter, in bytes.
+pub const ADVANCED_LIMIT: usize = 8 * 1024;
+/// Values above this share of the Standard limit are flagged as close to it.
+const NEAR_LIMIT: usize = STANDARD_LIMIT * 9 / 10;
+
+/// Describes how a value of `len` bytes relates to the tier limits, or `None`
+/// when it fits comfortably in the Standard tier.
+pub fn size_warning(len: usize) -> Option<String> {
+    if len > ADVANCED_LIMIT {
+        Some(format!(
+            "{} bytes is over the 8 KB Advanced tier limit; split the value across several parameters",
+            len
+        ))
+    } else if len > STANDARD_LIMIT {
+        Some(format!(
+            "{} bytes is over the 4 KB Standard tier limit; it needs the Advanced tier",
+            len
+        ))
+    } else if len >= NEAR_LIMIT {
+        Some(format!(
+            "{} bytes is close to the 4 KB Standard tier limit",
+            len
+        ))
+    } else {
+        None
+    }
+}
+
+/// Refuses values no tier can hold, before PutParameter is even tried. With
+/// `chunking` on, large values are split on write instead.
+pub fn check_value_size(path: &str, value: &str, chunking: bool) -> Result<(), String> {
+    if chunking && chunking::needs_chunking(value) {
+        let count = chunking::split(value).len();
+        if count > chunking::MAX_CHUNKS {
+            return Err(format!(
+                "Cannot write {}: {} bytes needs {} chunks, at most {} are allowed",
+                path,
+                value.len(),
+                count,
+                chunking::MAX_CHUNKS
+            ));
+        }
+        return Ok(());
+    }
+    if value.len() > ADVANCED_LIMIT {
+        return Err(format!(
+            "Cannot write {}: {}",
+            path,
+            size_warning(value.len()).unwrap_or_default()
+        ));
+    }
+    if let Some(warning) = size_warning(value.len()) {
+        println!(
+            "{} {}: {}",
+            "⚠ Size:".yellow().bold(),
+            path.yellow(),
+            warning
+        );
+    }
+    Ok(())
+}
+
+/// Handles `size [prefix]`: lists the size of every cached value under
+/// `prefix` (the base path by default), largest first, followed by the total
+/// per subtree. Values near or over the tier limits are flagged.
+pub fn size(helper: &ParamStoreHelper, prefix: &str) {
+    let completer = &helper.completer;
+    let prefix = if prefix.is_empty() {
+        completer.root()
+    } else {
+        prefix
+    };
+
+    let paths: Vec<String> = completer
+        .values
+        .keys()
+        .filter(|path| path.starts_with(prefix) && completer.in_scope(path))
+        .cloned()
+        .collect();
+    let values = completer.cached_values(&paths);
+    let mut sizes: Vec<(&str, usize)> = values
+        .iter()
+        .map(|(path, value)| (path.as_str(), value.len()))
+        .collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    // Every directory of a parameter within the prefix gets its size added.
+    let mut subtrees: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for (path, len) in &sizes {
+        for (index, _) in path.match_indices('/') {
+            let dir = &path[..=index];
+            if !dir.starts_with(prefix) {
+                continue;
+            }
+            let entry = subtrees.entry(dir).or_default();
+            entry.0 += 1;
+            entry.1 += len;
+        }
+    }
+
+    if completer.output.is_json() {
+        for (path, len) in &sizes {
+            output::print_json(json!({
+                "path": path,
+                "bytes": len,
+                "warning": size_warning(*len),
+            }));
+        }
+        for (path, (count, total)) in &subtrees {
+            output::print_json(json!({ "subtree": path, "parameters": count, "bytes": total }));
+        }
+        return;
+    }
+
+    if sizes.is_empty() {
+        println!("No cached values under {}", prefix);
+        return;
+    }
+    for (path, len) in &sizes {
+        match size_warning(*len) {
+            Some(warning) if *len > STANDARD_LIMIT => {
+                println!("{:>8}  {}  {}", len, path.red(), warning.red())
+            }
+            Some(warning) => println!("{:>8}  {}  {}", len, path.yellow(), warning.yellow()),
+            None => println!("{:>8}  {}", len, path),
+        }
+    }
+    if subtrees.is_empty() {
+        return;
+    }
+    println!("\n{}", "Subtree totals".bold());
+    for (path, (count, total)) in &subtrees {
+        println!("{:>8}  {} ({} parameter(s))", total, path.cyan(), count);
+    }
+}
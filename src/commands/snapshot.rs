@@ -0,0 +1,402 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::snapshots::{self, Change, Snapshot, SnapshotEntry};
+use crate::utils::{format_timestamp, prompt_line, region_spec};
+use colored::Colorize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "Usage: snapshot create <name> [prefix] | snapshot list | snapshot diff <name> | snapshot restore <name> [--delete-extra] [--dry-run] [--yes] | snapshot rm <name> | snapshot auto [on|off]";
+
+/// Handles the `snapshot` subcommands: `create` saves the names, types and
+/// values under a prefix to the store directory, `diff` compares a snapshot
+/// with Parameter Store now and `restore` writes the snapshot back. `auto`
+/// turns the automatic snapshots taken before the session's writes on or off.
+pub async fn snapshot(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match words[..] {
+        [] | ["list"] => list(helper),
+        ["create", name] => create(helper, name, None).await,
+        ["create", name, prefix] => create(helper, name, Some(prefix)).await,
+        ["diff", name] => diff(helper, name).await,
+        ["auto"] => {
+            let policy = &helper.completer.auto_snapshots;
+            let state = if policy.enabled { "on" } else { "off" };
+            println!(
+                "Automatic snapshots are {} (keeping {} for up to {} days)",
+                state.cyan(),
+                policy.keep,
+                policy.max_age_days
+            );
+            Ok(())
+        }
+        ["auto", state @ ("on" | "off")] => {
+            let completer = &mut helper.completer;
+            if state == "on" && !completer.encryption.can_persist_values() {
+                return Err(snapshots::AUTO_NEEDS_KEY.into());
+            }
+            completer.auto_snapshots.enabled = state == "on";
+            println!(
+                "Automatic snapshots {}",
+                if state == "on" { "on" } else { "off" }.cyan()
+            );
+            Ok(())
+        }
+        ["restore", name, ref options @ ..] => restore(helper, name, options).await,
+        ["rm", name] => {
+            snapshots::validate_name(name)?;
+            let file = helper
+                .completer
+                .store
+                .snapshots()
+                .join(format!("{}.json", name));
+            match std::fs::remove_file(file) {
+                Ok(()) => println!("Removed snapshot {}", name.cyan()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(format!("No snapshot named '{}'", name).into());
+                }
+                Err(e) => return Err(e.into()),
+            }
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn list(helper: &ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = helper.completer.store.snapshots();
+    let names = snapshots::list(&dir);
+    let json = helper.completer.output.is_json();
+    if names.is_empty() && !json {
+        println!("No snapshots. Use 'snapshot create <name> [prefix]' to take one.");
+    }
+    for name in names {
+        let snapshot = snapshots::load(&dir, &name)?;
+        let created = format_timestamp(snapshot.created_at as f64);
+        let count = snapshot.paths().len();
+        if json {
+            output::print_json(json!({
+                "snapshot": name,
+                "prefix": snapshot.prefix,
+                "region": snapshot.region,
+                "parameters": count,
+                "created": created,
+                "auto": snapshot.auto,
+            }));
+        } else {
+            let prefix = if snapshot.auto {
+                "(automatic)"
+            } else {
+                snapshot.prefix.as_str()
+            };
+            println!(
+                "  {:<24} {:<30} {:>5} parameter(s)  {} UTC",
+                name.cyan(),
+                prefix,
+                count,
+                created.dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Takes a snapshot of the parameters under `prefix` (the scope or base path
+/// by default), fetched fresh from AWS, or from the cache when offline.
+async fn create(
+    helper: &mut ParamStoreHelper,
+    name: &str,
+    prefix: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    snapshots::validate_name(name)?;
+    let completer = &helper.completer;
+    if !completer.encryption.can_persist_values() {
+        return Err(
+            "Snapshots keep values on disk: set DAPS_ENCRYPTION_KEY to encrypt them, \
+             or pass --allow-plaintext-cache"
+                .into(),
+        );
+    }
+    let prefix = match prefix {
+        Some(prefix) => completer.aliases.resolve(prefix)?,
+        None => completer.root().to_string(),
+    };
+    if !prefix.starts_with('/') {
+        return Err(USAGE.into());
+    }
+    let prefix = match prefix.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+
+    let values = values_under(helper, &prefix).await?;
+    if values.is_empty() {
+        return Err(format!("No parameters under {}", prefix).into());
+    }
+    let completer = &helper.completer;
+    let parameters = values
+        .into_iter()
+        .map(|(path, value)| {
+            let entry = SnapshotEntry {
+                param_type: completer.types.get(&path).cloned(),
+                value: completer.encryption.encrypt_value(&value),
+            };
+            (path, entry)
+        })
+        .collect();
+    let snapshot = Snapshot {
+        prefix,
+        region: region_spec(&completer.region),
+        profile: completer.client_options.profile.clone(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        encrypted: completer.encryption.enabled,
+        parameters,
+        auto: false,
+        absent: Vec::new(),
+    };
+    snapshots::save(&completer.store.snapshots(), name, &snapshot)?;
+    println!(
+        "Saved snapshot {} of {} ({} parameter(s))",
+        name.cyan(),
+        snapshot.prefix.green(),
+        snapshot.parameters.len()
+    );
+    Ok(())
+}
+
+async fn diff(helper: &mut ParamStoreHelper, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (snapshot, saved) = open(helper, name)?;
+    let current = current_values(helper, &snapshot).await?;
+    let changes = snapshots::plan(&saved, &current, true);
+    if changes.is_empty() {
+        println!(
+            "{} matches snapshot {}",
+            snapshot.prefix.green(),
+            name.cyan()
+        );
+        return Ok(());
+    }
+    let json = helper.completer.output.is_json();
+    for (path, change) in &changes {
+        let (label, marker) = match change {
+            Change::Create => ("deleted", "-".red()),
+            Change::Update => ("changed", "~".yellow()),
+            Change::Delete => ("added", "+".green()),
+        };
+        if json {
+            output::print_json(json!({ "path": path, "change": label }));
+        } else {
+            println!("  {} {:<60} {}", marker, path, label.dimmed());
+        }
+    }
+    if !json {
+        println!(
+            "{} change(s) since {} UTC",
+            changes.len(),
+            format_timestamp(snapshot.created_at as f64)
+        );
+    }
+    Ok(())
+}
+
+/// Writes the values of a snapshot back: parameters deleted since are
+/// created again and changed ones are set back. Parameters created since the
+/// snapshot are only deleted with `--delete-extra`.
+async fn restore(
+    helper: &mut ParamStoreHelper,
+    name: &str,
+    options: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut delete_extra, mut dry_run, mut yes) = (false, false, false);
+    for option in options {
+        match *option {
+            "--delete-extra" => delete_extra = true,
+            "--dry-run" => dry_run = true,
+            "--yes" => yes = true,
+            other => return Err(format!("Unknown option {}. {}", other, USAGE).into()),
+        }
+    }
+    if helper.completer.offline && !dry_run {
+        return Err("snapshot restore writes to AWS and is not available offline".into());
+    }
+    let (snapshot, saved) = open(helper, name)?;
+    let current = current_values(helper, &snapshot).await?;
+    // What an automatic snapshot saw created is always deleted again.
+    let changes = snapshots::plan(&saved, &current, delete_extra || snapshot.auto);
+
+    let json = helper.completer.output.is_json();
+    for (path, change) in &changes {
+        let (action, marker) = match change {
+            Change::Create => ("create", "+".green()),
+            Change::Update => ("update", "~".yellow()),
+            Change::Delete => ("delete", "-".red()),
+        };
+        if json {
+            output::print_json(json!({ "action": action, "path": path }));
+        } else {
+            println!("  {} {:<60} {}", marker, path, action.dimmed());
+        }
+    }
+    if changes.is_empty() {
+        println!(
+            "{} already matches snapshot {}",
+            snapshot.prefix.green(),
+            name.cyan()
+        );
+        return Ok(());
+    }
+    if !delete_extra && !snapshot.auto && !json {
+        let extra = current
+            .keys()
+            .filter(|path| !saved.contains_key(*path))
+            .count();
+        if extra > 0 {
+            println!(
+                "{} parameter(s) created since the snapshot are kept; pass --delete-extra to delete them",
+                extra
+            );
+        }
+    }
+    if dry_run {
+        return Ok(());
+    }
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass --yes to restore a snapshot without a prompt".into());
+        }
+        if !prompt_line("Restore these parameters? [y/N]: ")?.eq_ignore_ascii_case("y") {
+            println!("Restore cancelled");
+            return Ok(());
+        }
+    }
+
+    let completer = &mut helper.completer;
+    let mut failed = 0;
+    for (path, change) in &changes {
+        let result = match change {
+            Change::Create | Change::Update => {
+                let param_type = snapshot.parameters[path].param_type.clone();
+                let value = saved[path].clone();
+                match completer
+                    .set_parameter(path, value.clone(), param_type)
+                    .await
+                {
+                    Ok(()) => completer.update_all(path, value).await,
+                    Err(e) => Err(e),
+                }
+            }
+            Change::Delete => completer.delete_parameter(path).await,
+        };
+        if let Err(err) = result {
+            failed += 1;
+            println!("{} {}: {}", "Failed".red(), path, err);
+        }
+    }
+    println!(
+        "Restored {} of {} parameter(s) from snapshot {}",
+        changes.len() - failed,
+        changes.len(),
+        name.cyan()
+    );
+    if failed > 0 {
+        return Err(format!("{} change(s) failed", failed).into());
+    }
+    Ok(())
+}
+
+/// Loads a snapshot taken against the current region and profile, with its
+/// values decrypted.
+fn open(
+    helper: &ParamStoreHelper,
+    name: &str,
+) -> Result<(Snapshot, BTreeMap<String, String>), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    let snapshot = snapshots::load(&completer.store.snapshots(), name)?;
+    let region = region_spec(&completer.region);
+    if snapshot.region != region || snapshot.profile != completer.client_options.profile {
+        return Err(format!(
+            "Snapshot {} was taken in {} with profile {}; switch to them first",
+            name,
+            snapshot.region,
+            snapshot.profile.as_deref().unwrap_or("default")
+        )
+        .into());
+    }
+    if !snapshot.auto {
+        completer.check_scope(&snapshot.prefix)?;
+    }
+    if snapshot.encrypted && !completer.encryption.enabled {
+        return Err(format!(
+            "Snapshot {} is encrypted; set DAPS_ENCRYPTION_KEY to read it",
+            name
+        )
+        .into());
+    }
+
+    let mut values = BTreeMap::new();
+    for (path, entry) in &snapshot.parameters {
+        let value = if snapshot.encrypted {
+            completer
+                .encryption
+                .try_decrypt(&entry.value)
+                .map_err(|e| {
+                    format!(
+                        "Cannot decrypt {} in snapshot {}: {} (was the cache key rotated?)",
+                        path, name, e
+                    )
+                })?
+        } else {
+            entry.value.clone()
+        };
+        values.insert(path.clone(), value);
+    }
+    Ok((snapshot, values))
+}
+
+/// The values of the parameters a snapshot covers now.
+async fn current_values(
+    helper: &mut ParamStoreHelper,
+    snapshot: &Snapshot,
+) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+    if !snapshot.auto {
+        return values_under(helper, &snapshot.prefix).await;
+    }
+    let completer = &mut helper.completer;
+    let paths = snapshot.paths();
+    let values = if completer.offline {
+        completer.hydrate(&paths).await?;
+        completer.cached_values(&paths)
+    } else {
+        completer.get_set_values_by_names(&paths).await?
+    };
+    Ok(values.into_iter().collect())
+}
+
+/// The values under `prefix` now: from AWS, or from the cache when offline.
+async fn values_under(
+    helper: &mut ParamStoreHelper,
+    prefix: &str,
+) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    let root = format!("{}/", prefix.trim_end_matches('/'));
+    let values = if completer.offline {
+        let paths = completer.parameters_under(&root);
+        completer.hydrate(&paths).await?;
+        completer.cached_values(&paths)
+    } else {
+        completer.progress(&format!("Fetching the parameters under {}", prefix));
+        completer.get_set_values(prefix).await?
+    };
+    Ok(values
+        .into_iter()
+        .filter(|(path, _)| path.starts_with(&root))
+        .collect())
+}
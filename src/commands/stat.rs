@@ -0,0 +1,93 @@
+use crate::completer::Revision;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_date;
+use colored::Colorize;
+use serde_json::json;
+
+/// Handles `stat [path]`: shows the type, data type, version, last change,
+/// value size and allowed pattern of a parameter (the selected one by
+/// default). Everything comes from the
+/// cache, so it works offline; online, a revision the cache does not have yet
+/// is looked up with DescribeParameters and recorded.
+pub async fn stat(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+    selected: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let completer = &mut helper.completer;
+    let path = match args.trim() {
+        "" if selected.is_empty() => return Err("Usage: stat [path]".into()),
+        "" => selected.to_string(),
+        path => completer.aliases.resolve(path)?,
+    };
+    if !completer.is_parameter(&path) {
+        return Err(format!("{} is not a known parameter", path).into());
+    }
+
+    if !completer.offline
+        && (!completer.revisions.contains_key(&path)
+            || !completer.allowed_patterns.contains_key(&path))
+    {
+        let described = completer
+            .describe_parameters(&path)
+            .await?
+            .into_iter()
+            .find(|metadata| metadata.name.as_deref() == Some(path.as_str()));
+        if let Some(metadata) = described {
+            completer.record_constraints(&path, &metadata);
+            if let Some(param_type) = metadata.type_ {
+                completer.types.insert(path.clone(), param_type);
+            }
+            let revision = Revision {
+                version: metadata.version,
+                last_modified: metadata.last_modified_date,
+            };
+            completer.revisions.insert(path.clone(), revision);
+        }
+    }
+
+    let param_type = completer.types.get(&path).cloned();
+    let revision = completer.revisions.get(&path).copied().unwrap_or_default();
+    let size = completer.cached_value(&path).map(|value| value.len());
+    let modified = revision.last_modified.map(format_date);
+    let pattern = completer.allowed_patterns.get(&path).cloned().flatten();
+    let data_type = completer.data_types.get(&path).cloned();
+
+    if completer.output.is_json() {
+        output::print_json(json!({
+            "path": path,
+            "type": param_type,
+            "version": revision.version,
+            "last_modified": modified,
+            "bytes": size,
+            "allowed_pattern": pattern,
+            "data_type": data_type.as_deref().unwrap_or("text"),
+            "sensitive": completer.is_sensitive(&path),
+        }));
+        return Ok(path);
+    }
+
+    let unknown = || "unknown".dimmed().to_string();
+    println!("{}", path.green());
+    println!("  Type:           {}", param_type.unwrap_or_else(unknown));
+    if let Some(data_type) = data_type {
+        println!("  Data type:      {}", data_type);
+    }
+    println!(
+        "  Version:        {}",
+        revision
+            .version
+            .map_or_else(unknown, |version| version.to_string())
+    );
+    println!("  Last modified:  {}", modified.unwrap_or_else(unknown));
+    let size = match size {
+        Some(size) => format!("{} bytes", size),
+        None => "not cached".dimmed().to_string(),
+    };
+    println!("  Value size:     {}", size);
+    if let Some(pattern) = pattern {
+        println!("  Pattern:        {}", pattern);
+    }
+    Ok(path)
+}
@@ -0,0 +1,125 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::stats::{Counters, Stats};
+use colored::Colorize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Handles `stats [reset]`: prints per-operation call counts, latency, errors,
+/// retries and throttling for the session, and the value cache hit rate.
+/// `stats reset` starts the counters over.
+pub fn stats(helper: &ParamStoreHelper, args: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    match args.trim() {
+        "" => {}
+        "reset" => {
+            completer.stats.reset();
+            println!("Statistics reset");
+            return Ok(());
+        }
+        other => {
+            return Err(format!("Unknown stats option '{}'. Usage: stats [reset]", other).into());
+        }
+    }
+
+    let counters = completer.stats.snapshot();
+    if completer.output.is_json() {
+        for (name, op) in &counters.operations {
+            output::print_json(json!({
+                "operation": name,
+                "calls": op.calls,
+                "errors": op.errors,
+                "retries": op.retries,
+                "throttled": op.throttled,
+                "avg_ms": op.average().as_millis() as u64,
+                "max_ms": op.max.as_millis() as u64,
+                "total_ms": op.total.as_millis() as u64,
+            }));
+        }
+        output::print_json(json!({
+            "calls": counters.calls(),
+            "total_ms": counters.api_time().as_millis() as u64,
+            "cache_hits": counters.cache_hits,
+            "cache_misses": counters.cache_misses,
+        }));
+        return Ok(());
+    }
+    print!("{}", report(&counters));
+    Ok(())
+}
+
+/// The text form of `stats`, also printed to stderr on exit with `--timings`.
+pub fn report(counters: &Counters) -> String {
+    let mut text = String::new();
+    if counters.operations.is_empty() {
+        text.push_str("No AWS API calls this session\n");
+    } else {
+        text.push_str(&format!(
+            "{}\n",
+            format!(
+                "{:<28} {:>6} {:>6} {:>7} {:>9} {:>9} {:>9} {:>9}",
+                "Operation", "Calls", "Errors", "Retries", "Throttled", "Avg", "Max", "Total"
+            )
+            .bold()
+        ));
+        for (name, op) in &counters.operations {
+            text.push_str(&format!(
+                "{:<28} {:>6} {:>6} {:>7} {:>9} {:>9} {:>9} {:>9}\n",
+                name,
+                op.calls,
+                op.errors,
+                op.retries,
+                op.throttled,
+                millis(op.average()),
+                millis(op.max),
+                millis(op.total)
+            ));
+        }
+    }
+    text.push_str(&format!(
+        "{} API call(s) in {}",
+        counters.calls(),
+        millis(counters.api_time())
+    ));
+    match counters.hit_rate() {
+        Some(rate) => text.push_str(&format!(
+            "; value cache hit rate {:.0}% ({} of {} lookups)\n",
+            rate * 100.0,
+            counters.cache_hits,
+            counters.cache_hits + counters.cache_misses
+        )),
+        None => text.push('\n'),
+    }
+    text
+}
+
+/// One line summarizing the API calls made between two snapshots, printed
+/// after each command with `--timings`. `None` when there were none.
+pub fn command_timings(before: &Counters, after: &Counters) -> Option<String> {
+    // Saturating, since `stats reset` may have run in between.
+    let calls = after.calls().saturating_sub(before.calls());
+    if calls == 0 {
+        return None;
+    }
+    Some(format!(
+        "{} API call(s), {} retried, {} in AWS",
+        calls,
+        after.retries().saturating_sub(before.retries()),
+        millis(after.api_time().saturating_sub(before.api_time()))
+    ))
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{} ms", duration.as_millis())
+}
+
+/// Prints the session's statistics to stderr when dropped, so `--timings`
+/// reports on every exit path.
+pub struct ExitReport(pub Arc<Stats>);
+
+impl Drop for ExitReport {
+    fn drop(&mut self) {
+        eprint!("{}", report(&self.0.snapshot()));
+    }
+}
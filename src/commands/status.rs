@@ -0,0 +1,104 @@
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::utils::format_age;
+use colored::Colorize;
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What `status` reports about the selection and the offline queue.
+#[derive(Debug, Default, PartialEq)]
+pub struct Status {
+    pub selected: Option<String>,
+    /// When the selected value was fetched or written this session.
+    pub fetched_at: Option<f64>,
+    /// Paths with a change queued while offline.
+    pub pending: Vec<String>,
+}
+
+/// Handles `status`: shows the selected parameter and how old its cached
+/// value is, where commands go (region, profile, workspace, base path and
+/// scope) and the changes queued while offline. Only reads session state.
+pub fn status(helper: &ParamStoreHelper, selected: &str) -> Status {
+    let completer = &helper.completer;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64());
+    let selected = (!selected.is_empty()).then_some(selected);
+    let value = selected.and_then(|path| completer.cached_value(path));
+    let fetched_at = selected.and_then(|path| completer.fetched_at.get(path).copied());
+    let changed_at = selected
+        .and_then(|path| completer.revisions.get(path))
+        .and_then(|revision| revision.last_modified);
+    let profile = completer.client_options.profile.as_deref();
+    let pending = &completer.pending.changes;
+    let report = Status {
+        selected: selected.map(str::to_string),
+        fetched_at,
+        pending: pending.iter().map(|change| change.path.clone()).collect(),
+    };
+
+    if completer.output.is_json() {
+        output::print_json(json!({
+            "selected": selected,
+            "value": value.as_deref().map(|value| completer.shown(value)),
+            "fetched_age": fetched_at.map(|at| format_age(at, now)),
+            "changed_age": changed_at.map(|at| format_age(at, now)),
+            "region": completer.region.name(),
+            "profile": profile,
+            "workspace": completer.workspace,
+            "base_path": completer.base_path,
+            "scope": completer.scope,
+            "offline": completer.offline,
+            "pending": report.pending,
+        }));
+        return report;
+    }
+
+    let none = |text: &str| text.dimmed().to_string();
+    match selected {
+        Some(path) => println!("  Selected:   {}", path.green()),
+        None => println!("  Selected:   {}", none("nothing; use sel <path>")),
+    }
+    if let Some(path) = selected {
+        let shown = match &value {
+            Some(value) => completer.clipped(value).red().to_string(),
+            None => none("not cached"),
+        };
+        println!("  Value:      {}", shown);
+        match fetched_at {
+            Some(at) => println!("  Fetched:    {} ago", format_age(at, now)),
+            None if value.is_some() => {
+                println!("  Fetched:    {}", none("before this session (disk cache)"))
+            }
+            None => {}
+        }
+        if let Some(at) = changed_at {
+            println!("  Changed:    {} ago", format_age(at, now));
+        }
+        if completer.pending.get(path).is_some() {
+            println!("              {}", "has a pending change".yellow());
+        }
+    }
+    println!("  Region:     {}", completer.region.name());
+    println!("  Profile:    {}", profile.unwrap_or("default"));
+    if let Some(workspace) = &completer.workspace {
+        println!("  Workspace:  {}", workspace.cyan());
+    }
+    println!("  Base path:  {}", completer.base_path.green());
+    if let Some(scope) = &completer.scope {
+        println!("  Scope:      {}", scope.green());
+    }
+    if completer.offline {
+        println!("  Mode:       {}", "offline".yellow());
+    }
+    match pending.len() {
+        0 => println!("  Pending:    {}", none("none")),
+        count => {
+            println!("  Pending:    {} change(s)", count.to_string().yellow());
+            for change in pending {
+                println!("              {}", change.path.green());
+            }
+        }
+    }
+    report
+}
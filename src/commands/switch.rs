@@ -0,0 +1,60 @@
+use crate::helper::ParamStoreHelper;
+use crate::utils::parse_region;
+use colored::Colorize;
+
+/// Handles `region <name>`: rebuilds the SSM client for `name` and reloads
+/// parameters from AWS, since the cache belongs to the previous region.
+pub async fn switch_region(
+    helper: &mut ParamStoreHelper,
+    name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if name.is_empty() {
+        println!("Current region: {}", helper.completer.region.name().green());
+        return Ok(());
+    }
+
+    let region = parse_region(name)?;
+    let profile = helper.completer.client_options.profile.clone();
+    helper.completer.switch_client(region, profile)?;
+    helper.completer.workspace = None;
+    reload(helper).await?;
+    println!("Switched to region {}", name.green());
+    Ok(())
+}
+
+/// Handles `profile <name>`: rebuilds the SSM client with the named profile's
+/// credentials and reloads parameters from AWS.
+pub async fn switch_profile(
+    helper: &mut ParamStoreHelper,
+    name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if name.is_empty() {
+        let current = helper
+            .completer
+            .client_options
+            .profile
+            .clone()
+            .unwrap_or_else(|| "default".to_string());
+        println!("Current profile: {}", current.green());
+        return Ok(());
+    }
+
+    let region = helper.completer.region.clone();
+    helper
+        .completer
+        .switch_client(region, Some(name.to_string()))?;
+    helper.completer.workspace = None;
+    reload(helper).await?;
+    println!("Switched to profile {}", name.green());
+    Ok(())
+}
+
+async fn reload(helper: &mut ParamStoreHelper) -> Result<(), Box<dyn std::error::Error>> {
+    let refresh = helper.completer.refresh;
+    helper.completer.refresh = true;
+    let result = helper.completer.load_parameters().await;
+    helper.completer.refresh = refresh;
+    result?;
+    helper.completer.load_vocabulary(true).await;
+    Ok(())
+}
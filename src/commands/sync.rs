@@ -0,0 +1,382 @@
+use crate::backend::{BackendKind, ParameterBackend, build_backend};
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::progress::ProgressBar;
+use crate::shutdown::Cancellation;
+use crate::utils::{parse_region, prompt_line};
+use colored::Colorize;
+use rusoto_core::Region;
+use rusoto_ssm::{DeleteParameterRequest, GetParametersByPathRequest, PutParameterRequest};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+
+const USAGE: &str =
+    "Usage: sync <source> <dest> [--prefix <path>] [--delete-extra] [--dry-run] [--yes]";
+
+/// One side of a sync: `[ssm://|sm://]/path[?region=<name>&profile=<name>]`.
+/// The backend, region and profile default to the current session's.
+struct Endpoint {
+    kind: BackendKind,
+    path: String,
+    region: Region,
+    profile: Option<String>,
+}
+
+impl Endpoint {
+    fn parse(spec: &str, completer: &ParameterCompleter) -> Result<Self, String> {
+        let (location, query) = spec.split_once('?').unwrap_or((spec, ""));
+        let (scheme, path) = BackendKind::split_scheme(location);
+        let path = completer.aliases.resolve(&path)?;
+        if !path.starts_with('/') {
+            return Err(format!(
+                "Invalid endpoint '{}': the path must start with '/'",
+                spec
+            ));
+        }
+
+        let mut endpoint = Endpoint {
+            kind: scheme.unwrap_or(completer.backend_kind),
+            path,
+            region: completer.region.clone(),
+            profile: completer.client_options.profile.clone(),
+        };
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            match pair.split_once('=') {
+                Some(("region", region)) => endpoint.region = parse_region(region)?,
+                Some(("profile", profile)) => endpoint.profile = Some(profile.to_string()),
+                _ => {
+                    return Err(format!(
+                        "Invalid endpoint option '{}' (expected region=<name> or profile=<name>)",
+                        pair
+                    ));
+                }
+            }
+        }
+        Ok(endpoint)
+    }
+
+    fn connect(
+        &self,
+        completer: &ParameterCompleter,
+    ) -> Result<Box<dyn ParameterBackend>, Box<dyn std::error::Error>> {
+        let mut options = completer.client_options.clone();
+        options.profile = self.profile.clone();
+        build_backend(self.kind, self.region.clone(), &options)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}{} ({}, profile {})",
+            self.kind.scheme(),
+            self.path.trim_start_matches('/'),
+            self.region.name(),
+            self.profile.as_deref().unwrap_or("default")
+        )
+    }
+
+    /// Whether this endpoint is what the current session has loaded.
+    fn is_session(&self, completer: &ParameterCompleter) -> bool {
+        self.kind == completer.backend_kind
+            && self.region == completer.region
+            && self.profile == completer.client_options.profile
+    }
+}
+
+struct Stored {
+    value: String,
+    param_type: Option<String>,
+}
+
+#[derive(PartialEq)]
+enum Change {
+    Create,
+    Update,
+    Delete,
+}
+
+/// Handles `sync <source> <dest> [--prefix <path>] [--delete-extra] [--dry-run] [--yes]`:
+/// makes every parameter under `dest` match `source`, which can be another
+/// backend, region or profile. The change plan is printed first; it is applied
+/// after confirmation, or right away with `--yes`. `--prefix` limits the sync
+/// to a sub-path relative to both endpoints, and `--delete-extra` also deletes
+/// destination parameters that do not exist in the source. A parameter whose
+/// type differs is updated too. A destination on the session's own backend,
+/// region and profile is written like any other change, within the scope and
+/// with protected paths needing `--yes`, and the cache is kept current.
+/// Ctrl-C stops the sync between two changes.
+pub async fn sync(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let completer = &helper.completer;
+    if completer.offline {
+        return Err("sync talks to both endpoints and is not available offline".into());
+    }
+
+    let mut positional = Vec::new();
+    let (mut prefix, mut delete_extra, mut dry_run, mut yes) = (String::new(), false, false, false);
+    let mut words = args.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "--prefix" => prefix = words.next().ok_or(USAGE)?.trim_matches('/').to_string(),
+            "--delete-extra" => delete_extra = true,
+            "--dry-run" => dry_run = true,
+            "--yes" => yes = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option {}. {}", flag, USAGE).into());
+            }
+            _ => positional.push(word),
+        }
+    }
+    let [source, dest] = positional[..] else {
+        return Err(USAGE.into());
+    };
+    let source = Endpoint::parse(source, completer)?;
+    let dest = Endpoint::parse(dest, completer)?;
+
+    let source_backend = source.connect(completer)?;
+    let dest_backend = dest.connect(completer)?;
+    let source_root = join(&source.path, &prefix);
+    let dest_root = join(&dest.path, &prefix);
+    completer.progress(&format!(
+        "Comparing {} with {}",
+        source.describe(),
+        dest.describe()
+    ));
+    let from = fetch_all(completer, source_backend.as_ref(), &source_root).await?;
+    let to = fetch_all(completer, dest_backend.as_ref(), &dest_root).await?;
+
+    // Plan, keyed by the path relative to the sync roots. Types are compared
+    // only within one kind of backend: Secrets Manager reports every secret
+    // as a SecureString.
+    let same_kind = source.kind == dest.kind;
+    let mut plan: Vec<(String, Change)> = Vec::new();
+    for (relative, stored) in &from {
+        match to.get(relative) {
+            None => plan.push((relative.clone(), Change::Create)),
+            Some(existing)
+                if existing.value != stored.value
+                    || (same_kind && existing.param_type != stored.param_type) =>
+            {
+                plan.push((relative.clone(), Change::Update))
+            }
+            Some(_) => {}
+        }
+    }
+    if delete_extra {
+        for relative in to.keys().filter(|relative| !from.contains_key(*relative)) {
+            plan.push((relative.clone(), Change::Delete));
+        }
+    }
+    plan.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let json = completer.output.is_json();
+    for (relative, change) in &plan {
+        let path = format!("{}{}", dest_root, relative);
+        let param_type = from
+            .get(relative)
+            .and_then(|stored| stored.param_type.as_deref())
+            .unwrap_or("-");
+        let (action, marker) = match change {
+            Change::Create => ("create", "+".green()),
+            Change::Update => ("update", "~".yellow()),
+            Change::Delete => ("delete", "-".red()),
+        };
+        if json {
+            output::print_json(json!({ "action": action, "path": path, "type": param_type }));
+        } else {
+            println!("  {} {:<60} {}", marker, path, param_type.dimmed());
+        }
+    }
+    let count = |kind: Change| plan.iter().filter(|(_, change)| *change == kind).count();
+    let (creates, updates, deletes) = (
+        count(Change::Create),
+        count(Change::Update),
+        count(Change::Delete),
+    );
+    if !json {
+        println!(
+            "{} to create, {} to update, {} to delete, {} unchanged",
+            creates,
+            updates,
+            deletes,
+            from.len() - creates - updates
+        );
+    }
+    if plan.is_empty() || dry_run {
+        return Ok(());
+    }
+    // Writes to the session's own target go through the completer, so the
+    // scope, protected paths and pre-write snapshots apply as for any write.
+    let in_session = dest.is_session(completer);
+    if in_session {
+        let mut protected = Vec::new();
+        for (relative, _) in &plan {
+            let path = format!("{}{}", dest_root, relative);
+            completer.check_scope(&path)?;
+            if let Some(pattern) = completer.protected.matching(&path) {
+                protected.push(format!("{} ('{}')", path, pattern));
+            }
+        }
+        if !protected.is_empty() && !yes {
+            return Err(format!(
+                "Protected paths would change: {}; pass --yes to sync them",
+                protected.join(", ")
+            )
+            .into());
+        }
+    }
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("Pass --yes to run sync without a prompt".into());
+        }
+        if !prompt_line("Apply these changes? [y/N]: ")?.eq_ignore_ascii_case("y") {
+            println!("Sync cancelled");
+            return Ok(());
+        }
+    }
+
+    let completer = &mut helper.completer;
+    let mut bar = ProgressBar::new("Syncing", plan.len(), completer);
+    let cancel = Cancellation::begin();
+    let mut failed = 0;
+    for (index, (relative, change)) in plan.iter().enumerate() {
+        // The plan is recomputed from both endpoints, so a rerun only has
+        // what is left to do.
+        if cancel.requested() {
+            drop(bar);
+            return Err(format!(
+                "Sync stopped after {} of {} change(s); run the same sync again to apply the rest",
+                index,
+                plan.len()
+            )
+            .into());
+        }
+        let path = format!("{}{}", dest_root, relative);
+        let result = match change {
+            Change::Create | Change::Update if in_session => {
+                let stored = &from[relative];
+                write_in_session(completer, &path, stored)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Change::Delete if in_session => completer
+                .delete_parameter(&path)
+                .await
+                .map_err(|e| e.to_string()),
+            Change::Create | Change::Update => {
+                let stored = &from[relative];
+                let request = PutParameterRequest {
+                    name: path.clone(),
+                    value: stored.value.clone(),
+                    overwrite: Some(true),
+                    type_: stored.param_type.clone(),
+                    ..Default::default()
+                };
+                completer
+                    .call("PutParameter", || {
+                        dest_backend.put_parameter(request.clone())
+                    })
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+            Change::Delete => {
+                let request = DeleteParameterRequest { name: path.clone() };
+                completer
+                    .call("DeleteParameter", || {
+                        dest_backend.delete_parameter(request.clone())
+                    })
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+        };
+        if let Err(err) = result {
+            failed += 1;
+            bar.println(&format!("{} {}: {}", "Failed".red(), path, err));
+        }
+        bar.inc();
+    }
+    drop(bar);
+
+    println!(
+        "Applied {} of {} change(s)",
+        plan.len() - failed,
+        plan.len()
+    );
+    if failed > 0 {
+        return Err(format!("{} change(s) failed", failed).into());
+    }
+    Ok(())
+}
+
+/// Creates or overwrites `path` in the session's own target and caches it.
+async fn write_in_session(
+    completer: &mut ParameterCompleter,
+    path: &str,
+    stored: &Stored,
+) -> Result<(), Box<dyn std::error::Error>> {
+    completer
+        .set_parameter(path, stored.value.clone(), stored.param_type.clone())
+        .await?;
+    completer.update_all(path, stored.value.clone()).await
+}
+
+/// `/prod/` + `db` → `/prod/db/`; an empty prefix keeps the root as a directory.
+fn join(root: &str, prefix: &str) -> String {
+    let root = root.trim_end_matches('/');
+    if prefix.is_empty() {
+        format!("{}/", root)
+    } else {
+        format!("{}/{}/", root, prefix)
+    }
+}
+
+/// Reads every parameter under `root`, keyed by its path relative to `root`.
+async fn fetch_all(
+    completer: &ParameterCompleter,
+    backend: &dyn ParameterBackend,
+    root: &str,
+) -> Result<BTreeMap<String, Stored>, Box<dyn std::error::Error>> {
+    let mut request = GetParametersByPathRequest {
+        path: root.trim_end_matches('/').to_string(),
+        recursive: Some(true),
+        with_decryption: Some(true),
+        max_results: Some(10),
+        next_token: None,
+        parameter_filters: None,
+    };
+    if request.path.is_empty() {
+        request.path = "/".to_string();
+    }
+
+    let mut parameters = BTreeMap::new();
+    loop {
+        let result = completer
+            .call("GetParametersByPath", || {
+                backend.get_parameters_by_path(request.clone())
+            })
+            .await?;
+        for param in result.parameters.unwrap_or_default() {
+            if let (Some(name), Some(value)) = (param.name, param.value)
+                && let Some(relative) = name.strip_prefix(root)
+            {
+                parameters.insert(
+                    relative.to_string(),
+                    Stored {
+                        value,
+                        param_type: param.type_,
+                    },
+                );
+            }
+        }
+        match result.next_token {
+            Some(token) => request.next_token = Some(token),
+            None => break,
+        }
+    }
+    Ok(parameters)
+}
@@ -0,0 +1,196 @@
+use crate::completer::ParameterCompleter;
+use crate::helper::ParamStoreHelper;
+use crate::output;
+use crate::store_paths::StorePaths;
+use crate::utils::parse_region;
+use crate::workspaces::{self, Workspace};
+use colored::Colorize;
+use rusoto_core::Region;
+use serde_json::json;
+
+const USAGE: &str =
+    "Usage: workspace [list] | workspace show [name] | workspace use <name> [base-path]";
+
+/// Handles `workspace [list] | show [name] | use <name> [base-path]`: lists
+/// the workspaces of the config file and switches the session to one.
+pub async fn workspace(
+    helper: &mut ParamStoreHelper,
+    args: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match words.as_slice() {
+        [] | ["list"] => {
+            list(&helper.completer);
+            Ok(())
+        }
+        ["show"] => match helper.completer.workspace.clone() {
+            Some(name) => show(&helper.completer, &name),
+            None => {
+                println!("No workspace is active");
+                Ok(())
+            }
+        },
+        ["show", name] => show(&helper.completer, name),
+        ["use", name] => use_workspace(&mut helper.completer, name, None).await,
+        ["use", name, path] => use_workspace(&mut helper.completer, name, Some(path)).await,
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn list(completer: &ParameterCompleter) {
+    if completer.output.is_json() {
+        for (name, workspace) in &completer.workspaces {
+            output::print_json(json!({
+                "name": name,
+                "active": completer.workspace.as_deref() == Some(name.as_str()),
+                "profile": workspace.profile,
+                "region": workspace.region,
+                "base_paths": workspace.base_paths,
+                "store_dir": workspace.store_dir,
+            }));
+        }
+        return;
+    }
+    if completer.workspaces.is_empty() {
+        println!("No workspaces configured; add them under [workspaces] in the config file");
+        return;
+    }
+    for (name, workspace) in &completer.workspaces {
+        let marker = if completer.workspace.as_deref() == Some(name.as_str()) {
+            "*".green()
+        } else {
+            " ".normal()
+        };
+        println!(
+            "{} {:<16} {} {} {}",
+            marker,
+            name.cyan(),
+            workspace.profile.as_deref().unwrap_or("default"),
+            workspace.region.as_deref().unwrap_or("-"),
+            workspace.base_path().green()
+        );
+    }
+}
+
+fn show(completer: &ParameterCompleter, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = workspaces::find(&completer.workspaces, name)?;
+    let store = store_for(completer, workspace);
+    if completer.output.is_json() {
+        output::print_json(json!({
+            "name": name,
+            "active": completer.workspace.as_deref() == Some(name),
+            "profile": workspace.profile,
+            "region": workspace.region,
+            "base_paths": workspace.base_paths,
+            "store": store.root().display().to_string(),
+        }));
+        return Ok(());
+    }
+    println!("Workspace {}", name.cyan());
+    println!(
+        "  profile     {}",
+        workspace.profile.as_deref().unwrap_or("default")
+    );
+    println!(
+        "  region      {}",
+        workspace.region.as_deref().unwrap_or("(current)")
+    );
+    for (index, path) in workspace.base_paths.iter().enumerate() {
+        let label = if index == 0 { "base paths" } else { "" };
+        println!("  {:<11} {}", label, path.green());
+    }
+    println!("  store       {}", store.root().display());
+    Ok(())
+}
+
+/// Switches profile, region, store and base path together. When the reload
+/// fails, everything is put back as it was so the session never points at a
+/// mix of two targets.
+async fn use_workspace(
+    completer: &mut ParameterCompleter,
+    name: &str,
+    path: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = workspaces::find(&completer.workspaces, name)?.clone();
+    // A workspace's own store holds a cache of its target; the shared one
+    // may hold another account's or region's values, so bypass it.
+    let fetch = workspace.store_dir.is_none();
+    if fetch && completer.offline {
+        return Err(format!(
+            "Workspace '{}' has no store_dir of its own, so switching fetches from AWS and is not available offline",
+            name
+        )
+        .into());
+    }
+    let base_path = workspace.pick_base_path(name, path)?;
+    let region = match &workspace.region {
+        Some(region) => parse_region(region)?,
+        None => completer.region.clone(),
+    };
+    let store = store_for(completer, &workspace);
+
+    let previous = Target {
+        region: completer.region.clone(),
+        profile: completer.client_options.profile.clone(),
+        base_path: completer.base_path.clone(),
+        store: completer.store.clone(),
+        scope: completer.scope.clone(),
+    };
+    let target = Target {
+        region,
+        profile: workspace.profile.clone(),
+        base_path,
+        store,
+        scope: None,
+    };
+    if let Err(err) = switch(completer, &target, fetch).await {
+        if let Err(restore_err) = switch(completer, &previous, false).await {
+            log::warn!("Could not restore the previous target: {}", restore_err);
+        }
+        return Err(format!("Could not switch to workspace '{}': {}", name, err).into());
+    }
+
+    completer.workspace = Some(name.to_string());
+    completer.load_vocabulary(fetch).await;
+    println!(
+        "Switched to workspace {} ({} {} {})",
+        name.cyan(),
+        target.profile.as_deref().unwrap_or("default"),
+        target.region.name(),
+        target.base_path.green()
+    );
+    Ok(())
+}
+
+/// Where a session points: what `workspace use` changes at once.
+struct Target {
+    region: Region,
+    profile: Option<String>,
+    base_path: String,
+    store: StorePaths,
+    scope: Option<String>,
+}
+
+async fn switch(
+    completer: &mut ParameterCompleter,
+    target: &Target,
+    refresh: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    completer.switch_store(target.store.clone())?;
+    completer.switch_client(target.region.clone(), target.profile.clone())?;
+    completer.switch_base_path(&target.base_path);
+    completer.scope = target.scope.clone();
+
+    let previous = completer.refresh;
+    completer.refresh = refresh;
+    let loaded = completer.load_parameters().await;
+    completer.refresh = previous;
+    loaded?;
+    Ok(())
+}
+
+fn store_for(completer: &ParameterCompleter, workspace: &Workspace) -> StorePaths {
+    workspace
+        .store(completer.backend_kind)
+        .unwrap_or_else(|| completer.shared_store.clone())
+}
@@ -1,80 +1,709 @@
+use crate::aliases::Aliases;
+use crate::backend::{BackendKind, ParameterBackend, build_backend};
+use crate::cache_file::{self, CacheError};
+use crate::chunking;
+use crate::client::{ClientOptions, build_kms_client};
 use crate::encryption::Encryption;
-use crate::utils::replace_first_line_containing;
+use crate::env_vars::EnvMapping;
+use crate::hooks::Hooks;
+use crate::lint::LintConfig;
+use crate::macros::Macros;
+use crate::output::OutputFormat;
+use crate::pending::{PendingChange, PendingQueue};
+use crate::pins::{self, Pins};
+use crate::policy::{
+    ProtectedPaths, SecretPolicy, TypePolicy, check_allowed_pattern, check_data_type,
+};
+use crate::recent::Recent;
+use crate::refresher::{BackgroundRefresher, MIN_INTERVAL, Snapshot, fetch_snapshot};
+use crate::retry::{RateLimiter, with_retry};
+use crate::search_index::SearchIndex;
+use crate::snapshots::{self, AutoSnapshots, SnapshotEntry};
+use crate::stats::Stats;
+use crate::store_paths::StorePaths;
+use crate::utils::truncate_value;
+use crate::value_cache::ValueCache;
+use crate::vocabulary::{self, PARAMETER_TYPES, REGIONS_PATH, Vocabulary};
+use crate::workspaces::Workspace;
+use colored::Colorize;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use rusoto_core::request::HttpDispatchError;
 use rusoto_core::{Region, RusotoError};
-use rusoto_ssm::{GetParameterRequest, GetParametersByPathRequest, Ssm, SsmClient};
-use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, Write};
+use rusoto_kms::{Kms, ListAliasesRequest};
+use rusoto_ssm::{
+    DeleteParameterRequest, DescribeParametersRequest, GetParameterError, GetParameterRequest,
+    GetParametersByPathRequest, GetParametersRequest, ListTagsForResourceRequest, Parameter,
+    ParameterMetadata, ParameterStringFilter, Tag,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+
+/// A parameter to create, with the optional settings only available on creation.
+#[derive(Debug, Clone, Default)]
+pub struct NewParameter {
+    pub path: String,
+    pub value: String,
+    pub param_type: String,
+    /// KMS key for `SecureString`; AWS uses `alias/aws/ssm` when unset.
+    pub key_id: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<(String, String)>,
+    /// Regular expression AWS checks every later value against.
+    pub allowed_pattern: Option<String>,
+    /// `text` (the default) or `aws:ec2:image`, for AMI ids.
+    pub data_type: Option<String>,
+}
+
+/// Version and time of the last change of a parameter, as AWS last reported
+/// them. Cached next to its type, so they are known offline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<i64>,
+    /// Seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<f64>,
+}
+
+impl Revision {
+    pub fn of(param: &Parameter) -> Self {
+        Self {
+            version: param.version,
+            last_modified: param.last_modified_date,
+        }
+    }
+
+    /// A change written by this session just now.
+    fn written(version: Option<i64>) -> Self {
+        Self {
+            version,
+            last_modified: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|elapsed| elapsed.as_secs_f64()),
+        }
+    }
+}
+
+/// One entry of the types file. Files written before revisions were cached
+/// hold just the type name.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TypeEntry {
+    Plain(String),
+    Detailed {
+        #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+        param_type: Option<String>,
+        #[serde(flatten)]
+        revision: Revision,
+    },
+}
+
+/// Whether an AWS error says the caller may not decrypt with the parameter's
+/// KMS key.
+pub fn is_decrypt_denied(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("kms")
+        && (message.contains("accessdenied") || message.contains("not authorized"))
+}
+
+/// The current time in seconds since the Unix epoch.
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64())
+}
+
+/// Most names GetParameters accepts in one call.
+const GET_PARAMETERS_BATCH: usize = 10;
+
+/// Maximum number of candidates offered by fuzzy completion.
+const FUZZY_COMPLETION_LIMIT: usize = 20;
 
+/// Shown in place of every value while `redact` is on.
+pub const REDACTED: &str = "********";
+
+/// `value_width` unless the config file sets one.
+pub const DEFAULT_VALUE_WIDTH: usize = 120;
+
+/// The session's cache and AWS access. It has a single owner, the editor's
+/// helper, and is changed through `&mut self`, so the maps need no locks. The
+/// only state shared with other tasks is the rate limiter, whose lock is never
+/// held across an await, and the background refresher's snapshot, which is
+/// handed over whole.
 pub struct ParameterCompleter {
     pub parameters: HashMap<String, Vec<String>>,
-    pub values: HashMap<String, String>,
-    pub client: SsmClient,
+    pub values: ValueCache,
+    /// Parameter type (`String`, `StringList`, `SecureString`) by path, where known.
+    pub types: HashMap<String, String>,
+    /// Version and last change by path, where known.
+    pub revisions: HashMap<String, Revision>,
+    /// When each value was last fetched from AWS or written this session, in
+    /// seconds since the Unix epoch. Values read from the disk cache have none.
+    pub fetched_at: HashMap<String, f64>,
+    /// `AllowedPattern` by path, for the parameters looked up this session;
+    /// `None` for one that has no pattern.
+    pub allowed_patterns: HashMap<String, Option<String>>,
+    /// Data type by path, for the parameters looked up this session whose
+    /// data type is not `text`.
+    pub data_types: HashMap<String, String>,
+    /// Where parameters are read from and written to.
+    pub backend: Box<dyn ParameterBackend>,
+    pub backend_kind: BackendKind,
     pub base_path: String,
     pub refresh: bool,
-    pub store_dir: String,
-    pub verbose: bool,
+    pub store: StorePaths,
     pub metadata: HashMap<String, String>,
     pub encryption: Encryption,
     pub search_result: Vec<String>,
+    pub client_options: ClientOptions,
+    pub limiter: Arc<RateLimiter>,
+    /// API call and cache counters for `stats` and `--timings`.
+    pub stats: Arc<Stats>,
+    /// Print the API calls each command made (`--timings`).
+    pub timings: bool,
+    pub region: Region,
+    pub vocabulary: Vocabulary,
+    /// Serve everything from the local cache and never contact AWS.
+    pub offline: bool,
+    /// Prefix the session is narrowed to with `scope`, without a trailing
+    /// `/`. Paths outside it are neither shown nor read or written.
+    pub scope: Option<String>,
+    /// Fetch `SecureString` values decrypted when loading. Off with
+    /// `--no-decrypt`, or after AWS refused kms:Decrypt; such values are then
+    /// decrypted one at a time when shown.
+    pub decrypt: bool,
+    /// Writes queued while offline, applied by `push`.
+    pub pending: PendingQueue,
+    /// Expected-value pins checked for drift.
+    pub pins: Pins,
+    /// Falls back to subsequence matching when no path segment starts with the input.
+    pub fuzzy_completion: bool,
+    /// Text or JSON output for command results.
+    pub output: OutputFormat,
+    /// Suppresses progress and clipboard messages (`--quiet`).
+    pub quiet: bool,
+    /// Masks every displayed value and keeps values off the clipboard
+    /// (`--redact`, `redact on|off`), for demos and screen sharing.
+    pub redact: bool,
+    /// `@name` shortcuts for paths, from the config file.
+    pub aliases: Aliases,
+    /// Command aliases and recorded macros, from the config file.
+    pub macros: Macros,
+    /// External commands run before and after daps commands.
+    pub hooks: Hooks,
+    /// Variable names for parameters exported as environment variables.
+    pub env_mapping: EnvMapping,
+    /// Recently viewed or changed parameters, for `recent` and `sel -N`.
+    pub recent: Recent,
+    /// Checked by `set` and `insert` before writing to password/secret paths.
+    pub secret_policy: SecretPolicy,
+    /// Paths where `set`, `insert` and `delete` ask for confirmation.
+    pub protected: ProtectedPaths,
+    /// Default and required types for new parameters.
+    pub type_policy: TypePolicy,
+    /// Days after which `age` highlights a parameter.
+    pub age_threshold_days: u64,
+    /// Splits values over 4 KB across `.chunkN` parameters on write.
+    pub chunking: bool,
+    /// Naming and hygiene rules for `lint`.
+    pub lint: LintConfig,
+    /// Seconds between background re-fetches of the base path; 0 turns it off.
+    pub background_refresh_secs: u64,
+    /// Empty the clipboard on exit if it still holds a value daps copied.
+    pub clear_clipboard_on_exit: bool,
+    /// Longest value listings print; see `clipped`. 0 never cuts.
+    pub value_width: usize,
+    /// Whether writes are preceded by an automatic snapshot, and its retention.
+    pub auto_snapshots: AutoSnapshots,
+    /// Workspaces from the config file, for `workspace use`.
+    pub workspaces: BTreeMap<String, Workspace>,
+    /// Store of workspaces without their own `store_dir`: the one given by
+    /// `--store-dir` or the platform default.
+    pub shared_store: StorePaths,
+    /// Name of the active workspace, shown in the prompt. Cleared when the
+    /// region or profile is switched on its own.
+    pub workspace: Option<String>,
+    /// This session's automatic snapshot and its name, once something was written.
+    session_snapshot: Option<(String, snapshots::Snapshot)>,
+    refresher: Option<BackgroundRefresher>,
+    /// Index over the tree's paths for `search` and fuzzy completion, rebuilt
+    /// when the cache loads and kept up to date by local writes.
+    search_index: SearchIndex,
+    /// Last write to the in-memory cache; older background snapshots are dropped.
+    last_change: Instant,
 }
 
 impl ParameterCompleter {
-    /// Creates a platform-appropriate file path for parameter storage.
-    pub fn get_file_path(&self, base_path: &str, file_type: &str) -> String {
-        if cfg!(target_os = "windows") {
-            format!("{}\\{}_{}.txt", self.store_dir, file_type, base_path)
-        } else {
-            format!("{}/{}_{}.txt", self.store_dir, file_type, base_path)
-        }
-    }
-
-    /// Returns a sanitized version of `base_path` (slashes replaced with underscores).
-    pub fn get_sanitized_base_path(&self) -> String {
-        self.base_path.replace('/', "_")
-    }
-
     pub fn new(
+        backend_kind: BackendKind,
         region: Region,
         base_path: String,
         refresh: bool,
-        store_dir: String,
-        verbose: bool,
+        store: StorePaths,
         encryption: Encryption,
-    ) -> Self {
-        let client = SsmClient::new(region);
+        client_options: ClientOptions,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let backend = build_backend(backend_kind, region.clone(), &client_options)?;
 
-        std::fs::create_dir_all(&store_dir).unwrap_or_else(|_| {
-            println!("Failed to create directory: {}", store_dir);
+        std::fs::create_dir_all(store.root()).unwrap_or_else(|_| {
+            println!("Failed to create directory: {}", store.root().display());
         });
 
-        Self {
+        let pending = PendingQueue::load(&store.pending(&base_path), &encryption);
+        let pins = Pins::load(
+            &store.pins(),
+            &pins::target(client_options.profile.as_deref(), region.name()),
+            encryption.integrity_key(),
+        );
+        let recent_file = store.recent();
+
+        Ok(Self {
             parameters: HashMap::new(),
-            client,
+            backend,
+            backend_kind,
             base_path,
-            values: HashMap::new(),
+            values: ValueCache::default(),
+            types: HashMap::new(),
+            revisions: HashMap::new(),
+            fetched_at: HashMap::new(),
+            allowed_patterns: HashMap::new(),
+            data_types: HashMap::new(),
             refresh,
-            store_dir,
-            verbose,
+            store: store.clone(),
             metadata: HashMap::new(),
             encryption,
             search_result: Vec::new(),
+            limiter: Arc::new(RateLimiter::new(client_options.retry.max_tps)),
+            client_options,
+            region,
+            vocabulary: Vocabulary::default(),
+            offline: false,
+            scope: None,
+            decrypt: true,
+            pending,
+            pins,
+            fuzzy_completion: true,
+            output: OutputFormat::Text,
+            quiet: false,
+            redact: false,
+            aliases: Aliases::default(),
+            macros: Macros::default(),
+            hooks: Hooks::default(),
+            env_mapping: EnvMapping::default(),
+            recent: Recent::load(&recent_file),
+            secret_policy: SecretPolicy::default(),
+            protected: ProtectedPaths::default(),
+            type_policy: TypePolicy::default(),
+            age_threshold_days: 365,
+            chunking: false,
+            lint: LintConfig::default(),
+            background_refresh_secs: 0,
+            clear_clipboard_on_exit: false,
+            value_width: DEFAULT_VALUE_WIDTH,
+            auto_snapshots: AutoSnapshots::default(),
+            workspaces: BTreeMap::new(),
+            shared_store: store,
+            workspace: None,
+            session_snapshot: None,
+            refresher: None,
+            stats: Arc::new(Stats::default()),
+            timings: false,
+            search_index: SearchIndex::default(),
+            last_change: Instant::now(),
+        })
+    }
+
+    /// Rebuilds the backend client for another region and/or profile. The caller is
+    /// expected to reload parameters afterwards, since the cache reflects the old target.
+    pub fn switch_client(
+        &mut self,
+        region: Region,
+        profile: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut options = self.client_options.clone();
+        options.profile = profile;
+        self.backend = build_backend(self.backend_kind, region.clone(), &options)?;
+        self.client_options = options;
+        self.region = region;
+        self.pins = self.load_pins(&self.store);
+        self.session_snapshot = None;
+        if self.refresher.is_some() {
+            self.start_background_refresh()?;
         }
+        Ok(())
     }
 
-    pub async fn set_parameter(
+    /// The pins kept in `store` for the current profile and region.
+    fn load_pins(&self, store: &StorePaths) -> Pins {
+        Pins::load(
+            &store.pins(),
+            &pins::target(self.client_options.profile.as_deref(), self.region.name()),
+            self.encryption.integrity_key(),
+        )
+    }
+
+    /// Points the session at another base path and loads that path's offline
+    /// queue. The caller is expected to reload parameters afterwards.
+    pub fn switch_base_path(&mut self, base_path: &str) {
+        self.base_path = base_path.to_string();
+        self.pending = PendingQueue::load(&self.store.pending(base_path), &self.encryption);
+        if self.refresher.is_some()
+            && let Err(err) = self.start_background_refresh()
+        {
+            log::warn!("Could not restart the background refresh: {}", err);
+        }
+    }
+
+    /// Moves the session to another store directory, reloading the pins,
+    /// recent list and offline queue kept there. The caller is expected to
+    /// reload parameters afterwards. Refused when that store already has its
+    /// own salt, since the cache key was derived with the current one.
+    pub fn switch_store(&mut self, store: StorePaths) -> Result<(), Box<dyn std::error::Error>> {
+        if store.root() == self.store.root() {
+            return Ok(());
+        }
+        let salt = std::fs::read_to_string(self.store.salt()).ok();
+        match std::fs::read_to_string(store.salt()) {
+            Ok(other) if self.encryption.enabled && salt.as_deref() != Some(other.as_str()) => {
+                return Err(format!(
+                    "{} has its own cache key salt; start daps with --store-dir {} to use it",
+                    store.root().display(),
+                    store.root().display()
+                )
+                .into());
+            }
+            Ok(_) => {}
+            Err(_) => {
+                std::fs::create_dir_all(store.root())?;
+                if let Some(salt) = salt {
+                    std::fs::write(store.salt(), salt)?;
+                }
+            }
+        }
+        self.pending = PendingQueue::load(&store.pending(&self.base_path), &self.encryption);
+        self.pins = self.load_pins(&store);
+        self.recent = Recent::load(&store.recent());
+        self.store = store;
+        self.session_snapshot = None;
+        Ok(())
+    }
+
+    /// Records a write for later `push` and reflects it in memory only, so the
+    /// on-disk cache keeps mirroring what is actually stored in AWS.
+    pub fn queue_change(
+        &mut self,
+        path: &str,
+        value: &str,
+        param_type: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_scope(path)?;
+        // The queue holds the value until `push`, so it follows the rules of
+        // the values file.
+        if !self.encryption.can_persist_values() {
+            return Err(
+                "Offline changes are stored on disk; set DAPS_ENCRYPTION_KEY to encrypt them, \
+                 or pass --allow-plaintext-cache to store them unencrypted"
+                    .into(),
+            );
+        }
+        let mut change = PendingChange::new(path, value, param_type);
+        change.base_version = self.revisions.get(path).and_then(|r| r.version);
+        self.pending.push(change);
+        self.pending.save(&self.encryption)?;
+        self.add_path(path);
+        self.values
+            .insert_pinned(path.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// Queued changes whose parameter changed in AWS since they were queued,
+    /// judged by the versions of the last load.
+    pub fn pending_conflicts(&self) -> Vec<PendingChange> {
+        self.pending
+            .changes
+            .iter()
+            .filter(|change| {
+                change.conflicts_with(self.revisions.get(&change.path).and_then(|r| r.version))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Resolves a conflict for the queued change: it is now based on the
+    /// current version in AWS, which `push` will overwrite.
+    pub fn keep_local(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let version = self.revisions.get(path).and_then(|r| r.version);
+        if let Some(change) = self.pending.changes.iter_mut().find(|c| c.path == path) {
+            change.base_version = version;
+        }
+        self.pending.save(&self.encryption)
+    }
+
+    /// Resolves a conflict by dropping the queued change and showing the
+    /// value in AWS, or nothing when it was deleted there.
+    pub fn take_remote(
+        &mut self,
+        path: &str,
+        remote: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.pending.remove(path);
+        self.pending.save(&self.encryption)?;
+        match remote {
+            Some(value) => self.values.insert(path.to_string(), value),
+            None => self.forget_path(path),
+        }
+        Ok(())
+    }
+
+    /// Loads the cached region / KMS alias lists, fetching them when missing or
+    /// when `force` is set. Failures are logged and never fatal.
+    pub async fn load_vocabulary(&mut self, force: bool) {
+        let path = self.store.vocabulary();
+        if !force && let Some(cached) = Vocabulary::load(&path) {
+            self.vocabulary = cached;
+            return;
+        }
+        if self.offline {
+            return;
+        }
+
+        match self.fetch_regions().await {
+            Ok(regions) => self.vocabulary.regions = regions,
+            Err(e) => log::info!("Could not fetch region list: {}", e),
+        }
+        match self.fetch_kms_aliases().await {
+            Ok(aliases) => self.vocabulary.kms_aliases = aliases,
+            Err(e) => log::info!("Could not fetch KMS aliases: {}", e),
+        }
+
+        if let Err(e) = self.vocabulary.save(&path) {
+            log::info!("Could not write vocabulary cache: {}", e);
+        }
+    }
+
+    async fn fetch_regions(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut request = GetParametersByPathRequest {
+            path: REGIONS_PATH.to_string(),
+            recursive: Some(false),
+            with_decryption: Some(false),
+            max_results: Some(10),
+            next_token: None,
+            parameter_filters: None,
+        };
+
+        let mut regions = Vec::new();
+        loop {
+            let result = self
+                .call("GetParametersByPath", || {
+                    self.backend.get_parameters_by_path(request.clone())
+                })
+                .await?;
+            regions.extend(
+                result
+                    .parameters
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|p| p.value),
+            );
+            match result.next_token {
+                Some(token) => request.next_token = Some(token),
+                None => break,
+            }
+        }
+        regions.sort();
+        Ok(regions)
+    }
+
+    async fn fetch_kms_aliases(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let kms = build_kms_client(self.region.clone(), &self.client_options)?;
+        let mut request = ListAliasesRequest {
+            key_id: None,
+            limit: Some(100),
+            marker: None,
+        };
+
+        let mut aliases = Vec::new();
+        loop {
+            let result = self
+                .call("ListAliases", || kms.list_aliases(request.clone()))
+                .await?;
+            aliases.extend(
+                result
+                    .aliases
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|a| a.alias_name),
+            );
+            match (result.truncated, result.next_marker) {
+                (Some(true), Some(marker)) => request.marker = Some(marker),
+                _ => break,
+            }
+        }
+        aliases.sort();
+        Ok(aliases)
+    }
+
+    /// Lists the metadata (type, description, last change...) of every
+    /// parameter whose name starts with `prefix`. Values are not fetched.
+    pub async fn describe_parameters(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<ParameterMetadata>, Box<dyn std::error::Error>> {
+        self.describe_filtered(prefix, &[]).await
+    }
+
+    /// What the cache knows of the metadata of every parameter whose name
+    /// starts with `prefix`: type, version and last change where they were
+    /// recorded. Needs no AWS call, so it works offline.
+    pub fn cached_metadata(&self, prefix: &str) -> Vec<ParameterMetadata> {
+        let mut parameters: Vec<ParameterMetadata> = self
+            .values
+            .keys()
+            .filter(|path| path.starts_with(prefix) && self.in_scope(path))
+            .map(|path| {
+                let revision = self.revisions.get(path).copied().unwrap_or_default();
+                ParameterMetadata {
+                    name: Some(path.clone()),
+                    type_: self.types.get(path).cloned(),
+                    version: revision.version,
+                    last_modified_date: revision.last_modified,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        parameters.sort_by(|a, b| a.name.cmp(&b.name));
+        parameters
+    }
+
+    /// Like `describe_parameters`, with extra server-side filters given as
+    /// `(key, value)` pairs such as `("Type", "SecureString")` or
+    /// `("Tier", "Advanced")`. Each one must match exactly.
+    pub async fn describe_filtered(
         &self,
+        prefix: &str,
+        filters: &[(&str, &str)],
+    ) -> Result<Vec<ParameterMetadata>, Box<dyn std::error::Error>> {
+        let prefix = self.scoped(prefix)?;
+        let mut parameter_filters = vec![ParameterStringFilter {
+            key: "Name".to_string(),
+            option: Some("BeginsWith".to_string()),
+            values: Some(vec![prefix.to_string()]),
+        }];
+        parameter_filters.extend(filters.iter().map(|(key, value)| ParameterStringFilter {
+            key: key.to_string(),
+            option: Some("Equals".to_string()),
+            values: Some(vec![value.to_string()]),
+        }));
+        let mut request = DescribeParametersRequest {
+            filters: None,
+            max_results: Some(50),
+            next_token: None,
+            parameter_filters: Some(parameter_filters),
+        };
+
+        let mut parameters = Vec::new();
+        loop {
+            let result = self
+                .call("DescribeParameters", || {
+                    self.backend.describe_parameters(request.clone())
+                })
+                .await?;
+            parameters.extend(result.parameters.unwrap_or_default());
+            match result.next_token {
+                Some(token) => request.next_token = Some(token),
+                None => break,
+            }
+        }
+        parameters.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(parameters)
+    }
+
+    /// Lists the tags of one parameter as `(key, value)` pairs.
+    pub async fn parameter_tags(
+        &self,
+        path: &str,
+    ) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let request = ListTagsForResourceRequest {
+            resource_id: path.to_string(),
+            resource_type: "Parameter".to_string(),
+        };
+        let result = self
+            .call("ListTagsForResource", || {
+                self.backend.list_tags_for_resource(request.clone())
+            })
+            .await?;
+        Ok(result
+            .tag_list
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tag| (tag.key, tag.value))
+            .collect())
+    }
+
+    /// Runs an SSM call through the rate limiter and retry policy.
+    pub async fn call<T, E, F, Fut>(&self, operation: &str, op: F) -> Result<T, RusotoError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, RusotoError<E>>>,
+        E: std::error::Error + 'static,
+    {
+        if self.offline {
+            return Err(RusotoError::HttpDispatch(HttpDispatchError::new(format!(
+                "{} not sent: offline mode never contacts AWS",
+                operation
+            ))));
+        }
+
+        let options = &self.client_options;
+        with_retry(
+            &options.retry,
+            &self.limiter,
+            &self.stats,
+            operation,
+            options.request_timeout,
+            op,
+            |attempt, delay, err| {
+                log::info!(
+                    "{} failed (attempt {}/{}): {}; retrying in {:?}",
+                    operation,
+                    attempt,
+                    options.retry.max_attempts,
+                    err,
+                    delay
+                );
+            },
+        )
+        .await
+    }
+
+    pub async fn set_parameter(
+        &mut self,
         path: &str,
         value: String,
         param_type: Option<String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_scope(path)?;
+        self.check_constraints(path, &value).await?;
+        self.snapshot_before_write(path).await?;
+        let value = self
+            .write_chunks(path, &value, param_type.clone())
+            .await?
+            .unwrap_or(value);
         let request = rusoto_ssm::PutParameterRequest {
             name: path.to_string(),
             value,
             overwrite: Some(true),
             type_: param_type,
             tier: None,
-            data_type: None,
+            // Sent again so AWS keeps validating the value as an AMI id.
+            data_type: self.data_types.get(path).cloned(),
             allowed_pattern: None,
             description: None,
             key_id: None,
@@ -82,181 +711,825 @@ impl ParameterCompleter {
             tags: None,
         };
 
-        self.client.put_parameter(request).await?;
+        let result = self
+            .call("PutParameter", || {
+                self.backend.put_parameter(request.clone())
+            })
+            .await?;
+        if let Some(param_type) = &request.type_ {
+            self.types.insert(path.to_string(), param_type.clone());
+        }
+        self.revisions
+            .insert(path.to_string(), Revision::written(result.version));
+        Ok(())
+    }
+
+    /// The `AllowedPattern` of `path`, looked up with DescribeParameters the
+    /// first time and remembered for the session along with its data type.
+    /// Offline, only a pattern already looked up is known.
+    pub async fn allowed_pattern(
+        &mut self,
+        path: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if let Some(pattern) = self.allowed_patterns.get(path) {
+            return Ok(pattern.clone());
+        }
+        if self.offline {
+            return Ok(None);
+        }
+        let described = self
+            .describe_parameters(path)
+            .await?
+            .into_iter()
+            .find(|metadata| metadata.name.as_deref() == Some(path));
+        let Some(metadata) = described else {
+            self.allowed_patterns.insert(path.to_string(), None);
+            return Ok(None);
+        };
+        self.record_constraints(path, &metadata);
+        Ok(metadata.allowed_pattern)
+    }
+
+    /// Remembers the allowed pattern and data type DescribeParameters
+    /// reported for `path`.
+    pub fn record_constraints(&mut self, path: &str, metadata: &ParameterMetadata) {
+        self.allowed_patterns
+            .insert(path.to_string(), metadata.allowed_pattern.clone());
+        match metadata.data_type.as_deref() {
+            Some(data_type) if data_type != "text" => {
+                self.data_types
+                    .insert(path.to_string(), data_type.to_string());
+            }
+            _ => {
+                self.data_types.remove(path);
+            }
+        }
+    }
+
+    /// Refuses a value that the parameter's `AllowedPattern` or data type
+    /// would make AWS reject. A pattern using syntax the regex crate does not
+    /// know is left to AWS.
+    async fn check_constraints(
+        &mut self,
+        path: &str,
+        value: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let pattern = self.allowed_pattern(path).await?;
+        if let Some(data_type) = self.data_types.get(path) {
+            check_data_type(path, value, data_type)?;
+        }
+        let Some(pattern) = pattern else {
+            return Ok(());
+        };
+        if let Err(err) = regex::Regex::new(&pattern) {
+            log::info!(
+                "Not checking the allowed pattern of {} locally: {}",
+                path,
+                err
+            );
+            return Ok(());
+        }
+        check_allowed_pattern(path, value, &pattern)?;
         Ok(())
     }
 
+    /// With automatic snapshots on, records what `path` holds in AWS before
+    /// the session first writes to it, in a snapshot started with the first
+    /// write and saved after each addition. Starting it prunes old ones.
+    async fn snapshot_before_write(
+        &mut self,
+        path: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.auto_snapshots.enabled
+            || self
+                .session_snapshot
+                .as_ref()
+                .is_some_and(|(_, snapshot)| snapshot.records(path))
+        {
+            return Ok(());
+        }
+        if !self.encryption.can_persist_values() {
+            return Err(snapshots::AUTO_NEEDS_KEY.into());
+        }
+        let before = self.fetch_parameter(path).await?;
+
+        let dir = self.store.snapshots();
+        let (name, snapshot) = match &mut self.session_snapshot {
+            Some(session) => session,
+            None => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default();
+                let name = snapshots::auto_name(now);
+                for removed in snapshots::prune(&dir, &self.auto_snapshots, now, &name)? {
+                    log::info!("Pruned automatic snapshot {}", removed);
+                }
+                let snapshot = snapshots::Snapshot {
+                    prefix: "/".to_string(),
+                    region: crate::utils::region_spec(&self.region),
+                    profile: self.client_options.profile.clone(),
+                    created_at: now,
+                    encrypted: self.encryption.enabled,
+                    parameters: Default::default(),
+                    auto: true,
+                    absent: Vec::new(),
+                };
+                self.session_snapshot.insert((name, snapshot))
+            }
+        };
+        match before.and_then(|param| Some((param.value?, param.type_))) {
+            Some((value, param_type)) => {
+                let entry = SnapshotEntry {
+                    param_type,
+                    value: self.encryption.encrypt_value(&value),
+                };
+                snapshot.parameters.insert(path.to_string(), entry);
+            }
+            None => snapshot.absent.push(path.to_string()),
+        }
+        snapshots::save(&dir, name, snapshot)?;
+        Ok(())
+    }
+
+    /// Creates a parameter that must not exist yet, then caches it. Unlike
+    /// `set_parameter` this never overwrites, which is what lets AWS accept tags.
+    pub async fn create_parameter(
+        &mut self,
+        new: &NewParameter,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_scope(&new.path)?;
+        self.type_policy
+            .resolve(&new.path, Some(&new.param_type), &new.param_type)?;
+        if let Some(pattern) = &new.allowed_pattern {
+            check_allowed_pattern(&new.path, &new.value, pattern)?;
+        }
+        if let Some(data_type) = &new.data_type {
+            check_data_type(&new.path, &new.value, data_type)?;
+            if data_type == "aws:ec2:image" && new.param_type != "String" {
+                return Err(format!(
+                    "{}: aws:ec2:image parameters must be of type String",
+                    new.path
+                )
+                .into());
+            }
+        }
+        self.snapshot_before_write(&new.path).await?;
+        let tags = (!new.tags.is_empty()).then(|| {
+            new.tags
+                .iter()
+                .map(|(key, value)| Tag {
+                    key: key.clone(),
+                    value: value.clone(),
+                })
+                .collect()
+        });
+        let value = self
+            .write_chunks(&new.path, &new.value, Some(new.param_type.clone()))
+            .await?
+            .unwrap_or_else(|| new.value.clone());
+        let request = rusoto_ssm::PutParameterRequest {
+            name: new.path.clone(),
+            value,
+            overwrite: Some(false),
+            type_: Some(new.param_type.clone()),
+            tier: None,
+            data_type: new.data_type.clone(),
+            allowed_pattern: new.allowed_pattern.clone(),
+            description: new.description.clone(),
+            key_id: new.key_id.clone(),
+            policies: None,
+            tags,
+        };
+
+        let result = self
+            .call("PutParameter", || {
+                self.backend.put_parameter(request.clone())
+            })
+            .await?;
+        self.types.insert(new.path.clone(), new.param_type.clone());
+        self.revisions
+            .insert(new.path.clone(), Revision::written(result.version));
+        self.allowed_patterns
+            .insert(new.path.clone(), new.allowed_pattern.clone());
+        if let Some(data_type) = new.data_type.clone().filter(|t| t != "text") {
+            self.data_types.insert(new.path.clone(), data_type);
+        }
+        self.update_all(&new.path, new.value.clone()).await
+    }
+
+    /// With `chunking` on, writes a value too large for one parameter as
+    /// `.chunkN` children and returns the manifest to store under `path`
+    /// itself; returns `None` when the value fits. Chunks left over from a
+    /// previous, longer value are deleted.
+    async fn write_chunks(
+        &self,
+        path: &str,
+        value: &str,
+        param_type: Option<String>,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let old_count = self
+            .cached_value(path)
+            .filter(|old| self.chunking && chunking::needs_chunking(old))
+            .map_or(0, |old| chunking::split(&old).len());
+        if !self.chunking || !chunking::needs_chunking(value) {
+            self.delete_chunks(path, 0..old_count).await;
+            return Ok(None);
+        }
+
+        let chunks = chunking::split(value);
+        if chunks.len() > chunking::MAX_CHUNKS {
+            return Err(format!(
+                "{} is {} bytes, more than {} chunks of {} bytes",
+                path,
+                value.len(),
+                chunking::MAX_CHUNKS,
+                chunking::CHUNK_SIZE
+            )
+            .into());
+        }
+        self.progress(&format!("Writing {} as {} chunks", path, chunks.len()));
+        for (index, chunk) in chunks.iter().enumerate() {
+            let request = rusoto_ssm::PutParameterRequest {
+                name: chunking::chunk_path(path, index),
+                value: chunk.to_string(),
+                overwrite: Some(true),
+                type_: param_type.clone(),
+                tier: None,
+                data_type: None,
+                allowed_pattern: None,
+                description: None,
+                key_id: None,
+                policies: None,
+                tags: None,
+            };
+            self.call("PutParameter", || {
+                self.backend.put_parameter(request.clone())
+            })
+            .await?;
+        }
+        self.delete_chunks(path, chunks.len()..old_count).await;
+        Ok(Some(chunking::manifest(value, chunks.len())))
+    }
+
+    /// Best-effort removal of chunks that are no longer referenced.
+    async fn delete_chunks(&self, path: &str, indices: std::ops::Range<usize>) {
+        for index in indices {
+            let request = DeleteParameterRequest {
+                name: chunking::chunk_path(path, index),
+            };
+            if let Err(e) = self
+                .call("DeleteParameter", || {
+                    self.backend.delete_parameter(request.clone())
+                })
+                .await
+            {
+                log::info!("Could not delete {}: {}", request.name, e);
+            }
+        }
+    }
+
+    /// Fetches the chunks named by a manifest value and puts them back together.
+    async fn fetch_chunked(
+        &self,
+        path: &str,
+        manifest: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let (count, _) = chunking::parse_manifest(manifest).unwrap_or_default();
+        let mut chunks = HashMap::new();
+        for index in 0..count {
+            let chunk_path = chunking::chunk_path(path, index);
+            if let Some(value) = self
+                .fetch_parameter(&chunk_path)
+                .await?
+                .and_then(|param| param.value)
+            {
+                chunks.insert(chunk_path, value);
+            }
+        }
+        Ok(chunking::reassemble(path, manifest, |chunk| {
+            chunks.get(chunk).map(String::as_str)
+        })?)
+    }
+
     pub async fn update_all(
         &mut self,
         path: &str,
         value: String,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        self.log(format!("Updating parameter: {}", path).as_str());
-        self.log(format!("New value: {}", value).as_str());
+        log::debug!("Updating parameter: {}", path);
+        log::trace!("New value: {}", value);
 
-        let base_path = self.get_sanitized_base_path();
-        let values_file = self.get_file_path(&base_path, "values");
+        self.write_cached_value(path, Some(&value))?;
 
-        // Encrypt before moving value into the map
-        let encrypted_value = self.encryption.encrypt_value(&value);
-        let new_line = format!("{}: {}\n", path, encrypted_value);
-
-        Self::process_parameter_path(path, &mut self.parameters);
+        self.add_path(path);
+        self.last_change = Instant::now();
         // Move value — no clone needed
         self.values.insert(path.to_string(), value);
 
-        self.log(format!("Writing value to file: {}", values_file).as_str());
-
-        fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(values_file)?
-            .write_all(new_line.as_bytes())?;
-
         // Pass by reference — no HashMap clone
-        self.write_parameters_to_file(&base_path, &self.parameters)?;
+        self.write_parameters_to_file(&self.base_path, &self.parameters)?;
 
-        self.log("Updated all parameters and values");
+        log::info!("Updated all parameters and values");
         Ok(())
     }
 
+    /// Overwrites an existing parameter, keeping its type. The type comes from
+    /// the cache when known; otherwise the parameter is fetched first.
     pub async fn change_value(
         &mut self,
         path: &str,
         value: String,
     ) -> Result<String, Box<dyn std::error::Error>> {
+        let param_type = match self.types.get(path) {
+            Some(param_type) => Some(param_type.clone()),
+            None => {
+                let request = GetParameterRequest {
+                    name: path.to_string(),
+                    with_decryption: Some(true),
+                };
+
+                log::debug!("Fetching parameter: {}", path);
+
+                let result = self
+                    .call("GetParameter", || {
+                        self.backend.get_parameter(request.clone())
+                    })
+                    .await?;
+                match result.parameter {
+                    Some(param) => param.type_,
+                    None => return Ok(value),
+                }
+            }
+        };
+        self.set_parameter(path, value.clone(), param_type).await?;
+
+        log::debug!("Setting parameter: {}", path);
+
+        self.last_change = Instant::now();
+        self.values.insert(path.to_string(), value.clone());
+        self.write_cached_value(path, Some(&value))?;
+        self.write_types_to_file(&self.base_path)?;
+
+        log::info!("Updated parameter: {}", path);
+        Ok(value)
+    }
+
+    pub async fn get_set_values(
+        &mut self,
+        paths: &str,
+    ) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+        let mut request = GetParametersByPathRequest {
+            path: self.scoped(paths)?.to_string(),
+            recursive: Some(true),
+            with_decryption: Some(true),
+            max_results: None,
+            next_token: None,
+            parameter_filters: None,
+        };
+
+        log::debug!("Fetching parameters from path: {}", paths);
+
+        // Collect all pages first so we hold no borrow across the &mut self calls below
+        let mut raw: Vec<(String, String)> = Vec::new();
+        loop {
+            let result = self
+                .call("GetParametersByPath", || {
+                    self.backend.get_parameters_by_path(request.clone())
+                })
+                .await?;
+            let next_token = result.next_token;
+
+            if let Some(params) = result.parameters {
+                for param in params {
+                    let revision = Revision::of(&param);
+                    if let (Some(name), Some(value)) = (param.name, param.value) {
+                        if let Some(param_type) = param.type_ {
+                            self.types.insert(name.clone(), param_type);
+                        }
+                        if !chunking::is_chunk_path(&name) {
+                            self.revisions.insert(name.clone(), revision);
+                        }
+                        raw.push((name, value));
+                    }
+                }
+            }
+
+            match next_token {
+                Some(token) => request.next_token = Some(token),
+                None => break,
+            }
+        }
+
+        let mut raw: HashMap<String, String> = raw.into_iter().collect();
+        for err in chunking::assemble_all(&mut raw) {
+            eprintln!("{}", err.yellow());
+        }
+
+        // Process: update local cache then build the results map.
+        // Value is cloned once for update_all; name is moved into results.
+        let mut results = HashMap::with_capacity(raw.len());
+        for (name, value) in raw {
+            self.update_all(&name, value.clone()).await?;
+            results.insert(name, value);
+        }
+
+        log::info!("Fetched {} parameters", results.len());
+        if results.is_empty() {
+            log::debug!("No parameters found in the specified path");
+        } else {
+            log::debug!("Parameters fetched successfully");
+        }
+        Ok(results)
+    }
+
+    pub async fn get_set_value(
+        &mut self,
+        path: &str,
+    ) -> Result<String, RusotoError<rusoto_ssm::GetParameterError>> {
+        self.check_scope(path).map_err(RusotoError::Validation)?;
+        log::debug!("Fetching parameter: {}", path);
+
         let request = GetParameterRequest {
             name: path.to_string(),
             with_decryption: Some(true),
-            ..Default::default()
         };
 
-        self.log(format!("Fetching parameter: {}", path).as_str());
+        log::debug!("Fetching parameter: {}", path);
+        let result = self
+            .call("GetParameter", || {
+                self.backend.get_parameter(request.clone())
+            })
+            .await?;
 
-        let result = self.client.get_parameter(request).await?;
+        if let Some(param) = result.parameter
+            && let Some(mut value) = param.value.clone()
+        {
+            if let Some(param_type) = &param.type_ {
+                self.types.insert(path.to_string(), param_type.clone());
+            }
+            self.revisions
+                .insert(path.to_string(), Revision::of(&param));
+            if chunking::parse_manifest(&value).is_some() {
+                value = self.fetch_chunked(path, &value).await.map_err(|e| {
+                    RusotoError::HttpDispatch(HttpDispatchError::new(e.to_string()))
+                })?;
+            }
+            self.cache_value(path, value.clone()).await;
+            log::info!("Updated parameter: {}", path);
+            return Ok(value);
+        }
 
-        if let Some(param) = result.parameter {
-            self.set_parameter(path, value.clone(), param.type_).await?;
+        log::info!("Parameter not found: {}", path);
+        Ok("".to_string())
+    }
+
+    /// Fetches the given parameters with GetParameters, 10 names per call, and
+    /// caches them. Returns the values found; names that do not exist are
+    /// left out.
+    pub async fn get_set_values_by_names(
+        &mut self,
+        paths: &[String],
+    ) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+        for path in paths {
+            self.check_scope(path)?;
+        }
+        let mut fetched = Vec::new();
+        for batch in paths.chunks(GET_PARAMETERS_BATCH) {
+            let request = GetParametersRequest {
+                names: batch.to_vec(),
+                with_decryption: Some(true),
+            };
+            let result = self
+                .call("GetParameters", || {
+                    self.backend.get_parameters(request.clone())
+                })
+                .await?;
+            for name in result.invalid_parameters.unwrap_or_default() {
+                log::info!("Parameter not found: {}", name);
+            }
+            for param in result.parameters.unwrap_or_default() {
+                let revision = Revision::of(&param);
+                if let (Some(name), Some(value)) = (param.name, param.value) {
+                    if let Some(param_type) = param.type_ {
+                        self.types.insert(name.clone(), param_type);
+                    }
+                    self.revisions.insert(name.clone(), revision);
+                    fetched.push((name, value));
+                }
+            }
         }
 
-        self.log(format!("Setting parameter: {}", path).as_str());
+        let mut results = HashMap::with_capacity(fetched.len());
+        for (name, mut value) in fetched {
+            if chunking::parse_manifest(&value).is_some() {
+                value = self.fetch_chunked(&name, &value).await?;
+            }
+            self.cache_value(&name, value.clone()).await;
+            results.insert(name, value);
+        }
+        log::info!(
+            "Fetched {} of {} parameters by name",
+            results.len(),
+            paths.len()
+        );
+        Ok(results)
+    }
 
-        self.values.insert(path.to_string(), value.clone());
+    /// Brings the values of `paths` into memory: evicted ones from the disk
+    /// cache, unknown ones from AWS in batches of GetParameters calls (not
+    /// offline).
+    pub async fn hydrate(&mut self, paths: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let missing: Vec<&str> = paths
+            .iter()
+            .map(String::as_str)
+            .filter(|path| !self.values.is_resident(path))
+            .collect();
+        if missing.is_empty() {
+            self.stats.record_cache(paths.len(), 0);
+            return Ok(());
+        }
+        let from_disk = self.read_evicted(&missing);
+        let remaining: Vec<String> = missing
+            .iter()
+            .filter(|path| !from_disk.contains_key(**path))
+            .map(|path| path.to_string())
+            .collect();
+        self.stats
+            .record_cache(paths.len() - remaining.len(), remaining.len());
+        for (path, value) in from_disk {
+            self.values.insert(path, value);
+        }
+        if remaining.is_empty() || self.offline {
+            return Ok(());
+        }
+        self.progress(&format!("Fetching {} uncached value(s)", remaining.len()));
+        self.get_set_values_by_names(&remaining).await?;
+        Ok(())
+    }
 
-        let base_path = self.get_sanitized_base_path();
-        let file_path = self.get_file_path(&base_path, "values");
+    /// The value of `path` from memory, or from the disk cache when it was
+    /// evicted. Never contacts AWS.
+    pub fn cached_value(&self, path: &str) -> Option<String> {
+        if let Some(value) = self.values.get(path) {
+            return Some(value.clone());
+        }
+        if !self.values.contains_key(path) {
+            return None;
+        }
+        self.read_evicted(&[path]).remove(path)
+    }
+
+    /// `cached_value` for many paths, reading the disk cache at most once.
+    /// Unknown paths are left out.
+    pub fn cached_values(&self, paths: &[String]) -> HashMap<String, String> {
+        let mut found = HashMap::with_capacity(paths.len());
+        let mut evicted = Vec::new();
+        for path in paths {
+            match self.values.get(path) {
+                Some(value) => {
+                    found.insert(path.clone(), value.clone());
+                }
+                None if self.values.contains_key(path) => evicted.push(path.as_str()),
+                None => {}
+            }
+        }
+        if !evicted.is_empty() {
+            found.extend(self.read_evicted(&evicted));
+        }
+        found
+    }
+
+    /// Reads values back from the values file, decrypting only the ones asked for.
+    fn read_evicted(&self, paths: &[&str]) -> HashMap<String, String> {
+        if !self.encryption.can_persist_values() {
+            return HashMap::new();
+        }
+        let file_path = self.store.values(&self.base_path);
+        let stored = match cache_file::read_map(&file_path, &self.encryption, str::to_string) {
+            Ok(stored) => stored,
+            Err(e) => {
+                log::info!("Could not read evicted values: {}", e);
+                return HashMap::new();
+            }
+        };
+        paths
+            .iter()
+            .filter_map(|path| {
+                let value = self.encryption.try_decrypt(stored.get(*path)?).ok()?;
+                Some((path.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Parameters in the tree under `prefix` and in scope, whether or not
+    /// their value is cached: the leaves, plus anything with a cached value.
+    pub fn parameters_under(&self, prefix: &str) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .parameters
+            .iter()
+            .filter(|(path, children)| children.is_empty() && path.starts_with(prefix))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.values
+                    .keys()
+                    .filter(|path| path.starts_with(prefix))
+                    .cloned(),
+            )
+            .filter(|path| self.in_scope(path))
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
 
-        let encrypted_value = self.encryption.encrypt_value(&value);
-        replace_first_line_containing(
-            &file_path,
-            path,
-            format!("{}: {}", path, encrypted_value).as_str(),
-        )?;
+    /// Fetches a parameter's full record (value, type, last-modified) without
+    /// touching the local cache. Returns `None` when it does not exist.
+    pub async fn fetch_parameter(
+        &self,
+        path: &str,
+    ) -> Result<Option<Parameter>, RusotoError<GetParameterError>> {
+        let request = GetParameterRequest {
+            name: path.to_string(),
+            with_decryption: Some(true),
+        };
 
-        self.log(format!("Updated parameter: {}", path).as_str());
-        Ok(value)
+        match self
+            .call("GetParameter", || {
+                self.backend.get_parameter(request.clone())
+            })
+            .await
+        {
+            Ok(result) => Ok(result.parameter),
+            Err(RusotoError::Service(GetParameterError::ParameterNotFound(_))) => Ok(None),
+            Err(e) => Err(e),
+        }
     }
 
-    pub async fn get_set_values(
-        &mut self,
-        paths: &str,
-    ) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    /// Reads AWS public parameters (`/aws/service/...`): the parameter itself,
+    /// or the ones directly under `path` when it is a branch. They live outside
+    /// the base path and any scope, and are neither cached nor added to the tree.
+    pub async fn fetch_public(
+        &self,
+        path: &str,
+    ) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let path = path.trim_end_matches('/');
+        if let Some(param) = self.fetch_parameter(path).await? {
+            return Ok(vec![(path.to_string(), param.value.unwrap_or_default())]);
+        }
+
         let mut request = GetParametersByPathRequest {
-            path: paths.to_string(),
-            recursive: Some(true),
-            with_decryption: Some(true),
+            path: path.to_string(),
+            recursive: Some(false),
             ..Default::default()
         };
-
-        self.log(format!("Fetching parameters from path: {}", paths).as_str());
-
-        // Collect all pages first so we hold no borrow across the &mut self calls below
-        let mut raw: Vec<(String, String)> = Vec::new();
+        let mut found = Vec::new();
         loop {
-            let result = self.client.get_parameters_by_path(request.clone()).await?;
-            let next_token = result.next_token;
-
-            if let Some(params) = result.parameters {
-                for param in params {
-                    if let (Some(name), Some(value)) = (param.name, param.value) {
-                        raw.push((name, value));
-                    }
-                }
-            }
-
-            match next_token {
+            let result = self
+                .call("GetParametersByPath", || {
+                    self.backend.get_parameters_by_path(request.clone())
+                })
+                .await?;
+            found.extend(
+                result
+                    .parameters
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|param| Some((param.name?, param.value?))),
+            );
+            match result.next_token {
                 Some(token) => request.next_token = Some(token),
                 None => break,
             }
         }
+        found.sort();
+        Ok(found)
+    }
 
-        // Process: update local cache then build the results map.
-        // Value is cloned once for update_all; name is moved into results.
-        let mut results = HashMap::with_capacity(raw.len());
-        for (name, value) in raw {
-            self.update_all(&name, value.clone()).await?;
-            results.insert(name, value);
+    /// Stores a freshly fetched or written value in the in-memory maps and the
+    /// values file, adding the path to the tree if it is new.
+    pub async fn cache_value(&mut self, path: &str, value: String) {
+        self.fetched_at.insert(path.to_string(), now_secs());
+        if !self.parameters.contains_key(path) {
+            match self.update_all(path, value).await {
+                Ok(_) => {
+                    log::info!("Added parameter: {}", path);
+                }
+                Err(e) => {
+                    log::info!("Error adding parameter: {}", e);
+                }
+            }
+            return;
         }
 
-        self.log(format!("Fetched {} parameters", results.len()).as_str());
-        if results.is_empty() {
-            self.log("No parameters found in the specified path");
-        } else {
-            self.log("Parameters fetched successfully");
+        if let Err(e) = self.write_cached_value(path, Some(&value)) {
+            log::info!("Error caching value: {}", e);
         }
-        Ok(results)
+        self.last_change = Instant::now();
+        self.values.insert(path.to_string(), value);
     }
 
-    pub async fn get_set_value(
-        &mut self,
+    /// Updates (or with `None`, removes) one entry of the values file, keeping every
+    /// other entry as it is on disk so values only queued in memory are never persisted.
+    fn write_cached_value(
+        &self,
         path: &str,
-    ) -> Result<String, RusotoError<rusoto_ssm::GetParameterError>> {
-        self.log(format!("Fetching parameter: {}", path).as_str());
+        value: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.encryption.can_persist_values() {
+            return Ok(());
+        }
 
-        let request = GetParameterRequest {
-            name: path.to_string(),
-            with_decryption: Some(true),
-            ..Default::default()
+        let file_path = self.store.values(&self.base_path);
+        let mut stored = if file_path.exists() {
+            cache_file::read_map(&file_path, &self.encryption, str::to_string)?
+        } else {
+            HashMap::new()
+        };
+        match value {
+            Some(value) => stored.insert(path.to_string(), self.encryption.encrypt_value(value)),
+            None => stored.remove(path),
         };
 
-        self.log(format!("Fetching parameter: {}", path).as_str());
-        let result = self.client.get_parameter(request).await?;
-
-        if let Some(param) = result.parameter {
-            if let Some(value) = param.value {
-                self.values.insert(path.to_string(), value.clone());
-
-                let base_path = self.get_sanitized_base_path();
-                let values_file_path = self.get_file_path(&base_path, "values");
-                let path_exists = self.parameters.contains_key(path);
+        log::debug!("Writing value to file: {}", file_path.display());
+        cache_file::write_map(&file_path, &stored, &self.encryption)?;
+        Ok(())
+    }
 
-                let encrypted_value = self.encryption.encrypt_value(&value);
+    /// Deletes a parameter from AWS and drops it from the cache.
+    pub async fn delete_parameter(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_scope(path)?;
+        self.snapshot_before_write(path).await?;
+        let request = DeleteParameterRequest {
+            name: path.to_string(),
+        };
+        self.call("DeleteParameter", || {
+            self.backend.delete_parameter(request.clone())
+        })
+        .await?;
+
+        self.last_change = Instant::now();
+        self.forget_path(path);
+        self.write_cached_value(path, None)?;
+        self.write_parameters_to_file(&self.base_path, &self.parameters)?;
+        Ok(())
+    }
 
-                if !path_exists {
-                    match self.update_all(path, value.to_string()).await {
-                        Ok(_) => {
-                            self.log(format!("Added parameter: {}", path).as_str());
-                        }
-                        Err(e) => {
-                            self.log(format!("Error adding parameter: {}", e).as_str());
-                        }
-                    }
-                } else {
-                    replace_first_line_containing(
-                        &values_file_path,
-                        path,
-                        format!("{}: {}", path, encrypted_value).as_str(),
-                    )
-                    .unwrap_or_default();
-                }
+    /// Drops parameters that no longer exist in AWS from the tree, values,
+    /// types and search index, and from the cache files, each written once.
+    pub fn forget(&mut self, paths: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        self.last_change = Instant::now();
+        for path in paths {
+            self.forget_path(path);
+        }
 
-                self.log(format!("Updated parameter: {}", path).as_str());
-                return Ok(value);
+        let file_path = self.store.values(&self.base_path);
+        if self.encryption.can_persist_values() && file_path.exists() {
+            let mut stored = cache_file::read_map(&file_path, &self.encryption, str::to_string)?;
+            for path in paths {
+                stored.remove(path);
             }
+            cache_file::write_map(&file_path, &stored, &self.encryption)?;
         }
+        self.write_parameters_to_file(&self.base_path, &self.parameters)?;
+        Ok(())
+    }
 
-        self.log(format!("Parameter not found: {}", path).as_str());
-        Ok("".to_string())
+    /// Removes `path` from memory, and the directories above it it leaves
+    /// empty, so completion stops offering them. The base path stays.
+    fn forget_path(&mut self, path: &str) {
+        self.values.remove(path);
+        self.types.remove(path);
+        self.revisions.remove(path);
+        self.allowed_patterns.remove(path);
+        self.data_types.remove(path);
+        let base_path = self.base_path.trim_end_matches('/');
+        let mut path = path.to_string();
+        while path != base_path
+            && !self.values.contains_key(&path)
+            && self
+                .parameters
+                .get(&path)
+                .is_none_or(|children| children.is_empty())
+        {
+            self.parameters.remove(&path);
+            self.search_index.remove(&path);
+            let Some((parent, name)) = path.rsplit_once('/') else {
+                break;
+            };
+            let parent = if parent.is_empty() { "/" } else { parent };
+            match self.parameters.get_mut(parent) {
+                Some(children) => children.retain(|child| child != name),
+                None => break,
+            }
+            if parent == "/" {
+                break;
+            }
+            path = parent.to_string();
+        }
     }
 
     fn add_commands(&self, paths_map: &mut HashMap<String, Vec<String>>) {
@@ -277,6 +1550,10 @@ impl ParameterCompleter {
     ) -> Result<(), RusotoError<rusoto_ssm::GetParametersByPathError>> {
         self.parameters.clear();
         self.values.clear();
+        self.types.clear();
+        self.revisions.clear();
+        self.allowed_patterns.clear();
+        self.data_types.clear();
 
         let mut paths_map: HashMap<String, Vec<String>> = HashMap::new();
         let mut values_d: HashMap<String, String> = HashMap::new();
@@ -284,135 +1561,334 @@ impl ParameterCompleter {
         paths_map.insert(self.base_path.clone(), Vec::new());
         self.add_commands(&mut paths_map);
 
-        let mut next_token: Option<String> = None;
         let mut is_parameters_loaded = false;
         let mut is_values_loaded = false;
 
         if !self.refresh {
-            self.log("Checking for existing parameters and values files...");
-            let base_path = self.base_path.replace('/', "_");
-
-            if let Err(e) = self.load_parameters_from_file(base_path.as_str(), &mut paths_map) {
-                self.log(format!("Error loading parameters from file: {}", e).as_str());
+            log::debug!("Checking for existing parameters and values files...");
+            if let Err(e) = self.load_parameters_from_file(&self.base_path, &mut paths_map) {
+                self.report_cache_error("parameters", e.as_ref());
             } else {
                 is_parameters_loaded = true;
             }
 
-            if let Err(e) = self.load_values_from_file(base_path.as_str(), &mut values_d) {
-                self.log(format!("Error loading values from file: {}", e).as_str());
+            if let Err(e) = self.load_values_from_file(&self.base_path, &mut values_d) {
+                self.report_cache_error("values", e.as_ref());
             } else {
                 is_values_loaded = true;
             }
 
             if is_parameters_loaded && is_values_loaded {
-                self.log("Parameters and values loaded from file");
-                self.log(
-                    format!(
-                        "Loaded {} parameter paths and {} values",
-                        paths_map.len(),
-                        values_d.len(),
-                    )
-                    .as_str(),
+                log::info!("Parameters and values loaded from file");
+                log::info!(
+                    "Loaded {} parameter paths and {} values",
+                    paths_map.len(),
+                    values_d.len(),
                 );
 
                 self.parameters = paths_map;
-                self.values = values_d;
+                self.values.replace_all(values_d);
+                (self.types, self.revisions) = self.load_types_from_file(&self.base_path);
+                // Parameters in the tree without a cached value were loaded
+                // without decryption.
+                let undecrypted: Vec<String> = self
+                    .parameters
+                    .iter()
+                    .filter(|(path, children)| {
+                        children.is_empty()
+                            && path.starts_with('/')
+                            && **path != self.base_path
+                            && !self.values.contains_key(path)
+                    })
+                    .map(|(path, _)| path.clone())
+                    .collect();
+                for path in undecrypted {
+                    self.values.insert_unavailable(path);
+                }
+                self.apply_pending();
+                self.reindex();
                 return Ok(());
             }
         }
 
-        self.log(
-            format!(
-                "Loading parameters from AWS Parameter Store from path {} ...",
-                self.base_path
-            )
-            .as_str(),
+        if self.offline {
+            return Err(RusotoError::HttpDispatch(HttpDispatchError::new(format!(
+                "Offline mode: no usable cache for {} in {}. Run daps online once to build it.",
+                self.base_path,
+                self.store.root().display()
+            ))));
+        }
+
+        log::info!(
+            "Loading parameters from AWS Parameter Store from path {} ...",
+            self.base_path
         );
+        let fetched = fetch_snapshot(
+            self.backend.as_ref(),
+            &self.base_path,
+            self.decrypt,
+            &self.client_options,
+            &self.limiter,
+            &self.stats,
+        )
+        .await;
+        let snapshot = match fetched {
+            Err(err) if self.decrypt && is_decrypt_denied(&err.to_string()) => {
+                eprintln!(
+                    "{}",
+                    "No permission to decrypt SecureString values (kms:Decrypt); loading names only"
+                        .yellow()
+                );
+                self.decrypt = false;
+                fetch_snapshot(
+                    self.backend.as_ref(),
+                    &self.base_path,
+                    false,
+                    &self.client_options,
+                    &self.limiter,
+                    &self.stats,
+                )
+                .await?
+            }
+            fetched => fetched?,
+        };
+        self.install_snapshot(snapshot)?;
+        log::info!("Loaded {} parameter paths", self.parameters.len());
+        Ok(())
+    }
 
-        let mut total = 0;
+    /// Replaces the tree, values and types with a freshly fetched snapshot in
+    /// one go, writes them to the cache files and overlays queued changes.
+    fn install_snapshot(&mut self, snapshot: Snapshot) -> io::Result<()> {
+        let mut paths_map = snapshot.parameters;
+        paths_map.entry(self.base_path.clone()).or_default();
+        self.add_commands(&mut paths_map);
 
-        loop {
-            let request = GetParametersByPathRequest {
-                path: self.base_path.clone(),
-                recursive: Some(true),
-                parameter_filters: None,
-                next_token: next_token.clone(),
-                max_results: Some(10),
-                with_decryption: Some(true),
-            };
+        self.parameters = paths_map;
+        self.types = snapshot.types;
+        self.revisions = snapshot.revisions;
+        self.last_change = Instant::now();
+
+        // Values decrypted on demand are kept over a snapshot without them.
+        let mut values = snapshot.values;
+        let mut unavailable = Vec::new();
+        for path in snapshot.unavailable {
+            match self.cached_value(&path) {
+                Some(value) => {
+                    values.insert(path, value);
+                }
+                None => unavailable.push(path),
+            }
+        }
 
-            let result = self.client.get_parameters_by_path(request).await?;
+        log::debug!("Writing parameters and values to file...");
+        self.write_parameters_to_file(&self.base_path, &self.parameters)?;
+        // The file gets every value; memory only what the capacity allows.
+        self.write_values_to_file(&self.base_path, &values)?;
+        let now = now_secs();
+        self.fetched_at = values.keys().map(|path| (path.clone(), now)).collect();
+        self.values.replace_all(values);
+        for path in unavailable {
+            self.values.insert_unavailable(path);
+        }
+        self.apply_pending();
+        self.reindex();
+        Ok(())
+    }
 
-            if result.parameters.is_none() {
-                break;
+    /// Starts (or restarts, for the current target) the background refresher
+    /// when `background_refresh_secs` is set. Does nothing offline.
+    pub fn start_background_refresh(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.refresher = None;
+        if self.background_refresh_secs == 0 || self.offline {
+            return Ok(());
+        }
+        let interval = Duration::from_secs(self.background_refresh_secs).max(MIN_INTERVAL);
+        let backend = build_backend(self.backend_kind, self.region.clone(), &self.client_options)?;
+        self.refresher = Some(BackgroundRefresher::spawn(
+            interval,
+            backend,
+            self.base_path.clone(),
+            self.decrypt,
+            self.client_options.clone(),
+            Arc::clone(&self.limiter),
+            Arc::clone(&self.stats),
+        ));
+        log::info!(
+            "Refreshing {} in the background every {:?}",
+            self.base_path,
+            interval
+        );
+        Ok(())
+    }
+
+    /// Swaps in the latest background snapshot, unless something was written
+    /// locally since it was fetched. Returns whether the cache was replaced.
+    pub fn apply_background_refresh(&mut self) -> bool {
+        let Some(snapshot) = self.refresher.as_ref().and_then(|r| r.take()) else {
+            return false;
+        };
+        if snapshot.started < self.last_change {
+            log::info!("Dropped a background refresh older than local changes");
+            return false;
+        }
+        match self.install_snapshot(snapshot) {
+            Ok(()) => {
+                log::info!("Background refresh: {} values", self.values.len());
+                true
+            }
+            Err(err) => {
+                log::warn!("Could not store the background refresh: {}", err);
+                false
             }
+        }
+    }
 
-            let len = result.parameters.as_ref().map_or(0, |p| p.len());
-            self.log(format!("Fetched {} parameters", len).as_str());
-            total += len;
-            self.log(format!("Total parameters fetched: {}", total).as_str());
+    /// Untrusted cache files are always reported; a missing cache only at info level (`-v`).
+    fn report_cache_error(&self, file_type: &str, err: &(dyn std::error::Error + 'static)) {
+        if err.downcast_ref::<CacheError>().is_some() {
+            eprintln!("{}", err.to_string().yellow());
+        } else {
+            log::info!("Error loading {} from file: {}", file_type, err);
+        }
+    }
 
-            // Consume params so name/value are owned Strings — no clone needed
-            if let Some(params) = result.parameters {
-                for param in params {
-                    if let (Some(name), Some(value)) = (param.name, param.value) {
-                        Self::process_parameter_path(&name, &mut paths_map);
-                        values_d.insert(name, value);
-                    }
-                }
-            }
+    /// Overlays queued offline writes on the freshly loaded cache.
+    fn apply_pending(&mut self) {
+        for change in &self.pending.changes {
+            Self::process_parameter_path(&change.path, &mut self.parameters);
+            self.values
+                .insert_pinned(change.path.clone(), change.value.clone());
+        }
+    }
 
-            next_token = result.next_token;
-            if next_token.is_none() {
-                break;
-            }
+    /// Re-encrypts the values file with the Argon2id key. Values that do not
+    /// decrypt with the current key abort the migration without writing,
+    /// since they may be ciphertext under another passphrase; with
+    /// `assume_plaintext` they are taken to be plaintext from an unencrypted
+    /// cache and encrypted as they are.
+    pub async fn migrate_encryption(
+        &self,
+        assume_plaintext: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.encryption.enabled {
+            return Err("Encryption is off; set DAPS_ENCRYPTION_KEY to migrate the cache".into());
         }
 
-        // Move into self first, then write from self — no clone
-        self.parameters = paths_map;
-        self.values = values_d;
+        let file_path = self.store.values(&self.base_path);
 
-        let base_path = self.base_path.replace('/', "_");
+        if !file_path.exists() {
+            return Ok(());
+        }
+
+        let mut stored = cache_file::read_map(&file_path, &self.encryption, str::to_string)?;
+        let mut undecryptable = Vec::new();
+        for (key, value) in stored.iter_mut() {
+            // Legacy and current ciphertexts are re-encrypted with the Argon2id key.
+            let plaintext = match self.encryption.try_decrypt(value) {
+                Ok(plaintext) => plaintext,
+                Err(_) if assume_plaintext => value.clone(),
+                Err(_) => {
+                    undecryptable.push(key.clone());
+                    continue;
+                }
+            };
+            *value = self.encryption.encrypt_value(&plaintext);
+        }
+        if !undecryptable.is_empty() {
+            undecryptable.sort();
+            return Err(format!(
+                "{} value(s) do not decrypt with the current key ({}); nothing was changed. \
+                 Check DAPS_ENCRYPTION_KEY, or run 'migration --assume-plaintext' if the cache \
+                 was written unencrypted",
+                undecryptable.len(),
+                undecryptable.join(", ")
+            )
+            .into());
+        }
 
-        self.log("Writing parameters and values to file...");
-        self.write_parameters_to_file(&base_path, &self.parameters)?;
-        self.write_values_to_file(&base_path, &self.values)?;
+        cache_file::write_map(&file_path, &stored, &self.encryption)?;
 
-        self.log(format!("Loaded {} parameter paths", self.parameters.len()).as_str());
+        log::info!("Migration completed");
         Ok(())
     }
 
-    pub async fn migrate_encryption(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let base_path = self.get_sanitized_base_path();
-        let file_path = self.get_file_path(&base_path, "values");
-
-        if !std::path::Path::new(&file_path).exists() {
-            return Ok(());
+    /// Re-encrypts every cached values file in the store directory with a key derived
+    /// from `new_secret` and a fresh salt, and re-seals the parameters and types files. Each value
+    /// must decrypt with the current key and round-trip through the new one before any
+    /// file is replaced. Returns the number of values re-encrypted.
+    pub fn rotate_key(&mut self, new_secret: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        if !self.encryption.enabled {
+            return Err("Encryption is off; there is no key to rotate".into());
         }
 
-        let file = File::open(&file_path)?;
-        let reader = BufReader::new(file);
-        let mut lines = Vec::new();
+        let salt = crate::encryption::generate_salt();
+        let mut new_encryption = Encryption::new(true, new_secret, &salt)?;
+        new_encryption.allow_plaintext = self.encryption.allow_plaintext;
+        new_encryption.seal_cache = self.encryption.seal_cache;
+
+        let store_dir = self.store.root();
+        let mut rewritten = Vec::new();
+        let mut count = 0;
+
+        for entry in std::fs::read_dir(store_dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if !name.ends_with(".txt") {
+                continue;
+            }
 
-        for line in reader.lines() {
-            let line = line?;
-            if line.contains(':') {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() == 2 {
-                    let key = parts[0].trim();
-                    let value = parts[1].trim();
-                    lines.push(format!("{}: {}", key, self.encryption.encrypt_value(value)));
+            let staged = path.with_extension("txt.rotate");
+            if name.starts_with("values_") {
+                let mut stored = cache_file::read_map(&path, &self.encryption, str::to_string)?;
+                for (key, value) in stored.iter_mut() {
+                    let plaintext = self.encryption.try_decrypt(value).map_err(|e| {
+                        format!(
+                            "Cannot decrypt {} in {} with the current key ({}); nothing was changed",
+                            key, name, e
+                        )
+                    })?;
+                    let encrypted = new_encryption.encrypt_value(&plaintext);
+                    if new_encryption.try_decrypt(&encrypted).as_deref() != Ok(plaintext.as_str()) {
+                        return Err(format!(
+                            "Round-trip check failed for {}; nothing was changed",
+                            key
+                        )
+                        .into());
+                    }
+                    *value = encrypted;
+                    count += 1;
                 }
+                cache_file::write_map(&staged, &stored, &new_encryption)?;
+            } else if name.starts_with("parameters_") {
+                let stored: HashMap<String, Vec<String>> =
+                    cache_file::read_map(&path, &self.encryption, |_| Vec::new())?;
+                cache_file::write_map(&staged, &stored, &new_encryption)?;
+            } else if name.starts_with("types_") {
+                let stored: HashMap<String, serde_json::Value> =
+                    cache_file::read_map(&path, &self.encryption, |v| v.into())?;
+                cache_file::write_map(&staged, &stored, &new_encryption)?;
+            } else {
+                continue;
             }
+            rewritten.push((staged, path));
         }
 
-        let mut file = File::create(&file_path)?;
-        for line in lines {
-            writeln!(file, "{}", line)?;
+        // Every file was staged successfully; swap them in together with the new salt.
+        let salt_file = self.store.salt();
+        let staged_salt = salt_file.with_extension("rotate");
+        crate::encryption::write_salt(&staged_salt, &salt)?;
+        for (staged, path) in rewritten {
+            std::fs::rename(staged, path)?;
         }
+        std::fs::rename(staged_salt, salt_file)?;
 
-        self.log("Migration completed");
-        Ok(())
+        self.encryption = new_encryption;
+        log::info!("Key rotation completed");
+        Ok(count)
     }
 
     pub fn load_parameters_from_file(
@@ -420,32 +1896,73 @@ impl ParameterCompleter {
         base_path: &str,
         paths_map: &mut HashMap<String, Vec<String>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let store_dir = &self.store_dir;
-        let file_path = if cfg!(target_os = "windows") {
-            format!("{}\\parameters_{}.txt", store_dir, base_path)
-        } else {
-            format!("{}/parameters_{}.txt", store_dir, base_path)
-        };
+        let file_path = self.store.parameters(base_path);
 
-        self.log(format!("Loading parameters from file: {}", file_path).as_str());
-        let file = File::open(file_path)?;
-        let reader = io::BufReader::new(file);
+        log::debug!("Loading parameters from file: {}", file_path.display());
+        let stored: HashMap<String, Vec<String>> =
+            cache_file::read_map(&file_path, &self.encryption, |_| Vec::new())?;
 
         paths_map.insert(self.base_path.clone(), Vec::new());
 
-        for line in reader.lines() {
-            let line = line?;
-            if line.contains(':') {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() == 2 {
-                    let path = parts[0].trim();
-                    Self::process_parameter_path(path, paths_map);
+        // Children are rebuilt from the paths themselves.
+        for path in stored.keys() {
+            Self::process_parameter_path(path, paths_map);
+        }
+
+        log::info!("Parameters loaded from file");
+        Ok(())
+    }
+
+    /// Types and revisions are only hints, so a missing or unreadable file is
+    /// not an error.
+    fn load_types_from_file(
+        &self,
+        base_path: &str,
+    ) -> (HashMap<String, String>, HashMap<String, Revision>) {
+        let stored = cache_file::read_map(&self.store.types(base_path), &self.encryption, |v| {
+            TypeEntry::Plain(v.to_string())
+        })
+        .unwrap_or_default();
+        let mut types = HashMap::with_capacity(stored.len());
+        let mut revisions = HashMap::new();
+        for (path, entry) in stored {
+            match entry {
+                TypeEntry::Plain(param_type) => {
+                    types.insert(path, param_type);
+                }
+                TypeEntry::Detailed {
+                    param_type,
+                    revision,
+                } => {
+                    if let Some(param_type) = param_type {
+                        types.insert(path.clone(), param_type);
+                    }
+                    revisions.insert(path, revision);
                 }
             }
         }
+        (types, revisions)
+    }
 
-        self.log("Parameters loaded from file");
-        Ok(())
+    /// Writes the types file: each known type together with its revision.
+    fn write_types_to_file(&self, base_path: &str) -> io::Result<()> {
+        let mut stored: HashMap<String, TypeEntry> = self
+            .revisions
+            .iter()
+            .map(|(path, revision)| {
+                let entry = TypeEntry::Detailed {
+                    param_type: self.types.get(path).cloned(),
+                    revision: *revision,
+                };
+                (path.clone(), entry)
+            })
+            .collect();
+        for (path, param_type) in &self.types {
+            stored
+                .entry(path.clone())
+                .or_insert_with(|| TypeEntry::Plain(param_type.clone()));
+        }
+        cache_file::write_map(&self.store.types(base_path), &stored, &self.encryption)
     }
 
     pub fn load_values_from_file(
@@ -453,27 +1970,21 @@ impl ParameterCompleter {
         base_path: &str,
         values_map: &mut HashMap<String, String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let store_dir = &self.store_dir;
-        let file_path = if cfg!(target_os = "windows") {
-            format!("{}\\values_{}.txt", store_dir, base_path)
-        } else {
-            format!("{}/values_{}.txt", store_dir, base_path)
-        };
+        if !self.encryption.can_persist_values() {
+            return Err("values are not cached on disk while encryption is off".into());
+        }
 
-        self.log(format!("Loading values from file: {}", file_path).as_str());
-        let file = File::open(file_path)?;
-        let reader = io::BufReader::new(file);
-
-        for line in reader.lines() {
-            let line = line?;
-            if line.contains(':') {
-                let parts: Vec<&str> = line.split(':').collect();
-                if parts.len() == 2 {
-                    let key = parts[0].trim().to_owned();
-                    let value = parts[1].trim().to_owned();
-                    values_map.insert(key, self.encryption.decrypt_value(&value));
-                }
-            }
+        let file_path = self.store.values(base_path);
+
+        log::debug!("Loading values from file: {}", file_path.display());
+        let stored = cache_file::read_map(&file_path, &self.encryption, str::to_string)?;
+
+        for (key, value) in stored {
+            let value = self
+                .encryption
+                .try_decrypt(&value)
+                .map_err(|_| CacheError::Undecryptable(file_path.clone(), key.clone()))?;
+            values_map.insert(key, value);
         }
         Ok(())
     }
@@ -483,25 +1994,25 @@ impl ParameterCompleter {
         base_path: &str,
         values: &HashMap<String, String>,
     ) -> io::Result<()> {
-        self.log("Writing values to file...");
-        self.log(format!("Len of values: {}", values.len()).as_str());
+        if !self.encryption.can_persist_values() {
+            log::info!("Encryption is off; not writing values to disk");
+            return Ok(());
+        }
 
-        let store_dir = &self.store_dir;
-        let file_path = if cfg!(target_os = "windows") {
-            format!("{}\\values_{}.txt", store_dir, base_path)
-        } else {
-            format!("{}/values_{}.txt", store_dir, base_path)
-        };
+        log::debug!("Writing values to file...");
+        log::debug!("Len of values: {}", values.len());
 
-        self.log(format!("File path: {}", file_path).as_str());
+        let file_path = self.store.values(base_path);
 
-        let mut file = File::create(file_path)?;
-        for (key, value) in values.iter() {
-            let encrypted_value = self.encryption.encrypt_value(value);
-            writeln!(file, "{}: {}", key, encrypted_value)?;
-        }
+        log::debug!("File path: {}", file_path.display());
+
+        let encrypted: HashMap<String, String> = values
+            .iter()
+            .map(|(key, value)| (key.clone(), self.encryption.encrypt_value(value)))
+            .collect();
+        cache_file::write_map(&file_path, &encrypted, &self.encryption)?;
 
-        self.log("Values written to file");
+        log::debug!("Values written to file");
         Ok(())
     }
 
@@ -510,29 +2021,116 @@ impl ParameterCompleter {
         base_path: &str,
         parameters: &HashMap<String, Vec<String>>,
     ) -> io::Result<()> {
-        self.log("Writing parameters to file...");
-        self.log(format!("Len of parameters: {}", parameters.len()).as_str());
+        log::debug!("Writing parameters to file...");
+        log::debug!("Len of parameters: {}", parameters.len());
 
-        let store_dir = &self.store_dir;
-        let file_path = if cfg!(target_os = "windows") {
-            format!("{}\\parameters_{}.txt", store_dir, base_path)
-        } else {
-            format!("{}/parameters_{}.txt", store_dir, base_path)
-        };
+        let file_path = self.store.parameters(base_path);
 
-        let mut file = File::create(file_path)?;
-        for (path, children) in parameters.iter() {
-            writeln!(file, "{}: {:?}", path, children)?;
-        }
+        cache_file::write_map(&file_path, parameters, &self.encryption)?;
+        self.write_types_to_file(base_path)?;
 
-        self.log("Parameters written to file");
+        log::debug!("Parameters written to file");
         Ok(())
     }
 
-    pub fn process_parameter_path(
-        full_path: &str,
-        paths_map: &mut HashMap<String, Vec<String>>,
-    ) {
+    /// Adds `path` and its parents to the tree and the search index.
+    fn add_path(&mut self, path: &str) {
+        Self::process_parameter_path(path, &mut self.parameters);
+        for (end, _) in path.match_indices('/').skip(1) {
+            self.search_index.insert(&path[..end]);
+        }
+        self.search_index.insert(path);
+    }
+
+    fn reindex(&mut self) {
+        self.search_index =
+            SearchIndex::build(self.parameters.keys().filter(|path| path.starts_with('/')));
+    }
+
+    /// Whether `path` is inside the session's scope (always, when unscoped).
+    pub fn in_scope(&self, path: &str) -> bool {
+        match &self.scope {
+            None => true,
+            Some(scope) => path
+                .strip_prefix(scope.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/')),
+        }
+    }
+
+    /// Whether `path` is in scope or leads to it, so completion can reach it.
+    fn leads_into_scope(&self, path: &str) -> bool {
+        self.in_scope(path)
+            || self.scope.as_deref().is_some_and(|scope| {
+                scope
+                    .strip_prefix(path.trim_end_matches('/'))
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+    }
+
+    /// Where commands without a prefix start: the scope, else the base path.
+    pub fn root(&self) -> &str {
+        self.scope.as_deref().unwrap_or(&self.base_path)
+    }
+
+    /// `prefix` narrowed to the scope: itself when inside it, the scope when
+    /// `prefix` contains it, and an error when they do not overlap.
+    pub fn scoped<'a>(&'a self, prefix: &'a str) -> Result<&'a str, String> {
+        match &self.scope {
+            Some(scope) if !self.in_scope(prefix) => {
+                if self.leads_into_scope(prefix) {
+                    Ok(scope)
+                } else {
+                    Err(format!(
+                        "{} is outside the scope {}; 'unscope' to lift it",
+                        prefix, scope
+                    ))
+                }
+            }
+            _ => Ok(prefix),
+        }
+    }
+
+    /// Refuses to read or write a parameter outside the scope.
+    pub fn check_scope(&self, path: &str) -> Result<(), String> {
+        match &self.scope {
+            Some(scope) if !self.in_scope(path) => Err(format!(
+                "{} is outside the scope {}; 'unscope' to lift it",
+                path, scope
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `path` is a parameter rather than a directory of the tree.
+    pub fn is_parameter(&self, path: &str) -> bool {
+        self.values.contains_key(path)
+            || self
+                .parameters
+                .get(path)
+                .is_some_and(|children| children.is_empty())
+    }
+
+    /// Parameters that can fuzzy-match `query`, sorted, from the search index.
+    pub fn search_candidates(&self, query: &str) -> Vec<String> {
+        self.sorted_parameters(self.search_index.fuzzy_candidates(query))
+    }
+
+    /// Parameters whose path contains `term`, ignoring case, sorted.
+    pub fn parameters_containing(&self, term: &str) -> Vec<String> {
+        self.sorted_parameters(self.search_index.containing(term))
+    }
+
+    fn sorted_parameters(&self, paths: Vec<&String>) -> Vec<String> {
+        let mut paths: Vec<String> = paths
+            .into_iter()
+            .filter(|path| self.is_parameter(path) && self.in_scope(path))
+            .cloned()
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    pub fn process_parameter_path(full_path: &str, paths_map: &mut HashMap<String, Vec<String>>) {
         paths_map.entry("/".to_string()).or_default();
 
         let path_parts: Vec<&str> = full_path
@@ -542,10 +2140,10 @@ impl ParameterCompleter {
         let mut current_path = "/".to_string();
 
         for part in path_parts {
-            paths_map
-                .entry(current_path.clone())
-                .or_default()
-                .push(part.to_string());
+            let children = paths_map.entry(current_path.clone()).or_default();
+            if !children.iter().any(|child| child == part) {
+                children.push(part.to_string());
+            }
 
             if current_path.ends_with('/') {
                 current_path.push_str(part);
@@ -559,18 +2157,105 @@ impl ParameterCompleter {
     }
 
     pub fn get_completions(&self, path: &str) -> Vec<String> {
-        if path.to_lowercase().starts_with("set") {
-            let selected = self.metadata.get("selected").map(|s| s.as_str()).unwrap_or("");
-            let val = self.values.get(selected).map(|s| s.as_str()).unwrap_or("");
-            return vec![format!("set {}", val)];
+        let set_args = match path {
+            "set" => Some(""),
+            _ => path.strip_prefix("set "),
+        };
+        // `set @na<Tab>` is left to the alias completion below.
+        if let Some(args) = set_args
+            && (args.contains(' ') || !args.starts_with('@'))
+        {
+            // `set /pa<Tab>` completes the path itself.
+            if args.starts_with('/') && !args.contains(' ') {
+                return self
+                    .get_completions(args)
+                    .into_iter()
+                    .map(|completion| format!("set {}", completion))
+                    .collect();
+            }
+            // `set <path> <Tab>` fills in that parameter's value, a bare
+            // `set <Tab>` the selected one's.
+            let (head, target) = match args.split_once(' ') {
+                Some((first, _)) if first.starts_with(['/', '@']) => (
+                    format!("set {} ", first),
+                    self.aliases.resolve(first).unwrap_or_default(),
+                ),
+                _ => (
+                    "set ".to_string(),
+                    self.metadata.get("selected").cloned().unwrap_or_default(),
+                ),
+            };
+            // Never put a secret on the command line, where it would be shown.
+            if self.redact || self.is_sensitive(&target) {
+                return vec![head];
+            }
+            let val = self.cached_value(&target).unwrap_or_default();
+            return vec![format!("{}{}", head, val)];
+        }
+
+        if let Some(prefix) = path.strip_prefix("region ") {
+            return Self::complete_words("region ", prefix, &self.vocabulary.regions());
+        }
+
+        if let Some(prefix) = path.strip_prefix("profile ") {
+            return Self::complete_words("profile ", prefix, &vocabulary::aws_profiles());
         }
 
         if path.to_lowercase().starts_with("insert") {
-            let selected = self.metadata.get("selected").map(|s| s.as_str()).unwrap_or("");
-            let val = self.values.get(selected).map(|s| s.as_str()).unwrap_or("");
+            // KMS key aliases anywhere in the arguments
+            let token_start = path.rfind([' ', ':']).map_or(0, |i| i + 1);
+            if path[token_start..].starts_with("alias/") {
+                return Self::complete_words(
+                    &path[..token_start],
+                    &path[token_start..],
+                    &self.vocabulary.kms_aliases,
+                );
+            }
+
+            // `insert /path:value:<Tab>` completes the parameter type
+            if path.matches(':').count() >= 2 {
+                let type_start = path.rfind(':').map_or(0, |i| i + 1);
+                let types: Vec<String> = PARAMETER_TYPES.iter().map(|t| t.to_string()).collect();
+                return Self::complete_words(&path[..type_start], &path[type_start..], &types);
+            }
+
+            let selected = self
+                .metadata
+                .get("selected")
+                .map(|s| s.as_str())
+                .unwrap_or("");
+            let val = match self.redact || self.is_sensitive(selected) {
+                true => String::new(),
+                false => self.cached_value(selected).unwrap_or_default(),
+            };
             return vec![format!("insert {}:{}:{}", selected, val, "String")];
         }
 
+        // `@na<Tab>` completes alias names in any argument position.
+        let token_start = path.rfind(' ').map_or(0, |i| i + 1);
+        if let Some(prefix) = path[token_start..].strip_prefix('@')
+            && !prefix.contains('/')
+        {
+            let names: Vec<String> = self.aliases.entries.keys().cloned().collect();
+            return Self::complete_words(&path[..token_start + 1], prefix, &names);
+        }
+
+        // `sel <Tab>` completes paths, and partial paths to every parameter
+        // containing them; indexes and recent entries are left alone.
+        if let Some(arg) = path.strip_prefix("sel ") {
+            let arg = arg.trim_start();
+            let completions = match arg.chars().next() {
+                Some('/') => self.get_completions(arg),
+                Some(c) if c.is_ascii_digit() || c == '-' => Vec::new(),
+                Some(_) => self.parameters_containing(arg),
+                None => Vec::new(),
+            };
+            return completions
+                .into_iter()
+                .map(|completion| format!("sel {}", completion))
+                .collect();
+        }
+
         // Only complete paths when input starts with '/'; command completions
         // are handled separately by the helper's cmd_candidates.
         if !path.starts_with('/') {
@@ -591,12 +2276,12 @@ impl ParameterCompleter {
         };
 
         let prefix = if path.contains('/') {
-            path.split('/').last().unwrap_or("").to_string()
+            path.split('/').next_back().unwrap_or("").to_string()
         } else {
             path.to_string()
         };
 
-        parameters
+        let matches: Vec<String> = parameters
             .get(&lookup_path)
             .map(|children| {
                 children
@@ -609,13 +2294,83 @@ impl ParameterCompleter {
                             format!("{}/{}", lookup_path, child)
                         }
                     })
+                    .filter(|candidate| self.leads_into_scope(candidate))
                     .collect()
             })
-            .unwrap_or_default()
+            .unwrap_or_default();
+
+        if matches.is_empty() && self.fuzzy_completion {
+            return self.fuzzy_completions(path);
+        }
+        matches
+    }
+
+    /// Subsequence matches of `path` against every known path, best match first,
+    /// so `/prod/dbpass` finds `/prod/db/password`.
+    fn fuzzy_completions(&self, path: &str) -> Vec<String> {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut scored: Vec<(i64, &String)> = self
+            .search_index
+            .fuzzy_candidates(path)
+            .into_iter()
+            .filter(|candidate| self.in_scope(candidate))
+            .filter_map(|candidate| matcher.fuzzy_match(candidate, path).map(|s| (s, candidate)))
+            .collect();
+
+        // Higher score first; shorter paths win ties.
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.len().cmp(&b.1.len())));
+        scored
+            .into_iter()
+            .take(FUZZY_COMPLETION_LIMIT)
+            .map(|(_, candidate)| candidate.clone())
+            .collect()
+    }
+
+    /// `value` as it may be displayed: masked while `redact` is on.
+    pub fn shown<'a>(&self, value: &'a str) -> &'a str {
+        if self.redact { REDACTED } else { value }
+    }
+
+    /// `shown`, cut to `value_width` for listings that print many values.
+    pub fn clipped(&self, value: &str) -> String {
+        truncate_value(self.shown(value), self.value_width)
+    }
+
+    /// Values are treated as secret unless the parameter is known to be a plain
+    /// `String` or `StringList`.
+    pub fn is_sensitive(&self, path: &str) -> bool {
+        !matches!(
+            self.types.get(path).map(String::as_str),
+            Some("String") | Some("StringList")
+        )
+    }
+
+    /// Display form of a path candidate: branches get a trailing `/` and their
+    /// child count, parameters their type when known.
+    pub fn describe_candidate(&self, path: &str) -> String {
+        let mut display = path.to_string();
+        if let Some(children) = self.parameters.get(path).filter(|c| !c.is_empty()) {
+            display.push_str(&format!("/ ({})", children.len()));
+        }
+        if let Some(param_type) = self.types.get(path) {
+            display.push_str(&format!(" [{}]", param_type));
+        }
+        display
+    }
+
+    /// Completes `prefix` against `words`, returning full lines that start with `head`.
+    fn complete_words(head: &str, prefix: &str, words: &[String]) -> Vec<String> {
+        let prefix = prefix.to_lowercase();
+        words
+            .iter()
+            .filter(|word| word.to_lowercase().starts_with(&prefix))
+            .map(|word| format!("{}{}", head, word))
+            .collect()
     }
 
-    pub fn log(&self, message: &str) {
-        if self.verbose {
+    /// Prints a progress message unless running with `--quiet` or JSON output.
+    pub fn progress(&self, message: &str) {
+        if !self.quiet && !self.output.is_json() {
             println!("{}", message);
         }
     }
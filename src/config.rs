@@ -0,0 +1,110 @@
+use crate::env_vars::EnvMapping;
+use crate::lint::LintConfig;
+use crate::policy::{ProtectedPaths, SecretPolicy};
+use crate::snapshots::AutoSnapshots;
+use crate::workspaces::Workspace;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings read from the daps config file (TOML).
+///
+/// Every key is optional; command-line flags take precedence over the file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Storage backend: `ssm` (default) or `secretsmanager`.
+    pub backend: Option<String>,
+    /// Overrides the SSM endpoint, e.g. `http://localhost:4566` for LocalStack
+    /// or a VPC interface endpoint.
+    pub endpoint_url: Option<String>,
+    /// Cache encryption: `auto` (on when DAPS_ENCRYPTION_KEY is set), `on` or `off`.
+    pub encryption: Option<String>,
+    /// Allows caching values unencrypted when encryption is off.
+    pub allow_plaintext_cache: bool,
+    /// Encrypts whole cache files so parameter names are hidden too.
+    pub seal_cache: bool,
+    /// Loads `SecureString` parameters without their values, for callers
+    /// without kms:Decrypt; values are decrypted one at a time when shown.
+    pub no_decrypt: bool,
+    /// Tab completion style: `circular` (default) cycles through candidates in
+    /// place, `list` prints the annotated candidates like a shell.
+    pub completion: Option<String>,
+    /// Offers fuzzy (subsequence) path matches when nothing matches by prefix.
+    /// Defaults to on.
+    pub fuzzy_completion: Option<bool>,
+    /// Path aliases (`name = "/full/path"`), used as `@name`. Managed with the
+    /// `alias` command.
+    pub aliases: BTreeMap<String, String>,
+    /// Command shortcuts (`rot = "set --generate 32"`); the rest of the line is
+    /// appended, so `rot /app/key` runs `set --generate 32 /app/key`.
+    pub command_aliases: BTreeMap<String, String>,
+    /// Named command sequences run by `macro play`. Written by `macro record`.
+    pub macros: BTreeMap<String, Vec<String>>,
+    /// Shell commands run around daps commands (`post_set = "notify-send ..."`).
+    /// `pre_<command>` hooks can refuse the command by exiting non-zero; all
+    /// hooks get the command as JSON on stdin.
+    pub hooks: BTreeMap<String, String>,
+    /// How `env`, `exec` and `export dotenv` name variables after parameters.
+    pub env_mapping: EnvMapping,
+    /// Rules for values written to password/secret parameters.
+    pub secret_policy: SecretPolicy,
+    /// Path patterns where writes and deletes must be confirmed by name.
+    pub protected: ProtectedPaths,
+    /// Type `insert` uses when none is given. Defaults to `String`.
+    pub default_type: Option<String>,
+    /// Types required for new parameters under path patterns
+    /// (`"/prod/**/secrets/" = "SecureString"`).
+    pub type_policy: BTreeMap<String, String>,
+    /// `age` highlights parameters not changed for longer than this. Defaults to 365.
+    pub age_threshold_days: Option<u64>,
+    /// Stores values larger than 4 KB as several `.chunkN` parameters.
+    pub chunking: bool,
+    /// Rules checked by `lint`.
+    pub lint: LintConfig,
+    /// Re-fetches the base path in the background every this many seconds
+    /// (at least 30) during interactive sessions. Off when unset or 0.
+    pub background_refresh_secs: u64,
+    /// Most parameter values kept in memory; the rest are read back from the
+    /// values cache file when needed. Unlimited when unset or 0.
+    pub value_cache_capacity: usize,
+    /// Empty the clipboard on exit if it still holds a value daps copied.
+    pub clear_clipboard_on_exit: bool,
+    /// Longest value, in characters, that `get` and `search` print before
+    /// cutting it with `…`; `show` prints it in full. Defaults to 120, and 0
+    /// never cuts.
+    pub value_width: Option<usize>,
+    /// Snapshots taken before each session's writes, and how many are kept.
+    pub auto_snapshots: AutoSnapshots,
+    /// Named bundles of profile, region, base paths and store directory,
+    /// switched to with `workspace use <name>` or `--workspace`.
+    pub workspaces: BTreeMap<String, Workspace>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/daps/config.toml`, falling back to `~/.config/daps/config.toml`
+    /// (`%APPDATA%\daps\config.toml` on Windows).
+    pub fn default_path() -> PathBuf {
+        #[cfg(target_os = "windows")]
+        let base = std::env::var("APPDATA").map(PathBuf::from);
+
+        #[cfg(not(target_os = "windows"))]
+        let base = std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::var("HOME").map(|home| Path::new(&home).join(".config")));
+
+        base.unwrap_or_else(|_| PathBuf::from("."))
+            .join("daps")
+            .join("config.toml")
+    }
+
+    /// Loads the config file; a missing file yields the defaults.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -1,24 +1,58 @@
 use clipboard::{ClipboardContext, ClipboardProvider};
 
+/// The session clipboard. Batch runs use a disabled one so scripts never
+/// overwrite what the user has copied.
 pub struct Cpboard<'a> {
-    ctx: &'a mut ClipboardContext,
+    ctx: Option<&'a mut ClipboardContext>,
+    /// What daps last copied, so `scrub` only clears its own content.
+    copied: Option<String>,
 }
 
 impl<'a> Cpboard<'a> {
     pub fn new(ctx: &'a mut ClipboardContext) -> Cpboard<'a> {
-        Cpboard { ctx }
+        Cpboard {
+            ctx: Some(ctx),
+            copied: None,
+        }
     }
 
+    pub fn disabled() -> Cpboard<'a> {
+        Cpboard {
+            ctx: None,
+            copied: None,
+        }
+    }
+
+    /// Returns whether the content was copied; `false` when the clipboard is disabled.
     pub fn set_clipboard_content(
         &mut self,
         content: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        self.ctx.set_contents(content.to_owned())?;
-        Ok(())
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(ctx) = self.ctx.as_mut() else {
+            return Ok(false);
+        };
+        ctx.set_contents(content.to_owned())?;
+        self.copied = Some(content.to_owned());
+        Ok(true)
+    }
+
+    /// Empties the clipboard if it still holds what daps last copied. Returns
+    /// whether it was cleared.
+    pub fn scrub(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
+        let (Some(ctx), Some(copied)) = (self.ctx.as_mut(), self.copied.take()) else {
+            return Ok(false);
+        };
+        if ctx.get_contents().ok().as_ref() != Some(&copied) {
+            return Ok(false);
+        }
+        ctx.set_contents(String::new())?;
+        Ok(true)
     }
 
-    #[allow(dead_code)]
     pub fn get_clipboard_content(&mut self) -> Result<String, Box<dyn std::error::Error>> {
-        self.ctx.get_contents()
+        match self.ctx.as_mut() {
+            Some(ctx) => ctx.get_contents(),
+            None => Err("Clipboard is disabled".into()),
+        }
     }
 }
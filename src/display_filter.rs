@@ -0,0 +1,83 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+/// Names accepted after `|` in `get <path> | <filter>`.
+pub const FILTER_NAMES: [&str; 4] = ["b64d", "json", "lines", "trim"];
+
+/// A transformation applied to a value before it is shown, as in
+/// `get /app/cert | b64d | trim`. Filters only change what is displayed and
+/// copied; the stored value is never touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Decodes base64; the result must be UTF-8 text.
+    Base64Decode,
+    /// Pretty-prints a JSON value.
+    Json,
+    /// Puts each item of a comma-separated `StringList` on its own line.
+    Lines,
+    /// Strips leading and trailing whitespace.
+    Trim,
+}
+
+impl std::str::FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "b64d" => Ok(Self::Base64Decode),
+            "json" => Ok(Self::Json),
+            "lines" => Ok(Self::Lines),
+            "trim" => Ok(Self::Trim),
+            other => Err(format!(
+                "Unknown filter '{}' (expected {})",
+                other,
+                FILTER_NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl Filter {
+    pub fn apply(self, value: &str) -> Result<String, String> {
+        match self {
+            Self::Base64Decode => {
+                let bytes = BASE64
+                    .decode(value.trim())
+                    .map_err(|e| format!("not base64 ({})", e))?;
+                String::from_utf8(bytes)
+                    .map_err(|_| "the decoded bytes are not text; use get-binary".to_string())
+            }
+            Self::Json => {
+                let parsed: serde_json::Value =
+                    serde_json::from_str(value).map_err(|e| format!("not JSON ({})", e))?;
+                serde_json::to_string_pretty(&parsed).map_err(|e| e.to_string())
+            }
+            Self::Lines => Ok(value.split(',').collect::<Vec<_>>().join("\n")),
+            Self::Trim => Ok(value.trim().to_string()),
+        }
+    }
+}
+
+/// Splits `/path | b64d | json` into the path and its filters. `|` cannot
+/// occur in parameter names, so the first one always starts the filters.
+pub fn split(line: &str) -> Result<(String, Vec<Filter>), String> {
+    let mut parts = line.split('|').map(str::trim);
+    let path = parts.next().unwrap_or_default().to_string();
+    let filters = parts
+        .map(|name| match name {
+            "" => Err(format!(
+                "Empty filter after '|' (expected {})",
+                FILTER_NAMES.join(", ")
+            )),
+            name => name.parse(),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((path, filters))
+}
+
+/// Runs `value` through every filter in order.
+pub fn apply(value: &str, filters: &[Filter]) -> Result<String, String> {
+    filters
+        .iter()
+        .try_fold(value.to_string(), |value, filter| filter.apply(&value))
+}
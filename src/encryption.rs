@@ -1,17 +1,150 @@
 use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
 use base64::{Engine as _, engine::general_purpose};
 use rand::{Rng, thread_rng};
 use sha2::{Digest, Sha256};
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Prefix of values encrypted with the Argon2id-derived key. Legacy values
+/// (SHA-256-derived key) have no prefix. `.` never occurs in base64, so the
+/// prefix cannot be mistaken for the start of an encoded legacy value.
+const V2_PREFIX: &str = "v2.";
+
+/// Whether the local cache is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EncryptionMode {
+    /// On when an encryption key is available, off otherwise.
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl std::str::FromStr for EncryptionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "on" | "true" | "1" => Ok(Self::On),
+            "off" | "false" | "0" => Ok(Self::Off),
+            other => Err(format!(
+                "Invalid encryption mode '{}' (expected auto|on|off)",
+                other
+            )),
+        }
+    }
+}
 
 pub struct Encryption {
     pub enabled: bool,
-    pub key: String,
+    /// AES-256 key derived from the secret with Argon2id and the store's salt.
+    key_bytes: [u8; 32],
+    /// Single SHA-256 of the secret, used to read caches written before Argon2id.
+    legacy_key_bytes: [u8; 32],
+    /// Allows writing values to disk in plaintext when encryption is disabled.
+    pub allow_plaintext: bool,
+    /// Encrypts whole cache files, hiding parameter names as well as values.
+    pub seal_cache: bool,
+}
+
+/// Reads the per-store salt, creating a random one on first use.
+pub fn load_or_create_salt(salt_file: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if let Ok(encoded) = std::fs::read_to_string(salt_file) {
+        return Ok(general_purpose::STANDARD.decode(encoded.trim())?);
+    }
+
+    let salt = generate_salt();
+    if let Some(dir) = salt_file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    write_salt(salt_file, &salt)?;
+    Ok(salt)
+}
+
+pub fn generate_salt() -> Vec<u8> {
+    let mut salt = [0u8; 16];
+    thread_rng().fill(&mut salt);
+    salt.to_vec()
+}
+
+pub fn write_salt(salt_file: &Path, salt: &[u8]) -> std::io::Result<()> {
+    std::fs::write(salt_file, general_purpose::STANDARD.encode(salt))
+}
+
+/// Prompts for the cache passphrase without echoing it.
+pub fn prompt_passphrase(prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if !std::io::stdin().is_terminal() {
+        return Err(
+            "No DAPS_ENCRYPTION_KEY set and stdin is not a terminal to prompt for a passphrase"
+                .into(),
+        );
+    }
+    Ok(rpassword::prompt_password(prompt)?)
 }
 
 impl Encryption {
-    pub fn new(enabled: bool, key: String) -> Self {
-        Self { enabled, key }
+    /// Derives the cache keys from `secret` and the store's salt.
+    pub fn new(
+        enabled: bool,
+        secret: &str,
+        salt: &[u8],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut key_bytes = [0u8; 32];
+        if enabled {
+            Argon2::default()
+                .hash_password_into(secret.as_bytes(), salt, &mut key_bytes)
+                .map_err(|e| format!("Key derivation failed: {}", e))?;
+        }
+
+        Ok(Self {
+            enabled,
+            key_bytes,
+            legacy_key_bytes: Sha256::digest(secret.as_bytes()).into(),
+            allow_plaintext: false,
+            seal_cache: false,
+        })
+    }
+
+    /// Resolves the runtime encryption setting. `On` without a key prompts for a
+    /// passphrase; `Auto` turns encryption on exactly when a key is provided.
+    pub fn from_settings(
+        mode: EncryptionMode,
+        key: Option<String>,
+        allow_plaintext: bool,
+        salt_file: &Path,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let key = key.filter(|k| !k.is_empty());
+        let secret = match (mode, key) {
+            (EncryptionMode::On, None) => Some(prompt_passphrase("Cache passphrase: ")?),
+            (EncryptionMode::On, Some(key)) | (EncryptionMode::Auto, Some(key)) => Some(key),
+            (EncryptionMode::Auto, None) | (EncryptionMode::Off, _) => None,
+        };
+
+        let mut encryption = match secret {
+            Some(secret) => Self::new(true, &secret, &load_or_create_salt(salt_file)?)?,
+            None => Self::new(false, "", &[])?,
+        };
+        encryption.allow_plaintext = allow_plaintext;
+        Ok(encryption)
+    }
+
+    /// Key for the cache files' integrity check, separate from the encryption key.
+    /// `None` when encryption is off, in which case only a checksum is used.
+    pub fn integrity_key(&self) -> Option<[u8; 32]> {
+        self.enabled.then(|| {
+            let mut hasher = Sha256::new();
+            hasher.update(b"daps cache integrity");
+            hasher.update(self.key_bytes);
+            hasher.finalize().into()
+        })
+    }
+
+    /// Values may only be written to disk encrypted, unless plaintext was explicitly allowed.
+    pub fn can_persist_values(&self) -> bool {
+        self.enabled || self.allow_plaintext
     }
 
     pub fn encrypt_value(&self, value: &str) -> String {
@@ -25,13 +158,8 @@ impl Encryption {
         thread_rng().fill(&mut nonce_bytes);
         let nonce = Nonce::from_slice(&nonce_bytes);
 
-        // Derive key from ENCRYPTION_KEY using SHA-256
-        let mut hasher = Sha256::new();
-        hasher.update(self.key.as_bytes());
-        let key_bytes = hasher.finalize();
-
         // Create cipher instance
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+        let key = Key::<Aes256Gcm>::from_slice(&self.key_bytes);
         let cipher = Aes256Gcm::new(key);
 
         // Encrypt the value
@@ -44,49 +172,48 @@ impl Encryption {
         result.extend_from_slice(&nonce_bytes);
         result.extend_from_slice(&ciphertext);
 
-        general_purpose::STANDARD.encode(result)
+        format!("{}{}", V2_PREFIX, general_purpose::STANDARD.encode(result))
     }
 
-    pub fn decrypt_value(&self, value: &str) -> String {
+    /// Decrypts `value`, returning a description of the failure on error.
+    pub fn try_decrypt(&self, value: &str) -> Result<String, &'static str> {
         // If encryption is disabled, return the value as is
         if !self.enabled {
-            return value.to_string();
+            return Ok(value.to_string());
         }
 
         // Check if this is our old format placeholder
         if value.starts_with("encrypted(") && value.ends_with(")") {
-            return value.replace("encrypted(", "").replace(")", "");
+            return Ok(value.replace("encrypted(", "").replace(")", ""));
         }
 
-        // Decode base64
-        let decoded = match general_purpose::STANDARD.decode(value) {
-            Ok(d) => d,
-            Err(_) => return String::from("decryption error: invalid base64"),
+        let (encoded, key_bytes) = match value.strip_prefix(V2_PREFIX) {
+            Some(rest) => (rest, &self.key_bytes),
+            None => (value, &self.legacy_key_bytes),
         };
 
+        // Decode base64
+        let decoded = general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|_| "invalid base64")?;
+
         // Need at least 12 bytes for the nonce
         if decoded.len() <= 12 {
-            return String::from("decryption error: data too short");
+            return Err("data too short");
         }
 
         // Extract nonce and ciphertext
         let nonce = Nonce::from_slice(&decoded[0..12]);
         let ciphertext = &decoded[12..];
 
-        // Derive key from ENCRYPTION_KEY
-        let mut hasher = Sha256::new();
-        hasher.update(self.key.as_bytes());
-        let key_bytes = hasher.finalize();
-
         // Create cipher instance
-        let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
+        let key = Key::<Aes256Gcm>::from_slice(key_bytes);
         let cipher = Aes256Gcm::new(key);
 
         // Decrypt
-        match cipher.decrypt(nonce, ciphertext) {
-            Ok(plaintext) => String::from_utf8(plaintext)
-                .unwrap_or_else(|_| String::from("decryption error: invalid utf8")),
-            Err(_) => String::from("decryption error: authentication failed"),
-        }
+        let plaintext = cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| "authentication failed")?;
+        String::from_utf8(plaintext).map_err(|_| "invalid utf8")
     }
 }
@@ -0,0 +1,241 @@
+use crate::completer::ParameterCompleter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// How `daps env` prints each variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnvFormat {
+    /// `export KEY='value'`, for `eval "$(daps env /dev/app)"`.
+    #[default]
+    Shell,
+    /// `KEY=value`, for `docker run --env-file` and compose `env_file`.
+    Docker,
+    /// `KEY="value"` with `\n`, `\"` and `\\` escaped, for `.env` files read by
+    /// dotenv libraries.
+    Dotenv,
+}
+
+/// How parameter paths become variable names for `daps env`, `daps exec` and
+/// `export dotenv`: the `[env_mapping]` table of the config file. By default
+/// `/dev/app/db/host` under `/dev/app` is `DB_HOST`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvMapping {
+    /// Name variables after the path below the prefix; off uses the full path.
+    pub strip_prefix: bool,
+    pub uppercase: bool,
+    /// Put in front of every generated name, e.g. `APP_`.
+    pub name_prefix: String,
+    /// Explicit names for full paths (`"/prod/app/db/url" = "DATABASE_URL"`),
+    /// used as they are.
+    pub renames: BTreeMap<String, String>,
+}
+
+impl Default for EnvMapping {
+    fn default() -> Self {
+        Self {
+            strip_prefix: true,
+            uppercase: true,
+            name_prefix: String::new(),
+            renames: BTreeMap::new(),
+        }
+    }
+}
+
+impl EnvMapping {
+    /// Checks that every rename and the name prefix can appear in a variable name.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self
+            .name_prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!(
+                "Invalid env_mapping name_prefix '{}': use letters, digits and '_'",
+                self.name_prefix
+            ));
+        }
+        for (path, name) in &self.renames {
+            if !is_variable_name(name) {
+                return Err(format!(
+                    "Invalid env_mapping rename for {}: '{}' is not a variable name",
+                    path, name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The variable name of `path` exported from under `root`. Characters
+    /// other than letters, digits and `_`, such as `/` and `-`, become `_`, and
+    /// a name starting with a digit gets a leading `_`.
+    pub fn name(&self, path: &str, root: &str) -> String {
+        if let Some(name) = self.renames.get(path) {
+            return name.clone();
+        }
+        let relative = if self.strip_prefix {
+            path.strip_prefix(root).unwrap_or(path)
+        } else {
+            path
+        };
+        let converted: String = relative
+            .trim_matches('/')
+            .chars()
+            .map(|c| match c {
+                c if c.is_ascii_alphanumeric() && self.uppercase => c.to_ascii_uppercase(),
+                c if c.is_ascii_alphanumeric() => c,
+                _ => '_',
+            })
+            .collect();
+        let mut name = format!("{}{}", self.name_prefix, converted);
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        name
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl std::str::FromStr for EnvFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "shell" => Ok(Self::Shell),
+            "docker" => Ok(Self::Docker),
+            "dotenv" => Ok(Self::Dotenv),
+            other => Err(format!(
+                "Invalid format '{}' (expected shell|docker|dotenv)",
+                other
+            )),
+        }
+    }
+}
+
+/// The values of the parameters under `prefix`, fetching those missing from
+/// the cache, and the `<prefix>/` root their names are relative to.
+pub async fn values_under(
+    completer: &mut ParameterCompleter,
+    prefix: &str,
+) -> Result<(BTreeMap<String, String>, String), Box<dyn std::error::Error>> {
+    let prefix = completer.aliases.resolve(prefix)?;
+    if !prefix.starts_with('/') {
+        return Err(format!("Prefix must start with '/': {}", prefix).into());
+    }
+    let root = format!("{}/", prefix.trim_end_matches('/'));
+    let paths = completer.parameters_under(&root);
+    completer.hydrate(&paths).await?;
+    let values: BTreeMap<String, String> = completer.cached_values(&paths).into_iter().collect();
+    if values.is_empty() {
+        return Err(format!("No parameters under {}", root).into());
+    }
+    Ok((values, root))
+}
+
+/// Prints the parameters under `prefix` as environment variables on stdout.
+/// Values missing from the cache are fetched first.
+pub async fn print(
+    completer: &mut ParameterCompleter,
+    prefix: &str,
+    format: EnvFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (values, root) = values_under(completer, prefix).await?;
+    let document = render(&values, &root, format, &completer.env_mapping)?;
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(document.as_bytes())?;
+    Ok(())
+}
+
+/// Runs `command` with the parameters under `prefix` added to its
+/// environment, overriding variables of the same name. Returns its exit code.
+pub async fn exec(
+    completer: &mut ParameterCompleter,
+    prefix: &str,
+    command: &[String],
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let (program, args) = command.split_first().ok_or("No command to run")?;
+    let (values, root) = values_under(completer, prefix).await?;
+    let variables = variables(&values, &root, &completer.env_mapping)?;
+    let status = std::process::Command::new(program)
+        .args(args)
+        .envs(variables.iter().map(|(name, (_, value))| (name, value)))
+        .status()
+        .map_err(|e| format!("Cannot run {}: {}", program, e))?;
+    // A command killed by a signal has no exit code; report it like a shell does.
+    Ok(status.code().unwrap_or(128))
+}
+
+/// Variable names for the parameters in `values`, each with its path and
+/// value. Two paths that map to the same name are an error rather than one
+/// silently winning.
+pub fn variables<'a>(
+    values: &'a BTreeMap<String, String>,
+    root: &str,
+    mapping: &EnvMapping,
+) -> Result<BTreeMap<String, (&'a str, &'a str)>, String> {
+    let mut variables: BTreeMap<String, (&str, &str)> = BTreeMap::new();
+    for (path, value) in values {
+        let name = mapping.name(path, root);
+        if let Some((other, _)) = variables.insert(name.clone(), (path, value)) {
+            return Err(format!(
+                "{} and {} both map to the variable {}",
+                other, path, name
+            ));
+        }
+    }
+    Ok(variables)
+}
+
+/// One line per parameter, sorted by variable name, named by `mapping`.
+pub fn render(
+    values: &BTreeMap<String, String>,
+    root: &str,
+    format: EnvFormat,
+    mapping: &EnvMapping,
+) -> Result<String, String> {
+    let variables = variables(values, root, mapping)?;
+    let mut lines = String::new();
+    for (name, (path, value)) in variables {
+        match format {
+            EnvFormat::Shell => {
+                let _ = writeln!(lines, "export {}={}", name, shell_quote(value));
+            }
+            EnvFormat::Docker => {
+                // Env files have no quoting: the value runs to the end of the line.
+                if value.contains(['\n', '\r']) {
+                    return Err(format!(
+                        "{} spans several lines, which an env file cannot hold",
+                        path
+                    ));
+                }
+                let _ = writeln!(lines, "{}={}", name, value);
+            }
+            EnvFormat::Dotenv => {
+                let _ = writeln!(lines, "{}={}", name, dotenv_quote(value));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// `say "hi"` → `"say \"hi\""`, with line breaks as `\n`.
+fn dotenv_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("\"{}\"", escaped)
+}
+
+/// `it's` → `'it'\''s'`: single quotes keep everything literal except the
+/// quote itself.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,134 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::rngs::OsRng;
+use rand::{Rng, RngCore};
+
+/// Default length of `--generate` secrets.
+const DEFAULT_LENGTH: usize = 32;
+/// Default number of random bytes for `--generate-hex` and `--generate-base64`.
+const DEFAULT_BYTES: usize = 32;
+/// Standard-tier parameters hold at most 4 KB.
+const MAX_LENGTH: usize = 4096;
+
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&*+-.:=?@^_~";
+
+/// A way of producing a fresh secret value, given as `set`/`insert` flags.
+#[derive(Debug, PartialEq)]
+pub enum Generator {
+    /// `--generate [length] [--charset <name|chars>]`
+    Random { length: usize, charset: Vec<char> },
+    /// `--generate-uuid`: a random (version 4) UUID.
+    Uuid,
+    /// `--generate-hex [bytes]`
+    Hex(usize),
+    /// `--generate-base64 [bytes]`
+    Base64(usize),
+}
+
+impl Generator {
+    /// Parses generator flags. Returns `Ok(None)` when `args` does not start
+    /// with a `--generate` flag, so callers can fall back to a literal value.
+    pub fn parse(args: &str) -> Result<Option<Self>, String> {
+        let mut words = args.split_whitespace().peekable();
+        let Some(flag) = words.next().filter(|w| w.starts_with("--generate")) else {
+            return Ok(None);
+        };
+
+        // An optional number right after the flag.
+        let number = match words.peek().map(|w| w.parse::<usize>()) {
+            Some(Ok(n)) => {
+                words.next();
+                Some(n)
+            }
+            _ => None,
+        };
+
+        let generator = match flag {
+            "--generate" => {
+                let charset = match (words.next(), words.next()) {
+                    (None, _) => charset("alnum")?,
+                    (Some("--charset"), Some(name)) => charset(name)?,
+                    _ => return Err("Usage: --generate [length] [--charset <name|chars>]".into()),
+                };
+                Generator::Random {
+                    length: number.unwrap_or(DEFAULT_LENGTH),
+                    charset,
+                }
+            }
+            "--generate-uuid" if number.is_none() => Generator::Uuid,
+            "--generate-hex" => Generator::Hex(number.unwrap_or(DEFAULT_BYTES)),
+            "--generate-base64" => Generator::Base64(number.unwrap_or(DEFAULT_BYTES)),
+            other => return Err(format!("Unknown generator '{}'", other)),
+        };
+
+        if let Some(extra) = words.next() {
+            return Err(format!("Unexpected argument '{}' after {}", extra, flag));
+        }
+        match number {
+            Some(0) => Err("Length must be at least 1".into()),
+            Some(n) if n > MAX_LENGTH => Err(format!("Length must be at most {}", MAX_LENGTH)),
+            _ => Ok(Some(generator)),
+        }
+    }
+
+    /// Produces a value from the operating system's secure random source.
+    pub fn generate(&self) -> String {
+        let mut rng = OsRng;
+        match self {
+            Generator::Random { length, charset } => (0..*length)
+                .map(|_| charset[rng.gen_range(0..charset.len())])
+                .collect(),
+            Generator::Uuid => {
+                let mut bytes = [0u8; 16];
+                rng.fill_bytes(&mut bytes);
+                bytes[6] = (bytes[6] & 0x0f) | 0x40;
+                bytes[8] = (bytes[8] & 0x3f) | 0x80;
+                let hex = hex::encode(bytes);
+                format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..32]
+                )
+            }
+            Generator::Hex(bytes) => hex::encode(random_bytes(*bytes)),
+            Generator::Base64(bytes) => BASE64.encode(random_bytes(*bytes)),
+        }
+    }
+}
+
+fn random_bytes(count: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; count];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Named character sets; anything else is taken as the literal characters to use.
+fn charset(name: &str) -> Result<Vec<char>, String> {
+    let chars: String = match name {
+        "alnum" => [LOWER, UPPER, DIGITS].concat(),
+        "alpha" => [LOWER, UPPER].concat(),
+        "lower" => LOWER.to_string(),
+        "upper" => UPPER.to_string(),
+        "digits" => DIGITS.to_string(),
+        "hex" => "0123456789abcdef".to_string(),
+        "symbols" => [LOWER, UPPER, DIGITS, SYMBOLS].concat(),
+        custom => custom.to_string(),
+    };
+
+    let mut set: Vec<char> = chars.chars().collect();
+    set.sort_unstable();
+    set.dedup();
+    if set.len() < 2 {
+        return Err(format!(
+            "Character set '{}' needs at least two distinct characters",
+            name
+        ));
+    }
+    Ok(set)
+}
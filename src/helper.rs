@@ -1,12 +1,11 @@
-use crate::completer::ParameterCompleter;
+use crate::completer::{ParameterCompleter, REDACTED};
 use rustyline::{
-    Context,
+    Context, Helper,
     completion::{Completer, Pair},
     error::ReadlineError,
     highlight::{Highlighter, MatchingBracketHighlighter},
     hint::{Hint, Hinter},
     validate::Validator,
-    Helper,
 };
 use std::borrow::Cow::{self, Borrowed, Owned};
 
@@ -33,7 +32,11 @@ impl Completer for ParamStoreHelper {
         let mut candidates: Vec<Pair> = completions
             .into_iter()
             .map(|s| Pair {
-                display: s.clone(),
+                display: if s.starts_with('/') {
+                    self.completer.describe_candidate(&s)
+                } else {
+                    s.clone()
+                },
                 replacement: s,
             })
             .collect();
@@ -68,28 +71,85 @@ impl Highlighter for ParamStoreHelper {
         }
     }
 
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        use colored::*;
+
+        Owned(hint.dimmed().to_string())
+    }
+
     fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
         self.highlighter.highlight_char(_line, _pos)
     }
 }
 
-pub struct EmptyHint;
+/// Longest value shown in a hint before it is cut off.
+const HINT_VALUE_WIDTH: usize = 60;
 
-impl Hint for EmptyHint {
+/// Inline hint for a line that uniquely matches a cached parameter: the rest of
+/// its path (accepted with Right arrow) followed by its value.
+pub struct ValueHint {
+    display: String,
+    completion: String,
+}
+
+impl Hint for ValueHint {
     fn display(&self) -> &str {
-        ""
+        &self.display
     }
 
     fn completion(&self) -> Option<&str> {
-        Some("")
+        (!self.completion.is_empty()).then_some(self.completion.as_str())
+    }
+}
+
+impl ParamStoreHelper {
+    /// SecureString values, and values whose type is unknown, are masked, as
+    /// is everything while `redact` is on.
+    fn hint_value(&self, path: &str, value: &str) -> String {
+        if self.completer.is_sensitive(path) {
+            return REDACTED.to_string();
+        }
+
+        let flat = self.completer.shown(value).replace('\n', " ");
+        if flat.chars().count() > HINT_VALUE_WIDTH {
+            let cut: String = flat.chars().take(HINT_VALUE_WIDTH).collect();
+            format!("{}…", cut)
+        } else {
+            flat
+        }
     }
 }
 
 impl Hinter for ParamStoreHelper {
-    type Hint = EmptyHint;
+    type Hint = ValueHint;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<Self::Hint> {
+        if pos < line.len() || !line.starts_with('/') {
+            return None;
+        }
 
-    fn hint(&self, _line: &str, _pos: usize, _ctx: &Context<'_>) -> Option<Self::Hint> {
-        None
+        let completer = &self.completer;
+        let path = if completer.is_parameter(line) && completer.in_scope(line) {
+            line.to_string()
+        } else {
+            let mut matches = completer
+                .parameters_containing(line)
+                .into_iter()
+                .filter(|path| path.starts_with(line));
+            let first = matches.next()?;
+            if matches.next().is_some() {
+                return None;
+            }
+            first
+        };
+        // Hints must stay cheap, so evicted values get none.
+        let value = completer.values.get(&path)?;
+
+        let completion = path[line.len()..].to_string();
+        Some(ValueHint {
+            display: format!("{} = {}", completion, self.hint_value(&path, value)),
+            completion,
+        })
     }
 }
 
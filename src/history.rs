@@ -0,0 +1,103 @@
+use crate::utils::split_words;
+use std::io::Write;
+use std::path::Path;
+
+const REDACTED: &str = "<redacted>";
+
+/// Returns the form of `line` that may be written to disk: every value typed
+/// on the line is replaced so secrets never reach the history file. That is
+/// the value after `set [<path>|@alias|#N]`, inside `insert <path>:<value>` and
+/// `insert <path> <value>`, after each `key=` of `set-many`, the file given to
+/// `set-binary` and the `--find`/`--with` text of `replace`. Command aliases
+/// are expanded before lines reach the history, so aliased lines are covered
+/// too. Everything else is kept as typed.
+pub fn redact(line: &str) -> String {
+    let trimmed = line.trim();
+    let (keyword, rest) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return trimmed.to_string();
+    }
+    let first = rest.split_whitespace().next().unwrap_or(rest);
+
+    match keyword {
+        "set" if first != rest && first.starts_with(['/', '@', '#']) => {
+            format!("set {} {}", first, REDACTED)
+        }
+        "set" => format!("set {}", REDACTED),
+        "insert" => match first.split_once(':') {
+            Some((path, _)) => format!("insert {}:{}", path, REDACTED),
+            None if first != rest => format!("insert {} {}", first, REDACTED),
+            None => format!("insert {}", REDACTED),
+        },
+        "set-binary" if first != rest => format!("set-binary {} {}", first, REDACTED),
+        "set-many" => redact_words(keyword, rest, |word| {
+            word.split_once('=')
+                .filter(|_| !word.starts_with("--"))
+                .map(|(key, _)| format!("{}={}", key, REDACTED))
+        }),
+        "replace" => {
+            let mut after_flag = false;
+            redact_words(keyword, rest, |word| {
+                let redacted = after_flag.then(|| REDACTED.to_string());
+                after_flag = matches!(word, "--find" | "--with");
+                redacted
+            })
+        }
+        _ => trimmed.to_string(),
+    }
+}
+
+/// `keyword` and the words of `rest`, each replaced by what `redact_word`
+/// returns for it, if anything. A line that does not split into words is
+/// redacted whole.
+fn redact_words(
+    keyword: &str,
+    rest: &str,
+    mut redact_word: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let Ok(words) = split_words(rest) else {
+        return format!("{} {}", keyword, REDACTED);
+    };
+    let words: Vec<String> = words
+        .into_iter()
+        .map(|word| redact_word(&word).unwrap_or(word))
+        .collect();
+    format!("{} {}", keyword, words.join(" "))
+}
+
+/// Writes the session history, redacted, one entry per line. rustyline's
+/// `load_history` reads this plain format back.
+pub fn save<'a>(path: &Path, entries: impl Iterator<Item = &'a String>) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut file = std::fs::File::create(path)?;
+    for entry in entries {
+        writeln!(file, "{}", redact(entry))?;
+    }
+    Ok(())
+}
+
+/// Expands `!N` (1-based history index) and `!!` (previous entry). Returns
+/// `Ok(None)` when `line` is not a history reference.
+pub fn expand(line: &str, history: &rustyline::history::History) -> Result<Option<String>, String> {
+    let Some(reference) = line.trim().strip_prefix('!') else {
+        return Ok(None);
+    };
+
+    let entry = if reference == "!" {
+        history.last()
+    } else {
+        let index: usize = reference
+            .parse()
+            .map_err(|_| format!("!{}: expected !N or !!", reference))?;
+        index.checked_sub(1).and_then(|i| history.get(i))
+    };
+
+    entry
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| format!("!{}: event not found", reference))
+}
@@ -0,0 +1,86 @@
+use crate::command::Command;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::Stdio;
+
+/// External commands run around daps commands, from the `[hooks]` table of
+/// the config file: `pre_<command>` runs before it and can veto it by exiting
+/// non-zero, `post_<command>` runs after it succeeds. Each hook is run by the
+/// shell and gets a JSON description of the command on stdin.
+#[derive(Debug, Default, Clone)]
+pub struct Hooks {
+    entries: BTreeMap<String, String>,
+}
+
+impl Hooks {
+    /// Checks that every key names a stage and a known command, so a typo does
+    /// not silently disable a hook.
+    pub fn new(entries: BTreeMap<String, String>) -> Result<Self, String> {
+        let commands = Command::keywords();
+        for key in entries.keys() {
+            let command = key
+                .strip_prefix("pre_")
+                .or_else(|| key.strip_prefix("post_"))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid hook '{}': use pre_<command> or post_<command>",
+                        key
+                    )
+                })?;
+            if !commands.iter().any(|name| name == command) {
+                return Err(format!(
+                    "Invalid hook '{}': unknown command '{}'",
+                    key, command
+                ));
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Runs the `<stage>_<command>` hook, if one is configured, with `payload`
+    /// on stdin. Its output goes to the terminal. Fails when the hook cannot
+    /// be started or exits non-zero.
+    pub fn run(&self, stage: &str, command: &str, payload: &Value) -> Result<(), String> {
+        let key = format!("{}_{}", stage, command);
+        let Some(script) = self.entries.get(&key) else {
+            return Ok(());
+        };
+
+        #[cfg(target_os = "windows")]
+        let mut process = std::process::Command::new("cmd");
+        #[cfg(target_os = "windows")]
+        process.args(["/C", script]);
+
+        #[cfg(not(target_os = "windows"))]
+        let mut process = std::process::Command::new("sh");
+        #[cfg(not(target_os = "windows"))]
+        process.args(["-c", script]);
+
+        let mut child = process
+            .env("DAPS_HOOK", &key)
+            .env("DAPS_COMMAND", command)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Cannot run the {} hook: {}", key, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that does not read its input closes the pipe early.
+            if let Err(e) = writeln!(stdin, "{}", payload)
+                && e.kind() != std::io::ErrorKind::BrokenPipe
+            {
+                return Err(format!("Cannot write to the {} hook: {}", key, e));
+            }
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("The {} hook did not finish: {}", key, e))?;
+        if !status.success() {
+            return Err(format!("The {} hook failed ({})", key, status));
+        }
+        Ok(())
+    }
+}
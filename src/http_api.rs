@@ -0,0 +1,205 @@
+use crate::completer::ParameterCompleter;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, HOST, WWW_AUTHENTICATE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use percent_encoding::percent_decode_str;
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+
+/// Which values the HTTP API leaves out of its responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mask {
+    #[default]
+    None,
+    /// Everything but `String` and `StringList` parameters.
+    Secure,
+    /// Every value; only paths and types are served.
+    All,
+}
+
+impl std::str::FromStr for Mask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "secure" => Ok(Self::Secure),
+            "all" => Ok(Self::All),
+            other => Err(format!(
+                "Invalid mask '{}' (expected none|secure|all)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    /// Clients must send `Authorization: Bearer <token>` when set.
+    pub token: Option<String>,
+    pub mask: Mask,
+}
+
+/// Serves the cache read-only over HTTP until `shutdown` resolves:
+///
+/// - `GET /parameters?prefix=/dev/app`: every parameter under the prefix
+///   (the base path by default) with its type and value
+/// - `GET /parameter/dev/app/db/host`: one parameter, 404 when unknown
+///
+/// Nothing is fetched from AWS; values missing from the cache are `null`, and
+/// masked ones are left out. On a loopback address, requests must name that
+/// address or `localhost` in `Host`, so a web page cannot reach the API by
+/// rebinding its own domain to 127.0.0.1.
+pub async fn run(
+    completer: ParameterCompleter,
+    listener: TcpListener,
+    options: HttpOptions,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    listener.set_nonblocking(true)?;
+    let address = listener.local_addr()?;
+    let state = Arc::new((Mutex::new(completer), options, address));
+    let make_service = make_service_fn(move |_| {
+        let state = Arc::clone(&state);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let state = Arc::clone(&state);
+                async move {
+                    let (completer, options, address) = &*state;
+                    let mut completer = completer.lock().await;
+                    Ok::<_, Infallible>(respond(&mut completer, options, *address, &request))
+                }
+            }))
+        }
+    });
+    Server::from_tcp(listener)?
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    Ok(())
+}
+
+fn respond(
+    completer: &mut ParameterCompleter,
+    options: &HttpOptions,
+    address: SocketAddr,
+    request: &Request<Body>,
+) -> Response<Body> {
+    log::info!("{} {}", request.method(), request.uri().path());
+    if !expected_host(address, request) {
+        return error(
+            StatusCode::MISDIRECTED_REQUEST,
+            "the Host header must name the address daps listens on",
+        );
+    }
+    if !authorized(options, request) {
+        let mut response = error(StatusCode::UNAUTHORIZED, "missing or wrong bearer token");
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+        return response;
+    }
+    if request.method() != Method::GET {
+        return error(StatusCode::METHOD_NOT_ALLOWED, "the API is read-only");
+    }
+    completer.apply_background_refresh();
+
+    let path = request.uri().path();
+    if path == "/parameters" {
+        let prefix = query_param(request, "prefix").unwrap_or_else(|| completer.base_path.clone());
+        let parameters: Vec<Value> = completer
+            .parameters_under(&prefix)
+            .iter()
+            .map(|path| entry(completer, options.mask, path))
+            .collect();
+        return json_response(StatusCode::OK, json!({ "parameters": parameters }));
+    }
+    if let Some(name) = path.strip_prefix("/parameter/") {
+        let name = format!("/{}", percent_decode_str(name).decode_utf8_lossy());
+        if !completer.is_parameter(&name) {
+            return error(
+                StatusCode::NOT_FOUND,
+                &format!("{} is not a known parameter", name),
+            );
+        }
+        return json_response(StatusCode::OK, entry(completer, options.mask, &name));
+    }
+    error(
+        StatusCode::NOT_FOUND,
+        "expected /parameters?prefix=... or /parameter/<name>",
+    )
+}
+
+fn authorized(options: &HttpOptions, request: &Request<Body>) -> bool {
+    let Some(token) = &options.token else {
+        return true;
+    };
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())))
+}
+
+/// Whether `Host` names the address the API is bound to. Only checked on
+/// loopback addresses; elsewhere clients use names daps cannot know, and a
+/// token is required anyway.
+fn expected_host(address: SocketAddr, request: &Request<Body>) -> bool {
+    if !address.ip().is_loopback() {
+        return true;
+    }
+    let Some(host) = request.headers().get(HOST).and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) if !port.ends_with(']') => (name, port.parse().ok()),
+        _ => (host, Some(80)),
+    };
+    let ip = address.ip().to_string();
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    port == Some(address.port()) && (name.eq_ignore_ascii_case("localhost") || name == ip)
+}
+
+fn query_param(request: &Request<Body>, name: &str) -> Option<String> {
+    request.uri().query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| {
+            percent_decode_str(&value.replace('+', " "))
+                .decode_utf8_lossy()
+                .into_owned()
+        })
+    })
+}
+
+fn entry(completer: &ParameterCompleter, mask: Mask, path: &str) -> Value {
+    let masked = match mask {
+        Mask::None => false,
+        Mask::Secure => completer.is_sensitive(path),
+        Mask::All => true,
+    };
+    let mut entry = json!({ "path": path, "type": completer.types.get(path) });
+    if masked {
+        entry["masked"] = json!(true);
+    } else {
+        entry["value"] = json!(completer.cached_value(path));
+    }
+    entry
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, json!({ "error": message }))
+}
@@ -0,0 +1,50 @@
+//! The daps library: everything behind the `daps` binary, exposed so the
+//! integration tests can drive it against an in-memory backend.
+
+pub mod aliases;
+pub mod backend;
+pub mod cache_file;
+pub mod chunking;
+pub mod client;
+pub mod command;
+pub mod commands;
+pub mod completer;
+pub mod config;
+pub mod cpboard;
+pub mod display_filter;
+pub mod encryption;
+pub mod env_vars;
+pub mod generate;
+pub mod helper;
+pub mod history;
+pub mod hooks;
+pub mod http_api;
+pub mod lint;
+pub mod macros;
+pub mod mcp;
+pub mod mock_backend;
+pub mod names;
+pub mod output;
+pub mod pending;
+pub mod pins;
+pub mod policy;
+pub mod progress;
+pub mod reauth;
+pub mod recent;
+pub mod refresher;
+pub mod region;
+pub mod repl;
+pub mod retry;
+pub mod search_index;
+pub mod secrets_manager;
+pub mod serve;
+pub mod sessions;
+pub mod shutdown;
+pub mod snapshots;
+pub mod stats;
+pub mod store_paths;
+pub mod tui;
+pub mod utils;
+pub mod value_cache;
+pub mod vocabulary;
+pub mod workspaces;
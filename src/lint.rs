@@ -0,0 +1,153 @@
+use crate::utils::glob_match;
+use rusoto_ssm::ParameterMetadata;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Rules for `lint`, from the `[lint]` table of the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintConfig {
+    /// Paths matching these globs must be `SecureString`.
+    pub secure_patterns: Vec<String>,
+    /// Maximum number of path segments.
+    pub max_depth: usize,
+    /// `SecureString` values last changed longer ago than this are reported.
+    pub rotation_days: u64,
+    /// Rule names to skip, e.g. `["description"]`.
+    pub disabled: Vec<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            secure_patterns: vec!["*password*".into(), "*token*".into()],
+            max_depth: 6,
+            rotation_days: 90,
+            disabled: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub path: String,
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl LintConfig {
+    /// Checks every parameter and returns the findings, most severe first.
+    pub fn check(&self, parameters: &[ParameterMetadata]) -> Vec<Finding> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or_default();
+
+        let mut findings = Vec::new();
+        for param in parameters {
+            let Some(path) = param.name.as_deref() else {
+                continue;
+            };
+            let mut report = |rule: &'static str, severity: Severity, message: String| {
+                if !self.disabled.iter().any(|name| name == rule) {
+                    findings.push(Finding {
+                        path: path.to_string(),
+                        rule,
+                        severity,
+                        message,
+                    });
+                }
+            };
+
+            let secure = param.type_.as_deref() == Some("SecureString");
+            let lower = path.to_lowercase();
+            if !secure
+                && let Some(pattern) = self
+                    .secure_patterns
+                    .iter()
+                    .find(|pattern| glob_match(&pattern.to_lowercase(), &lower))
+            {
+                report(
+                    "secure-type",
+                    Severity::Error,
+                    format!(
+                        "matches '{}' but is {}, not SecureString",
+                        pattern,
+                        param.type_.as_deref().unwrap_or("untyped")
+                    ),
+                );
+            }
+
+            let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+            if let Some(segment) = segments.iter().find(|s| !is_kebab_case(s)) {
+                report(
+                    "naming",
+                    Severity::Warning,
+                    format!("segment '{}' is not lowercase-with-dashes", segment),
+                );
+            }
+            if segments.len() > self.max_depth {
+                report(
+                    "max-depth",
+                    Severity::Warning,
+                    format!(
+                        "{} levels deep, at most {} allowed",
+                        segments.len(),
+                        self.max_depth
+                    ),
+                );
+            }
+
+            if param.description.as_deref().is_none_or(str::is_empty) {
+                report("description", Severity::Info, "has no description".into());
+            }
+
+            if secure && let Some(modified) = param.last_modified_date {
+                let age_days = ((now - modified) / SECONDS_PER_DAY).floor() as u64;
+                if age_days > self.rotation_days {
+                    report(
+                        "rotation",
+                        Severity::Warning,
+                        format!(
+                            "last changed {} days ago, rotate every {} days",
+                            age_days, self.rotation_days
+                        ),
+                    );
+                }
+            }
+        }
+
+        findings.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.path.cmp(&b.path)));
+        findings
+    }
+}
+
+/// `db-password`, `v2`: lowercase letters and digits, words joined by single dashes.
+fn is_kebab_case(segment: &str) -> bool {
+    segment.split('-').all(|word| {
+        !word.is_empty()
+            && word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    })
+}
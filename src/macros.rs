@@ -0,0 +1,116 @@
+use crate::command::Command;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, Table, value};
+
+/// Config file table holding the recorded macros.
+const TABLE: &str = "macros";
+
+/// Command shortcuts and recorded macros. Command aliases come from the
+/// `[command_aliases]` table of the config file (`rot = "set --generate 32"`)
+/// and are only read; macros live in the `[macros]` table and are written by
+/// `macro record`.
+#[derive(Debug, Default)]
+pub struct Macros {
+    file: PathBuf,
+    pub commands: BTreeMap<String, String>,
+    pub entries: BTreeMap<String, Vec<String>>,
+}
+
+impl Macros {
+    pub fn new(
+        file: &Path,
+        commands: BTreeMap<String, String>,
+        entries: BTreeMap<String, Vec<String>>,
+    ) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            commands,
+            entries,
+        }
+    }
+
+    /// Returns `line` with a leading command alias replaced by what it stands
+    /// for, keeping the rest of the line: `rot /app/key` becomes
+    /// `set --generate 32 /app/key`. Built-in commands win over aliases of the
+    /// same name, and expansions are not expanded again.
+    pub fn expand(&self, line: &str) -> String {
+        let trimmed = line.trim();
+        let (keyword, rest) = match trimmed.find(' ') {
+            Some(pos) => (&trimmed[..pos], trimmed[pos..].trim_start()),
+            None => (trimmed, ""),
+        };
+        match self.commands.get(keyword) {
+            Some(expansion) if matches!(Command::parse(keyword), Command::Navigate(_)) => {
+                if rest.is_empty() {
+                    expansion.clone()
+                } else {
+                    format!("{} {}", expansion, rest)
+                }
+            }
+            _ => line.to_string(),
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        name: &str,
+        lines: Vec<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        check_name(name)?;
+        if lines.is_empty() {
+            return Err(format!("Macro '{}' has no commands", name).into());
+        }
+        self.entries.insert(name.to_string(), lines);
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.entries.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Rewrites only the `[macros]` table, keeping the rest of the config file
+    /// as the user wrote it.
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let content = match std::fs::read_to_string(&self.file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let mut doc: DocumentMut = content
+            .parse()
+            .map_err(|e| format!("Invalid config file {}: {}", self.file.display(), e))?;
+
+        let mut table = Table::new();
+        for (name, lines) in &self.entries {
+            let array: Array = lines.iter().map(String::as_str).collect();
+            table.insert(name, value(array));
+        }
+        doc.insert(TABLE, Item::Table(table));
+
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.file, doc.to_string())?;
+        Ok(())
+    }
+}
+
+/// Macro names follow the rules for path aliases.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid macro name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
@@ -1,25 +1,36 @@
-use crate::command::Command;
-use crate::completer::ParameterCompleter;
-use crate::encryption::Encryption;
-use crate::helper::ParamStoreHelper;
-use crate::utils::parse_region;
+use daps::aliases::Aliases;
+use daps::backend::BackendKind;
+use daps::client::ClientOptions;
+use daps::command::Command;
+use daps::commands::stats::ExitReport;
+use daps::completer::{DEFAULT_VALUE_WIDTH, ParameterCompleter};
+use daps::cpboard::Cpboard;
+use daps::encryption::{Encryption, EncryptionMode};
+use daps::env_vars::EnvFormat;
+use daps::helper::ParamStoreHelper;
+use daps::hooks::Hooks;
+use daps::http_api::{HttpOptions, Mask};
+use daps::macros::Macros;
+use daps::output::OutputFormat;
+use daps::policy::TypePolicy;
+use daps::region::RegionSource;
+use daps::retry::RetryPolicy;
+use daps::store_paths::StorePaths;
+use daps::utils::parse_region;
+use daps::{
+    config, env_vars, history, http_api, mcp, output, reauth, region, repl, serve, snapshots, tui,
+    workspaces,
+};
 use clipboard::ClipboardContext;
 use clipboard::ClipboardProvider;
 use rustyline::{
-    CompletionType, Config, EditMode, Editor,
+    Cmd, CompletionType, Config, EditMode, Editor, KeyCode, KeyEvent, Modifiers, Movement,
     highlight::MatchingBracketHighlighter,
 };
+use std::io::IsTerminal;
+use std::sync::Arc;
 use structopt::StructOpt;
-
-pub mod command;
-pub mod commands;
-pub mod completer;
-pub mod cpboard;
-pub mod encryption;
-pub mod helper;
-pub mod mcp;
-pub mod repl;
-pub mod utils;
+use structopt::clap::Shell;
 
 #[derive(Debug, StructOpt)]
 #[structopt(
@@ -28,94 +39,531 @@ pub mod utils;
     author = "D. Doffy <cuongnsm@gmail.com>"
 )]
 struct Opt {
-    /// AWS Region
-    #[structopt(long, default_value = "us-east-1")]
-    region: String,
+    /// AWS Region, including GovCloud (us-gov-west-1) and China (cn-north-1);
+    /// `name=https://endpoint` uses an explicit endpoint. Defaults to
+    /// AWS_REGION, AWS_DEFAULT_REGION, the profile's region, then instance metadata
+    #[structopt(long)]
+    region: Option<String>,
 
-    /// Starting path for parameter store (e.g., /prod/)
+    /// Starting path for parameter store (e.g., /prod/); `sm://prod/` selects
+    /// the Secrets Manager backend
     #[structopt(short, long, default_value = "/")]
     path: String,
 
+    /// Storage backend: ssm (Parameter Store, default) or secretsmanager.
+    /// Also settable via the `backend` config key
+    #[structopt(long)]
+    backend: Option<BackendKind>,
+
     /// Refresh parameter cache
     #[structopt(short, long)]
     refresh: bool,
 
-    /// Store directory for parameters and values
-    #[structopt(long, default_value = "parameters")]
-    store_dir: String,
+    /// Serve everything from the local cache and queue changes instead of contacting AWS
+    #[structopt(long, conflicts_with = "refresh")]
+    offline: bool,
+
+    /// Load SecureString parameters without their values, so no kms:Decrypt
+    /// permission is needed; a value is decrypted when it is shown
+    #[structopt(long)]
+    no_decrypt: bool,
+
+    /// Store directory for parameters and values; relative paths are resolved against
+    /// the home directory (defaults to the platform data directory, e.g. ~/.local/share/daps)
+    #[structopt(long)]
+    store_dir: Option<String>,
+
+    /// Log more detail: -v for progress, -vv for debug output, -vvv to trace AWS requests
+    #[structopt(short, long, parse(from_occurrences))]
+    verbose: u8,
+
+    /// Suppress the banner, progress and clipboard messages; only errors are logged
+    #[structopt(short, long, conflicts_with = "verbose")]
+    quiet: bool,
 
-    /// Verbose output
+    /// Mask every displayed value and keep values off the clipboard, e.g. for
+    /// demos and screen sharing (toggle with `redact on|off`)
     #[structopt(long)]
-    verbose: bool,
+    redact: bool,
 
     /// Run as an MCP (Model Context Protocol) server over stdio
     #[structopt(long)]
     mcp: bool,
+
+    /// Browse parameters in a full-screen terminal UI instead of the REPL
+    #[structopt(long, conflicts_with = "mcp")]
+    tui: bool,
+
+    /// Maximum attempts per SSM call when throttled or on transient errors
+    #[structopt(long, default_value = "5")]
+    max_attempts: u32,
+
+    /// Maximum SSM API calls per second (0 disables rate limiting)
+    #[structopt(long, default_value = "10")]
+    max_tps: f64,
+
+    /// Per-request timeout in seconds (0 disables the timeout)
+    #[structopt(long, default_value = "30")]
+    timeout: u64,
+
+    /// TCP connect timeout in seconds (0 disables the timeout)
+    #[structopt(long, default_value = "10")]
+    connect_timeout: u64,
+
+    /// HTTP(S) proxy URL; defaults to the HTTPS_PROXY environment variable
+    #[structopt(long)]
+    proxy: Option<String>,
+
+    /// Custom SSM endpoint URL (e.g. http://localhost:4566 for LocalStack)
+    #[structopt(long)]
+    endpoint_url: Option<String>,
+
+    /// AWS profile to use instead of the default credential chain
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Start in a workspace from the config file, taking its profile, region,
+    /// first base path and store directory unless given as flags
+    #[structopt(long, conflicts_with = "path")]
+    workspace: Option<String>,
+
+    /// Cache encryption: auto (on when DAPS_ENCRYPTION_KEY is set), on, or off.
+    /// Also settable via DAPS_ENCRYPTION or the `encryption` config key
+    #[structopt(long)]
+    encryption: Option<EncryptionMode>,
+
+    /// Allow caching values on disk unencrypted when encryption is off
+    #[structopt(long)]
+    allow_plaintext_cache: bool,
+
+    /// Encrypt whole cache files, hiding parameter names as well as values (needs encryption)
+    #[structopt(long)]
+    seal_cache: bool,
+
+    /// Snapshot the parameters each session changes before its first write to
+    /// them, for `snapshot restore auto-<time>` (needs values cached on disk)
+    #[structopt(long)]
+    auto_snapshot: bool,
+
+    /// Disable colored output (also disabled when NO_COLOR is set)
+    #[structopt(long)]
+    no_color: bool,
+
+    /// Output format for command results: text or json (one object per line)
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// Path to the config file (defaults to ~/.config/daps/config.toml)
+    #[structopt(long)]
+    config: Option<String>,
+
+    /// In batch mode (`daps run` or piped stdin), keep running after a command fails
+    #[structopt(long)]
+    keep_going: bool,
+
+    /// Print the AWS calls each command made, and session statistics on exit
+    #[structopt(long)]
+    timings: bool,
+
+    #[structopt(subcommand)]
+    subcommand: Option<Subcommand>,
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let encryption_key = std::env::var("DAPS_ENCRYPTION_KEY").unwrap_or_else(|_| {
-        println!("DAPS_ENCRYPTION_KEY not set, using default");
-        "default_key".to_string()
-    });
+#[derive(Debug, StructOpt)]
+enum Subcommand {
+    /// Print a shell completion script for daps' own flags and subcommands
+    Completions {
+        /// Target shell: bash, zsh, fish, powershell or elvish
+        #[structopt(possible_values = &Shell::variants(), case_insensitive = true)]
+        shell: Shell,
+    },
+    /// Run REPL commands from a script file, one per line, without prompting
+    Run {
+        /// Script to run; `#` starts a comment line
+        #[structopt(parse(from_os_str))]
+        script: std::path::PathBuf,
+    },
+    /// Answer list/get/set/search requests as JSON lines, or serve the cache
+    /// read-only over HTTP, keeping the cache loaded
+    Serve {
+        /// Read requests from stdin and write responses to stdout
+        #[structopt(long, conflicts_with = "http")]
+        stdio: bool,
+        /// Serve GET /parameters?prefix=... and /parameter/<name> on this address,
+        /// e.g. 127.0.0.1:7878
+        #[structopt(long)]
+        http: Option<std::net::SocketAddr>,
+        /// Bearer token HTTP clients must send; defaults to DAPS_SERVE_TOKEN.
+        /// Required unless the address is loopback
+        #[structopt(long)]
+        token: Option<String>,
+        /// Values left out of HTTP responses: none, secure (all but String and
+        /// StringList) or all. Defaults to none with a token, secure without
+        #[structopt(long)]
+        mask: Option<Mask>,
+    },
+    /// Print the parameters under a prefix as environment variables, for
+    /// `eval "$(daps env /dev/app)"` or `docker run --env-file <(daps env ...)`
+    Env {
+        /// Parameters under this prefix become variables named after the rest
+        /// of their path: /dev/app/db/host is DB_HOST
+        prefix: String,
+        /// shell (`export KEY='value'`), docker (`KEY=value`) or dotenv
+        /// (`KEY="value"`)
+        #[structopt(long, default_value = "shell")]
+        format: EnvFormat,
+    },
+    /// Run a command with the parameters under a prefix in its environment:
+    /// `daps exec /dev/app -- npm start`
+    Exec {
+        /// Parameters under this prefix become variables, named as by `env`
+        prefix: String,
+        /// The command and its arguments
+        #[structopt(required = true)]
+        command: Vec<String>,
+    },
+}
 
-    let opt = Opt::from_args();
-    let region = parse_region(&opt.region).map_err(|e| format!("Invalid region: {}", e))?;
-    let base_path = opt.path.clone();
+/// The store directory given with `--store-dir` (relative to the home
+/// directory), or the platform default.
+fn open_store(store_dir: Option<&str>) -> StorePaths {
+    match store_dir {
+        Some(store_dir) => {
+            let home_dir = directories::BaseDirs::new()
+                .map(|dirs| dirs.home_dir().to_path_buf())
+                .unwrap_or_else(|| {
+                    println!("Home directory not found, using current directory");
+                    std::path::PathBuf::from(".")
+                });
+            StorePaths::resolve(store_dir, &home_dir)
+        }
+        None => StorePaths::platform_default(),
+    }
+}
 
-    if !base_path.starts_with('/') {
-        return Err("Base path must start with '/'".into());
+/// Maps `-q` and `-v` counts to log levels. Up to `-vv` only daps' own messages
+/// are shown; `-vvv` also enables the AWS SDK and HTTP client logs.
+fn init_logging(verbose: u8, quiet: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => log::LevelFilter::Error,
+        (false, 0) => log::LevelFilter::Warn,
+        (false, 1) => log::LevelFilter::Info,
+        (false, 2) => log::LevelFilter::Debug,
+        (false, _) => log::LevelFilter::Trace,
+    };
+    let others = if verbose >= 3 {
+        log::LevelFilter::Trace
+    } else {
+        log::LevelFilter::Warn.min(level)
+    };
+
+    env_logger::Builder::new()
+        .filter_level(others)
+        .filter_module("daps", level)
+        .format_timestamp(None)
+        .init();
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut opt = Opt::from_args();
+    if let Some(Subcommand::Completions { shell }) = &opt.subcommand {
+        Opt::clap().gen_completions_to("daps", *shell, &mut std::io::stdout());
+        return Ok(());
     }
 
-    #[cfg(not(target_os = "windows"))]
-    let home_dir = std::env::var("HOME").unwrap_or_else(|_| {
-        println!("HOME not set, using current directory");
-        ".".to_string()
-    });
+    output::init_color(opt.no_color, opt.output);
+    init_logging(opt.verbose, opt.quiet);
+    let config_path = opt
+        .config
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(config::Config::default_path);
+    let config = config::Config::load(&config_path)?;
+    workspaces::validate(&config.workspaces)?;
 
-    #[cfg(target_os = "windows")]
-    let home_dir = std::env::var("APPDATA").unwrap_or_else(|_| {
-        println!("APPDATA not set, using current directory");
-        ".".to_string()
-    });
+    let shared_store_dir = opt.store_dir.clone();
+    if let Some(name) = &opt.workspace {
+        let workspace = workspaces::find(&config.workspaces, name)?;
+        opt.region = opt.region.take().or(workspace.region.clone());
+        opt.profile = opt.profile.take().or(workspace.profile.clone());
+        opt.store_dir = opt.store_dir.take().or(workspace.store_dir.clone());
+        opt.path = workspace.base_path().to_string();
+    }
 
-    let is_absolute = if cfg!(target_os = "windows") {
-        opt.store_dir.chars().nth(1) == Some(':')
+    let store = open_store(opt.store_dir.as_deref());
+    let shared_store = if shared_store_dir == opt.store_dir {
+        store.clone()
     } else {
-        opt.store_dir.starts_with('/')
+        open_store(shared_store_dir.as_deref())
     };
 
-    let store_dir = if is_absolute {
-        opt.store_dir.clone()
-    } else {
-        format!("{}/{}", home_dir, opt.store_dir)
+    let encryption_mode = match opt.encryption {
+        Some(mode) => mode,
+        None => std::env::var("DAPS_ENCRYPTION")
+            .ok()
+            .or(config.encryption.clone())
+            .map(|m| m.parse::<EncryptionMode>())
+            .transpose()?
+            .unwrap_or_default(),
     };
+    let mut encryption = Encryption::from_settings(
+        encryption_mode,
+        std::env::var("DAPS_ENCRYPTION_KEY").ok(),
+        opt.allow_plaintext_cache || config.allow_plaintext_cache,
+        &store.salt(),
+    )?;
+    encryption.seal_cache = opt.seal_cache || config.seal_cache;
+    if encryption.seal_cache && !encryption.enabled {
+        return Err(
+            "--seal-cache needs cache encryption; set DAPS_ENCRYPTION_KEY or use --encryption on"
+                .into(),
+        );
+    }
+    if !encryption.can_persist_values() {
+        eprintln!(
+            "Cache encryption is off: values are kept in memory only. \
+             Set DAPS_ENCRYPTION_KEY, use --encryption on to enter a passphrase, \
+             or pass --allow-plaintext-cache to store them unencrypted."
+        );
+    }
+    let (region, source) = region::detect(
+        opt.region.as_deref(),
+        opt.profile.as_deref(),
+        !opt.offline && opt.endpoint_url.is_none() && config.endpoint_url.is_none(),
+    )
+    .await;
+    match source {
+        RegionSource::Flag => {}
+        RegionSource::Default => eprintln!(
+            "No region configured; using {}. Pass --region or set AWS_REGION.",
+            region
+        ),
+        source if !opt.quiet => eprintln!("Using region {} (from {})", region, source),
+        _ => {}
+    }
+    let region = parse_region(&region)?;
+    let (scheme, base_path) = BackendKind::split_scheme(&opt.path);
+    let backend = match (scheme, opt.backend) {
+        (Some(scheme), Some(flag)) if scheme != flag => {
+            return Err(
+                format!("--path {} contradicts --backend {}", opt.path, flag.name()).into(),
+            );
+        }
+        (Some(kind), _) | (None, Some(kind)) => kind,
+        (None, None) => config
+            .backend
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default(),
+    };
+    let store = store.for_backend(backend);
+    let shared_store = shared_store.for_backend(backend);
 
+    if !base_path.starts_with('/') {
+        return Err("Base path must start with '/'".into());
+    }
+
+    let seconds = |s: u64| (s > 0).then(|| std::time::Duration::from_secs(s));
+    let client_options = ClientOptions {
+        retry: RetryPolicy::new(opt.max_attempts, opt.max_tps),
+        request_timeout: seconds(opt.timeout),
+        connect_timeout: seconds(opt.connect_timeout),
+        proxy: opt.proxy.clone(),
+        endpoint_url: opt.endpoint_url.clone().or(config.endpoint_url.clone()),
+        profile: opt.profile.clone(),
+    };
+
+    let history_file = store.history();
     let mut completer = ParameterCompleter::new(
+        backend,
         region,
         base_path,
         opt.refresh,
-        store_dir,
-        opt.verbose,
-        Encryption::new(true, encryption_key),
+        store,
+        encryption,
+        client_options,
+    )?;
+    completer.offline = opt.offline;
+    completer.decrypt = !(opt.no_decrypt || config.no_decrypt);
+    completer.output = opt.output;
+    completer.quiet = opt.quiet;
+    completer.redact = opt.redact;
+    completer.timings = opt.timings;
+    let exit_report = opt
+        .timings
+        .then(|| ExitReport(Arc::clone(&completer.stats)));
+    completer.aliases = Aliases::new(&config_path, config.aliases.clone());
+    completer.macros = Macros::new(
+        &config_path,
+        config.command_aliases.clone(),
+        config.macros.clone(),
     );
-    completer.load_parameters().await?;
+    completer.hooks = Hooks::new(config.hooks.clone())?;
+    config.env_mapping.validate()?;
+    completer.env_mapping = config.env_mapping.clone();
+    completer.workspaces = config.workspaces.clone();
+    completer.workspace = opt.workspace.clone();
+    completer.shared_store = shared_store;
+    completer.fuzzy_completion = config.fuzzy_completion.unwrap_or(true);
+    completer.secret_policy = config.secret_policy.clone();
+    completer.protected = config.protected.clone();
+    completer.type_policy =
+        TypePolicy::new(config.default_type.clone(), config.type_policy.clone())?;
+    completer.age_threshold_days = config.age_threshold_days.unwrap_or(365);
+    completer.chunking = config.chunking;
+    completer.lint = config.lint.clone();
+    completer.background_refresh_secs = config.background_refresh_secs;
+    completer.clear_clipboard_on_exit = config.clear_clipboard_on_exit;
+    completer.value_width = config.value_width.unwrap_or(DEFAULT_VALUE_WIDTH);
+    completer.auto_snapshots = config.auto_snapshots.clone();
+    completer.auto_snapshots.enabled |= opt.auto_snapshot;
+    if completer.auto_snapshots.enabled && !completer.encryption.can_persist_values() {
+        return Err(snapshots::AUTO_NEEDS_KEY.into());
+    }
+    if config.value_cache_capacity > 0 {
+        if completer.encryption.can_persist_values() {
+            completer.values.set_capacity(config.value_cache_capacity);
+        } else {
+            log::warn!("value_cache_capacity needs values cached on disk; keeping all in memory");
+        }
+    }
+    if let Err(err) = completer.load_parameters().await {
+        if reauth::is_expired(&err.to_string()) {
+            return Err(reauth::expired_message(opt.profile.as_deref()).into());
+        }
+        return Err(err.into());
+    }
+
+    // ── JSON-lines server mode ─────────────────────────────────────────────
+    if let Some(Subcommand::Serve {
+        stdio,
+        http,
+        token,
+        mask,
+    }) = &opt.subcommand
+    {
+        let Some(address) = http else {
+            if !stdio {
+                return Err("serve needs a transport: --stdio or --http <address>".into());
+            }
+            return serve::run_stdio(&mut completer).await;
+        };
+        let token = token
+            .clone()
+            .or_else(|| std::env::var("DAPS_SERVE_TOKEN").ok())
+            .filter(|t| !t.is_empty());
+        if token.is_none() && !address.ip().is_loopback() {
+            return Err(format!(
+                "Serving on {} needs --token or DAPS_SERVE_TOKEN; only loopback addresses may go without",
+                address
+            )
+            .into());
+        }
+        let listener = std::net::TcpListener::bind(address)
+            .map_err(|e| format!("Cannot listen on {}: {}", address, e))?;
+        if let Err(err) = completer.start_background_refresh() {
+            log::warn!("Background refresh disabled: {}", err);
+        }
+        if !opt.quiet {
+            eprintln!("Serving {} on http://{}", completer.base_path, address);
+        }
+        // Without a token any local process can read the API, so secrets
+        // are only served when asked for explicitly.
+        let mask = mask.unwrap_or(if token.is_some() {
+            Mask::None
+        } else {
+            Mask::Secure
+        });
+        let options = HttpOptions { token, mask };
+        let shutdown = async {
+            let _ = tokio::signal::ctrl_c().await;
+        };
+        return http_api::run(completer, listener, options, shutdown).await;
+    }
+
+    // ── Environment variables for a shell or container ─────────────────────
+    if let Some(Subcommand::Env { prefix, format }) = &opt.subcommand {
+        return env_vars::print(&mut completer, prefix, *format).await;
+    }
+    if let Some(Subcommand::Exec { prefix, command }) = &opt.subcommand {
+        let code = env_vars::exec(&mut completer, prefix, command).await?;
+        // `exit` skips destructors, so the timings report is printed first.
+        drop(exit_report);
+        std::process::exit(code);
+    }
 
     // ── MCP server mode ────────────────────────────────────────────────────
     if opt.mcp {
         return mcp::run(&mut completer).await;
     }
 
+    // ── Full-screen TUI mode ───────────────────────────────────────────────
+    if opt.tui {
+        return tui::run(&mut completer).await;
+    }
+
+    // ── Batch mode: a script file, or commands piped on stdin ───────────────
+    let script = match &opt.subcommand {
+        Some(Subcommand::Run { script }) => Some(script.clone()),
+        _ => None,
+    };
+    if script.is_some() || !std::io::stdin().is_terminal() {
+        let mut rl: Editor<ParamStoreHelper> = Editor::new()?;
+        rl.set_helper(Some(ParamStoreHelper {
+            completer,
+            highlighter: MatchingBracketHighlighter::new(),
+            commands: Command::keywords(),
+        }));
+        let cpboard = Cpboard::disabled();
+        return match script {
+            Some(path) => {
+                let file = std::fs::File::open(&path)
+                    .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+                let source = path.display().to_string();
+                let input = std::io::BufReader::new(file);
+                repl::run_batch(&mut rl, cpboard, input, &source, opt.keep_going).await
+            }
+            None => {
+                let input = std::io::stdin().lock();
+                repl::run_batch(&mut rl, cpboard, input, "stdin", opt.keep_going).await
+            }
+        };
+    }
+
     // ── Interactive REPL mode ──────────────────────────────────────────────
-    let config = Config::builder()
+    completer.load_vocabulary(false).await;
+
+    let editor_config = Config::builder()
         .edit_mode(EditMode::Vi)
-        .completion_type(CompletionType::Circular)
-        .auto_add_history(true)
+        .completion_type(match config.completion.as_deref() {
+            Some("list") => CompletionType::List,
+            _ => CompletionType::Circular,
+        })
+        // The REPL adds entries itself, after expanding `!N` references.
+        .auto_add_history(false)
         .bell_style(rustyline::config::BellStyle::None)
         .build();
 
-    let mut rl: Editor<ParamStoreHelper> = Editor::with_config(config)?;
+    let mut rl: Editor<ParamStoreHelper> = Editor::with_config(editor_config)?;
+    // Ctrl-R searches history backwards; Up/Down recall entries starting with what is typed.
+    rl.bind_sequence(KeyEvent::ctrl('R'), Cmd::ReverseSearchHistory);
+    rl.bind_sequence(
+        KeyEvent(KeyCode::Up, Modifiers::NONE),
+        Cmd::HistorySearchBackward,
+    );
+    rl.bind_sequence(
+        KeyEvent(KeyCode::Down, Modifiers::NONE),
+        Cmd::HistorySearchForward,
+    );
+    // Alt-V types `show`, so Enter prints the selected value in full.
+    rl.bind_sequence(
+        KeyEvent::alt('v'),
+        Cmd::Replace(Movement::WholeLine, Some("show".to_string())),
+    );
+    // A missing history file just means this is the first session.
+    let _ = rl.load_history(&history_file);
     rl.set_helper(Some(ParamStoreHelper {
         completer,
         highlighter: MatchingBracketHighlighter::new(),
@@ -125,5 +573,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut ctx = ClipboardContext::new()
         .map_err(|e| format!("Failed to create clipboard context: {}", e))?;
 
-    repl::run(&mut rl, &mut ctx).await
+    let result = repl::run(&mut rl, Cpboard::new(&mut ctx)).await;
+    if let Err(e) = history::save(&history_file, rl.history().iter()) {
+        eprintln!(
+            "Could not save history to {}: {}",
+            history_file.display(),
+            e
+        );
+    }
+    result
 }
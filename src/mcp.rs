@@ -157,6 +157,7 @@ async fn call_tool(
         "set_parameter" => {
             let path = args["path"].as_str().ok_or("missing 'path'")?;
             let value = args["value"].as_str().ok_or("missing 'value'")?;
+            refuse_protected(completer, path)?;
             completer
                 .change_value(path, value.to_string())
                 .await
@@ -167,9 +168,14 @@ async fn call_tool(
         "insert_parameter" => {
             let path = args["path"].as_str().ok_or("missing 'path'")?;
             let value = args["value"].as_str().ok_or("missing 'value'")?;
-            let param_type = args["type"].as_str().unwrap_or("String");
+            refuse_protected(completer, path)?;
+            let param_type = completer.type_policy.resolve(
+                path,
+                args["type"].as_str(),
+                completer.type_policy.default_type(),
+            )?;
             completer
-                .set_parameter(path, value.to_string(), Some(param_type.to_string()))
+                .set_parameter(path, value.to_string(), Some(param_type))
                 .await
                 .map_err(|e| e.to_string())?;
             completer.update_all(path, value.to_string()).await.map_err(|e| e.to_string())?;
@@ -186,7 +192,7 @@ async fn call_tool(
                 .keys()
                 .filter_map(|k| matcher.fuzzy_match(k, term).map(|score| (score, k.as_str())))
                 .collect();
-            matches.sort_by(|a, b| b.0.cmp(&a.0));
+            matches.sort_by_key(|m| std::cmp::Reverse(m.0));
             let keys: Vec<&str> = matches.iter().map(|(_, k)| *k).collect();
             Ok(json!({ "results": keys }))
         }
@@ -205,6 +211,16 @@ async fn call_tool(
     }
 }
 
+/// Protected paths need a human to type the name back, so tools may not write them.
+fn refuse_protected(completer: &ParameterCompleter, path: &str) -> Result<(), String> {
+    match completer.protected.matching(path) {
+        Some(pattern) => Err(format!(
+            "{path} is protected by '{pattern}'; change it from the REPL"
+        )),
+        None => Ok(()),
+    }
+}
+
 // ── MCP content helper ────────────────────────────────────────────────────────
 
 fn text_content(value: Value) -> Value {
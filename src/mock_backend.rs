@@ -0,0 +1,465 @@
+use crate::backend::ParameterBackend;
+use async_trait::async_trait;
+use rusoto_core::RusotoError;
+use rusoto_core::request::HttpDispatchError;
+use rusoto_ssm::{
+    DeleteParameterError, DeleteParameterRequest, DeleteParameterResult, DescribeParametersError,
+    DescribeParametersRequest, DescribeParametersResult, GetParameterError, GetParameterRequest,
+    GetParameterResult, GetParametersByPathError, GetParametersByPathRequest,
+    GetParametersByPathResult, GetParametersError, GetParametersRequest, GetParametersResult,
+    ListTagsForResourceError, ListTagsForResourceRequest, ListTagsForResourceResult, Parameter,
+    ParameterMetadata, PutParameterError, PutParameterRequest, PutParameterResult, Tag,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Largest value a standard-tier parameter holds.
+const STANDARD_LIMIT: usize = 4096;
+/// Largest value an advanced-tier parameter holds.
+const ADVANCED_LIMIT: usize = 8192;
+/// Page sizes SSM uses when the request leaves `max_results` unset.
+const PATH_PAGE: i64 = 10;
+const DESCRIBE_PAGE: i64 = 50;
+
+/// An in-memory Parameter Store for tests. Clones share the same store, so a
+/// test can hand one to a completer and inspect or seed it through another.
+/// Paging, overwrite, not-found and size rules follow SSM closely enough for
+/// the code paths daps exercises.
+#[derive(Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    parameters: BTreeMap<String, MockParameter>,
+    /// Requests received per operation, failed ones included.
+    calls: HashMap<&'static str, usize>,
+    /// Calls left to fail with a transport error before the store answers again.
+    failures: usize,
+    /// Refuses to decrypt `SecureString` values, like a caller without kms:Decrypt.
+    deny_decrypt: bool,
+}
+
+#[derive(Debug, Clone)]
+struct MockParameter {
+    value: String,
+    type_: String,
+    version: i64,
+    /// Seconds since the Unix epoch, like `LastModifiedDate`.
+    modified: f64,
+    description: Option<String>,
+    tags: Vec<Tag>,
+    allowed_pattern: Option<String>,
+    data_type: Option<String>,
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64())
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store holding `String` parameters with the given names and values.
+    pub fn with_parameters<'a>(parameters: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let backend = Self::new();
+        for (name, value) in parameters {
+            backend.put(name, value, "String");
+        }
+        backend
+    }
+
+    /// Writes a parameter directly, bypassing the request rules and the call count.
+    pub fn put(&self, name: &str, value: &str, type_: &str) {
+        let mut state = self.lock();
+        let version = state.parameters.get(name).map_or(1, |p| p.version + 1);
+        state.parameters.insert(
+            name.to_string(),
+            MockParameter {
+                value: value.to_string(),
+                type_: type_.to_string(),
+                version,
+                modified: now(),
+                description: None,
+                tags: Vec::new(),
+                allowed_pattern: None,
+                data_type: None,
+            },
+        );
+    }
+
+    pub fn value(&self, name: &str) -> Option<String> {
+        self.lock().parameters.get(name).map(|p| p.value.clone())
+    }
+
+    pub fn type_of(&self, name: &str) -> Option<String> {
+        self.lock().parameters.get(name).map(|p| p.type_.clone())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.lock().parameters.keys().cloned().collect()
+    }
+
+    /// How many requests `operation` (e.g. `"PutParameter"`) received.
+    pub fn calls(&self, operation: &str) -> usize {
+        self.lock().calls.get(operation).copied().unwrap_or(0)
+    }
+
+    /// Requests received across all operations.
+    pub fn total_calls(&self) -> usize {
+        self.lock().calls.values().sum()
+    }
+
+    /// Makes the next `count` requests fail with a retryable transport error.
+    pub fn fail_next(&self, count: usize) {
+        self.lock().failures = count;
+    }
+
+    /// Makes every request that decrypts a `SecureString` fail with AccessDenied.
+    pub fn deny_decrypt(&self) {
+        self.lock().deny_decrypt = true;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Counts the request and returns the store, or the injected failure.
+    fn begin(&self, operation: &'static str) -> Result<MutexGuard<'_, State>, HttpDispatchError> {
+        let mut state = self.lock();
+        *state.calls.entry(operation).or_default() += 1;
+        if state.failures > 0 {
+            state.failures -= 1;
+            return Err(HttpDispatchError::new(format!(
+                "{}: injected failure",
+                operation
+            )));
+        }
+        Ok(state)
+    }
+}
+
+impl State {
+    /// The AccessDenied error SSM returns when `names` include a
+    /// `SecureString` the caller may not decrypt.
+    fn check_decrypt<'a>(
+        &self,
+        with_decryption: Option<bool>,
+        mut names: impl Iterator<Item = &'a String>,
+    ) -> Result<(), String> {
+        if !self.deny_decrypt || with_decryption != Some(true) {
+            return Ok(());
+        }
+        match names.find(|name| {
+            self.parameters
+                .get(*name)
+                .is_some_and(|p| p.type_ == "SecureString")
+        }) {
+            Some(name) => Err(format!(
+                "AccessDeniedException: not authorized to perform kms:Decrypt for {}",
+                name
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl MockParameter {
+    /// Without decryption a `SecureString` comes back as opaque ciphertext.
+    fn to_parameter(&self, name: &str, with_decryption: Option<bool>) -> Parameter {
+        let value = if self.type_ == "SecureString" && with_decryption != Some(true) {
+            format!("AQICAH{}", hex::encode(&self.value))
+        } else {
+            self.value.clone()
+        };
+        Parameter {
+            name: Some(name.to_string()),
+            value: Some(value),
+            type_: Some(self.type_.clone()),
+            version: Some(self.version),
+            last_modified_date: Some(self.modified),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl ParameterBackend for MockBackend {
+    async fn get_parameter(
+        &self,
+        input: GetParameterRequest,
+    ) -> Result<GetParameterResult, RusotoError<GetParameterError>> {
+        let state = self
+            .begin("GetParameter")
+            .map_err(RusotoError::HttpDispatch)?;
+        let parameter = state.parameters.get(&input.name).ok_or_else(|| {
+            RusotoError::Service(GetParameterError::ParameterNotFound(input.name.clone()))
+        })?;
+        state
+            .check_decrypt(input.with_decryption, std::iter::once(&input.name))
+            .map_err(RusotoError::Validation)?;
+        Ok(GetParameterResult {
+            parameter: Some(parameter.to_parameter(&input.name, input.with_decryption)),
+        })
+    }
+
+    async fn get_parameters(
+        &self,
+        input: GetParametersRequest,
+    ) -> Result<GetParametersResult, RusotoError<GetParametersError>> {
+        let state = self
+            .begin("GetParameters")
+            .map_err(RusotoError::HttpDispatch)?;
+        if input.names.len() > 10 {
+            return Err(RusotoError::Validation(
+                "GetParameters accepts at most 10 names".to_string(),
+            ));
+        }
+        state
+            .check_decrypt(input.with_decryption, input.names.iter())
+            .map_err(RusotoError::Validation)?;
+        let mut parameters = Vec::new();
+        let mut invalid = Vec::new();
+        for name in input.names {
+            match state.parameters.get(&name) {
+                Some(parameter) => {
+                    parameters.push(parameter.to_parameter(&name, input.with_decryption))
+                }
+                None => invalid.push(name),
+            }
+        }
+        Ok(GetParametersResult {
+            parameters: Some(parameters),
+            invalid_parameters: Some(invalid),
+        })
+    }
+
+    async fn get_parameters_by_path(
+        &self,
+        input: GetParametersByPathRequest,
+    ) -> Result<GetParametersByPathResult, RusotoError<GetParametersByPathError>> {
+        let state = self
+            .begin("GetParametersByPath")
+            .map_err(RusotoError::HttpDispatch)?;
+        let prefix = format!("{}/", input.path.trim_end_matches('/'));
+        let names: Vec<&String> = state
+            .parameters
+            .keys()
+            .filter(|name| {
+                name.strip_prefix(&prefix)
+                    .is_some_and(|rest| input.recursive == Some(true) || !rest.contains('/'))
+            })
+            .collect();
+        state
+            .check_decrypt(input.with_decryption, names.iter().copied())
+            .map_err(RusotoError::Validation)?;
+        let matching: Vec<Parameter> = names
+            .into_iter()
+            .map(|name| state.parameters[name].to_parameter(name, input.with_decryption))
+            .collect();
+        let (parameters, next_token) =
+            page(matching, input.max_results, PATH_PAGE, input.next_token).map_err(|token| {
+                RusotoError::Service(GetParametersByPathError::InvalidNextToken(token))
+            })?;
+        Ok(GetParametersByPathResult {
+            parameters: Some(parameters),
+            next_token,
+        })
+    }
+
+    async fn put_parameter(
+        &self,
+        input: PutParameterRequest,
+    ) -> Result<PutParameterResult, RusotoError<PutParameterError>> {
+        let mut state = self
+            .begin("PutParameter")
+            .map_err(RusotoError::HttpDispatch)?;
+        let limit = match input.tier.as_deref() {
+            Some("Advanced") => ADVANCED_LIMIT,
+            _ => STANDARD_LIMIT,
+        };
+        if input.value.is_empty() || input.value.len() > limit {
+            return Err(RusotoError::Validation(format!(
+                "Value for {} must be 1 to {} bytes, got {}",
+                input.name,
+                limit,
+                input.value.len()
+            )));
+        }
+
+        let existing = state.parameters.get(&input.name).cloned();
+        let allowed_pattern = input.allowed_pattern.clone().or_else(|| {
+            existing
+                .as_ref()
+                .and_then(|old| old.allowed_pattern.clone())
+        });
+        if let Some(pattern) = &allowed_pattern
+            && !regex::Regex::new(pattern).is_ok_and(|regex| regex.is_match(&input.value))
+        {
+            return Err(RusotoError::Service(
+                PutParameterError::ParameterPatternMismatch(format!(
+                    "{} does not match {}",
+                    input.name, pattern
+                )),
+            ));
+        }
+        let parameter = match existing {
+            Some(_) if input.overwrite != Some(true) => {
+                return Err(RusotoError::Service(
+                    PutParameterError::ParameterAlreadyExists(input.name.clone()),
+                ));
+            }
+            Some(old) => MockParameter {
+                value: input.value,
+                type_: input.type_.unwrap_or(old.type_),
+                version: old.version + 1,
+                modified: now(),
+                description: input.description.or(old.description),
+                tags: old.tags,
+                allowed_pattern,
+                data_type: input.data_type.or(old.data_type),
+            },
+            None => MockParameter {
+                value: input.value,
+                type_: input.type_.ok_or_else(|| {
+                    RusotoError::Validation(format!("A type is required to create {}", input.name))
+                })?,
+                version: 1,
+                modified: now(),
+                description: input.description,
+                tags: input.tags.unwrap_or_default(),
+                allowed_pattern,
+                data_type: input.data_type,
+            },
+        };
+        let version = parameter.version;
+        state.parameters.insert(input.name, parameter);
+        Ok(PutParameterResult {
+            version: Some(version),
+            tier: Some(input.tier.unwrap_or_else(|| "Standard".to_string())),
+        })
+    }
+
+    async fn delete_parameter(
+        &self,
+        input: DeleteParameterRequest,
+    ) -> Result<DeleteParameterResult, RusotoError<DeleteParameterError>> {
+        let mut state = self
+            .begin("DeleteParameter")
+            .map_err(RusotoError::HttpDispatch)?;
+        match state.parameters.remove(&input.name) {
+            Some(_) => Ok(DeleteParameterResult {}),
+            None => Err(RusotoError::Service(
+                DeleteParameterError::ParameterNotFound(input.name),
+            )),
+        }
+    }
+
+    /// Supports the `Name BeginsWith` filter daps uses; other filters are ignored.
+    async fn describe_parameters(
+        &self,
+        input: DescribeParametersRequest,
+    ) -> Result<DescribeParametersResult, RusotoError<DescribeParametersError>> {
+        let state = self
+            .begin("DescribeParameters")
+            .map_err(RusotoError::HttpDispatch)?;
+        let prefix = input
+            .parameter_filters
+            .iter()
+            .flatten()
+            .filter(|filter| filter.key == "Name")
+            .find_map(|filter| filter.values.as_ref()?.first().cloned())
+            .unwrap_or_default();
+        let matching: Vec<ParameterMetadata> = state
+            .parameters
+            .iter()
+            .filter(|(name, _)| name.starts_with(&prefix))
+            .map(|(name, parameter)| ParameterMetadata {
+                name: Some(name.clone()),
+                type_: Some(parameter.type_.clone()),
+                tier: Some(
+                    if parameter.value.len() > STANDARD_LIMIT {
+                        "Advanced"
+                    } else {
+                        "Standard"
+                    }
+                    .to_string(),
+                ),
+                version: Some(parameter.version),
+                last_modified_date: Some(parameter.modified),
+                description: parameter.description.clone(),
+                allowed_pattern: parameter.allowed_pattern.clone(),
+                data_type: Some(
+                    parameter
+                        .data_type
+                        .clone()
+                        .unwrap_or_else(|| "text".to_string()),
+                ),
+                ..Default::default()
+            })
+            .filter(|metadata| {
+                // Type, KeyId and Tier filters compare exactly.
+                input.parameter_filters.iter().flatten().all(|filter| {
+                    let field = match filter.key.as_str() {
+                        "Type" => &metadata.type_,
+                        "KeyId" => &metadata.key_id,
+                        "Tier" => &metadata.tier,
+                        _ => return true,
+                    };
+                    let wanted = filter.values.as_deref().unwrap_or_default();
+                    field.as_ref().is_some_and(|value| wanted.contains(value))
+                })
+            })
+            .collect();
+        let (parameters, next_token) =
+            page(matching, input.max_results, DESCRIBE_PAGE, input.next_token).map_err(
+                |token| RusotoError::Service(DescribeParametersError::InvalidNextToken(token)),
+            )?;
+        Ok(DescribeParametersResult {
+            parameters: Some(parameters),
+            next_token,
+        })
+    }
+
+    async fn list_tags_for_resource(
+        &self,
+        input: ListTagsForResourceRequest,
+    ) -> Result<ListTagsForResourceResult, RusotoError<ListTagsForResourceError>> {
+        let state = self
+            .begin("ListTagsForResource")
+            .map_err(RusotoError::HttpDispatch)?;
+        let parameter = state.parameters.get(&input.resource_id).ok_or_else(|| {
+            RusotoError::Service(ListTagsForResourceError::InvalidResourceId(
+                input.resource_id.clone(),
+            ))
+        })?;
+        Ok(ListTagsForResourceResult {
+            tag_list: Some(parameter.tags.clone()),
+        })
+    }
+}
+
+/// Cuts one page out of `items`. Tokens are offsets; a token that is not one
+/// is returned as the error.
+fn page<T>(
+    items: Vec<T>,
+    max_results: Option<i64>,
+    default_size: i64,
+    next_token: Option<String>,
+) -> Result<(Vec<T>, Option<String>), String> {
+    let start = match next_token {
+        Some(token) => token.parse::<usize>().map_err(|_| token)?,
+        None => 0,
+    };
+    let size = max_results.unwrap_or(default_size).max(1) as usize;
+    let end = (start + size).min(items.len());
+    let next_token = (end < items.len()).then(|| end.to_string());
+    let page = items.into_iter().skip(start).take(size).collect();
+    Ok((page, next_token))
+}
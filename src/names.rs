@@ -0,0 +1,70 @@
+/// Longest parameter name SSM accepts.
+pub const MAX_NAME_LENGTH: usize = 1011;
+/// Deepest hierarchy SSM accepts: `/a/b/.../o` has 15 levels.
+pub const MAX_DEPTH: usize = 15;
+/// Where AWS publishes public parameters, such as the latest AMI IDs.
+pub const PUBLIC_PREFIX: &str = "/aws/service/";
+
+/// Whether `path` is one of the public parameters every account can read.
+pub fn is_public(path: &str) -> bool {
+    path.starts_with(PUBLIC_PREFIX)
+}
+
+/// Checks a parameter name against the SSM naming rules before it is sent, so
+/// a bad name gets a precise error instead of a generic ValidationException.
+/// Names are letters, digits, `_`, `.`, `-` and `/`; hierarchical names start
+/// with `/`, have no empty levels, no trailing `/` and at most 15 levels; and
+/// names beginning with `aws` or `ssm` are reserved.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Parameter name is empty".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '/')))
+    {
+        return Err(format!(
+            "Invalid name {}: '{}' is not allowed; use letters, digits, '_', '.', '-' and '/'",
+            name,
+            c.escape_default()
+        ));
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "Invalid name {}...: {} characters, at most {} are allowed",
+            &name[..40],
+            name.len(),
+            MAX_NAME_LENGTH
+        ));
+    }
+    if name.contains('/') {
+        if !name.starts_with('/') {
+            return Err(format!(
+                "Invalid name {}: hierarchical names must start with '/'",
+                name
+            ));
+        }
+        if name.len() > 1 && name.ends_with('/') {
+            return Err(format!("Invalid name {}: names cannot end with '/'", name));
+        }
+        if name.len() == 1 || name.contains("//") {
+            return Err(format!("Invalid name {}: a level is empty", name));
+        }
+        let depth = name.matches('/').count();
+        if depth > MAX_DEPTH {
+            return Err(format!(
+                "Invalid name {}: {} levels, at most {} are allowed",
+                name, depth, MAX_DEPTH
+            ));
+        }
+    }
+    let first = name.trim_start_matches('/').get(..3).unwrap_or_default();
+    if first.eq_ignore_ascii_case("aws") || first.eq_ignore_ascii_case("ssm") {
+        return Err(format!(
+            "Invalid name {}: names beginning with '{}' are reserved by AWS",
+            name,
+            first.to_lowercase()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use colored::Colorize;
+use serde_json::Value;
+
+/// How command results are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colored, human-oriented text.
+    #[default]
+    Text,
+    /// One JSON object per line, without banners or prose, for scripts.
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Invalid output format '{}' (expected text|json)",
+                other
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == Self::Json
+    }
+}
+
+/// Turns off ANSI colors when `--no-color` is given, `NO_COLOR` is set to a
+/// non-empty value (https://no-color.org) or the output is JSON.
+pub fn init_color(no_color: bool, format: OutputFormat) {
+    let env_no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || env_no_color || format.is_json() {
+        colored::control::set_override(false);
+    }
+}
+
+/// Prints `value` as a single line of JSON.
+pub fn print_json(value: Value) {
+    println!("{}", value);
+}
+
+/// Rows printed as aligned columns under a header. Cells may be colored;
+/// widths are measured on the visible text.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    /// Prints the header and rows; the last column is not padded.
+    pub fn print(&self) {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| visible_width(h)).collect();
+        for row in &self.rows {
+            for (index, cell) in row.iter().enumerate() {
+                match widths.get_mut(index) {
+                    Some(width) => *width = (*width).max(visible_width(cell)),
+                    None => widths.push(visible_width(cell)),
+                }
+            }
+        }
+        let headers: Vec<String> = self
+            .headers
+            .iter()
+            .map(|header| header.dimmed().to_string())
+            .collect();
+        println!("{}", render_row(&headers, &widths));
+        for row in &self.rows {
+            println!("{}", render_row(row, &widths));
+        }
+    }
+}
+
+fn render_row(cells: &[String], widths: &[usize]) -> String {
+    let mut line = String::new();
+    for (index, cell) in cells.iter().enumerate() {
+        line.push_str(cell);
+        if index + 1 < cells.len() {
+            let padding = widths[index].saturating_sub(visible_width(cell));
+            line.push_str(&" ".repeat(padding + 2));
+        }
+    }
+    line
+}
+
+/// Characters of `text` as shown on a terminal, skipping ANSI color codes.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip to the end of the `ESC [ ... m` sequence.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
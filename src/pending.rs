@@ -0,0 +1,156 @@
+use crate::cache_file::write_private;
+use crate::encryption::Encryption;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A write that was requested while offline and has not been sent to AWS yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingChange {
+    pub path: String,
+    pub value: String,
+    /// `None` keeps the remote type (a `set`); `Some` creates with that type (an `insert`).
+    pub param_type: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub queued_at: u64,
+    /// Version of the parameter in AWS when the first change to it was
+    /// queued, to notice it changing remotely before `push`. `None` when it
+    /// did not exist yet or its version was unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_version: Option<i64>,
+    /// Whether `value` is encrypted with the cache key in the queue file.
+    /// Always `false` in memory.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+}
+
+impl PendingChange {
+    pub fn new(path: &str, value: &str, param_type: Option<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            value: value.to_string(),
+            param_type,
+            queued_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            base_version: None,
+            encrypted: false,
+        }
+    }
+
+    /// Whether AWS now holds something other than what the change was based
+    /// on. Only known for changes with a recorded version, and for inserts,
+    /// which were based on the parameter not existing.
+    pub fn conflicts_with(&self, remote_version: Option<i64>) -> bool {
+        (self.base_version.is_some() || self.param_type.is_some())
+            && remote_version != self.base_version
+    }
+}
+
+/// Queue of pending changes persisted as JSON in the store directory. Values
+/// are encrypted with the cache key, like the values file.
+#[derive(Debug, Default)]
+pub struct PendingQueue {
+    file: PathBuf,
+    pub changes: Vec<PendingChange>,
+    /// Changes whose value the current key cannot decrypt, kept as they were
+    /// read so that saving the queue does not lose them.
+    undecryptable: Vec<PendingChange>,
+}
+
+impl PendingQueue {
+    /// Loads the queue from `file`; a missing or unreadable file yields an empty queue.
+    pub fn load(file: &Path, encryption: &Encryption) -> Self {
+        let stored: Vec<PendingChange> = std::fs::read_to_string(file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let mut changes = Vec::with_capacity(stored.len());
+        let mut undecryptable = Vec::new();
+        for mut change in stored {
+            if !change.encrypted {
+                changes.push(change);
+                continue;
+            }
+            match encryption
+                .enabled
+                .then(|| encryption.try_decrypt(&change.value).ok())
+                .flatten()
+            {
+                Some(value) => {
+                    change.value = value;
+                    change.encrypted = false;
+                    changes.push(change);
+                }
+                None => undecryptable.push(change),
+            }
+        }
+        if !undecryptable.is_empty() {
+            log::warn!(
+                "{} pending change(s) in {} cannot be decrypted with this cache key and are left queued as they are",
+                undecryptable.len(),
+                file.display()
+            );
+        }
+        Self {
+            file: file.to_path_buf(),
+            changes,
+            undecryptable,
+        }
+    }
+
+    /// Writes the queue, owner-readable only, with values encrypted when a
+    /// cache key is set.
+    pub fn save(&self, encryption: &Encryption) -> Result<(), Box<dyn std::error::Error>> {
+        if self.changes.is_empty() && self.undecryptable.is_empty() {
+            if self.file.exists() {
+                std::fs::remove_file(&self.file)?;
+            }
+            return Ok(());
+        }
+        let stored: Vec<PendingChange> = self
+            .changes
+            .iter()
+            .map(|change| PendingChange {
+                value: encryption.encrypt_value(&change.value),
+                encrypted: encryption.enabled,
+                ..change.clone()
+            })
+            .chain(self.undecryptable.iter().cloned())
+            .collect();
+        write_private(&self.file, serde_json::to_string_pretty(&stored)?)?;
+        Ok(())
+    }
+
+    /// Queues a change, replacing any earlier pending change for the same path.
+    /// An earlier `insert` keeps its type so the parameter is still created,
+    /// and the version it was based on.
+    pub fn push(&mut self, mut change: PendingChange) {
+        if let Some(pos) = self.changes.iter().position(|c| c.path == change.path) {
+            let previous = self.changes.remove(pos);
+            change.param_type = change.param_type.or(previous.param_type);
+            change.base_version = previous.base_version;
+        }
+        self.changes.push(change);
+    }
+
+    pub fn get(&self, path: &str) -> Option<&PendingChange> {
+        self.changes.iter().find(|c| c.path == path)
+    }
+
+    /// Removes the change for `path`, returning whether one existed.
+    pub fn remove(&mut self, path: &str) -> bool {
+        let before = self.changes.len();
+        self.changes.retain(|c| c.path != path);
+        before != self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+}
@@ -0,0 +1,161 @@
+use crate::cache_file::write_private;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The expected value of a critical parameter, stored as a hash so the pin
+/// file never contains the value itself. With a cache key the hash is an
+/// HMAC, so short secrets cannot be recovered from the file by brute force.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pin {
+    /// Plain SHA-256, for pins made without a cache key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// HMAC-SHA256 with the cache integrity key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hmac_sha256: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub pinned_at: u64,
+}
+
+/// How a pinned parameter deviates from its pin.
+#[derive(Debug, PartialEq)]
+pub enum Drift {
+    Changed,
+    Missing,
+}
+
+impl Drift {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Drift::Changed => "value differs from its pin",
+            Drift::Missing => "parameter no longer exists",
+        }
+    }
+}
+
+/// Pinned parameters, persisted as `pins.json` in the store directory. The
+/// file keeps the pins of every target (profile and region) apart, since the
+/// same path names a different parameter in another account or region; only
+/// the current target's pins are in `entries`.
+#[derive(Debug, Default)]
+pub struct Pins {
+    file: PathBuf,
+    /// The current target, e.g. `prod eu-west-1`.
+    target: String,
+    /// Cache integrity key; `None` without a cache key.
+    key: Option<[u8; 32]>,
+    pub entries: BTreeMap<String, Pin>,
+    /// Pins of the other targets, written back untouched.
+    others: BTreeMap<String, BTreeMap<String, Pin>>,
+}
+
+/// The file written since pins were kept per target.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PinFile {
+    targets: BTreeMap<String, BTreeMap<String, Pin>>,
+}
+
+/// Names the target pins belong to.
+pub fn target(profile: Option<&str>, region: &str) -> String {
+    format!("{} {}", profile.unwrap_or("default"), region)
+}
+
+fn sha256(value: &str) -> String {
+    format!("{:x}", Sha256::digest(value.as_bytes()))
+}
+
+fn hmac_sha256(key: &[u8; 32], value: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(value.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+impl Pins {
+    /// Loads the pins of `target`. Files written before pins were kept per
+    /// target hold a single map, which is taken to belong to `target`.
+    pub fn load(file: &Path, target: &str, key: Option<[u8; 32]>) -> Self {
+        let content = std::fs::read_to_string(file).unwrap_or_default();
+        let mut targets = match serde_json::from_str::<PinFile>(&content) {
+            Ok(stored) => stored.targets,
+            Err(_) => serde_json::from_str::<BTreeMap<String, Pin>>(&content)
+                .map(|entries| BTreeMap::from([(target.to_string(), entries)]))
+                .unwrap_or_default(),
+        };
+        let entries = targets.remove(target).unwrap_or_default();
+        Self {
+            file: file.to_path_buf(),
+            target: target.to_string(),
+            key,
+            entries,
+            others: targets,
+        }
+    }
+
+    /// Writes the pins of every target, owner-readable only.
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut targets = self.others.clone();
+        if !self.entries.is_empty() {
+            targets.insert(self.target.clone(), self.entries.clone());
+        }
+        write_private(
+            &self.file,
+            serde_json::to_string_pretty(&PinFile { targets })?,
+        )?;
+        Ok(())
+    }
+
+    pub fn pin(&mut self, path: &str, value: &str) {
+        self.entries.insert(
+            path.to_string(),
+            Pin {
+                sha256: self.key.is_none().then(|| sha256(value)),
+                hmac_sha256: self.key.map(|key| hmac_sha256(&key, value)),
+                pinned_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default(),
+            },
+        );
+    }
+
+    pub fn unpin(&mut self, path: &str) -> bool {
+        self.entries.remove(path).is_some()
+    }
+
+    /// Checks one pinned path against its current value.
+    pub fn check(&self, path: &str, value: Option<&str>) -> Option<Drift> {
+        let pin = self.entries.get(path)?;
+        let value = match value {
+            None => return Some(Drift::Missing),
+            Some(value) => value,
+        };
+        // A pin made with a cache key cannot be checked without it.
+        let matches = match (&pin.hmac_sha256, &self.key, &pin.sha256) {
+            (Some(expected), Some(key), _) => hmac_sha256(key, value) == *expected,
+            (_, _, Some(expected)) => sha256(value) == *expected,
+            _ => true,
+        };
+        (!matches).then_some(Drift::Changed)
+    }
+
+    /// Checks every pin under `base_path` against the loaded values. Pins outside
+    /// the base path are ignored since their values are not loaded.
+    pub fn drifted(
+        &self,
+        base_path: &str,
+        values: &HashMap<String, String>,
+    ) -> Vec<(String, Drift)> {
+        self.entries
+            .keys()
+            .filter(|path| path.starts_with(base_path))
+            .filter_map(|path| {
+                self.check(path, values.get(path).map(String::as_str))
+                    .map(|drift| (path.clone(), drift))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,265 @@
+use crate::utils::{glob_match, prompt_line};
+use crate::vocabulary::{DATA_TYPES, PARAMETER_TYPES};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+
+/// Rules checked by `set` and `insert` before a value is written to a
+/// parameter whose path matches one of `patterns`. Configured in the
+/// `[secret_policy]` table of the config file; `--no-validate` skips the check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretPolicy {
+    /// Turns the policy off entirely.
+    pub enabled: bool,
+    /// Case-insensitive globs (`*` and `?`) matched against the full path.
+    pub patterns: Vec<String>,
+    pub min_length: usize,
+    /// Character classes the value must contain: `lower`, `upper`, `digit`
+    /// and `symbol`.
+    pub require: Vec<String>,
+    /// Values refused outright, compared case-insensitively.
+    pub deny: Vec<String>,
+}
+
+impl Default for SecretPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: vec!["*password*".into(), "*secret*".into()],
+            min_length: 12,
+            require: vec!["lower".into(), "upper".into(), "digit".into()],
+            deny: [
+                "changeme", "password", "secret", "123456", "12345678", "qwerty", "letmein",
+                "admin", "default", "example", "test", "todo",
+            ]
+            .iter()
+            .map(|value| value.to_string())
+            .collect(),
+        }
+    }
+}
+
+impl SecretPolicy {
+    /// Whether values written to `path` are checked.
+    pub fn applies_to(&self, path: &str) -> bool {
+        self.enabled
+            && self
+                .patterns
+                .iter()
+                .any(|pattern| glob_match(&pattern.to_lowercase(), &path.to_lowercase()))
+    }
+
+    /// Checks `value` for `path`, listing every broken rule in the error.
+    pub fn check(&self, path: &str, value: &str) -> Result<(), String> {
+        if !self.applies_to(path) {
+            return Ok(());
+        }
+
+        let mut problems = Vec::new();
+        if self
+            .deny
+            .iter()
+            .any(|denied| denied.eq_ignore_ascii_case(value))
+        {
+            problems.push("the value is on the deny list".to_string());
+        }
+        let length = value.chars().count();
+        if length < self.min_length {
+            problems.push(format!(
+                "it has {} characters, at least {} are required",
+                length, self.min_length
+            ));
+        }
+        for class in &self.require {
+            let (found, name) = match class.as_str() {
+                "lower" => (value.chars().any(char::is_lowercase), "a lowercase letter"),
+                "upper" => (value.chars().any(char::is_uppercase), "an uppercase letter"),
+                "digit" => (value.chars().any(|c| c.is_ascii_digit()), "a digit"),
+                "symbol" => (
+                    value
+                        .chars()
+                        .any(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+                    "a symbol",
+                ),
+                other => {
+                    return Err(format!(
+                        "Unknown character class '{}' in secret_policy.require (expected lower, upper, digit or symbol)",
+                        other
+                    ));
+                }
+            };
+            if !found {
+                problems.push(format!("it needs {}", name));
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Value for {} does not meet the secret policy: {}. Use --no-validate to write it anyway.",
+            path,
+            problems.join("; ")
+        ))
+    }
+}
+
+/// Paths where `set`, `insert` and `delete` only go ahead once the parameter
+/// name is typed back, or with `--yes`. Configured as `protected = ["/prod/**"]`;
+/// `*` matches across `/`, and a pattern without wildcards is a prefix.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProtectedPaths(pub Vec<String>);
+
+impl ProtectedPaths {
+    /// The first pattern protecting `path`, if any.
+    pub fn matching(&self, path: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|pattern| {
+                if pattern.contains(['*', '?']) {
+                    glob_match(pattern, path)
+                } else {
+                    path.starts_with(pattern.as_str())
+                }
+            })
+            .map(String::as_str)
+    }
+
+    /// Asks for `path` to be typed back before `action` when it is protected.
+    /// `yes` skips the prompt; without a terminal it is required.
+    pub fn confirm(&self, path: &str, action: &str, yes: bool) -> Result<(), String> {
+        let Some(pattern) = self.matching(path) else {
+            return Ok(());
+        };
+        if yes {
+            return Ok(());
+        }
+        if !std::io::stdin().is_terminal() {
+            return Err(format!(
+                "{} is protected by '{}'; pass --yes to {} it without a prompt",
+                path, pattern, action
+            ));
+        }
+        let answer = prompt_line(&format!(
+            "{} is protected by '{}'. Type its full name to {} it: ",
+            path, pattern, action
+        ))
+        .map_err(|e| e.to_string())?;
+        if answer != path {
+            return Err(format!("Name did not match; {} was left unchanged", path));
+        }
+        Ok(())
+    }
+}
+
+/// Checks `value` against a parameter's `AllowedPattern`, which SSM matches
+/// anywhere in the value unless the pattern is anchored.
+pub fn check_allowed_pattern(path: &str, value: &str, pattern: &str) -> Result<(), String> {
+    let regex = regex::Regex::new(pattern)
+        .map_err(|e| format!("Invalid allowed pattern {}: {}", pattern, e))?;
+    if regex.is_match(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The value for {} does not match its allowed pattern {}",
+            path, pattern
+        ))
+    }
+}
+
+/// Checks that `value` suits a parameter's data type: an `aws:ec2:image`
+/// parameter holds an AMI id, and only as a `String`.
+pub fn check_data_type(path: &str, value: &str, data_type: &str) -> Result<(), String> {
+    if !DATA_TYPES.contains(&data_type) {
+        return Err(format!(
+            "Unknown data type '{}' (expected {})",
+            data_type,
+            DATA_TYPES.join(", ")
+        ));
+    }
+    let ami = regex::Regex::new("^ami-([0-9a-f]{8}|[0-9a-f]{17})$").expect("valid regex");
+    if data_type == "aws:ec2:image" && !ami.is_match(value) {
+        return Err(format!(
+            "The value for {} must be an AMI id such as ami-0abcdef1234567890, got '{}'",
+            path, value
+        ));
+    }
+    Ok(())
+}
+
+/// Types for new parameters: `default_type` when `insert` gives none, and
+/// `[type_policy]` patterns whose type is required underneath them, e.g.
+/// `"/prod/**/secrets/" = "SecureString"`. Patterns match like `protected`,
+/// and one ending in `/` covers everything below it; when several match, the
+/// longest wins.
+#[derive(Debug, Clone, Default)]
+pub struct TypePolicy {
+    default_type: Option<String>,
+    rules: BTreeMap<String, String>,
+}
+
+impl TypePolicy {
+    /// Checks that every configured type is one SSM knows.
+    pub fn new(
+        default_type: Option<String>,
+        rules: BTreeMap<String, String>,
+    ) -> Result<Self, String> {
+        for param_type in default_type.iter().chain(rules.values()) {
+            if !PARAMETER_TYPES.contains(&param_type.as_str()) {
+                return Err(format!(
+                    "Unknown parameter type '{}' in the type policy (expected {})",
+                    param_type,
+                    PARAMETER_TYPES.join(", ")
+                ));
+            }
+        }
+        Ok(Self {
+            default_type,
+            rules,
+        })
+    }
+
+    /// `default_type`, or `String`.
+    pub fn default_type(&self) -> &str {
+        self.default_type.as_deref().unwrap_or("String")
+    }
+
+    /// The most specific rule for `path`: its pattern and required type.
+    pub fn rule(&self, path: &str) -> Option<(&str, &str)> {
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| {
+                if !pattern.contains(['*', '?']) {
+                    path.starts_with(pattern.as_str())
+                } else if pattern.ends_with('/') {
+                    glob_match(&format!("{}*", pattern), path)
+                } else {
+                    glob_match(pattern, path)
+                }
+            })
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(pattern, param_type)| (pattern.as_str(), param_type.as_str()))
+    }
+
+    /// The type for a new parameter at `path`: `given` when the rules allow
+    /// it, else the required type, else `fallback`. A `given` type other than
+    /// the required one is an error.
+    pub fn resolve(
+        &self,
+        path: &str,
+        given: Option<&str>,
+        fallback: &str,
+    ) -> Result<String, String> {
+        match (given, self.rule(path)) {
+            (Some(given), Some((pattern, required))) if given != required => Err(format!(
+                "{} must be a {} (type_policy '{}'), not a {}",
+                path, required, pattern, given
+            )),
+            (Some(given), _) => Ok(given.to_string()),
+            (None, Some((_, required))) => Ok(required.to_string()),
+            (None, None) => Ok(fallback.to_string()),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::cache_file::write_atomic;
+use crate::completer::ParameterCompleter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+/// Width of the bar itself, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// A progress bar for bulk commands, drawn on stderr. Only drawn when stderr
+/// is a terminal and progress messages are on; otherwise `note` prints each
+/// record's message as a line, like `ParameterCompleter::progress`.
+pub struct ProgressBar {
+    label: String,
+    total: usize,
+    done: usize,
+    drawn: bool,
+    quiet: bool,
+}
+
+impl ProgressBar {
+    pub fn new(label: &str, total: usize, completer: &ParameterCompleter) -> Self {
+        let quiet = completer.quiet || completer.output.is_json();
+        Self {
+            label: label.to_string(),
+            total,
+            done: 0,
+            drawn: !quiet && std::io::stderr().is_terminal(),
+            quiet,
+        }
+    }
+
+    /// Starts at `done` records, for a resumed command.
+    pub fn resume_at(&mut self, done: usize) {
+        self.done = done.min(self.total);
+        self.draw();
+    }
+
+    /// Counts one more record.
+    pub fn inc(&mut self) {
+        self.done = (self.done + 1).min(self.total);
+        self.draw();
+    }
+
+    /// Prints a per-record message where no bar is drawn, in its place.
+    pub fn note(&self, message: &str) {
+        if !self.drawn && !self.quiet {
+            println!("{}", message);
+        }
+    }
+
+    /// Prints a line above the bar, such as a failure.
+    pub fn println(&self, line: &str) {
+        self.clear();
+        println!("{}", line);
+        self.draw();
+    }
+
+    fn draw(&self) {
+        if !self.drawn || self.total == 0 {
+            return;
+        }
+        let filled = BAR_WIDTH * self.done / self.total;
+        eprint!(
+            "\r\x1b[2K{} [{}{}] {}/{}",
+            self.label,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.done,
+            self.total
+        );
+        let _ = std::io::stderr().flush();
+    }
+
+    fn clear(&self) {
+        if self.drawn {
+            eprint!("\r\x1b[2K");
+            let _ = std::io::stderr().flush();
+        }
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// What an interrupted bulk command had finished, saved after every record
+/// so that running the same command again skips it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The command and the arguments that define its work; a checkpoint only
+    /// resumes the same run.
+    pub command: String,
+    /// Records finished, as the command names them.
+    pub done: BTreeSet<String>,
+}
+
+impl Checkpoint {
+    /// The saved progress of `command`, or a fresh checkpoint. Refuses to
+    /// start while another run of the same kind is unfinished.
+    pub fn resume(file: &Path, command: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let saved: Self = match std::fs::read_to_string(file) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid checkpoint {}: {}", file.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    command: command.to_string(),
+                    done: BTreeSet::new(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if saved.command != command {
+            return Err(format!(
+                "'{}' is unfinished; run it again to resume, or delete {}",
+                saved.command,
+                file.display()
+            )
+            .into());
+        }
+        Ok(saved)
+    }
+
+    /// Replaces the saved checkpoint in one step, so an interruption never
+    /// leaves a truncated file.
+    pub fn save(&self, file: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        write_atomic(file, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Deletes the checkpoint of a command that ran to the end.
+    pub fn finish(file: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        match std::fs::remove_file(file) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::completer::ParameterCompleter;
+use crate::utils::prompt_line;
+use crate::vocabulary::{active_profile, profile_settings};
+use colored::Colorize;
+use std::io::IsTerminal;
+
+/// Whether an error message says the session's AWS credentials have expired.
+/// Errors reach the REPL as text, so this looks at the message.
+pub fn is_expired(message: &str) -> bool {
+    let message = message.to_lowercase();
+    [
+        "expiredtoken",
+        "security token included in the request is expired",
+        "token has expired",
+        "sso session associated with this profile has expired",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+/// What to tell a user whose credentials expired when they cannot be asked.
+pub fn expired_message(profile: Option<&str>) -> String {
+    match login_command(profile) {
+        Some(command) => format!(
+            "AWS credentials have expired; run '{}' and try again",
+            command.join(" ")
+        ),
+        None => "AWS credentials have expired; renew them and try again".to_string(),
+    }
+}
+
+/// `aws sso login` for profiles configured with `sso_*` keys, `None` for
+/// anything else (static keys, assume-role, environment variables).
+fn login_command(profile: Option<&str>) -> Option<Vec<String>> {
+    let profile = active_profile(profile);
+    profile_settings(&profile)
+        .iter()
+        .any(|(key, _)| key.starts_with("sso_"))
+        .then(|| {
+            vec![
+                "aws".to_string(),
+                "sso".to_string(),
+                "login".to_string(),
+                "--profile".to_string(),
+                profile,
+            ]
+        })
+}
+
+/// Offers to renew expired credentials, then rebuilds the session's AWS
+/// clients so the new ones are picked up. The cache, selection and history
+/// are left alone. Returns whether the session reconnected, in which case the
+/// failed command can be run again.
+pub fn reauthenticate(
+    completer: &mut ParameterCompleter,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let profile = completer.client_options.profile.clone();
+    if !std::io::stdin().is_terminal() {
+        return Err(expired_message(profile.as_deref()).into());
+    }
+
+    println!("{}", "AWS credentials have expired.".red().bold());
+    match login_command(profile.as_deref()) {
+        Some(command) => {
+            let answer = prompt_line(&format!("Run '{}' now? [Y/n]: ", command.join(" ")))?;
+            if answer.eq_ignore_ascii_case("n") {
+                return Ok(false);
+            }
+            let status = std::process::Command::new(&command[0])
+                .args(&command[1..])
+                .status()
+                .map_err(|e| format!("Cannot run '{}': {}", command.join(" "), e))?;
+            if !status.success() {
+                return Err(format!("'{}' failed ({})", command.join(" "), status).into());
+            }
+        }
+        None => {
+            let answer = prompt_line(
+                "Renew the credentials (for example assume the role again in another terminal), \
+                 then press Enter to reconnect, or type n to skip: ",
+            )?;
+            if answer.eq_ignore_ascii_case("n") {
+                return Ok(false);
+            }
+        }
+    }
+
+    let region = completer.region.clone();
+    completer.switch_client(region, profile)?;
+    println!("Reconnected to AWS");
+    Ok(true)
+}
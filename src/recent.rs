@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+/// How many parameters `recent` remembers.
+pub const RECENT_LIMIT: usize = 20;
+
+/// Parameters viewed or changed most recently, newest first. Persisted as
+/// `recent.json` in the store directory so the list survives restarts.
+#[derive(Debug, Default)]
+pub struct Recent {
+    file: PathBuf,
+    pub entries: Vec<String>,
+}
+
+impl Recent {
+    pub fn load(file: &Path) -> Self {
+        let entries = std::fs::read_to_string(file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            file: file.to_path_buf(),
+            entries,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(&self.file, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    /// Moves `path` to the front of the list, dropping the oldest entry when full.
+    pub fn touch(&mut self, path: &str) {
+        self.entries.retain(|p| p != path);
+        self.entries.insert(0, path.to_string());
+        self.entries.truncate(RECENT_LIMIT);
+    }
+
+    /// Looks up `-N`: `-1` is the most recent parameter, `-2` the one before it.
+    pub fn get(&self, reference: &str) -> Result<&str, String> {
+        let n: usize = reference
+            .strip_prefix('-')
+            .and_then(|n| n.parse().ok())
+            .filter(|n| *n > 0)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid recent reference '{}': expected -1, -2, ...",
+                    reference
+                )
+            })?;
+        self.entries.get(n - 1).map(String::as_str).ok_or_else(|| {
+            format!(
+                "Only {} recent parameter(s); see 'recent'",
+                self.entries.len()
+            )
+        })
+    }
+}
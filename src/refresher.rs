@@ -0,0 +1,178 @@
+use crate::backend::ParameterBackend;
+use crate::chunking;
+use crate::client::ClientOptions;
+use crate::completer::{ParameterCompleter, Revision};
+use crate::retry::{RateLimiter, with_retry};
+use crate::stats::Stats;
+use colored::Colorize;
+use rusoto_core::RusotoError;
+use rusoto_ssm::{GetParametersByPathError, GetParametersByPathRequest};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Shortest allowed interval between background refreshes.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Everything under a base path as fetched from AWS: the tree, values, types
+/// and revisions.
+pub struct Snapshot {
+    pub parameters: HashMap<String, Vec<String>>,
+    pub values: HashMap<String, String>,
+    pub types: HashMap<String, String>,
+    pub revisions: HashMap<String, Revision>,
+    /// `SecureString` parameters fetched without decryption, so without a value.
+    pub unavailable: Vec<String>,
+    /// When the fetch started; local writes made after it make it stale.
+    pub started: Instant,
+}
+
+/// Fetches every parameter under `base_path`, putting chunked values back
+/// together. Calls go through the shared rate limiter and retry policy.
+/// Without `decrypt`, `SecureString` values are not requested, so no KMS
+/// permission is needed; those parameters are listed as unavailable.
+pub async fn fetch_snapshot(
+    backend: &dyn ParameterBackend,
+    base_path: &str,
+    decrypt: bool,
+    options: &ClientOptions,
+    limiter: &RateLimiter,
+    stats: &Stats,
+) -> Result<Snapshot, RusotoError<GetParametersByPathError>> {
+    let started = Instant::now();
+    let mut parameters = HashMap::new();
+    let mut values = HashMap::new();
+    let mut types = HashMap::new();
+    let mut revisions = HashMap::new();
+    let mut unavailable = Vec::new();
+    let mut next_token: Option<String> = None;
+    let mut total = 0;
+
+    loop {
+        let request = GetParametersByPathRequest {
+            path: base_path.to_string(),
+            recursive: Some(true),
+            parameter_filters: None,
+            next_token: next_token.clone(),
+            max_results: Some(10),
+            with_decryption: Some(decrypt),
+        };
+        let result = with_retry(
+            &options.retry,
+            limiter,
+            stats,
+            "GetParametersByPath",
+            options.request_timeout,
+            || backend.get_parameters_by_path(request.clone()),
+            |attempt, delay, err| {
+                log::info!(
+                    "GetParametersByPath failed (attempt {}/{}): {}; retrying in {:?}",
+                    attempt,
+                    options.retry.max_attempts,
+                    err,
+                    delay
+                );
+            },
+        )
+        .await?;
+
+        let Some(params) = result.parameters else {
+            break;
+        };
+        total += params.len();
+        log::info!("Fetched {} parameters ({} total)", params.len(), total);
+        for param in params {
+            let revision = Revision::of(&param);
+            if let (Some(name), Some(value)) = (param.name, param.value) {
+                ParameterCompleter::process_parameter_path(&name, &mut parameters);
+                revisions.insert(name.clone(), revision);
+                let encrypted = !decrypt && param.type_.as_deref() == Some("SecureString");
+                if let Some(param_type) = param.type_ {
+                    types.insert(name.clone(), param_type);
+                }
+                if encrypted {
+                    unavailable.push(name);
+                } else {
+                    values.insert(name, value);
+                }
+            }
+        }
+
+        next_token = result.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    for err in chunking::assemble_all(&mut values) {
+        eprintln!("{}", err.yellow());
+    }
+    chunking::hide_chunks(&mut parameters);
+    types.retain(|path, _| !chunking::is_chunk_path(path));
+    revisions.retain(|path, _| !chunking::is_chunk_path(path));
+    unavailable.retain(|path| !chunking::is_chunk_path(path));
+    Ok(Snapshot {
+        parameters,
+        values,
+        types,
+        revisions,
+        unavailable,
+        started,
+    })
+}
+
+/// A tokio task that re-fetches the base path every `interval` and leaves
+/// the result for the session to swap in. The task stops when this is dropped.
+pub struct BackgroundRefresher {
+    latest: Arc<Mutex<Option<Snapshot>>>,
+    task: JoinHandle<()>,
+}
+
+impl BackgroundRefresher {
+    pub fn spawn(
+        interval: Duration,
+        backend: Box<dyn ParameterBackend>,
+        base_path: String,
+        decrypt: bool,
+        options: ClientOptions,
+        limiter: Arc<RateLimiter>,
+        stats: Arc<Stats>,
+    ) -> Self {
+        let latest = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&latest);
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let fetched = fetch_snapshot(
+                    backend.as_ref(),
+                    &base_path,
+                    decrypt,
+                    &options,
+                    &limiter,
+                    &stats,
+                )
+                .await;
+                match fetched {
+                    Ok(snapshot) => {
+                        *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(snapshot);
+                    }
+                    Err(err) => log::warn!("Background refresh of {} failed: {}", base_path, err),
+                }
+            }
+        });
+        Self { latest, task }
+    }
+
+    /// Takes the newest snapshot not yet taken, if any.
+    pub fn take(&self) -> Option<Snapshot> {
+        self.latest.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl Drop for BackgroundRefresher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
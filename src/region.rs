@@ -0,0 +1,129 @@
+use crate::vocabulary::{active_profile, profile_settings};
+use hyper::{Body, Client, Method, Request};
+use std::fmt;
+use std::time::Duration;
+
+/// Used when nothing else names a region.
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+/// Instance metadata is link-local; anything slower means we are not on EC2.
+const METADATA_TIMEOUT: Duration = Duration::from_secs(1);
+const EC2_METADATA: &str = "http://169.254.169.254";
+
+/// Where the session's region came from, reported at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionSource {
+    Flag,
+    Environment(&'static str),
+    Profile(String),
+    EcsMetadata,
+    Ec2Metadata,
+    Default,
+}
+
+impl fmt::Display for RegionSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegionSource::Flag => write!(f, "--region"),
+            RegionSource::Environment(name) => write!(f, "${}", name),
+            RegionSource::Profile(profile) => write!(f, "profile {} in the AWS config", profile),
+            RegionSource::EcsMetadata => write!(f, "ECS task metadata"),
+            RegionSource::Ec2Metadata => write!(f, "EC2 instance metadata"),
+            RegionSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// Resolves the region the way the AWS CLI does: the flag, `AWS_REGION`,
+/// `AWS_DEFAULT_REGION`, the profile's `region`, then container or instance
+/// metadata when `use_metadata` is set, and finally `us-east-1`.
+pub async fn detect(
+    flag: Option<&str>,
+    profile: Option<&str>,
+    use_metadata: bool,
+) -> (String, RegionSource) {
+    if let Some(region) = flag {
+        return (region.to_string(), RegionSource::Flag);
+    }
+    for name in ["AWS_REGION", "AWS_DEFAULT_REGION"] {
+        if let Ok(region) = std::env::var(name)
+            && !region.trim().is_empty()
+        {
+            return (region.trim().to_string(), RegionSource::Environment(name));
+        }
+    }
+    let profile = active_profile(profile);
+    if let Some((_, region)) = profile_settings(&profile)
+        .into_iter()
+        .find(|(key, value)| key == "region" && !value.is_empty())
+    {
+        return (region, RegionSource::Profile(profile));
+    }
+    if use_metadata {
+        if let Some(region) = ecs_region().await {
+            return (region, RegionSource::EcsMetadata);
+        }
+        if let Some(region) = ec2_region().await {
+            return (region, RegionSource::Ec2Metadata);
+        }
+    }
+    (DEFAULT_REGION.to_string(), RegionSource::Default)
+}
+
+/// The region in the task ARN from the ECS task metadata endpoint.
+async fn ecs_region() -> Option<String> {
+    let base = std::env::var("ECS_CONTAINER_METADATA_URI_V4")
+        .or_else(|_| std::env::var("ECS_CONTAINER_METADATA_URI"))
+        .ok()?;
+    let request = Request::get(format!("{}/task", base.trim_end_matches('/')))
+        .body(Body::empty())
+        .ok()?;
+    let body = fetch(request).await?;
+    let task: serde_json::Value = serde_json::from_str(&body).ok()?;
+    // arn:aws:ecs:<region>:<account>:task/...
+    task["TaskARN"]
+        .as_str()?
+        .split(':')
+        .nth(3)
+        .filter(|region| !region.is_empty())
+        .map(str::to_string)
+}
+
+/// The instance's region from IMDSv2, skipped when `AWS_EC2_METADATA_DISABLED`
+/// is set.
+async fn ec2_region() -> Option<String> {
+    if std::env::var("AWS_EC2_METADATA_DISABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true")) {
+        return None;
+    }
+    let token = Request::builder()
+        .method(Method::PUT)
+        .uri(format!("{}/latest/api/token", EC2_METADATA))
+        .header("X-aws-ec2-metadata-token-ttl-seconds", "60")
+        .body(Body::empty())
+        .ok()?;
+    let token = fetch(token).await?;
+    let request = Request::get(format!(
+        "{}/latest/meta-data/placement/region",
+        EC2_METADATA
+    ))
+    .header("X-aws-ec2-metadata-token", token)
+    .body(Body::empty())
+    .ok()?;
+    fetch(request)
+        .await
+        .map(|region| region.trim().to_string())
+        .filter(|region| !region.is_empty())
+}
+
+async fn fetch(request: Request<Body>) -> Option<String> {
+    let response = tokio::time::timeout(METADATA_TIMEOUT, async {
+        let response = Client::new().request(request).await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        hyper::body::to_bytes(response.into_body()).await.ok()
+    })
+    .await
+    .ok()??;
+    String::from_utf8(response.to_vec()).ok()
+}
@@ -1,209 +1,322 @@
 use crate::command::Command;
+use crate::commands::age::age;
+use crate::commands::alias::alias;
+use crate::commands::appconfig::appconfig;
+use crate::commands::binary::{get_binary, set_binary};
+use crate::commands::cache::cache;
+use crate::commands::compare::compare;
+use crate::commands::copy::copy;
+use crate::commands::count::count;
+use crate::commands::delete::delete;
+use crate::commands::deps::{deps, render_resolved};
+use crate::commands::export::export;
+use crate::commands::gc::gc;
 use crate::commands::handle_command_result;
-use crate::commands::insert::insert_value;
+use crate::commands::help::{cheatsheet, help};
+use crate::commands::history::list_history;
+use crate::commands::import::import;
+use crate::commands::insert::{InsertArgs, insert_value, insert_wizard, inserted_path};
+use crate::commands::lint::lint;
+use crate::commands::list::list;
+use crate::commands::macros::{Recording, manage_macro};
 use crate::commands::migration::migration;
+use crate::commands::move_tree::move_tree;
+use crate::commands::notify::notify;
+use crate::commands::parse_db::parse_db;
+use crate::commands::paste::paste;
+use crate::commands::pending::{discard_pending, list_pending, push_pending};
+use crate::commands::pin::{list_pins, pin, unpin, warn_on_drift};
+use crate::commands::public::{get_public, latest_ami};
+use crate::commands::recent::list_recent;
+use crate::commands::redact::redact;
 use crate::commands::refresh::refresh;
 use crate::commands::reload::{reload, reload_by_path};
+use crate::commands::reload_all::reload_all;
 use crate::commands::reload_by_paths::reload_by_paths;
-use crate::commands::parse_db::parse_db;
+use crate::commands::render::render;
+use crate::commands::replace::replace;
+use crate::commands::rotate_key::rotate_key;
+use crate::commands::scope::{scope, unscope};
 use crate::commands::search::search;
-use crate::commands::select::select_by_index;
-use crate::commands::set::set_value;
+use crate::commands::select::select;
+use crate::commands::session::manage_session;
+use crate::commands::set::{self, set_value};
+use crate::commands::set_many::{self, set_many};
+use crate::commands::show::show;
+use crate::commands::size::size;
+use crate::commands::snapshot::snapshot;
+use crate::commands::stat::stat;
+use crate::commands::stats::{command_timings, stats};
+use crate::commands::status::status;
+use crate::commands::switch::{switch_profile, switch_region};
+use crate::commands::sync::sync;
+use crate::commands::workspace::workspace;
+use crate::completer::ParameterCompleter;
 use crate::cpboard::Cpboard;
+use crate::display_filter;
 use crate::helper::ParamStoreHelper;
-use clipboard::ClipboardContext;
+use crate::history;
+use crate::names::is_public;
+use crate::output::{self, OutputFormat};
+use crate::reauth;
+use crate::shutdown::Shutdown;
+use crate::utils::{json_field, take_flag};
 use colored::Colorize;
 use rustyline::Editor;
+use serde_json::json;
+use std::io::BufRead;
 
-/// Runs the interactive REPL loop.
-///
-/// Accepts the already-configured `Editor` (with helper attached) and a
-/// `ClipboardContext`.  Returns when the user types `exit`, presses CTRL-C /
-/// CTRL-D, or an unrecoverable readline error occurs.
-pub async fn run(
-    rl: &mut Editor<ParamStoreHelper>,
-    ctx: &mut ClipboardContext,
-) -> Result<(), Box<dyn std::error::Error>> {
-    println!("AWS Parameter Store CLI");
-    println!(
-        "Type a parameter path and use {} for completion",
-        "Tab".red()
-    );
-    println!("Type '{}' to quit", "exit".yellow());
+const NO_HELPER: &str = "REPL helper is not set";
 
-    let mut cpboard = Cpboard::new(ctx);
-    let mut selected = String::new();
+/// Longest selected path shown in the prompt; longer ones keep their tail.
+const PROMPT_PATH_MAX: usize = 40;
 
-    loop {
-        match rl.readline(">> ") {
-            Ok(line) => {
-                match Command::parse(&line) {
-                    Command::Exit => break,
-
-                    Command::Refresh => {
-                        if let Some(helper) = rl.helper_mut() {
-                            if let Err(err) = refresh(helper).await {
-                                println!("Error refreshing parameters: {}", err);
-                            }
-                        }
-                    }
+/// Whether the session continues after a command.
+pub enum Flow {
+    Continue,
+    Exit,
+}
 
-                    Command::Migration => {
-                        if let Some(helper) = rl.helper_mut() {
-                            if let Err(err) = migration(helper).await {
-                                println!("Error during migration: {}", err);
-                            }
-                        }
-                    }
+/// State that lives for one REPL or batch session.
+pub struct Session<'a> {
+    pub cpboard: Cpboard<'a>,
+    /// The parameter `set`, `reload`, `pin` and `parse-db` act on.
+    pub selected: String,
+    /// The macro being recorded, if any.
+    pub recording: Option<Recording>,
+}
 
-                    Command::Reload => {
-                        if let Some(helper) = rl.helper_mut() {
-                            handle_command_result(
-                                reload(helper, &selected).await,
-                                &mut cpboard,
-                            )
-                            .await;
-                        }
-                    }
+impl<'a> Session<'a> {
+    pub fn new(cpboard: Cpboard<'a>) -> Self {
+        Session {
+            cpboard,
+            selected: String::new(),
+            recording: None,
+        }
+    }
+}
 
-                    Command::ShowSelected => {
-                        if !selected.is_empty() {
-                            println!("Currently selected parameter: {}", selected.green());
-                        } else {
-                            println!(
-                                "No parameter selected. Use 'sel <index>' to select one."
-                            );
-                        }
-                    }
+fn output_format(rl: &Editor<ParamStoreHelper>) -> OutputFormat {
+    rl.helper()
+        .map_or(OutputFormat::Text, |helper| helper.completer.output)
+}
 
-                    Command::ReloadSelected => {
-                        if let Some(helper) = rl.helper_mut() {
-                            let paths = if selected.is_empty() {
-                                println!("No parameter selected. Reloading all parameters.");
-                                String::new()
-                            } else {
-                                selected.clone()
-                            };
-                            reload_by_paths(helper, &paths).await?;
-                        }
-                    }
+/// Expands a leading `@alias` in a path argument.
+fn resolve_path(
+    rl: &Editor<ParamStoreHelper>,
+    arg: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let helper = rl.helper().ok_or(NO_HELPER)?;
+    Ok(helper.completer.aliases.resolve(arg)?)
+}
 
-                    Command::ReloadByPaths(paths) => {
-                        if let Some(helper) = rl.helper_mut() {
-                            let paths = if paths.is_empty() {
-                                println!("No paths provided, using selected.");
-                                selected.clone()
-                            } else {
-                                paths
-                            };
-                            reload_by_paths(helper, &paths).await?;
-                        }
-                    }
+/// Like `resolve_path`, then narrows the path to the session's scope.
+fn scoped_path(
+    rl: &Editor<ParamStoreHelper>,
+    arg: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let path = resolve_path(rl, arg)?;
+    let helper = rl.helper().ok_or(NO_HELPER)?;
+    Ok(helper.completer.scoped(&path)?.to_string())
+}
 
-                    Command::ReloadByPath(path) => {
-                        if let Some(helper) = rl.helper_mut() {
-                            let path = if path.is_empty() {
-                                println!("No path provided, using selected.");
-                                selected.clone()
-                            } else {
-                                path
-                            };
-                            handle_command_result(
-                                reload_by_path(helper, &path).await,
-                                &mut cpboard,
-                            )
-                            .await;
-                        }
-                    }
+/// Records `path` in the recent list if it is a known parameter.
+fn remember(helper: &mut ParamStoreHelper, path: &str) {
+    let completer = &mut helper.completer;
+    if !completer.values.contains_key(path) {
+        return;
+    }
+    completer.recent.touch(path);
+    if let Err(err) = completer.recent.save() {
+        log::warn!("Could not save recent parameters: {}", err);
+    }
+}
 
-                    Command::Set(value) => {
-                        if let Some(helper) = rl.helper_mut() {
-                            handle_command_result(
-                                set_value(helper, &value, &selected).await,
-                                &mut cpboard,
-                            )
-                            .await;
-                        }
-                    }
+/// The prompt, showing where commands go: the profile, the region, the scope
+/// if any and the selected parameter, e.g.
+/// `prod eu-west-1 (/prod/db) [/prod/db/password] >> `.
+pub fn prompt(completer: &ParameterCompleter, selected: &str) -> String {
+    let profile = completer
+        .client_options
+        .profile
+        .as_deref()
+        .unwrap_or("default");
+    let mut prompt = String::new();
+    if let Some(workspace) = &completer.workspace {
+        prompt.push_str(&format!("{}: ", workspace));
+    }
+    prompt.push_str(&format!("{} {} ", profile, completer.region.name()));
+    if let Some(scope) = &completer.scope {
+        prompt.push_str(&format!("({}) ", scope));
+    }
+    if !selected.is_empty() {
+        let length = selected.chars().count();
+        if length > PROMPT_PATH_MAX {
+            let tail: String = selected
+                .chars()
+                .skip(length - PROMPT_PATH_MAX + 1)
+                .collect();
+            prompt.push_str(&format!("[…{}] ", tail));
+        } else {
+            prompt.push_str(&format!("[{}] ", selected));
+        }
+    }
+    prompt.push_str(">> ");
+    prompt
+}
 
-                    Command::SelectByIndex(arg) => {
-                        if let Some(helper) = rl.helper_mut() {
-                            match select_by_index(helper, &arg) {
-                                Ok(param) => selected = param,
-                                Err(err) => println!("{}", err),
-                            }
-                        }
-                    }
+/// Whether `line` has a secret value typed inline: `set <value>` on a secret
+/// parameter, `set-many` with a secret among its keys, or `insert` of a
+/// `SecureString`. Such lines stay out of history.
+fn types_secret(rl: &Editor<ParamStoreHelper>, selected: &str, line: &str) -> bool {
+    let Some(helper) = rl.helper() else {
+        return false;
+    };
+    match Command::parse(line) {
+        Command::Set(args) => {
+            let (args, _) = take_flag(&args, "--yes");
+            let (args, _) = take_flag(args, "--no-validate");
+            set::target(helper, args, selected).is_ok_and(|(path, value)| {
+                !value.is_empty()
+                    && !value.starts_with("--generate")
+                    && helper.completer.is_sensitive(&path)
+            })
+        }
+        Command::SetMany(args) => set_many::has_inline_secret(&helper.completer, &args),
+        Command::Insert(raw) => {
+            let (raw, _) = take_flag(&raw, "--yes");
+            let (raw, _) = take_flag(raw, "--no-validate");
+            let policy = &helper.completer.type_policy;
+            !raw.contains(" --generate")
+                && InsertArgs::parse(raw).is_ok_and(|args| {
+                    policy
+                        .resolve(
+                            &args.path,
+                            args.param_type.as_deref(),
+                            policy.default_type(),
+                        )
+                        .is_ok_and(|param_type| param_type == "SecureString")
+                })
+        }
+        _ => false,
+    }
+}
 
-                    Command::Insert(raw) => {
-                        if let Some(helper) = rl.helper_mut() {
-                            handle_command_result(
-                                insert_value(helper, &raw).await,
-                                &mut cpboard,
-                            )
-                            .await;
-                        }
-                    }
+/// Prints a failed command: a JSON `{"error": ...}` line in JSON mode, the
+/// message as-is otherwise.
+fn report_error(format: OutputFormat, err: &dyn std::error::Error) {
+    if format.is_json() {
+        output::print_json(json!({ "error": err.to_string() }));
+    } else {
+        println!("{}", err);
+    }
+}
 
-                    Command::Search(term) => {
-                        if term.is_empty() {
-                            println!("Please provide a search term. Usage: search <term>");
-                        } else if let Some(helper) = rl.helper_mut() {
-                            search(helper, &term);
-                        }
-                    }
+/// Runs the interactive REPL loop.
+///
+/// Accepts the already-configured `Editor` (with helper attached) and the
+/// session's clipboard.  Returns when the user types `exit`, presses CTRL-C /
+/// CTRL-D, the process gets SIGINT / SIGTERM (after the running command
+/// finishes), or an unrecoverable readline error occurs.
+pub async fn run(
+    rl: &mut Editor<ParamStoreHelper>,
+    cpboard: Cpboard<'_>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let format = output_format(rl);
+    let quiet = rl.helper().is_some_and(|helper| helper.completer.quiet);
 
-                    Command::ParseDb => {
-                        if selected.is_empty() {
-                            println!("No parameter selected. Use 'sel <index>' or navigate to a key first.");
-                        } else if let Some(helper) = rl.helper() {
-                            let value = helper.completer.values.get(&selected).cloned();
-                            match value {
-                                Some(conn_str) => parse_db(&selected, &conn_str, &mut cpboard),
-                                None => println!("No cached value for '{}'. Try 'reload' first.", selected),
-                            }
-                        }
-                    }
+    if !format.is_json() && !quiet {
+        println!("AWS Parameter Store CLI");
+        println!(
+            "Type a parameter path and use {} for completion",
+            "Tab".red()
+        );
+        println!(
+            "Type '{}' for commands, '{}' for common workflows, '{}' to quit",
+            "help".yellow(),
+            "cheatsheet".yellow(),
+            "exit".yellow()
+        );
+    }
 
-                    Command::Navigate(path) => {
-                        rl.add_history_entry(&path);
-                        selected = path.clone();
-
-                        if let Some(helper) = rl.helper_mut() {
-                            helper
-                                .completer
-                                .metadata
-                                .insert("selected".to_string(), selected.clone());
-
-                            let matching_paths: Vec<String> = helper
-                                .completer
-                                .values
-                                .keys()
-                                .filter(|k| k.starts_with(&path))
-                                .cloned()
-                                .collect();
-
-                            let mut clipboard_content = String::new();
-                            for p in matching_paths {
-                                if let Some(value) = helper.completer.values.get(&p) {
-                                    println!(
-                                        "Found value for {}: {}",
-                                        p.green(),
-                                        value.red()
-                                    );
-                                    clipboard_content
-                                        .push_str(&format!("{}: {}\n", p, value));
-                                }
-                            }
-                            if let Err(err) =
-                                cpboard.set_clipboard_content(&clipboard_content)
-                            {
-                                println!("Error copying to clipboard: {}", err);
-                            } else {
-                                println!("Copied to clipboard:\n{}", clipboard_content);
-                            }
-                        }
+    if let Some(helper) = rl.helper()
+        && !format.is_json()
+    {
+        let completer = &helper.completer;
+        if completer.offline && !quiet {
+            println!(
+                "{}",
+                "Offline mode: values come from the local cache and changes are queued".yellow()
+            );
+        }
+        warn_on_drift(completer);
+        if !completer.pending.is_empty() && !quiet {
+            println!(
+                "{} pending change(s) queued; type '{}' to review or '{}' to apply",
+                completer.pending.len(),
+                "pending".yellow(),
+                "push".yellow()
+            );
+        }
+    }
+
+    if let Some(helper) = rl.helper_mut()
+        && let Err(err) = helper.completer.start_background_refresh()
+    {
+        report_error(format, err.as_ref());
+    }
+
+    let mut session = Session::new(cpboard);
+    let mut shutdown = Shutdown::install();
+
+    loop {
+        let mut prompt = match rl.helper() {
+            Some(helper) => prompt(&helper.completer, &session.selected),
+            None => ">> ".to_string(),
+        };
+        if let Some(recording) = &session.recording {
+            prompt.insert_str(0, &format!("(rec {}) ", recording.name));
+        }
+        match rl.readline(&prompt) {
+            // A signal that arrived at the prompt ends the session here, so
+            // the line typed meanwhile is not run.
+            Ok(_) if shutdown.requested().is_some() => break,
+            Ok(line) => {
+                if let Some(helper) = rl.helper_mut() {
+                    helper.completer.apply_background_refresh();
+                }
+                let line = match history::expand(&line, rl.history()) {
+                    Ok(Some(expanded)) => {
+                        println!("{}", expanded);
+                        expanded
+                    }
+                    Ok(None) => line,
+                    Err(err) => {
+                        println!("{}", err);
+                        continue;
                     }
+                };
+                // History, recording and hooks all see the command that runs.
+                let line = expand_alias(rl, &line);
+                let secret = types_secret(rl, &session.selected, &line);
+                if !line.trim().is_empty() && !secret {
+                    rl.add_history_entry(line.as_str());
+                }
+
+                let mut outcome = shutdown.guard(execute_timed(rl, &mut session, &line)).await;
+                if let Some(Err(err)) = &outcome
+                    && reauth::is_expired(&err.to_string())
+                    && renew_credentials(rl, format)
+                {
+                    outcome = shutdown.guard(execute_timed(rl, &mut session, &line)).await;
+                }
+                match outcome {
+                    Some(Ok(Flow::Continue)) => record(&mut session, &line, secret),
+                    Some(Ok(Flow::Exit)) | None => break,
+                    Some(Err(err)) => report_error(format, err.as_ref()),
+                }
+                if shutdown.requested().is_some() {
+                    break;
                 }
             }
 
@@ -222,5 +335,812 @@ pub async fn run(
         }
     }
 
+    finish(rl, &mut session, format);
+    Ok(())
+}
+
+/// Adds a command that succeeded to the macro being recorded. `macro`
+/// commands themselves and lines with a secret typed inline are left out.
+fn record(session: &mut Session<'_>, line: &str, secret: bool) {
+    let Some(recording) = &mut session.recording else {
+        return;
+    };
+    let line = line.trim();
+    if line.is_empty() || matches!(Command::parse(line), Command::Macro(_)) {
+        return;
+    }
+    if secret {
+        println!("Not recorded: the line has a secret value in it");
+        return;
+    }
+    recording.lines.push(line.to_string());
+}
+
+/// Offers to renew expired credentials; true when the session reconnected and
+/// the failed command should be run again.
+fn renew_credentials(rl: &mut Editor<ParamStoreHelper>, format: OutputFormat) -> bool {
+    let Some(helper) = rl.helper_mut() else {
+        return false;
+    };
+    match reauth::reauthenticate(&mut helper.completer) {
+        Ok(reconnected) => reconnected,
+        Err(err) => {
+            report_error(format, err.as_ref());
+            false
+        }
+    }
+}
+
+/// Wraps up an interactive session: reminds about queued offline changes and,
+/// when configured, clears a value daps left on the clipboard.
+fn finish(rl: &mut Editor<ParamStoreHelper>, session: &mut Session<'_>, format: OutputFormat) {
+    let Some(helper) = rl.helper() else {
+        return;
+    };
+    let completer = &helper.completer;
+    if !completer.pending.is_empty() && !format.is_json() {
+        println!(
+            "{} pending change(s) stay queued in the store; type '{}' next time to apply them",
+            completer.pending.len(),
+            "push".yellow()
+        );
+    }
+    if completer.clear_clipboard_on_exit {
+        match session.cpboard.scrub() {
+            Ok(true) if !completer.quiet => println!("Clipboard cleared"),
+            Ok(_) => {}
+            Err(err) => eprintln!("Could not clear the clipboard: {}", err),
+        }
+    }
+}
+
+/// Runs commands from `input` one line at a time, without prompting. Blank lines
+/// and lines starting with `#` are skipped. Stops at the first failing command
+/// unless `keep_going` is set; any failure makes the result an error so the
+/// process exits non-zero.
+pub async fn run_batch(
+    rl: &mut Editor<ParamStoreHelper>,
+    cpboard: Cpboard<'_>,
+    input: impl BufRead,
+    source: &str,
+    keep_going: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let format = output_format(rl);
+    let mut session = Session::new(cpboard);
+    let mut shutdown = Shutdown::install();
+    let mut failures = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if let Some(signal) = shutdown.requested() {
+            return Err(format!("{}:{}: stopped by {}", source, index + 1, signal).into());
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let line = expand_alias(rl, trimmed);
+        rl.add_history_entry(line.as_str());
+
+        let Some(result) = shutdown.guard(execute_timed(rl, &mut session, &line)).await else {
+            return Err(format!("{}:{}: command abandoned", source, index + 1).into());
+        };
+        match result {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Exit) => break,
+            Err(err) => {
+                let err = match rl.helper() {
+                    Some(helper) if reauth::is_expired(&err.to_string()) => {
+                        let profile = helper.completer.client_options.profile.as_deref();
+                        reauth::expired_message(profile).into()
+                    }
+                    _ => err,
+                };
+                report_error(format, err.as_ref());
+                failures += 1;
+                if !keep_going {
+                    return Err(
+                        format!("{}:{}: stopped after failed command", source, index + 1).into(),
+                    );
+                }
+            }
+        }
+        if let Some(signal) = shutdown.requested() {
+            return Err(format!("{}:{}: stopped by {}", source, index + 1, signal).into());
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{}: {} command(s) failed", source, failures).into());
+    }
     Ok(())
 }
+
+/// Runs the commands of a macro in order, stopping at the first one that fails.
+/// Macros cannot start other macros.
+async fn play(
+    rl: &mut Editor<ParamStoreHelper>,
+    session: &mut Session<'_>,
+    lines: &[String],
+) -> Result<Flow, Box<dyn std::error::Error>> {
+    for (index, line) in lines.iter().enumerate() {
+        if matches!(Command::parse(line), Command::Macro(_)) {
+            return Err(format!("Step {}: macros cannot run 'macro'", index + 1).into());
+        }
+        println!("{}", format!(">> {}", line).dimmed());
+        match Box::pin(execute(rl, session, line)).await {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Exit) => return Ok(Flow::Exit),
+            Err(err) => {
+                return Err(
+                    format!("Macro stopped at step {} ({}): {}", index + 1, line, err).into(),
+                );
+            }
+        }
+    }
+    Ok(Flow::Continue)
+}
+
+/// `execute` for a line whose command alias is already expanded, followed by
+/// a line on the AWS calls the command made when `--timings` is on.
+async fn execute_timed(
+    rl: &mut Editor<ParamStoreHelper>,
+    session: &mut Session<'_>,
+    line: &str,
+) -> Result<Flow, Box<dyn std::error::Error>> {
+    let before = rl
+        .helper()
+        .filter(|helper| helper.completer.timings)
+        .map(|helper| helper.completer.stats.snapshot());
+    let result = execute_expanded(rl, session, line).await;
+    if let Some(before) = before
+        && let Some(helper) = rl.helper()
+        && let Some(summary) = command_timings(&before, &helper.completer.stats.snapshot())
+    {
+        eprintln!("{}", summary.dimmed());
+    }
+    result
+}
+
+/// Expands a command alias, then parses and runs one command line, with its
+/// configured hooks. Errors are returned rather than printed so the
+/// interactive and batch loops can decide what a failure means.
+pub async fn execute(
+    rl: &mut Editor<ParamStoreHelper>,
+    session: &mut Session<'_>,
+    line: &str,
+) -> Result<Flow, Box<dyn std::error::Error>> {
+    let line = expand_alias(rl, line);
+    execute_expanded(rl, session, &line).await
+}
+
+/// `line` with a configured command alias replaced by what it stands for.
+fn expand_alias(rl: &Editor<ParamStoreHelper>, line: &str) -> String {
+    match rl.helper() {
+        Some(helper) => helper.completer.macros.expand(line),
+        None => line.to_string(),
+    }
+}
+
+/// Runs a line whose command alias is already expanded, with its hooks.
+async fn execute_expanded(
+    rl: &mut Editor<ParamStoreHelper>,
+    session: &mut Session<'_>,
+    line: &str,
+) -> Result<Flow, Box<dyn std::error::Error>> {
+    let Some((keyword, mut payload)) = hook_payload(rl, session, line) else {
+        return run_command(rl, session, line).await;
+    };
+    let hooks = &rl.helper().ok_or(NO_HELPER)?.completer.hooks;
+    hooks.run("pre", &keyword, &payload)?;
+
+    let flow = run_command(rl, session, line).await?;
+    payload["selected"] = json!(session.selected);
+    let hooks = &rl.helper().ok_or(NO_HELPER)?.completer.hooks;
+    if let Err(err) = hooks.run("post", &keyword, &payload) {
+        eprintln!("{}", err.yellow());
+    }
+    Ok(flow)
+}
+
+/// The command keyword and what hooks are told about the command, when hooks
+/// are configured and `line` is a command. The arguments are left out when
+/// the line has a secret value typed inline.
+fn hook_payload(
+    rl: &Editor<ParamStoreHelper>,
+    session: &Session<'_>,
+    line: &str,
+) -> Option<(String, serde_json::Value)> {
+    let completer = &rl.helper()?.completer;
+    if completer.hooks.is_empty() || matches!(Command::parse(line), Command::Navigate(_)) {
+        return None;
+    }
+    let line = line.trim();
+    let (keyword, args) = line.split_once(' ').unwrap_or((line, ""));
+    let args = if types_secret(rl, &session.selected, line) {
+        None
+    } else {
+        Some(args.trim())
+    };
+    let payload = json!({
+        "command": keyword,
+        "args": args,
+        "selected": session.selected,
+        "base_path": completer.base_path,
+        "region": completer.region.name(),
+        "profile": completer.client_options.profile,
+        "workspace": completer.workspace,
+        "offline": completer.offline,
+    });
+    Some((keyword.to_string(), payload))
+}
+
+/// Parses and runs one command line.
+async fn run_command(
+    rl: &mut Editor<ParamStoreHelper>,
+    session: &mut Session<'_>,
+    line: &str,
+) -> Result<Flow, Box<dyn std::error::Error>> {
+    let selected = &mut session.selected;
+    let cpboard = &mut session.cpboard;
+
+    match Command::parse(line) {
+        Command::Exit => return Ok(Flow::Exit),
+
+        Command::Refresh => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            refresh(helper)
+                .await
+                .map_err(|err| format!("Error refreshing parameters: {}", err))?;
+        }
+
+        Command::Gc(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            gc(helper, &args).await?;
+        }
+
+        Command::Cache(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            cache(helper, &args).await?;
+        }
+
+        Command::Migration(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            migration(helper, &args)
+                .await
+                .map_err(|err| format!("Error during migration: {}", err))?;
+        }
+
+        Command::RotateKey => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            rotate_key(helper).map_err(|err| format!("Error rotating key: {}", err))?;
+        }
+
+        Command::Reload => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let result = reload(helper, selected).await;
+            handle_command_result(result, cpboard, &helper.completer).await?;
+            remember(helper, selected);
+        }
+
+        Command::ShowSelected => {
+            if !selected.is_empty() {
+                println!("Currently selected parameter: {}", selected.green());
+            } else {
+                println!("No parameter selected. Use 'sel <path>' or 'sel <index>' to select one.");
+            }
+        }
+
+        Command::ReloadSelected => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let paths = if selected.is_empty() {
+                println!("No parameter selected. Reloading all parameters.");
+                String::new()
+            } else {
+                selected.clone()
+            };
+            reload_by_paths(helper, &paths).await?;
+        }
+
+        Command::ReloadByPaths(paths) => {
+            let paths = resolve_path(rl, &paths)?;
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let paths = if paths.is_empty() {
+                println!("No paths provided, using selected.");
+                selected.clone()
+            } else {
+                paths
+            };
+            reload_by_paths(helper, &paths).await?;
+        }
+
+        Command::ReloadByPath(path) => {
+            let path = resolve_path(rl, &path)?;
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = if path.is_empty() {
+                println!("No path provided, using selected.");
+                selected.clone()
+            } else {
+                path
+            };
+            let result = reload_by_path(helper, &path).await;
+            handle_command_result(result, cpboard, &helper.completer).await?;
+            remember(helper, &path);
+        }
+
+        Command::ReloadAll(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            reload_all(helper, &args).await?;
+        }
+
+        Command::Set(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let (path, value) = set_value(helper, &args, selected)
+                .await
+                .map_err(|err| format!("Error executing command: {}", err))?;
+            handle_command_result(Ok(value), cpboard, &helper.completer).await?;
+            remember(helper, &path);
+        }
+
+        Command::SetMany(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            set_many(helper, &args).await?;
+        }
+
+        Command::Paste(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = paste(helper, cpboard, &args, selected).await?;
+            remember(helper, &path);
+        }
+
+        Command::Cmp(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = compare(helper, cpboard, &args, selected).await?;
+            remember(helper, &path);
+        }
+
+        Command::Select(arg) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            *selected = select(helper, &arg)?;
+            remember(helper, selected);
+        }
+
+        Command::Insert(raw) if raw.is_empty() => {
+            if let Some(path) = insert_wizard(rl).await? {
+                let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+                remember(helper, &path);
+            }
+        }
+
+        Command::Insert(raw) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let result = insert_value(helper, &raw).await;
+            handle_command_result(result, cpboard, &helper.completer).await?;
+            if let Some(path) = inserted_path(&raw) {
+                remember(helper, &path);
+            }
+        }
+
+        Command::Search(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            search(helper, &args).await?;
+        }
+
+        Command::Import(raw) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            import(helper, &raw)
+                .await
+                .map_err(|err| format!("Error importing parameters: {}", err))?;
+        }
+
+        Command::Region(name) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            switch_region(helper, &name)
+                .await
+                .map_err(|err| format!("Error switching region: {}", err))?;
+        }
+
+        Command::Profile(name) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            switch_profile(helper, &name)
+                .await
+                .map_err(|err| format!("Error switching profile: {}", err))?;
+        }
+
+        Command::Workspace(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            workspace(helper, &args).await?;
+        }
+
+        Command::Pending => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            list_pending(helper);
+        }
+
+        Command::Status => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            status(helper, selected);
+        }
+
+        Command::Push => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            push_pending(helper)
+                .await
+                .map_err(|err| format!("Error pushing pending changes: {}", err))?;
+        }
+
+        Command::Discard(path) => {
+            let path = resolve_path(rl, &path)?;
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            discard_pending(helper, &path)
+                .map_err(|err| format!("Error discarding pending changes: {}", err))?;
+        }
+
+        Command::Pin(path) => {
+            let path = resolve_path(rl, &path)?;
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            pin(helper, &path, selected)
+                .map_err(|err| format!("Error pinning parameter: {}", err))?;
+        }
+
+        Command::Unpin(path) => {
+            let path = resolve_path(rl, &path)?;
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            unpin(helper, &path).map_err(|err| format!("Error unpinning parameter: {}", err))?;
+        }
+
+        Command::Session(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            manage_session(helper, &args, selected).await?;
+        }
+
+        Command::Recent => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            list_recent(helper);
+        }
+
+        Command::Scope(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            scope(helper, &args)?;
+            if !helper.completer.in_scope(selected) {
+                selected.clear();
+                helper.completer.metadata.remove("selected");
+            }
+        }
+
+        Command::Unscope => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            unscope(helper);
+        }
+
+        Command::Pins => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            list_pins(helper);
+        }
+
+        Command::Help(topic) => help(&topic),
+
+        Command::Cheatsheet => cheatsheet(),
+
+        Command::History(count) => {
+            let redact = rl.helper().is_some_and(|helper| helper.completer.redact);
+            list_history(rl.history(), &count, redact)
+                .map_err(|err| format!("Error listing history: {}", err))?;
+        }
+
+        Command::ParseDb => {
+            if selected.is_empty() {
+                return Err(
+                    "No parameter selected. Use 'sel <path>' or 'sel <index>' first.".into(),
+                );
+            }
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            let conn_str = helper.completer.cached_value(selected).ok_or_else(|| {
+                format!("No cached value for '{}'. Try 'reload' first.", selected)
+            })?;
+            parse_db(selected, &conn_str, cpboard, &helper.completer);
+        }
+
+        Command::Alias(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            alias(helper, &args)?;
+        }
+
+        Command::Macro(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            if let Some(lines) = manage_macro(helper, &args, &mut session.recording)? {
+                return play(rl, session, &lines).await;
+            }
+        }
+
+        Command::Lint(prefix) => {
+            let prefix = scoped_path(rl, &prefix)?;
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            lint(helper, &prefix).await?;
+        }
+
+        Command::Age(prefix) => {
+            let prefix = scoped_path(rl, &prefix)?;
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            age(helper, &prefix).await?;
+        }
+
+        Command::Size(prefix) => {
+            let prefix = scoped_path(rl, &prefix)?;
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            size(helper, &prefix);
+        }
+
+        Command::Count(prefix) => {
+            let prefix = scoped_path(rl, &prefix)?;
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            count(helper, &prefix).await?;
+        }
+
+        Command::List(args) => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            list(helper, &args).await?;
+        }
+
+        Command::AppConfig(args) => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            if let Some(content) = appconfig(helper, &args).await? {
+                handle_command_result(Ok(content), cpboard, &helper.completer).await?;
+            }
+        }
+
+        Command::Notify(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            notify(helper, &args).await?;
+        }
+
+        Command::Stat(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = stat(helper, &args, selected).await?;
+            remember(helper, &path);
+        }
+
+        Command::Show(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = show(helper, &args, selected).await?;
+            remember(helper, &path);
+        }
+
+        Command::SetBinary(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = set_binary(helper, &args).await?;
+            remember(helper, &path);
+        }
+
+        Command::GetBinary(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = get_binary(helper, &args).await?;
+            remember(helper, &path);
+        }
+
+        Command::Sync(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            sync(helper, &args).await?;
+        }
+
+        Command::Snapshot(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            snapshot(helper, &args).await?;
+        }
+
+        Command::Export(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            export(helper, &args).await?;
+        }
+
+        Command::Render(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            render(helper, &args).await?;
+        }
+
+        Command::Deps(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            deps(helper, &args, selected).await?;
+        }
+
+        Command::RenderResolved(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let (path, value) = render_resolved(helper, &args, selected).await?;
+            remember(helper, &path);
+            handle_command_result(Ok(value), cpboard, &helper.completer).await?;
+        }
+
+        Command::Stats(args) => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            stats(helper, &args)?;
+        }
+
+        Command::Redact(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            redact(helper, &args)?;
+        }
+
+        Command::Delete(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let path = delete(helper, &args, selected).await?;
+            if *selected == path {
+                selected.clear();
+                helper.completer.metadata.remove("selected");
+            }
+        }
+
+        Command::Copy(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            copy(helper, &args, false).await?;
+        }
+
+        Command::Move(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let (source, destination) = copy(helper, &args, true).await?;
+            if *selected == source {
+                helper
+                    .completer
+                    .metadata
+                    .insert("selected".to_string(), destination.clone());
+                *selected = destination;
+            }
+        }
+
+        Command::MoveTree(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            move_tree(helper, &args).await?;
+        }
+
+        Command::Replace(args) => {
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            replace(helper, &args).await?;
+        }
+
+        // AWS public parameters are read straight from AWS, whatever the base path.
+        Command::Navigate(path) | Command::Get(path) if is_public(&path) => {
+            let (path, filters) = display_filter::split(&path)?;
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            get_public(helper, &path, &filters, cpboard).await?;
+        }
+
+        Command::LatestAmi(args) => {
+            let helper = rl.helper().ok_or(NO_HELPER)?;
+            let result = latest_ami(helper, &args).await;
+            handle_command_result(result, cpboard, &helper.completer).await?;
+        }
+
+        Command::Navigate(path) | Command::Get(path) => {
+            // `/path | b64d | json` shows the values through display filters.
+            let (path, filters) = display_filter::split(&path)?;
+            let path = scoped_path(rl, &path)?;
+
+            // Viewing a path never changes the selection; only `sel` does.
+            let helper = rl.helper_mut().ok_or(NO_HELPER)?;
+            let format = helper.completer.output;
+
+            // Values loaded without decryption are decrypted when asked for.
+            let param = path
+                .split_once('#')
+                .map_or(path.as_str(), |(param, _)| param);
+            if helper.completer.values.is_unavailable(param)
+                && !helper.completer.offline
+                && let Err(err) = helper.completer.get_set_value(param).await
+            {
+                println!(
+                    "{} {}: {}",
+                    "Value unavailable for".yellow(),
+                    param.green(),
+                    err
+                );
+            }
+
+            // `/path#key` shows one field of a JSON value.
+            let entries: Vec<(String, String)> = match path.split_once('#') {
+                Some((param, key)) => {
+                    let value = helper.completer.cached_value(param).ok_or_else(|| {
+                        format!(
+                            "No cached value for {}. Try 'reload-by-path {}'.",
+                            param, param
+                        )
+                    })?;
+                    vec![(path.clone(), json_field(&value, key)?)]
+                }
+                None => {
+                    let under: Vec<String> = helper
+                        .completer
+                        .values
+                        .keys()
+                        .filter(|k| k.starts_with(&path) && helper.completer.in_scope(k))
+                        .cloned()
+                        .collect();
+                    helper.completer.cached_values(&under).into_iter().collect()
+                }
+            };
+
+            let mut clipboard_content = String::new();
+            // What is echoed after copying: long values are cut unless
+            // filters asked for a particular view; `show` prints them whole.
+            let mut copied_preview = String::new();
+            for (p, stored) in &entries {
+                let filtered = match display_filter::apply(stored, &filters) {
+                    Ok(filtered) => filtered,
+                    Err(err) => {
+                        println!("{} {}: {}", "Cannot filter".yellow(), p.green(), err);
+                        continue;
+                    }
+                };
+                let value = filtered.as_str();
+                let displayed = if filters.is_empty() {
+                    helper.completer.clipped(value)
+                } else {
+                    helper.completer.shown(value).to_string()
+                };
+                let pending = helper.completer.pending.get(p).is_some();
+                if format.is_json() {
+                    output::print_json(json!({
+                        "path": p,
+                        "value": helper.completer.shown(value),
+                        "pending": pending,
+                        "cached": helper.completer.offline,
+                    }));
+                } else {
+                    let marker = if pending {
+                        " (pending)".yellow().to_string()
+                    } else if helper.completer.offline {
+                        " (cached)".dimmed().to_string()
+                    } else {
+                        String::new()
+                    };
+                    // Multi-line values, such as pretty-printed JSON, start on their own line.
+                    let separator = if displayed.contains('\n') { "\n" } else { " " };
+                    println!(
+                        "Found value for {}:{}{}{}",
+                        p.green(),
+                        separator,
+                        displayed.red(),
+                        marker
+                    );
+                }
+                clipboard_content.push_str(&format!("{}: {}\n", p, value));
+                copied_preview.push_str(&format!("{}: {}\n", p, displayed));
+            }
+            let mut unavailable: Vec<&String> = helper
+                .completer
+                .values
+                .keys()
+                .filter(|p| {
+                    p.starts_with(&path)
+                        && helper.completer.in_scope(p)
+                        && helper.completer.values.is_unavailable(p)
+                })
+                .collect();
+            unavailable.sort();
+            for p in unavailable {
+                if format.is_json() {
+                    output::print_json(json!({ "path": p, "value": null, "unavailable": true }));
+                } else {
+                    println!(
+                        "{}: {}",
+                        p.green(),
+                        "value unavailable (not decrypted)".dimmed()
+                    );
+                }
+            }
+            remember(helper, &path);
+            // JSON output already carries the values; the clipboard is best effort.
+            if helper.completer.redact {
+                return Ok(Flow::Continue);
+            }
+            match cpboard.set_clipboard_content(&clipboard_content) {
+                _ if format.is_json() => {}
+                Ok(true) if !helper.completer.quiet => {
+                    println!("Copied to clipboard:\n{}", copied_preview)
+                }
+                Ok(_) => {}
+                Err(err) => println!("Error copying to clipboard: {}", err),
+            }
+        }
+    }
+
+    Ok(Flow::Continue)
+}
@@ -0,0 +1,171 @@
+use crate::stats::Stats;
+use rand::{Rng, thread_rng};
+use rusoto_core::RusotoError;
+use rusoto_core::request::HttpDispatchError;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How failed SSM calls are retried.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Upper bound on API calls per second; `0` disables rate limiting.
+    pub max_tps: f64,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, max_tps: f64) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            max_tps,
+        }
+    }
+
+    /// Exponential backoff with full jitter: a random delay in
+    /// `[0, min(max_delay, base_delay * 2^(attempt - 1))]`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+        let cap = exp.min(self.max_delay);
+        let millis = cap.as_millis() as u64;
+        Duration::from_millis(thread_rng().gen_range(0..=millis))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(5, 10.0)
+    }
+}
+
+/// Spaces out API calls so the session stays under a transactions-per-second budget.
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// `max_tps <= 0` disables limiting.
+    pub fn new(max_tps: f64) -> Self {
+        let interval = if max_tps > 0.0 {
+            Some(Duration::from_secs_f64(1.0 / max_tps))
+        } else {
+            None
+        };
+        Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the next call slot is available.
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        // Reserve a slot under the lock, then sleep without holding it.
+        let wait = {
+            let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let slot = (*next).max(now);
+            *next = slot + interval;
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Returns true for errors worth retrying: throttling, server-side failures and
+/// transport errors.
+pub fn is_retryable<E: std::fmt::Debug>(err: &RusotoError<E>) -> bool {
+    match err {
+        RusotoError::HttpDispatch(_) => true,
+        RusotoError::Unknown(resp) => {
+            resp.status.as_u16() == 429
+                || resp.status.is_server_error()
+                || is_throttling_message(resp.body_as_str())
+        }
+        RusotoError::Service(e) => is_throttling_message(&format!("{:?}", e)),
+        _ => false,
+    }
+}
+
+/// Whether a retryable error was AWS throttling rather than a transient failure.
+fn is_throttled<E: std::fmt::Debug>(err: &RusotoError<E>) -> bool {
+    match err {
+        RusotoError::Unknown(resp) => {
+            resp.status.as_u16() == 429 || is_throttling_message(resp.body_as_str())
+        }
+        RusotoError::Service(e) => is_throttling_message(&format!("{:?}", e)),
+        _ => false,
+    }
+}
+
+fn is_throttling_message(message: &str) -> bool {
+    [
+        "ThrottlingException",
+        "Rate exceeded",
+        "TooManyUpdates",
+        "Throttling",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+/// Runs `op` until it succeeds, fails with a non-retryable error, or the policy's
+/// attempts are exhausted. Each attempt is bounded by `timeout` when set.
+/// `on_retry` is called with the attempt number and the delay before the next try.
+/// Every attempt and retry is recorded in `stats` under `operation`.
+pub async fn with_retry<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    limiter: &RateLimiter,
+    stats: &Stats,
+    operation: &str,
+    timeout: Option<Duration>,
+    mut op: F,
+    on_retry: impl Fn(u32, Duration, &RusotoError<E>),
+) -> Result<T, RusotoError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, RusotoError<E>>>,
+    E: std::fmt::Debug,
+{
+    let mut attempt = 1;
+    loop {
+        limiter.acquire().await;
+        let started = Instant::now();
+        let result = match timeout {
+            Some(limit) => match tokio::time::timeout(limit, op()).await {
+                Ok(result) => result,
+                Err(_) => Err(RusotoError::HttpDispatch(HttpDispatchError::new(format!(
+                    "Request timed out after {:?}",
+                    limit
+                )))),
+            },
+            None => op().await,
+        };
+        stats.record_call(operation, started.elapsed(), result.is_ok());
+        match result {
+            Err(err) if attempt < policy.max_attempts && is_retryable(&err) => {
+                stats.record_retry(operation, is_throttled(&err));
+                let delay = policy.backoff(attempt);
+                on_retry(attempt, delay, &err);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
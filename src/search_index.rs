@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+/// An index over parameter paths, so queries only look at paths that can
+/// match instead of scanning the whole tree. Every path has a bitmask of the
+/// characters it contains, which rules out fuzzy matches cheaply, and the
+/// lowercased paths are indexed by trigram for substring queries.
+#[derive(Default)]
+pub struct SearchIndex {
+    /// Paths by id; removed ones stay as tombstones until the next rebuild.
+    paths: Vec<String>,
+    masks: Vec<u64>,
+    live: Vec<bool>,
+    ids: HashMap<String, u32>,
+    trigrams: HashMap<[u8; 3], Vec<u32>>,
+}
+
+impl SearchIndex {
+    pub fn build<'a>(paths: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut index = Self::default();
+        for path in paths {
+            index.insert(path);
+        }
+        log::debug!("Search index: {} paths", index.ids.len());
+        index
+    }
+
+    pub fn insert(&mut self, path: &str) {
+        if self.ids.contains_key(path) {
+            return;
+        }
+        let id = self.paths.len() as u32;
+        let lower = path.to_lowercase();
+        for trigram in trigrams(&lower) {
+            let postings = self.trigrams.entry(trigram).or_default();
+            if postings.last() != Some(&id) {
+                postings.push(id);
+            }
+        }
+        self.paths.push(path.to_string());
+        self.masks.push(char_mask(&lower));
+        self.live.push(true);
+        self.ids.insert(path.to_string(), id);
+    }
+
+    pub fn remove(&mut self, path: &str) {
+        if let Some(id) = self.ids.remove(path) {
+            self.live[id as usize] = false;
+        }
+    }
+
+    /// Paths holding every character of `query`, ignoring case: the only ones
+    /// a fuzzy (subsequence) match can succeed on.
+    pub fn fuzzy_candidates(&self, query: &str) -> Vec<&String> {
+        let wanted = char_mask(&query.to_lowercase());
+        self.masks
+            .iter()
+            .enumerate()
+            .filter(|&(id, mask)| mask & wanted == wanted && self.live[id])
+            .map(|(id, _)| &self.paths[id])
+            .collect()
+    }
+
+    /// Paths containing `term`, ignoring case.
+    pub fn containing(&self, term: &str) -> Vec<&String> {
+        let term = term.to_lowercase();
+        let mut lists: Vec<&Vec<u32>> = Vec::new();
+        for trigram in trigrams(&term) {
+            match self.trigrams.get(&trigram) {
+                Some(postings) => lists.push(postings),
+                None => return Vec::new(),
+            }
+        }
+        let Some(shortest) = lists.iter().min_by_key(|postings| postings.len()) else {
+            // Shorter than a trigram: fall back to the character masks.
+            return self
+                .fuzzy_candidates(&term)
+                .into_iter()
+                .filter(|path| path.to_lowercase().contains(&term))
+                .collect();
+        };
+        shortest
+            .iter()
+            .filter(|id| {
+                lists
+                    .iter()
+                    .all(|postings| postings.binary_search(id).is_ok())
+            })
+            .filter(|&&id| self.live[id as usize])
+            .map(|&id| &self.paths[id as usize])
+            .filter(|path| path.to_lowercase().contains(&term))
+            .collect()
+    }
+}
+
+fn trigrams(text: &str) -> impl Iterator<Item = [u8; 3]> + '_ {
+    text.as_bytes().windows(3).map(|w| [w[0], w[1], w[2]])
+}
+
+/// One bit per letter and digit, a few for path punctuation, and the rest of
+/// the characters folded onto the remaining bits.
+fn char_mask(lower: &str) -> u64 {
+    lower.chars().fold(0, |mask, c| {
+        let bit = match c {
+            'a'..='z' => c as u32 - 'a' as u32,
+            '0'..='9' => 26 + c as u32 - '0' as u32,
+            '/' => 36,
+            '-' => 37,
+            '_' => 38,
+            '.' => 39,
+            _ => 40 + c as u32 % 24,
+        };
+        mask | 1 << bit
+    })
+}
@@ -0,0 +1,362 @@
+use crate::backend::ParameterBackend;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rusoto_core::RusotoError;
+use rusoto_secretsmanager::{
+    CreateSecretError, CreateSecretRequest, DeleteSecretError, DeleteSecretRequest,
+    DescribeSecretError, DescribeSecretRequest, Filter, GetSecretValueError, GetSecretValueRequest,
+    ListSecretsRequest, ListSecretsResponse, PutSecretValueError, PutSecretValueRequest,
+    SecretListEntry, SecretsManager, SecretsManagerClient, Tag,
+};
+use rusoto_ssm::{
+    DeleteParameterError, DeleteParameterRequest, DeleteParameterResult, DescribeParametersError,
+    DescribeParametersRequest, DescribeParametersResult, GetParameterError, GetParameterRequest,
+    GetParameterResult, GetParametersByPathError, GetParametersByPathRequest,
+    GetParametersByPathResult, GetParametersError, GetParametersRequest, GetParametersResult,
+    ListTagsForResourceError, ListTagsForResourceRequest, ListTagsForResourceResult, Parameter,
+    ParameterMetadata, PutParameterError, PutParameterRequest, PutParameterResult,
+};
+
+/// Secrets are always encrypted, so they are reported as `SecureString`.
+const SECRET_TYPE: &str = "SecureString";
+/// Largest page ListSecrets accepts.
+const MAX_PAGE: i64 = 100;
+
+/// Secrets Manager behind the `ParameterBackend` interface. Secret names map
+/// to paths by adding a leading `/`: the secret `prod/db` is `/prod/db`.
+/// Binary secrets are returned base64-encoded.
+pub struct SecretsManagerBackend {
+    client: SecretsManagerClient,
+}
+
+impl SecretsManagerBackend {
+    pub fn new(client: SecretsManagerClient) -> Self {
+        Self { client }
+    }
+
+    /// Lists one page of secrets whose name starts with `prefix`.
+    async fn list_secrets(
+        &self,
+        prefix: &str,
+        max_results: Option<i64>,
+        next_token: Option<String>,
+    ) -> Result<ListSecretsResponse, RusotoError<String>> {
+        let name = secret_name(prefix);
+        let filters = (!name.is_empty()).then(|| {
+            vec![Filter {
+                key: Some("name".to_string()),
+                values: Some(vec![name.to_string()]),
+            }]
+        });
+        let request = ListSecretsRequest {
+            filters,
+            max_results: Some(max_results.unwrap_or(MAX_PAGE).min(MAX_PAGE)),
+            next_token,
+            sort_order: Some("asc".to_string()),
+        };
+        self.client
+            .list_secrets(request)
+            .await
+            .map_err(|e| convert(e, |e| e.to_string()))
+    }
+
+    /// Reads the current value of a secret; `Ok(None)` when it does not exist.
+    async fn secret_value(
+        &self,
+        name: &str,
+    ) -> Result<Option<String>, RusotoError<GetSecretValueError>> {
+        let request = GetSecretValueRequest {
+            secret_id: name.to_string(),
+            version_id: None,
+            version_stage: None,
+        };
+        match self.client.get_secret_value(request).await {
+            Ok(response) => Ok(response
+                .secret_string
+                .or_else(|| response.secret_binary.map(|b| BASE64.encode(b.as_ref())))),
+            Err(RusotoError::Service(GetSecretValueError::ResourceNotFound(_))) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn create_secret(
+        &self,
+        input: &PutParameterRequest,
+    ) -> Result<(), RusotoError<CreateSecretError>> {
+        let tags = input.tags.as_ref().map(|tags| {
+            tags.iter()
+                .map(|tag| Tag {
+                    key: Some(tag.key.clone()),
+                    value: Some(tag.value.clone()),
+                })
+                .collect()
+        });
+        let request = CreateSecretRequest {
+            name: secret_name(&input.name).to_string(),
+            secret_string: Some(input.value.clone()),
+            description: input.description.clone(),
+            kms_key_id: input.key_id.clone(),
+            tags,
+            ..Default::default()
+        };
+        self.client.create_secret(request).await.map(|_| ())
+    }
+}
+
+#[async_trait]
+impl ParameterBackend for SecretsManagerBackend {
+    async fn get_parameter(
+        &self,
+        input: GetParameterRequest,
+    ) -> Result<GetParameterResult, RusotoError<GetParameterError>> {
+        let value = self
+            .secret_value(secret_name(&input.name))
+            .await
+            .map_err(|e| convert(e, |e| GetParameterError::InternalServerError(e.to_string())))?
+            .ok_or_else(|| {
+                RusotoError::Service(GetParameterError::ParameterNotFound(input.name.clone()))
+            })?;
+        Ok(GetParameterResult {
+            parameter: Some(Parameter {
+                name: Some(parameter_path(&input.name)),
+                value: Some(value),
+                type_: Some(SECRET_TYPE.to_string()),
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Secrets Manager has no batch read here: one GetSecretValue per name.
+    async fn get_parameters(
+        &self,
+        input: GetParametersRequest,
+    ) -> Result<GetParametersResult, RusotoError<GetParametersError>> {
+        let mut parameters = Vec::new();
+        let mut invalid = Vec::new();
+        for name in input.names {
+            let value = self.secret_value(secret_name(&name)).await.map_err(|e| {
+                convert(e, |e| {
+                    GetParametersError::InternalServerError(e.to_string())
+                })
+            })?;
+            match value {
+                Some(value) => parameters.push(Parameter {
+                    name: Some(parameter_path(&name)),
+                    value: Some(value),
+                    type_: Some(SECRET_TYPE.to_string()),
+                    ..Default::default()
+                }),
+                None => invalid.push(name),
+            }
+        }
+        Ok(GetParametersResult {
+            parameters: Some(parameters),
+            invalid_parameters: Some(invalid),
+        })
+    }
+
+    /// Lists the secrets under `input.path` and fetches each value, one
+    /// GetSecretValue call per secret.
+    async fn get_parameters_by_path(
+        &self,
+        input: GetParametersByPathRequest,
+    ) -> Result<GetParametersByPathResult, RusotoError<GetParametersByPathError>> {
+        let page = self
+            .list_secrets(&input.path, input.max_results, input.next_token)
+            .await
+            .map_err(|e| convert(e, GetParametersByPathError::InternalServerError))?;
+
+        let mut parameters = Vec::new();
+        for entry in page.secret_list.unwrap_or_default() {
+            let Some(name) = entry
+                .name
+                .as_deref()
+                .filter(|_| entry.deleted_date.is_none())
+            else {
+                continue;
+            };
+            let path = parameter_path(name);
+            let nested = path
+                .strip_prefix(input.path.trim_end_matches('/'))
+                .is_some_and(|rest| rest.trim_start_matches('/').contains('/'));
+            if input.recursive != Some(true) && nested {
+                continue;
+            }
+            // A secret deleted between the listing and this call is skipped.
+            let Some(value) = self.secret_value(name).await.map_err(|e| {
+                convert(e, |e| {
+                    GetParametersByPathError::InternalServerError(e.to_string())
+                })
+            })?
+            else {
+                continue;
+            };
+            parameters.push(Parameter {
+                name: Some(path),
+                value: Some(value),
+                type_: Some(SECRET_TYPE.to_string()),
+                last_modified_date: entry.last_changed_date,
+                ..Default::default()
+            });
+        }
+        Ok(GetParametersByPathResult {
+            parameters: Some(parameters),
+            next_token: page.next_token,
+        })
+    }
+
+    /// Writes a new version of the secret, or creates it. Without `overwrite`
+    /// an existing secret is an error, like for SSM. The parameter type is
+    /// ignored: every secret is encrypted.
+    async fn put_parameter(
+        &self,
+        input: PutParameterRequest,
+    ) -> Result<PutParameterResult, RusotoError<PutParameterError>> {
+        let to_put_error = |e: RusotoError<CreateSecretError>| {
+            convert(e, |e| match e {
+                CreateSecretError::ResourceExists(message) => {
+                    PutParameterError::ParameterAlreadyExists(message)
+                }
+                other => PutParameterError::InternalServerError(other.to_string()),
+            })
+        };
+        if input.overwrite != Some(true) {
+            self.create_secret(&input).await.map_err(to_put_error)?;
+            return Ok(PutParameterResult::default());
+        }
+
+        let request = PutSecretValueRequest {
+            secret_id: secret_name(&input.name).to_string(),
+            secret_string: Some(input.value.clone()),
+            ..Default::default()
+        };
+        match self.client.put_secret_value(request).await {
+            Ok(_) => {}
+            Err(RusotoError::Service(PutSecretValueError::ResourceNotFound(_))) => {
+                self.create_secret(&input).await.map_err(to_put_error)?;
+            }
+            Err(e) => {
+                return Err(convert(e, |e| {
+                    PutParameterError::InternalServerError(e.to_string())
+                }));
+            }
+        }
+        Ok(PutParameterResult::default())
+    }
+
+    /// Schedules the secret for deletion with the default recovery window, so
+    /// it can still be restored from the console for a while.
+    async fn delete_parameter(
+        &self,
+        input: DeleteParameterRequest,
+    ) -> Result<DeleteParameterResult, RusotoError<DeleteParameterError>> {
+        let request = DeleteSecretRequest {
+            secret_id: secret_name(&input.name).to_string(),
+            force_delete_without_recovery: None,
+            recovery_window_in_days: None,
+        };
+        self.client.delete_secret(request).await.map_err(|e| {
+            convert(e, |e| match e {
+                DeleteSecretError::ResourceNotFound(message) => {
+                    DeleteParameterError::ParameterNotFound(message)
+                }
+                other => DeleteParameterError::InternalServerError(other.to_string()),
+            })
+        })?;
+        Ok(DeleteParameterResult {})
+    }
+
+    /// Supports the `Name BeginsWith` filter daps uses; other filters are ignored.
+    async fn describe_parameters(
+        &self,
+        input: DescribeParametersRequest,
+    ) -> Result<DescribeParametersResult, RusotoError<DescribeParametersError>> {
+        let prefix = input
+            .parameter_filters
+            .iter()
+            .flatten()
+            .filter(|filter| filter.key == "Name")
+            .find_map(|filter| filter.values.as_ref()?.first().cloned())
+            .unwrap_or_default();
+        let page = self
+            .list_secrets(&prefix, input.max_results, input.next_token)
+            .await
+            .map_err(|e| convert(e, DescribeParametersError::InternalServerError))?;
+
+        let parameters = page
+            .secret_list
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| entry.deleted_date.is_none())
+            .filter_map(metadata)
+            .collect();
+        Ok(DescribeParametersResult {
+            parameters: Some(parameters),
+            next_token: page.next_token,
+        })
+    }
+
+    /// The resource id is the parameter path; the resource type is ignored.
+    async fn list_tags_for_resource(
+        &self,
+        input: ListTagsForResourceRequest,
+    ) -> Result<ListTagsForResourceResult, RusotoError<ListTagsForResourceError>> {
+        let request = DescribeSecretRequest {
+            secret_id: secret_name(&input.resource_id).to_string(),
+        };
+        let secret = self.client.describe_secret(request).await.map_err(|e| {
+            convert(e, |e| match e {
+                DescribeSecretError::ResourceNotFound(message) => {
+                    ListTagsForResourceError::InvalidResourceId(message)
+                }
+                other => ListTagsForResourceError::InternalServerError(other.to_string()),
+            })
+        })?;
+        let tag_list = secret.tags.map(|tags| {
+            tags.into_iter()
+                .filter_map(|tag| {
+                    Some(rusoto_ssm::Tag {
+                        key: tag.key?,
+                        value: tag.value.unwrap_or_default(),
+                    })
+                })
+                .collect()
+        });
+        Ok(ListTagsForResourceResult { tag_list })
+    }
+}
+
+fn metadata(entry: SecretListEntry) -> Option<ParameterMetadata> {
+    Some(ParameterMetadata {
+        name: Some(parameter_path(&entry.name?)),
+        type_: Some(SECRET_TYPE.to_string()),
+        description: entry.description,
+        key_id: entry.kms_key_id,
+        last_modified_date: entry.last_changed_date,
+        ..Default::default()
+    })
+}
+
+fn secret_name(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
+fn parameter_path(name: &str) -> String {
+    format!("/{}", name.trim_start_matches('/'))
+}
+
+/// Carries a Secrets Manager error over to the SSM error type the caller
+/// expects; transport and credential errors pass through unchanged so retries
+/// and messages behave the same for both backends.
+fn convert<E, F>(err: RusotoError<E>, service: impl FnOnce(E) -> F) -> RusotoError<F> {
+    match err {
+        RusotoError::Service(e) => RusotoError::Service(service(e)),
+        RusotoError::HttpDispatch(e) => RusotoError::HttpDispatch(e),
+        RusotoError::Credentials(e) => RusotoError::Credentials(e),
+        RusotoError::Validation(message) => RusotoError::Validation(message),
+        RusotoError::ParseError(message) => RusotoError::ParseError(message),
+        RusotoError::Unknown(response) => RusotoError::Unknown(response),
+        RusotoError::Blocking => RusotoError::Blocking,
+    }
+}
@@ -0,0 +1,205 @@
+use crate::completer::{NewParameter, ParameterCompleter};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+
+/// Results `search` returns when the request gives no `limit`.
+const SEARCH_LIMIT: usize = 20;
+
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const FAILED: i32 = -32000;
+
+/// One request line: `{"id": 1, "method": "get", "params": {"path": "/app/db"}}`.
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct Failure {
+    code: i32,
+    message: String,
+}
+
+impl Failure {
+    fn params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+}
+
+impl<E: std::fmt::Display> From<E> for Failure {
+    fn from(err: E) -> Self {
+        Self {
+            code: FAILED,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Serves `list`, `get`, `set` and `search` requests, one JSON object per
+/// line on stdin, answering each with one line on stdout: `{"id", "result"}`
+/// or `{"id", "error": {"code", "message"}}`. The cache stays loaded between
+/// requests, so editors and tools can keep one daps process around instead of
+/// starting one per lookup. Ends at end of input.
+pub async fn run_stdio(
+    completer: &mut ParameterCompleter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Err(err) = completer.start_background_refresh() {
+        log::warn!("Background refresh disabled: {}", err);
+    }
+    serve_lines(completer, io::stdin().lock(), io::stdout()).await
+}
+
+/// The protocol loop behind `run_stdio`, over any input and output.
+pub async fn serve_lines(
+    completer: &mut ParameterCompleter,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        completer.apply_background_refresh();
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => match handle(completer, &request.method, &request.params).await {
+                Ok(result) => json!({ "id": request.id, "result": result }),
+                Err(failure) => error(request.id, failure),
+            },
+            Err(err) => error(
+                Value::Null,
+                Failure {
+                    code: PARSE_ERROR,
+                    message: format!("Parse error: {}", err),
+                },
+            ),
+        };
+        writeln!(output, "{}", response)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn error(id: Value, failure: Failure) -> Value {
+    json!({ "id": id, "error": { "code": failure.code, "message": failure.message } })
+}
+
+async fn handle(
+    completer: &mut ParameterCompleter,
+    method: &str,
+    params: &Value,
+) -> Result<Value, Failure> {
+    match method {
+        // {"prefix": "/app"} -> {"parameters": [{"path", "type"}]}
+        "list" => {
+            let prefix = params["prefix"].as_str().unwrap_or(&completer.base_path);
+            let parameters: Vec<Value> = completer
+                .parameters_under(prefix)
+                .into_iter()
+                .map(|path| json!({ "type": completer.types.get(&path), "path": path }))
+                .collect();
+            Ok(json!({ "parameters": parameters }))
+        }
+
+        // {"path", "refresh": false} -> {"path", "value", "type"}
+        "get" => {
+            let path = required(params, "path")?;
+            let refresh = params["refresh"].as_bool().unwrap_or(false);
+            let value = match completer.cached_value(path).filter(|_| !refresh) {
+                Some(value) => value,
+                None => completer.get_set_value(path).await?,
+            };
+            Ok(json!({ "path": path, "value": value, "type": completer.types.get(path) }))
+        }
+
+        // {"path", "value", "type"?}; `type` is needed to create a parameter.
+        "set" => {
+            let path = required(params, "path")?;
+            let value = required(params, "value")?;
+            if let Some(pattern) = completer.protected.matching(path) {
+                return Err(Failure::params(format!(
+                    "{} is protected by '{}'; change it from the REPL",
+                    path, pattern
+                )));
+            }
+            if completer.is_parameter(path) {
+                completer.change_value(path, value.to_string()).await?;
+                return Ok(json!({ "path": path, "created": false }));
+            }
+            let Some(param_type) = params["type"].as_str() else {
+                return Err(Failure::params(format!(
+                    "{} does not exist; pass \"type\" to create it",
+                    path
+                )));
+            };
+            completer
+                .create_parameter(&NewParameter {
+                    path: path.to_string(),
+                    value: value.to_string(),
+                    param_type: param_type.to_string(),
+                    key_id: None,
+                    description: None,
+                    tags: Vec::new(),
+                    allowed_pattern: None,
+                    data_type: None,
+                })
+                .await?;
+            Ok(json!({ "path": path, "created": true }))
+        }
+
+        // {"term", "limit"?} -> {"results": [path]}, best match first
+        "search" => {
+            let term = required(params, "term")?;
+            let limit = params["limit"]
+                .as_u64()
+                .map_or(SEARCH_LIMIT, |limit| limit as usize);
+            Ok(json!({ "results": search(completer, term, limit) }))
+        }
+
+        other => Err(Failure {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unknown method '{}' (expected list|get|set|search)", other),
+        }),
+    }
+}
+
+fn required<'a>(params: &'a Value, name: &str) -> Result<&'a str, Failure> {
+    params[name]
+        .as_str()
+        .ok_or_else(|| Failure::params(format!("missing \"{}\"", name)))
+}
+
+/// Fuzzy matches ranked by score, or substring matches when there are none,
+/// like the REPL's `search`.
+fn search(completer: &ParameterCompleter, term: &str, limit: usize) -> Vec<String> {
+    let matcher = SkimMatcherV2::default();
+    let mut matches: Vec<(i64, String)> = completer
+        .search_candidates(term)
+        .into_iter()
+        .filter_map(|path| Some((matcher.fuzzy_match(&path, term)?, path)))
+        .collect();
+    if matches.is_empty() {
+        let mut paths = completer.parameters_containing(term);
+        paths.sort();
+        paths.truncate(limit);
+        return paths;
+    }
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, path)| path)
+        .collect()
+}
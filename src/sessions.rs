@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Everything needed to pick a session up where it was left: where daps was
+/// pointed, what was selected and the last search. Stored as
+/// `sessions/<name>.json` in the store directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub base_path: String,
+    pub region: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub selected: String,
+    #[serde(default)]
+    pub search_result: Vec<String>,
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Seconds since the Unix epoch.
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid session name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+pub fn save(
+    dir: &Path,
+    name: &str,
+    session: &SavedSession,
+) -> Result<(), Box<dyn std::error::Error>> {
+    validate_name(name)?;
+    std::fs::create_dir_all(dir)?;
+    let session = SavedSession {
+        saved_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        ..session.clone()
+    };
+    std::fs::write(
+        dir.join(format!("{}.json", name)),
+        serde_json::to_string_pretty(&session)?,
+    )?;
+    Ok(())
+}
+
+pub fn load(dir: &Path, name: &str) -> Result<SavedSession, Box<dyn std::error::Error>> {
+    validate_name(name)?;
+    let file = dir.join(format!("{}.json", name));
+    let content = match std::fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!("No saved session named '{}'", name).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    Ok(serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid session file {}: {}", file.display(), e))?)
+}
+
+/// Names of the saved sessions, sorted.
+pub fn list(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "json")
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,147 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::mpsc;
+
+/// Set while a bulk command that can stop between records is running.
+static CANCELLABLE: AtomicBool = AtomicBool::new(false);
+/// Set when a signal asked that command to stop.
+static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Marks a bulk command (`import`, `sync`, `mv-tree`) as running until it is
+/// dropped. A first signal then asks the command to stop at the next record
+/// boundary, after saving its checkpoint, and the session goes on; the
+/// command checks `requested` before each record.
+pub struct Cancellation(());
+
+impl Cancellation {
+    pub fn begin() -> Self {
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+        CANCELLABLE.store(true, Ordering::SeqCst);
+        Self(())
+    }
+
+    /// Whether a signal asked the command to stop.
+    pub fn requested(&self) -> bool {
+        CANCEL_REQUESTED.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        CANCELLABLE.store(false, Ordering::SeqCst);
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// SIGINT / SIGTERM handling for the REPL and batch loops. A signal while a
+/// command runs lets the command finish, so its cache writes complete, and
+/// then ends the session; a bulk command holding a `Cancellation` stops at
+/// its next record instead and the session goes on. A second signal abandons
+/// the command at its next await, which is always between cache writes. A
+/// signal while waiting for input ends the session as soon as the input
+/// returns, before anything else runs, so the normal teardown still happens;
+/// a second one exits right away.
+pub struct Shutdown {
+    signals: mpsc::UnboundedReceiver<&'static str>,
+    busy: Arc<AtomicBool>,
+    requested: Option<&'static str>,
+    /// The signal that arrived while waiting for input.
+    idle: Arc<OnceLock<&'static str>>,
+}
+
+impl Shutdown {
+    pub fn install() -> Self {
+        let (sender, signals) = mpsc::unbounded_channel();
+        let busy = Arc::new(AtomicBool::new(false));
+        let running = Arc::clone(&busy);
+        let idle = Arc::new(OnceLock::new());
+        let waiting = Arc::clone(&idle);
+        tokio::spawn(async move {
+            loop {
+                let (name, code) = next_signal().await;
+                if !running.load(Ordering::SeqCst) {
+                    if waiting.set(name).is_err() {
+                        eprintln!("\n{} received again, exiting", name);
+                        std::process::exit(code);
+                    }
+                    eprintln!(
+                        "\n{} received, ending the session when the input returns (send it again to exit now)",
+                        name
+                    );
+                    continue;
+                }
+                if sender.send(name).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            signals,
+            busy,
+            requested: None,
+            idle,
+        }
+    }
+
+    /// The signal that asked the session to end, if any.
+    pub fn requested(&self) -> Option<&'static str> {
+        self.requested.or_else(|| self.idle.get().copied())
+    }
+
+    /// Runs one command. Returns `None` when a second signal abandoned it.
+    pub async fn guard<F: Future>(&mut self, command: F) -> Option<F::Output> {
+        self.busy.store(true, Ordering::SeqCst);
+        tokio::pin!(command);
+        let output = loop {
+            tokio::select! {
+                output = &mut command => break Some(output),
+                Some(name) = self.signals.recv() => {
+                    if self.requested.is_some() || CANCEL_REQUESTED.load(Ordering::SeqCst) {
+                        eprintln!("{} received again, abandoning the command", name);
+                        break None;
+                    }
+                    if CANCELLABLE.load(Ordering::SeqCst) {
+                        CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+                        eprintln!(
+                            "\n{} received, stopping after the current record (send it again to stop now)",
+                            name
+                        );
+                        continue;
+                    }
+                    self.requested = Some(name);
+                    eprintln!(
+                        "\n{} received, finishing the current command first (send it again to stop now)",
+                        name
+                    );
+                }
+            }
+        };
+        self.busy.store(false, Ordering::SeqCst);
+        output
+    }
+}
+
+/// Waits for the next SIGINT or SIGTERM; returns its name and exit code.
+#[cfg(unix)]
+async fn next_signal() -> (&'static str, i32) {
+    use tokio::signal::unix::{SignalKind, signal};
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(err) => {
+            log::warn!("Cannot listen for SIGTERM: {}", err);
+            let _ = tokio::signal::ctrl_c().await;
+            return ("SIGINT", 130);
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => ("SIGINT", 130),
+        _ = terminate.recv() => ("SIGTERM", 143),
+    }
+}
+
+#[cfg(not(unix))]
+async fn next_signal() -> (&'static str, i32) {
+    let _ = tokio::signal::ctrl_c().await;
+    ("Ctrl-C", 130)
+}
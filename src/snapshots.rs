@@ -0,0 +1,219 @@
+use crate::cache_file::write_private;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Why automatic snapshots cannot be turned on without a cache key.
+pub const AUTO_NEEDS_KEY: &str = "Automatic snapshots keep values on disk: set DAPS_ENCRYPTION_KEY, or pass --allow-plaintext-cache";
+
+/// One parameter as it was when the snapshot was taken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<String>,
+    /// Encrypted with the cache key when `Snapshot::encrypted` is set.
+    pub value: String,
+}
+
+/// The parameters under a prefix at one point in time, for `snapshot diff`
+/// and `snapshot restore`. Stored as `snapshots/<name>.json` in the store
+/// directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub prefix: String,
+    pub region: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Whether the values are encrypted with the cache key.
+    pub encrypted: bool,
+    pub parameters: BTreeMap<String, SnapshotEntry>,
+    /// Taken automatically before a session's writes. Holds only the
+    /// parameters the session touched, not everything under `prefix`.
+    #[serde(default)]
+    pub auto: bool,
+    /// Parameters an automatic snapshot saw created: they did not exist before
+    /// the session wrote them, so a restore deletes them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub absent: Vec<String>,
+}
+
+impl Snapshot {
+    /// Whether an automatic snapshot already holds what `path` was before
+    /// the session changed it.
+    pub fn records(&self, path: &str) -> bool {
+        self.parameters.contains_key(path) || self.absent.iter().any(|absent| absent == path)
+    }
+
+    /// The parameters a restore compares: all under the prefix, or only the
+    /// ones an automatic snapshot recorded.
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.parameters.keys().cloned().collect();
+        paths.extend(self.absent.iter().cloned());
+        paths.sort();
+        paths
+    }
+}
+
+/// The `[auto_snapshots]` table of the config file: whether each session
+/// records the parameters it is about to change before its first write to
+/// them, and how many of these automatic snapshots are kept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoSnapshots {
+    pub enabled: bool,
+    /// Most automatic snapshots kept; 0 keeps any number.
+    pub keep: usize,
+    /// Automatic snapshots older than this are deleted; 0 keeps them forever.
+    pub max_age_days: u64,
+}
+
+impl Default for AutoSnapshots {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep: 20,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// Name of the automatic snapshot of a session started at `epoch_secs`:
+/// `auto-20240131-094500`.
+pub fn auto_name(epoch_secs: u64) -> String {
+    let stamp: String = crate::utils::format_timestamp(epoch_secs as f64)
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_ascii_digit() => Some(c),
+            _ => None,
+        })
+        .collect();
+    format!("auto-{}", stamp)
+}
+
+/// Deletes the automatic snapshots beyond `policy.keep` (newest first) or
+/// older than `policy.max_age_days`, never `current`. Snapshots created
+/// with `snapshot create` are left alone. Returns the names deleted.
+pub fn prune(
+    dir: &Path,
+    policy: &AutoSnapshots,
+    now: u64,
+    current: &str,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut automatic: Vec<(u64, String)> = list(dir)
+        .into_iter()
+        .filter(|name| name != current)
+        .filter_map(|name| {
+            let snapshot = load(dir, &name).ok()?;
+            snapshot.auto.then_some((snapshot.created_at, name))
+        })
+        .collect();
+    automatic.sort_by(|a, b| b.cmp(a));
+
+    // The current session's snapshot counts towards `keep`.
+    let kept = policy.keep.saturating_sub(1);
+    let max_age = policy.max_age_days * 86_400;
+    let mut removed = Vec::new();
+    for (index, (created_at, name)) in automatic.into_iter().enumerate() {
+        let too_many = policy.keep > 0 && index >= kept;
+        let too_old = policy.max_age_days > 0 && now.saturating_sub(created_at) > max_age;
+        if too_many || too_old {
+            std::fs::remove_file(dir.join(format!("{}.json", name)))?;
+            removed.push(name);
+        }
+    }
+    Ok(removed)
+}
+
+/// What `snapshot restore` does to a parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    /// Deleted since the snapshot; created again.
+    Create,
+    /// Changed since the snapshot; set back to the snapshot's value.
+    Update,
+    /// Created since the snapshot; only deleted with `--delete-extra`.
+    Delete,
+}
+
+/// The changes that bring `current` back to `snapshot` (plain values on both
+/// sides), sorted by path. Parameters created since are only listed when
+/// `delete_extra` is set.
+pub fn plan(
+    snapshot: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+    delete_extra: bool,
+) -> Vec<(String, Change)> {
+    let mut changes: Vec<(String, Change)> = Vec::new();
+    for (path, value) in snapshot {
+        match current.get(path) {
+            None => changes.push((path.clone(), Change::Create)),
+            Some(now) if now != value => changes.push((path.clone(), Change::Update)),
+            Some(_) => {}
+        }
+    }
+    if delete_extra {
+        for path in current.keys().filter(|path| !snapshot.contains_key(*path)) {
+            changes.push((path.clone(), Change::Delete));
+        }
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return Err(format!(
+            "Invalid snapshot name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Writes the snapshot, readable by its owner only on Unix since it holds
+/// values.
+pub fn save(dir: &Path, name: &str, snapshot: &Snapshot) -> Result<(), Box<dyn std::error::Error>> {
+    validate_name(name)?;
+    std::fs::create_dir_all(dir)?;
+    let file = dir.join(format!("{}.json", name));
+    write_private(&file, serde_json::to_string_pretty(snapshot)?)?;
+    Ok(())
+}
+
+pub fn load(dir: &Path, name: &str) -> Result<Snapshot, Box<dyn std::error::Error>> {
+    validate_name(name)?;
+    let file = dir.join(format!("{}.json", name));
+    let content = match std::fs::read_to_string(&file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!("No snapshot named '{}'", name).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    Ok(serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid snapshot file {}: {}", file.display(), e))?)
+}
+
+/// Names of the saved snapshots, sorted.
+pub fn list(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "json")
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    names.sort();
+    names
+}
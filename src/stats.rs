@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Counters for one AWS operation, such as `GetParametersByPath`.
+#[derive(Debug, Clone, Default)]
+pub struct OperationStats {
+    /// Requests sent, retries included.
+    pub calls: u64,
+    pub errors: u64,
+    pub retries: u64,
+    /// Retries caused by throttling.
+    pub throttled: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl OperationStats {
+    pub fn average(&self) -> Duration {
+        match self.calls {
+            0 => Duration::ZERO,
+            calls => self.total / calls as u32,
+        }
+    }
+}
+
+/// Session-wide API and cache statistics. Shared with the background refresher,
+/// so it sits behind a lock that is never held across an await.
+#[derive(Default)]
+pub struct Stats {
+    inner: Mutex<Counters>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Counters {
+    pub operations: BTreeMap<String, OperationStats>,
+    /// Value lookups answered from memory or the disk cache.
+    pub cache_hits: u64,
+    /// Value lookups that had to go to AWS.
+    pub cache_misses: u64,
+}
+
+impl Counters {
+    pub fn calls(&self) -> u64 {
+        self.operations.values().map(|op| op.calls).sum()
+    }
+
+    pub fn retries(&self) -> u64 {
+        self.operations.values().map(|op| op.retries).sum()
+    }
+
+    pub fn api_time(&self) -> Duration {
+        self.operations.values().map(|op| op.total).sum()
+    }
+
+    /// Share of lookups served from the cache, if there were any.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64)
+    }
+}
+
+impl Stats {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn record_call(&self, operation: &str, elapsed: Duration, ok: bool) {
+        let mut inner = self.lock();
+        let op = inner.operations.entry(operation.to_string()).or_default();
+        op.calls += 1;
+        op.total += elapsed;
+        op.max = op.max.max(elapsed);
+        if !ok {
+            op.errors += 1;
+        }
+    }
+
+    pub fn record_retry(&self, operation: &str, throttled: bool) {
+        let mut inner = self.lock();
+        let op = inner.operations.entry(operation.to_string()).or_default();
+        op.retries += 1;
+        if throttled {
+            op.throttled += 1;
+        }
+    }
+
+    pub fn record_cache(&self, hits: usize, misses: usize) {
+        let mut inner = self.lock();
+        inner.cache_hits += hits as u64;
+        inner.cache_misses += misses as u64;
+    }
+
+    pub fn snapshot(&self) -> Counters {
+        self.lock().clone()
+    }
+
+    pub fn reset(&self) {
+        *self.lock() = Counters::default();
+    }
+}
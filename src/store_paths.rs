@@ -0,0 +1,153 @@
+use crate::backend::BackendKind;
+use directories::BaseDirs;
+use std::path::{Path, PathBuf};
+
+/// Store directory used by versions that kept the cache in `$HOME/parameters`.
+const LEGACY_STORE_DIR: &str = "parameters";
+
+/// Locations of everything daps keeps in its store directory.
+#[derive(Debug, Clone)]
+pub struct StorePaths {
+    root: PathBuf,
+    /// Prepended to cache and pending file names so backends never share a cache.
+    namespace: &'static str,
+}
+
+impl StorePaths {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            namespace: "",
+        }
+    }
+
+    /// Keeps the Secrets Manager cache apart from the Parameter Store one;
+    /// Parameter Store keeps the original file names.
+    pub fn for_backend(mut self, backend: BackendKind) -> Self {
+        self.namespace = match backend {
+            BackendKind::Ssm => "",
+            BackendKind::SecretsManager => "sm_",
+        };
+        self
+    }
+
+    /// Resolves `store_dir` relative to `home`. `Path::join` keeps `store_dir` as-is
+    /// when it is absolute (including `C:\...` and UNC paths) and only borrows the
+    /// drive from `home` for rooted `\dir` paths on Windows.
+    pub fn resolve(store_dir: &str, home: &Path) -> Self {
+        Self::new(home.join(store_dir))
+    }
+
+    /// The platform data directory: `$XDG_DATA_HOME/daps` (or `~/.local/share/daps`)
+    /// on Linux, `~/Library/Application Support/daps` on macOS and `%APPDATA%\daps`
+    /// on Windows. An existing `~/parameters` store from older versions keeps being
+    /// used until the new location exists, so upgrading never drops the cache.
+    pub fn platform_default() -> Self {
+        let Some(dirs) = BaseDirs::new() else {
+            return Self::new(LEGACY_STORE_DIR);
+        };
+
+        let root = dirs.data_dir().join("daps");
+        let legacy = dirs.home_dir().join(LEGACY_STORE_DIR);
+        if !root.exists() && legacy.is_dir() {
+            return Self::new(legacy);
+        }
+        Self::new(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn parameters(&self, base_path: &str) -> PathBuf {
+        self.cache_file("parameters", base_path)
+    }
+
+    pub fn values(&self, base_path: &str) -> PathBuf {
+        self.cache_file("values", base_path)
+    }
+
+    pub fn types(&self, base_path: &str) -> PathBuf {
+        self.cache_file("types", base_path)
+    }
+
+    pub fn pending(&self, base_path: &str) -> PathBuf {
+        self.root.join(format!(
+            "{}pending_{}.json",
+            self.namespace,
+            Self::sanitize(base_path)
+        ))
+    }
+
+    /// Progress of an unfinished `import` or `mv-tree` (`command`), so a rerun
+    /// resumes it.
+    pub fn checkpoint(&self, command: &str) -> PathBuf {
+        self.root
+            .join(format!("{}{}_checkpoint.json", self.namespace, command))
+    }
+
+    pub fn pins(&self) -> PathBuf {
+        self.root.join("pins.json")
+    }
+
+    pub fn recent(&self) -> PathBuf {
+        self.root.join("recent.json")
+    }
+
+    pub fn sessions(&self) -> PathBuf {
+        self.root.join("sessions")
+    }
+
+    /// Saved `snapshot`s, kept per backend like the cache.
+    pub fn snapshots(&self) -> PathBuf {
+        self.root.join(format!("{}snapshots", self.namespace))
+    }
+
+    pub fn vocabulary(&self) -> PathBuf {
+        self.root.join("vocabulary.json")
+    }
+
+    pub fn history(&self) -> PathBuf {
+        self.root.join("history")
+    }
+
+    pub fn salt(&self) -> PathBuf {
+        self.root.join("salt")
+    }
+
+    /// The parameters, values and types files of every base path cached for
+    /// this backend, sorted.
+    pub fn cache_files(&self) -> Vec<PathBuf> {
+        let kinds =
+            ["parameters", "values", "types"].map(|kind| format!("{}{}_", self.namespace, kind));
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&self.root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.ends_with(".txt") && kinds.iter().any(|kind| name.starts_with(kind))
+                    })
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn cache_file(&self, kind: &str, base_path: &str) -> PathBuf {
+        self.root.join(format!(
+            "{}{}_{}.txt",
+            self.namespace,
+            kind,
+            Self::sanitize(base_path)
+        ))
+    }
+
+    /// `/prod/app/` becomes `_prod_app_`, so one store can hold several base paths.
+    fn sanitize(base_path: &str) -> String {
+        base_path.replace('/', "_")
+    }
+}
@@ -0,0 +1,416 @@
+use crate::completer::{ParameterCompleter, REDACTED};
+use clipboard::{ClipboardContext, ClipboardProvider};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const KEY_HELP: &str = "↑↓ move  →/Enter expand  ← collapse  / search  c copy  v reveal  s set  d delete  r refresh  q quit";
+
+/// What the bottom line is currently collecting.
+enum Input {
+    Browse,
+    Search,
+    Set(String),
+    ConfirmDelete(String),
+}
+
+struct Row {
+    path: String,
+    depth: usize,
+    is_branch: bool,
+}
+
+struct App {
+    expanded: HashSet<String>,
+    rows: Vec<Row>,
+    list: ListState,
+    search: String,
+    input: Input,
+    buffer: String,
+    reveal: bool,
+    status: String,
+    quit: bool,
+}
+
+/// Runs the full-screen browser until the user quits. The terminal is restored
+/// even when an action fails.
+pub async fn run(completer: &mut ParameterCompleter) -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, completer).await;
+    ratatui::restore();
+    result
+}
+
+async fn run_app(
+    terminal: &mut DefaultTerminal,
+    completer: &mut ParameterCompleter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = App {
+        expanded: HashSet::new(),
+        rows: Vec::new(),
+        list: ListState::default().with_selected(Some(0)),
+        search: String::new(),
+        input: Input::Browse,
+        buffer: String::new(),
+        reveal: false,
+        status: String::new(),
+        quit: false,
+    };
+
+    while !app.quit {
+        app.rebuild(completer);
+        terminal.draw(|frame| draw(frame, &mut app, completer))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key, completer).await;
+        }
+    }
+    Ok(())
+}
+
+fn join(parent: &str, child: &str) -> String {
+    if parent == "/" {
+        format!("/{}", child)
+    } else {
+        format!("{}/{}", parent, child)
+    }
+}
+
+impl App {
+    fn selected(&self) -> Option<&Row> {
+        self.list.selected().and_then(|i| self.rows.get(i))
+    }
+
+    /// Recomputes the visible rows: search matches when searching, otherwise the
+    /// expanded part of the tree under the base path.
+    fn rebuild(&mut self, completer: &ParameterCompleter) {
+        self.rows.clear();
+        if self.search.is_empty() {
+            let root = completer.base_path.trim_end_matches('/');
+            let root = if root.is_empty() { "/" } else { root };
+            self.push_children(completer, root, 0);
+        } else {
+            let matcher = SkimMatcherV2::default().ignore_case();
+            let mut matches: Vec<(i64, String)> = completer
+                .search_candidates(&self.search)
+                .into_iter()
+                .filter_map(|k| matcher.fuzzy_match(&k, &self.search).map(|s| (s, k)))
+                .collect();
+            matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            self.rows = matches
+                .into_iter()
+                .map(|(_, path)| Row {
+                    path,
+                    depth: 0,
+                    is_branch: false,
+                })
+                .collect();
+        }
+
+        let last = self.rows.len().saturating_sub(1);
+        self.list
+            .select(Some(self.list.selected().unwrap_or(0).min(last)));
+    }
+
+    fn push_children(&mut self, completer: &ParameterCompleter, parent: &str, depth: usize) {
+        let mut children = completer
+            .parameters
+            .get(parent)
+            .cloned()
+            .unwrap_or_default();
+        children.sort();
+        for child in children {
+            let path = join(parent, &child);
+            let is_branch = completer
+                .parameters
+                .get(&path)
+                .is_some_and(|c| !c.is_empty());
+            let expanded = is_branch && self.expanded.contains(&path);
+            self.rows.push(Row {
+                path: path.clone(),
+                depth,
+                is_branch,
+            });
+            if expanded {
+                self.push_children(completer, &path, depth + 1);
+            }
+        }
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent, completer: &mut ParameterCompleter) {
+        match std::mem::replace(&mut self.input, Input::Browse) {
+            Input::Browse => self.browse_key(key.code, completer).await,
+            Input::Search => match key.code {
+                KeyCode::Esc => self.search.clear(),
+                KeyCode::Enter => {}
+                KeyCode::Backspace => {
+                    self.search.pop();
+                    self.input = Input::Search;
+                }
+                KeyCode::Char(c) => {
+                    self.search.push(c);
+                    self.list.select(Some(0));
+                    self.input = Input::Search;
+                }
+                _ => self.input = Input::Search,
+            },
+            Input::Set(path) => match key.code {
+                KeyCode::Esc => self.status = "Set cancelled".to_string(),
+                KeyCode::Enter if completer.protected.matching(&path).is_some() => {
+                    self.buffer.clear();
+                    self.status = format!("{} is protected; use 'set' in the REPL", path);
+                }
+                KeyCode::Enter => {
+                    let value = std::mem::take(&mut self.buffer);
+                    self.status = match set_value(completer, &path, value).await {
+                        Ok(message) => message,
+                        Err(e) => format!("Error setting {}: {}", path, e),
+                    };
+                }
+                KeyCode::Backspace => {
+                    self.buffer.pop();
+                    self.input = Input::Set(path);
+                }
+                KeyCode::Char(c) => {
+                    self.buffer.push(c);
+                    self.input = Input::Set(path);
+                }
+                _ => self.input = Input::Set(path),
+            },
+            Input::ConfirmDelete(path) => {
+                self.status = if key.code != KeyCode::Char('y') {
+                    "Delete cancelled".to_string()
+                } else if completer.offline {
+                    "Deleting is not available in offline mode".to_string()
+                } else if completer.protected.matching(&path).is_some() {
+                    format!("{} is protected; use 'delete' in the REPL", path)
+                } else {
+                    match completer.delete_parameter(&path).await {
+                        Ok(()) => format!("Deleted {}", path),
+                        Err(e) => format!("Error deleting {}: {}", path, e),
+                    }
+                };
+            }
+        }
+    }
+
+    async fn browse_key(&mut self, code: KeyCode, completer: &mut ParameterCompleter) {
+        let selected = self.list.selected().unwrap_or(0);
+        match code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if !self.search.is_empty() => self.search.clear(),
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.list.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down | KeyCode::Char('j') => self.list.select(Some(selected + 1)),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
+                if let Some(row) = self.selected().filter(|r| r.is_branch) {
+                    self.expanded.insert(row.path.clone());
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Char('/') => self.input = Input::Search,
+            KeyCode::Char('v') if completer.redact => {
+                self.status = "Values are redacted".to_string();
+            }
+            KeyCode::Char('v') => self.reveal = !self.reveal,
+            KeyCode::Char('c') => self.copy(completer),
+            KeyCode::Char('s') => match self.selected().map(|r| r.path.clone()) {
+                Some(path) if completer.values.contains_key(&path) => {
+                    self.buffer.clear();
+                    self.input = Input::Set(path);
+                }
+                _ => self.status = "Select a parameter to set".to_string(),
+            },
+            KeyCode::Char('d') => match self.selected() {
+                Some(row) if completer.values.contains_key(&row.path) => {
+                    self.input = Input::ConfirmDelete(row.path.clone());
+                }
+                _ => self.status = "Select a parameter to delete".to_string(),
+            },
+            KeyCode::Char('r') => {
+                self.status = "Refreshing...".to_string();
+                completer.refresh = true;
+                let result = completer.load_parameters().await;
+                completer.refresh = false;
+                self.status = match result {
+                    Ok(()) => format!("Loaded {} parameters", completer.values.len()),
+                    Err(e) => format!("Error refreshing: {}", e),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// Collapses the selected branch, or jumps to the parent of a collapsed row.
+    fn collapse(&mut self) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        let path = row.path.clone();
+        if self.expanded.remove(&path) {
+            return;
+        }
+        if let Some((parent, _)) = path.rsplit_once('/')
+            && let Some(index) = self.rows.iter().position(|r| r.path == parent)
+        {
+            self.list.select(Some(index));
+        }
+    }
+
+    fn copy(&mut self, completer: &ParameterCompleter) {
+        if completer.redact {
+            self.status = "The clipboard is off while values are redacted".to_string();
+            return;
+        }
+        let Some(value) = self
+            .selected()
+            .and_then(|r| completer.cached_value(&r.path))
+        else {
+            self.status = "Nothing to copy".to_string();
+            return;
+        };
+        let result = ClipboardContext::new().and_then(|mut ctx| ctx.set_contents(value));
+        self.status = match result {
+            Ok(()) => "Copied value to clipboard".to_string(),
+            Err(e) => format!("Error copying to clipboard: {}", e),
+        };
+    }
+}
+
+async fn set_value(
+    completer: &mut ParameterCompleter,
+    path: &str,
+    value: String,
+) -> Result<String, Box<dyn std::error::Error>> {
+    if completer.offline {
+        completer.queue_change(path, &value, None)?;
+        return Ok(format!("Queued change to {} (push when back online)", path));
+    }
+    completer.change_value(path, value).await?;
+    Ok(format!("Updated {}", path))
+}
+
+fn draw(frame: &mut Frame, app: &mut App, completer: &ParameterCompleter) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(frame.area());
+    let [tree, detail] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+
+    let items: Vec<ListItem> = app
+        .rows
+        .iter()
+        .map(|row| {
+            let name = if app.search.is_empty() {
+                row.path.rsplit('/').next().unwrap_or(&row.path).to_string()
+            } else {
+                row.path.clone()
+            };
+            let marker = match (row.is_branch, app.expanded.contains(&row.path)) {
+                (true, true) => "▾ ",
+                (true, false) => "▸ ",
+                (false, _) => "  ",
+            };
+            let style = if row.is_branch {
+                Style::default().fg(Color::Blue)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw("  ".repeat(row.depth)),
+                Span::raw(marker),
+                Span::styled(name, style),
+            ]))
+        })
+        .collect();
+
+    let title = if app.search.is_empty() {
+        format!(" {} ", completer.base_path)
+    } else {
+        format!(" search: {} ({}) ", app.search, app.rows.len())
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, tree, &mut app.list);
+
+    frame.render_widget(
+        Paragraph::new(detail_lines(app, completer))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(" details ")),
+        detail,
+    );
+
+    let prompt = match &app.input {
+        Input::Browse => KEY_HELP.to_string(),
+        Input::Search => format!("/{}", app.search),
+        Input::Set(path) => format!("New value for {}: {}", path, app.buffer),
+        Input::ConfirmDelete(path) => format!("Delete {} from AWS? (y/N)", path),
+    };
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(prompt),
+            Line::from(Span::styled(
+                &app.status,
+                Style::default().fg(Color::Yellow),
+            )),
+        ]),
+        footer,
+    );
+}
+
+fn detail_lines<'a>(app: &App, completer: &'a ParameterCompleter) -> Vec<Line<'a>> {
+    let Some(row) = app.selected() else {
+        return vec![Line::from("No parameters")];
+    };
+    let label =
+        |name: &'static str| Span::styled(name, Style::default().add_modifier(Modifier::BOLD));
+
+    let mut lines = vec![Line::from(vec![
+        label("Path: "),
+        Span::raw(row.path.clone()),
+    ])];
+    if let Some(children) = completer
+        .parameters
+        .get(&row.path)
+        .filter(|c| !c.is_empty())
+    {
+        lines.push(Line::from(vec![
+            label("Children: "),
+            Span::raw(children.len().to_string()),
+        ]));
+    }
+    if let Some(value) = completer.cached_value(&row.path) {
+        let param_type = completer
+            .types
+            .get(&row.path)
+            .map_or("unknown", String::as_str);
+        lines.push(Line::from(vec![label("Type: "), Span::raw(param_type)]));
+        if completer.pending.get(&row.path).is_some() {
+            lines.push(Line::from(Span::styled(
+                "Pending change not yet pushed",
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        lines.push(Line::from(label("Value:")));
+        if completer.redact {
+            lines.push(Line::from(REDACTED));
+        } else if app.reveal || !completer.is_sensitive(&row.path) {
+            lines.extend(value.lines().map(|l| Line::from(l.to_string())));
+        } else {
+            lines.push(Line::from("******** (press v to reveal)"));
+        }
+    }
+    lines
+}
@@ -1,17 +1,68 @@
 use rusoto_core::Region;
 use std::fs;
-use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::io::{self, BufRead, BufReader, IsTerminal, Seek, SeekFrom, Write};
 
+/// Parses a region name. Besides the regions rusoto knows (GovCloud and China
+/// included), any well-formed name such as `ap-southeast-4` or `us-iso-east-1`
+/// is accepted, with endpoints derived from its partition, and
+/// `name=https://endpoint` targets a region served from an explicit endpoint.
 pub fn parse_region(region: &str) -> Result<Region, String> {
-    match region
-        .parse::<Region>()
-        .map_err(|_| format!("Invalid region: {}", region))
-    {
-        Ok(region) => Ok(region),
-        Err(err) => Err(format!("Error parsing region: {}", err)),
+    let region = region.trim();
+    if let Some((name, endpoint)) = region.split_once('=') {
+        let (name, endpoint) = (name.trim(), endpoint.trim().trim_end_matches('/'));
+        if !is_region_name(name) || endpoint.is_empty() {
+            return Err(format!(
+                "Invalid region '{}': expected name=https://endpoint",
+                region
+            ));
+        }
+        return Ok(Region::Custom {
+            name: name.to_lowercase(),
+            endpoint: endpoint.to_string(),
+        });
+    }
+    if let Ok(known) = region.parse::<Region>() {
+        return Ok(known);
+    }
+    if is_region_name(region) {
+        // An empty endpoint stands for the partition's standard endpoints; see
+        // `ClientOptions::resolve_region`.
+        return Ok(Region::Custom {
+            name: region.to_lowercase(),
+            endpoint: String::new(),
+        });
+    }
+    Err(format!(
+        "Invalid region '{}': expected a name like us-gov-west-1 or name=https://endpoint",
+        region
+    ))
+}
+
+/// The inverse of `parse_region`: the name, plus the endpoint for regions
+/// given one explicitly.
+pub fn region_spec(region: &Region) -> String {
+    match region {
+        Region::Custom { name, endpoint } if !endpoint.is_empty() => {
+            format!("{}={}", name, endpoint)
+        }
+        region => region.name().to_string(),
     }
 }
 
+/// `<area>-[<qualifier>-]<direction>-<n>`, e.g. `eu-west-1` or `us-gov-east-1`.
+fn is_region_name(name: &str) -> bool {
+    let parts: Vec<&str> = name.split('-').collect();
+    let Some((number, words)) = parts.split_last() else {
+        return false;
+    };
+    words.len() >= 2
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+        && words
+            .iter()
+            .all(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
 /// Replaces the first line matching a predicate and exits immediately.
 pub fn replace_first_matching_line(
     filepath: &str,
@@ -61,5 +112,238 @@ pub fn replace_first_line_containing(
     search_text: &str,
     replacement_line: &str,
 ) -> io::Result<bool> {
-    replace_first_matching_line(filepath, |line| line.contains(search_text), replacement_line)
+    replace_first_matching_line(
+        filepath,
+        |line| line.contains(search_text),
+        replacement_line,
+    )
+}
+
+/// Prints `message` and reads one trimmed line from stdin.
+pub fn prompt_line(message: &str) -> io::Result<String> {
+    print!("{}", message);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
+
+/// Reads a value without echoing it, for secrets typed at the prompt.
+pub fn prompt_hidden(message: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if !io::stdin().is_terminal() {
+        return Err("Cannot read a hidden value: stdin is not a terminal".into());
+    }
+    Ok(rpassword::prompt_password(message)?)
+}
+
+/// Matches `text` against a glob where `*` stands for any run of characters
+/// (including `/`) and `?` for exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Removes `flag` given as the first or last word of `args`, returning the
+/// remaining arguments and whether the flag was present.
+pub fn take_flag<'a>(args: &'a str, flag: &str) -> (&'a str, bool) {
+    if args == flag {
+        return ("", true);
+    }
+    if let Some(rest) = args
+        .strip_prefix(flag)
+        .and_then(|rest| rest.strip_prefix(' '))
+    {
+        return (rest.trim_start(), true);
+    }
+    if let Some(rest) = args
+        .strip_suffix(flag)
+        .and_then(|rest| rest.strip_suffix(' '))
+    {
+        return (rest.trim_end(), true);
+    }
+    (args, false)
+}
+
+/// Splits `input` into words the way a shell does: whitespace separates words,
+/// `'...'` keeps everything literal, `"..."` too except that `\"` and `\\`
+/// stand for `"` and `\`, and a backslash outside quotes escapes the next
+/// character. `""` is an empty word.
+pub fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated ' quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("Unterminated \" quote".to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated \" quote".to_string()),
+                    }
+                }
+            }
+            '\\' => word
+                .get_or_insert_with(String::new)
+                .push(chars.next().unwrap_or('\\')),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Splits two strings into their common prefix, the differing middles and the
+/// common suffix: `("abXcd", "abYYcd")` gives `("ab", "X", "YY", "cd")`.
+pub fn split_change<'a>(old: &'a str, new: &'a str) -> (&'a str, &'a str, &'a str, &'a str) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((i, _), _)| i);
+    let (old_rest, new_rest) = (&old[prefix..], &new[prefix..]);
+    let suffix = old_rest
+        .chars()
+        .rev()
+        .zip(new_rest.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    (
+        &old[..prefix],
+        &old_rest[..old_rest.len() - suffix],
+        &new_rest[..new_rest.len() - suffix],
+        &old_rest[old_rest.len() - suffix..],
+    )
+}
+
+/// Formats seconds since the Unix epoch as a UTC `YYYY-MM-DD` date.
+pub fn format_date(epoch_secs: f64) -> String {
+    // Howard Hinnant's days-to-civil algorithm.
+    let days = (epoch_secs / 86_400.0).floor() as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Formats seconds since the Unix epoch as a UTC `YYYY-MM-DD HH:MM:SS` time.
+pub fn format_timestamp(epoch_secs: f64) -> String {
+    let seconds = (epoch_secs.floor() as i64).rem_euclid(86_400);
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        format_date(epoch_secs),
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Returns the field `key` of a JSON object value: strings as-is, anything
+/// else as compact JSON.
+pub fn json_field(value: &str, key: &str) -> Result<String, String> {
+    let parsed: serde_json::Value =
+        serde_json::from_str(value).map_err(|e| format!("Value is not JSON: {}", e))?;
+    match parsed.get(key) {
+        Some(serde_json::Value::String(field)) => Ok(field.clone()),
+        Some(field) => Ok(field.to_string()),
+        None => Err(format!("JSON value has no key '{}'", key)),
+    }
+}
+
+/// Cuts `value` to its first line and at most `width` characters, ending it
+/// with `…` when anything was left out. A width of 0 keeps the whole value.
+pub fn truncate_value(value: &str, width: usize) -> String {
+    let first_line = value.lines().next().unwrap_or_default();
+    if width == 0 || (first_line.len() == value.len() && value.chars().count() <= width) {
+        return value.to_string();
+    }
+    let kept: String = first_line.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+/// Shows `text` through `$PAGER` (`less -R` by default) when stdout is a
+/// terminal, and prints it otherwise.
+pub fn page(text: &str) -> io::Result<()> {
+    if !io::stdout().is_terminal() {
+        print!("{}", text);
+        return io::stdout().flush();
+    }
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less -R".to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or("less");
+    let mut child = std::process::Command::new(program)
+        .args(words)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("Cannot start pager '{}': {}", pager, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input when quit early; that is not an error.
+        match stdin.write_all(text.as_bytes()) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+            other => other?,
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// How long ago `epoch_secs` was, in the largest whole unit: `45m`, `5h`,
+/// `12d`.
+pub fn format_age(epoch_secs: f64, now: f64) -> String {
+    let seconds = (now - epoch_secs).max(0.0) as u64;
+    match seconds {
+        0..3_600 => format!("{}m", seconds / 60),
+        3_600..86_400 => format!("{}h", seconds / 3_600),
+        _ => format!("{}d", seconds / 86_400),
+    }
 }
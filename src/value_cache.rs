@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Cached values by path. With a capacity set, only that many values are
+/// kept in memory, least recently used first out. Evicted entries keep their
+/// key, so the set of known parameters stays complete; the completer reads
+/// their values back from the disk cache or AWS when they are needed again.
+#[derive(Default)]
+pub struct ValueCache {
+    entries: HashMap<String, Entry>,
+    /// Most values held in memory; 0 keeps them all.
+    capacity: usize,
+    resident: usize,
+    clock: AtomicU64,
+}
+
+struct Entry {
+    value: Option<String>,
+    last_used: AtomicU64,
+    /// Only held in memory (a queued offline change), so never evicted.
+    pinned: bool,
+    /// Loaded without its value because it could not be decrypted.
+    unavailable: bool,
+}
+
+impl ValueCache {
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The value of `path` if it is in memory, marking it as recently used.
+    pub fn get(&self, path: &str) -> Option<&String> {
+        let entry = self.entries.get(path)?;
+        let value = entry.value.as_ref()?;
+        entry.last_used.store(self.tick(), Ordering::Relaxed);
+        Some(value)
+    }
+
+    pub fn get_key_value(&self, path: &str) -> Option<(&String, &String)> {
+        let (key, entry) = self.entries.get_key_value(path)?;
+        let value = entry.value.as_ref()?;
+        entry.last_used.store(self.tick(), Ordering::Relaxed);
+        Some((key, value))
+    }
+
+    /// Whether `path` is a known parameter, in memory or evicted.
+    pub fn contains_key(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    /// Whether `path` is known but its value could not be decrypted.
+    pub fn is_unavailable(&self, path: &str) -> bool {
+        self.entries
+            .get(path)
+            .is_some_and(|entry| entry.unavailable)
+    }
+
+    /// Whether the value of `path` is in memory.
+    pub fn is_resident(&self, path: &str) -> bool {
+        self.entries
+            .get(path)
+            .is_some_and(|entry| entry.value.is_some())
+    }
+
+    pub fn insert(&mut self, path: String, value: String) {
+        self.put(path, value, false);
+    }
+
+    /// Inserts a value that exists nowhere else, which is never evicted.
+    pub fn insert_pinned(&mut self, path: String, value: String) {
+        self.put(path, value, true);
+    }
+
+    /// Records a parameter whose value could not be decrypted, so it stays
+    /// known without a value until it is fetched with decryption.
+    pub fn insert_unavailable(&mut self, path: String) {
+        let entry = Entry {
+            value: None,
+            last_used: AtomicU64::new(self.tick()),
+            pinned: false,
+            unavailable: true,
+        };
+        if let Some(old) = self.entries.insert(path, entry)
+            && old.value.is_some()
+        {
+            self.resident -= 1;
+        }
+    }
+
+    fn put(&mut self, path: String, value: String, pinned: bool) {
+        let last_used = AtomicU64::new(self.tick());
+        let entry = Entry {
+            value: Some(value),
+            last_used,
+            pinned,
+            unavailable: false,
+        };
+        match self.entries.insert(path, entry) {
+            Some(old) if old.value.is_some() => {}
+            _ => self.resident += 1,
+        }
+        self.evict();
+    }
+
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        let value = self.entries.remove(path)?.value;
+        if value.is_some() {
+            self.resident -= 1;
+        }
+        value
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.resident = 0;
+    }
+
+    /// Replaces every entry; values beyond the capacity are evicted right away.
+    pub fn replace_all(&mut self, values: HashMap<String, String>) {
+        self.clear();
+        for (path, value) in values {
+            self.insert(path, value);
+        }
+    }
+
+    /// Every known path, including evicted ones.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.keys()
+    }
+
+    /// The values in memory, without marking them as used.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.entries
+            .iter()
+            .filter_map(|(path, entry)| Some((path, entry.value.as_ref()?)))
+    }
+
+    /// Number of known paths.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of values held in memory.
+    pub fn resident_len(&self) -> usize {
+        self.resident
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Drops the least recently used values once over capacity, down to 90%
+    /// of it so that eviction does not run on every insert.
+    fn evict(&mut self) {
+        if self.capacity == 0 || self.resident <= self.capacity {
+            return;
+        }
+        let target = self.capacity - self.capacity / 10;
+        let mut candidates: Vec<(u64, &String)> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.value.is_some() && !entry.pinned)
+            .map(|(path, entry)| (entry.last_used.load(Ordering::Relaxed), path))
+            .collect();
+        candidates.sort_unstable();
+        let evicted: Vec<String> = candidates
+            .into_iter()
+            .take(self.resident.saturating_sub(target))
+            .map(|(_, path)| path.clone())
+            .collect();
+        for path in evicted {
+            if let Some(entry) = self.entries.get_mut(&path) {
+                entry.value = None;
+                self.resident -= 1;
+            }
+        }
+        log::debug!(
+            "Value cache: {} of {} values in memory",
+            self.resident,
+            self.entries.len()
+        );
+    }
+}
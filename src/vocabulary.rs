@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// The three SSM parameter types.
+pub const PARAMETER_TYPES: [&str; 3] = ["String", "StringList", "SecureString"];
+
+/// Data types of SSM parameters; `aws:ec2:image` values are AMI ids.
+pub const DATA_TYPES: [&str; 2] = ["text", "aws:ec2:image"];
+
+/// Public SSM namespace listing every region code.
+pub const REGIONS_PATH: &str = "/aws/service/global-infrastructure/regions";
+
+/// Used when the region list cannot be fetched from SSM.
+pub const FALLBACK_REGIONS: [&str; 21] = [
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "ca-central-1",
+    "eu-central-1",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "eu-north-1",
+    "ap-northeast-1",
+    "ap-northeast-2",
+    "ap-south-1",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "sa-east-1",
+    "me-south-1",
+    "us-gov-west-1",
+    "us-gov-east-1",
+    "cn-north-1",
+    "cn-northwest-1",
+];
+
+/// Auxiliary completion words that are expensive to fetch, cached on disk
+/// next to the parameter cache.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Vocabulary {
+    pub regions: Vec<String>,
+    pub kms_aliases: Vec<String>,
+}
+
+impl Vocabulary {
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Region names, falling back to the built-in list when none were fetched.
+    pub fn regions(&self) -> Vec<String> {
+        if self.regions.is_empty() {
+            FALLBACK_REGIONS.iter().map(|r| r.to_string()).collect()
+        } else {
+            self.regions.clone()
+        }
+    }
+}
+
+fn aws_dir() -> PathBuf {
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".aws")
+}
+
+/// `~/.aws/config`, or `AWS_CONFIG_FILE` when set.
+pub fn aws_config_file() -> PathBuf {
+    std::env::var("AWS_CONFIG_FILE")
+        .map(Into::into)
+        .unwrap_or_else(|_| aws_dir().join("config"))
+}
+
+/// The profile AWS tools would use: the given one, `AWS_PROFILE`, or `default`.
+pub fn active_profile(profile: Option<&str>) -> String {
+    profile
+        .map(str::to_string)
+        .or_else(|| std::env::var("AWS_PROFILE").ok())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// The `key = value` lines of a profile's section in `~/.aws/config`.
+pub fn profile_settings(profile: &str) -> Vec<(String, String)> {
+    let Ok(content) = std::fs::read_to_string(aws_config_file()) else {
+        return Vec::new();
+    };
+    let header = if profile == "default" {
+        "[default]".to_string()
+    } else {
+        format!("[profile {}]", profile)
+    };
+
+    let mut settings = Vec::new();
+    let mut in_section = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == header;
+        } else if in_section && let Some((key, value)) = line.split_once('=') {
+            settings.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    settings
+}
+
+/// Profile names from `~/.aws/config` (`[profile name]`) and `~/.aws/credentials` (`[name]`).
+/// Read on demand since the files are cheap to parse and may change mid-session.
+pub fn aws_profiles() -> Vec<String> {
+    let credentials_file = std::env::var("AWS_SHARED_CREDENTIALS_FILE")
+        .map(Into::into)
+        .unwrap_or_else(|_| aws_dir().join("credentials"));
+
+    let mut profiles = BTreeSet::new();
+    for file in [aws_config_file(), credentials_file] {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = section.strip_prefix("profile ").unwrap_or(section).trim();
+                if !name.is_empty() && !name.starts_with("sso-session") {
+                    profiles.insert(name.to_string());
+                }
+            }
+        }
+    }
+    profiles.into_iter().collect()
+}
@@ -0,0 +1,96 @@
+use crate::backend::BackendKind;
+use crate::store_paths::StorePaths;
+use crate::utils::parse_region;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A named target from the `[workspaces]` table of the config file: the
+/// profile, region, base paths and cache location `workspace use <name>`
+/// switches to together.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    /// AWS profile; the default credential chain when unset.
+    pub profile: Option<String>,
+    /// Region, in any form `--region` accepts; the current one when unset.
+    pub region: Option<String>,
+    /// Base paths of the workspace. The first is the one opened; the others
+    /// can be picked with `workspace use <name> <path>`.
+    pub base_paths: Vec<String>,
+    /// Store directory for the workspace's cache, resolved like `--store-dir`;
+    /// the shared store when unset.
+    pub store_dir: Option<String>,
+}
+
+impl Workspace {
+    /// The base path opened when switching to the workspace.
+    pub fn base_path(&self) -> &str {
+        self.base_paths.first().map(String::as_str).unwrap_or("/")
+    }
+
+    /// Checks `path` is one of the workspace's base paths, so a workspace
+    /// never points at a prefix it was not configured for.
+    pub fn pick_base_path(&self, name: &str, path: Option<&str>) -> Result<String, String> {
+        let Some(path) = path else {
+            return Ok(self.base_path().to_string());
+        };
+        let wanted = path.trim_end_matches('/');
+        self.base_paths
+            .iter()
+            .find(|base| base.trim_end_matches('/') == wanted)
+            .cloned()
+            .ok_or_else(|| {
+                format!(
+                    "{} is not a base path of workspace '{}' ({})",
+                    path,
+                    name,
+                    self.base_paths.join(", ")
+                )
+            })
+    }
+
+    /// The workspace's own store for `backend`, or `None` when it shares the
+    /// current one.
+    pub fn store(&self, backend: BackendKind) -> Option<StorePaths> {
+        let store_dir = self.store_dir.as_deref()?;
+        let home_dir = directories::BaseDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+        Some(StorePaths::resolve(store_dir, &home_dir).for_backend(backend))
+    }
+}
+
+/// Checks every workspace of the config: base paths must be absolute and
+/// regions must parse.
+pub fn validate(workspaces: &BTreeMap<String, Workspace>) -> Result<(), String> {
+    for (name, workspace) in workspaces {
+        if let Some(path) = workspace.base_paths.iter().find(|p| !p.starts_with('/')) {
+            return Err(format!(
+                "Workspace '{}': base path {} must start with '/'",
+                name, path
+            ));
+        }
+        if let Some(region) = &workspace.region {
+            parse_region(region).map_err(|e| format!("Workspace '{}': {}", name, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Looks up a workspace by name, listing the configured ones when it is missing.
+pub fn find<'a>(
+    workspaces: &'a BTreeMap<String, Workspace>,
+    name: &str,
+) -> Result<&'a Workspace, String> {
+    workspaces.get(name).ok_or_else(|| {
+        if workspaces.is_empty() {
+            format!(
+                "No workspace named '{}'; none are configured in [workspaces]",
+                name
+            )
+        } else {
+            let names: Vec<&str> = workspaces.keys().map(String::as_str).collect();
+            format!("No workspace named '{}' ({})", name, names.join(", "))
+        }
+    })
+}
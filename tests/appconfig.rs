@@ -0,0 +1,58 @@
+use daps::commands::appconfig::{AppConfigArgs, Named, find, render_content};
+
+#[test]
+fn parses_appconfig_arguments() {
+    assert_eq!(AppConfigArgs::parse("apps"), Ok(AppConfigArgs::Apps));
+    assert_eq!(
+        AppConfigArgs::parse("get shop flags"),
+        Ok(AppConfigArgs::Get {
+            app: "shop".to_string(),
+            profile: "flags".to_string(),
+            version: None,
+            env: None,
+        })
+    );
+    assert_eq!(
+        AppConfigArgs::parse("get shop flags --env prod"),
+        Ok(AppConfigArgs::Get {
+            app: "shop".to_string(),
+            profile: "flags".to_string(),
+            version: None,
+            env: Some("prod".to_string()),
+        })
+    );
+    assert!(AppConfigArgs::parse("get shop flags 3 --env prod").is_err());
+    assert!(AppConfigArgs::parse("get shop flags latest").is_err());
+    assert!(AppConfigArgs::parse("versions shop").is_err());
+    assert!(AppConfigArgs::parse("show shop --env prod").is_err());
+}
+
+#[test]
+fn finds_by_name_or_id_and_renders_content() {
+    let apps = vec![
+        Named {
+            id: "a1b2c3".to_string(),
+            name: "shop".to_string(),
+            detail: None,
+        },
+        Named {
+            id: "d4e5f6".to_string(),
+            name: "billing".to_string(),
+            detail: None,
+        },
+    ];
+    assert_eq!(find(&apps, "billing", "application").unwrap().id, "d4e5f6");
+    assert_eq!(find(&apps, "a1b2c3", "application").unwrap().name, "shop");
+    let err = find(&apps, "search", "application").unwrap_err();
+    assert!(err.contains("found: shop, billing"), "{}", err);
+
+    assert_eq!(
+        render_content(br#"{"beta":true}"#, Some("application/json")),
+        "{\n  \"beta\": true\n}"
+    );
+    assert_eq!(
+        render_content(b"beta: true", Some("application/x-yaml")),
+        "beta: true"
+    );
+    assert_eq!(render_content(&[0xff, 0xfe], None), "//4=");
+}
@@ -0,0 +1,355 @@
+mod common;
+
+use common::{completer, encrypted, plaintext, sample_backend};
+use daps::cache_file;
+use daps::commands::cache::cache;
+use daps::commands::gc::gc;
+use daps::helper::ParamStoreHelper;
+use daps::store_paths::StorePaths;
+use rustyline::highlight::MatchingBracketHighlighter;
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn second_session_loads_from_the_cache() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    completer(store.path(), &backend, "/app", plaintext())
+        .load_parameters()
+        .await
+        .unwrap();
+    let calls = backend.total_calls();
+
+    let mut cached = completer(store.path(), &backend, "/app", plaintext());
+    cached.offline = true;
+    cached.load_parameters().await.unwrap();
+
+    assert_eq!(backend.total_calls(), calls);
+    assert!(cached.is_parameter("/app/db/port"));
+    assert_eq!(
+        cached.cached_value("/app/db/password").as_deref(),
+        Some("hunter2")
+    );
+    assert_eq!(
+        cached.types.get("/app/db/password").map(String::as_str),
+        Some("SecureString")
+    );
+}
+
+#[tokio::test]
+async fn writes_reach_the_cache() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut first = completer(store.path(), &backend, "/app", plaintext());
+    first.load_parameters().await.unwrap();
+    first
+        .change_value("/app/feature", "off".to_string())
+        .await
+        .unwrap();
+    first.delete_parameter("/app/db/host").await.unwrap();
+
+    let mut second = completer(store.path(), &backend, "/app", plaintext());
+    second.offline = true;
+    second.load_parameters().await.unwrap();
+
+    assert_eq!(second.cached_value("/app/feature").as_deref(), Some("off"));
+    assert!(!second.is_parameter("/app/db/host"));
+}
+
+#[tokio::test]
+async fn refresh_bypasses_the_cache() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    completer(store.path(), &backend, "/app", plaintext())
+        .load_parameters()
+        .await
+        .unwrap();
+    backend.put("/app/feature", "changed elsewhere", "String");
+
+    let mut refreshed = completer(store.path(), &backend, "/app", plaintext());
+    refreshed.refresh = true;
+    refreshed.load_parameters().await.unwrap();
+
+    assert_eq!(
+        refreshed.cached_value("/app/feature").as_deref(),
+        Some("changed elsewhere")
+    );
+}
+
+#[tokio::test]
+async fn encrypted_cache_hides_values_and_needs_the_key() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let encryption = encrypted(store.path(), "right key");
+    completer(store.path(), &backend, "/app", encryption)
+        .load_parameters()
+        .await
+        .unwrap();
+
+    let values = std::fs::read_to_string(StorePaths::new(store.path()).values("/app")).unwrap();
+    assert!(!values.contains("hunter2"));
+
+    let mut reopened = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "right key"),
+    );
+    reopened.offline = true;
+    reopened.load_parameters().await.unwrap();
+    assert_eq!(
+        reopened.cached_value("/app/db/password").as_deref(),
+        Some("hunter2")
+    );
+
+    let mut wrong = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "wrong key"),
+    );
+    wrong.offline = true;
+    assert!(wrong.load_parameters().await.is_err());
+}
+
+#[tokio::test]
+async fn revisions_are_cached_next_to_types() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut first = completer(store.path(), &backend, "/app", plaintext());
+    first.load_parameters().await.unwrap();
+    first
+        .change_value("/app/feature", "off".to_string())
+        .await
+        .unwrap();
+    // The cached type is enough to keep it; no fetch before the write.
+    assert_eq!(backend.calls("GetParameter"), 0);
+
+    let mut second = completer(store.path(), &backend, "/app", plaintext());
+    second.offline = true;
+    second.load_parameters().await.unwrap();
+
+    let revision = second.revisions["/app/feature"];
+    assert_eq!(revision.version, Some(2));
+    assert!(revision.last_modified.is_some());
+    let metadata = second.cached_metadata("/app/db/");
+    assert_eq!(metadata.len(), 3);
+    assert!(
+        metadata
+            .iter()
+            .all(|m| m.version == Some(1) && m.last_modified_date.is_some())
+    );
+}
+
+#[tokio::test]
+async fn reads_types_files_without_revisions() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    completer(store.path(), &backend, "/app", plaintext())
+        .load_parameters()
+        .await
+        .unwrap();
+    let types: HashMap<String, String> =
+        [("/app/db/password".to_string(), "SecureString".to_string())].into();
+    cache_file::write_map(
+        &StorePaths::new(store.path()).types("/app"),
+        &types,
+        &plaintext(),
+    )
+    .unwrap();
+
+    let mut cached = completer(store.path(), &backend, "/app", plaintext());
+    cached.offline = true;
+    cached.load_parameters().await.unwrap();
+
+    assert_eq!(
+        cached.types.get("/app/db/password").map(String::as_str),
+        Some("SecureString")
+    );
+    assert!(cached.revisions.is_empty());
+}
+
+#[tokio::test]
+async fn gc_drops_parameters_deleted_in_aws() {
+    let store = tempfile::tempdir().unwrap();
+    let other_store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    // Another session deletes the whole /app/db branch.
+    let mut other = common::completer(other_store.path(), &backend, "/app", plaintext());
+    other.load_parameters().await.unwrap();
+    for path in ["/app/db/host", "/app/db/port", "/app/db/password"] {
+        other.delete_parameter(path).await.unwrap();
+    }
+
+    gc(&mut helper, "--dry-run").await.unwrap();
+    assert!(helper.completer.is_parameter("/app/db/host"));
+
+    gc(&mut helper, "").await.unwrap();
+    let completer = &helper.completer;
+    assert!(!completer.is_parameter("/app/db/host"));
+    assert!(!completer.parameters.contains_key("/app/db"));
+    assert_eq!(completer.get_completions("/app/"), ["/app/feature"]);
+
+    let mut cached = common::completer(store.path(), &backend, "/app", plaintext());
+    cached.offline = true;
+    cached.load_parameters().await.unwrap();
+    assert_eq!(cached.parameters_under("/app/"), ["/app/feature"]);
+    assert_eq!(cached.cached_value("/app/db/port"), None);
+}
+
+#[tokio::test]
+async fn cache_verify_finds_undecryptable_values_and_rebuild_repairs_them() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let encryption = encrypted(store.path(), "verify key");
+    let mut completer = completer(store.path(), &backend, "/app", encryption);
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    cache(&mut helper, "verify").await.unwrap();
+
+    let values_file = StorePaths::new(store.path()).values("/app");
+    let key = encrypted(store.path(), "verify key");
+    let mut stored: HashMap<String, String> =
+        cache_file::read_map(&values_file, &key, str::to_string).unwrap();
+    stored.insert(
+        "/app/db/host".to_string(),
+        "v2.bm90IGVuY3J5cHRlZA==".to_string(),
+    );
+    cache_file::write_map(&values_file, &stored, &key).unwrap();
+
+    let err = cache(&mut helper, "verify").await.unwrap_err();
+    assert!(err.to_string().contains("1 of 3"), "{}", err);
+
+    cache(&mut helper, "rebuild").await.unwrap();
+    cache(&mut helper, "verify").await.unwrap();
+    assert_eq!(
+        helper.completer.cached_value("/app/db/host").as_deref(),
+        Some("db.internal")
+    );
+    assert!(cache(&mut helper, "clear").await.is_err());
+}
+
+#[tokio::test]
+async fn refresh_detects_queued_changes_that_changed_in_aws() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    completer(store.path(), &backend, "/app", plaintext())
+        .load_parameters()
+        .await
+        .unwrap();
+
+    let mut offline = completer(store.path(), &backend, "/app", plaintext());
+    offline.offline = true;
+    offline.load_parameters().await.unwrap();
+    offline.queue_change("/app/feature", "local", None).unwrap();
+    offline.queue_change("/app/db/port", "6432", None).unwrap();
+    offline
+        .queue_change("/app/new", "mine", Some("String".to_string()))
+        .unwrap();
+    backend.put("/app/feature", "remote", "String");
+    backend.put("/app/new", "theirs", "String");
+
+    let mut online = completer(store.path(), &backend, "/app", plaintext());
+    online.refresh = true;
+    online.load_parameters().await.unwrap();
+    let conflicts: Vec<String> = online
+        .pending_conflicts()
+        .into_iter()
+        .map(|change| change.path)
+        .collect();
+    assert_eq!(conflicts, ["/app/feature", "/app/new"]);
+    // The queued value still shows until the conflict is resolved.
+    assert_eq!(
+        online.cached_value("/app/feature").as_deref(),
+        Some("local")
+    );
+
+    online.keep_local("/app/feature").unwrap();
+    online
+        .take_remote("/app/new", Some("theirs".to_string()))
+        .unwrap();
+    assert!(online.pending_conflicts().is_empty());
+    assert_eq!(online.cached_value("/app/new").as_deref(), Some("theirs"));
+    let queued: Vec<&str> = online
+        .pending
+        .changes
+        .iter()
+        .map(|change| change.path.as_str())
+        .collect();
+    assert_eq!(queued, ["/app/feature", "/app/db/port"]);
+}
+
+#[tokio::test]
+async fn queued_changes_are_encrypted_on_disk() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut offline = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "right key"),
+    );
+    offline.offline = true;
+    offline
+        .queue_change("/app/db/password", "s3cret-queued", None)
+        .unwrap();
+
+    let file = StorePaths::new(store.path()).pending("/app");
+    let queued = std::fs::read_to_string(&file).unwrap();
+    assert!(queued.contains("/app/db/password"));
+    assert!(!queued.contains("s3cret-queued"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let reopened = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "right key"),
+    );
+    assert_eq!(
+        reopened.pending.get("/app/db/password").unwrap().value,
+        "s3cret-queued"
+    );
+
+    let mut unkeyed = completer(
+        store.path(),
+        &backend,
+        "/app",
+        daps::encryption::Encryption::new(false, "", &[]).unwrap(),
+    );
+    unkeyed.offline = true;
+    assert!(unkeyed.queue_change("/app/feature", "off", None).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn private_files_are_never_readable_by_others() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("pins.json");
+    let leftover = dir.path().join("pins.json.tmp");
+    std::fs::write(&leftover, "stale").unwrap();
+    std::fs::set_permissions(&leftover, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    cache_file::write_private(&file, "{}").unwrap();
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "{}");
+    let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert!(!leftover.exists());
+}
@@ -0,0 +1,104 @@
+use daps::chunking::{
+    CHUNK_SIZE, assemble_all, chunk_path, hide_chunks, is_chunk_path, manifest, reassemble, split,
+};
+use std::collections::HashMap;
+
+/// `value` as the parameters a chunked write leaves behind at `path`.
+fn chunked(path: &str, value: &str) -> HashMap<String, String> {
+    let chunks = split(value);
+    let mut values: HashMap<String, String> = chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| (chunk_path(path, index), chunk.to_string()))
+        .collect();
+    values.insert(path.to_string(), manifest(value, chunks.len()));
+    values
+}
+
+#[test]
+fn split_never_cuts_a_character_in_half() {
+    let value = format!("{}é{}", "a".repeat(CHUNK_SIZE - 1), "b".repeat(10));
+    let chunks = split(&value);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].len(), CHUNK_SIZE - 1);
+    assert!(chunks[1].starts_with('é'));
+    assert!(chunks.iter().all(|chunk| chunk.len() <= CHUNK_SIZE));
+    assert_eq!(chunks.concat(), value);
+
+    let wide = "€".repeat(CHUNK_SIZE);
+    let chunks = split(&wide);
+    assert!(chunks.iter().all(|chunk| chunk.len() <= CHUNK_SIZE));
+    assert_eq!(chunks.concat(), wide);
+    assert!(split("").is_empty());
+}
+
+#[test]
+fn reassemble_checks_every_chunk_and_the_digest() {
+    let value = "x".repeat(CHUNK_SIZE * 2 + 1);
+    let values = chunked("/app/big", &value);
+    let head = &values["/app/big"];
+    let lookup = |chunk: &str| values.get(chunk).map(String::as_str);
+    assert_eq!(reassemble("/app/big", head, lookup).unwrap(), value);
+
+    let mut missing = values.clone();
+    missing.remove(&chunk_path("/app/big", 1));
+    let err = reassemble("/app/big", head, |chunk| {
+        missing.get(chunk).map(String::as_str)
+    })
+    .unwrap_err();
+    assert!(err.contains("missing"), "{}", err);
+
+    let mut changed = values.clone();
+    changed.insert(chunk_path("/app/big", 2), "y".to_string());
+    let err = reassemble("/app/big", head, |chunk| {
+        changed.get(chunk).map(String::as_str)
+    })
+    .unwrap_err();
+    assert!(err.contains("do not match"), "{}", err);
+
+    assert!(reassemble("/app/big", "not a manifest", lookup).is_err());
+}
+
+#[test]
+fn assemble_all_restores_values_and_keeps_broken_manifests() {
+    let value = "z".repeat(CHUNK_SIZE + 5);
+    let mut values = chunked("/app/big", &value);
+    let broken = chunked("/app/broken", &value);
+    values.insert("/app/broken".to_string(), broken["/app/broken"].clone());
+    values.insert("/app/small".to_string(), "plain".to_string());
+
+    let errors = assemble_all(&mut values);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("/app/broken"), "{}", errors[0]);
+    assert_eq!(values["/app/big"], value);
+    assert_eq!(values["/app/small"], "plain");
+    assert_eq!(values["/app/broken"], broken["/app/broken"]);
+    assert!(values.keys().all(|path| !is_chunk_path(path)));
+}
+
+#[test]
+fn hide_chunks_drops_chunk_entries_from_the_tree() {
+    let mut parameters: HashMap<String, Vec<String>> = [
+        ("/app", vec!["big", "small"]),
+        ("/app/big", vec![".chunk0", ".chunk1"]),
+        ("/app/big/.chunk0", vec![]),
+        ("/app/big/.chunk1", vec![]),
+        ("/app/small", vec![]),
+    ]
+    .into_iter()
+    .map(|(path, children)| {
+        let children = children.into_iter().map(str::to_string).collect();
+        (path.to_string(), children)
+    })
+    .collect();
+
+    hide_chunks(&mut parameters);
+    let mut paths: Vec<&String> = parameters.keys().collect();
+    paths.sort();
+    assert_eq!(paths, ["/app", "/app/big", "/app/small"]);
+    assert!(parameters["/app/big"].is_empty());
+    assert_eq!(parameters["/app"], ["big", "small"]);
+    assert!(is_chunk_path("/app/big/.chunk12"));
+    assert!(!is_chunk_path("/app/big/.chunk"));
+    assert!(!is_chunk_path("/app/big/.chunkx"));
+}
@@ -0,0 +1,72 @@
+//! Shared setup for the integration tests: a completer wired to a
+//! `MockBackend` and a throwaway store directory.
+#![allow(dead_code)]
+
+use daps::backend::BackendKind;
+use daps::client::ClientOptions;
+use daps::completer::ParameterCompleter;
+use daps::encryption::Encryption;
+use daps::mock_backend::MockBackend;
+use daps::retry::RetryPolicy;
+use daps::store_paths::StorePaths;
+use rusoto_core::Region;
+use std::path::Path;
+use std::time::Duration;
+
+/// Retries without waiting and without a rate limit, so tests stay fast.
+pub fn client_options() -> ClientOptions {
+    let mut retry = RetryPolicy::new(3, 0.0);
+    retry.base_delay = Duration::from_millis(1);
+    retry.max_delay = Duration::from_millis(1);
+    ClientOptions {
+        retry,
+        ..Default::default()
+    }
+}
+
+/// No key: values are cached in plaintext, which the tests allow.
+pub fn plaintext() -> Encryption {
+    let mut encryption = Encryption::new(false, "", &[]).unwrap();
+    encryption.allow_plaintext = true;
+    encryption
+}
+
+/// Encryption with `secret` and the store's salt, created on first use.
+pub fn encrypted(store: &Path, secret: &str) -> Encryption {
+    let salt = daps::encryption::load_or_create_salt(&StorePaths::new(store).salt()).unwrap();
+    Encryption::new(true, secret, &salt).unwrap()
+}
+
+/// A completer for `base_path` that talks to `backend` and caches in `store`.
+pub fn completer(
+    store: &Path,
+    backend: &MockBackend,
+    base_path: &str,
+    encryption: Encryption,
+) -> ParameterCompleter {
+    let mut completer = ParameterCompleter::new(
+        BackendKind::Ssm,
+        Region::UsEast1,
+        base_path.to_string(),
+        false,
+        StorePaths::new(store),
+        encryption,
+        client_options(),
+    )
+    .unwrap();
+    completer.backend = Box::new(backend.clone());
+    completer.quiet = true;
+    completer
+}
+
+/// A store with a few parameters under `/app` and one outside it.
+pub fn sample_backend() -> MockBackend {
+    let backend = MockBackend::with_parameters([
+        ("/app/db/host", "db.internal"),
+        ("/app/db/port", "5432"),
+        ("/app/feature", "on"),
+        ("/other/key", "elsewhere"),
+    ]);
+    backend.put("/app/db/password", "hunter2", "SecureString");
+    backend
+}
@@ -0,0 +1,235 @@
+//! End-to-end tests against LocalStack, built only with `--features e2e`:
+//!
+//! ```sh
+//! docker run -d -p 4566:4566 localstack/localstack
+//! AWS_ACCESS_KEY_ID=test AWS_SECRET_ACCESS_KEY=test cargo test --features e2e --test e2e
+//! ```
+//!
+//! `DAPS_E2E_ENDPOINT` overrides the endpoint (default `http://localhost:4566`).
+//! Every test works under its own random prefix and deletes what it created.
+#![cfg(feature = "e2e")]
+
+mod common;
+
+use common::{client_options, plaintext};
+use daps::backend::BackendKind;
+use daps::client::build_kms_client;
+use daps::completer::{NewParameter, ParameterCompleter};
+use daps::store_paths::StorePaths;
+use rusoto_core::Region;
+use rusoto_kms::{CreateAliasRequest, CreateKeyRequest, Kms};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+fn endpoint() -> String {
+    std::env::var("DAPS_E2E_ENDPOINT").unwrap_or_else(|_| "http://localhost:4566".to_string())
+}
+
+/// A prefix no other run uses, e.g. `/daps-e2e/1718000000123-4242`.
+fn unique_prefix() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!("/daps-e2e/{}-{}", nanos, std::process::id())
+}
+
+fn localstack(store: &std::path::Path, base_path: &str, endpoint: &str) -> ParameterCompleter {
+    let mut options = client_options();
+    options.endpoint_url = Some(endpoint.to_string());
+    let mut completer = ParameterCompleter::new(
+        BackendKind::Ssm,
+        Region::UsEast1,
+        base_path.to_string(),
+        true,
+        StorePaths::new(store),
+        plaintext(),
+        options,
+    )
+    .unwrap();
+    completer.quiet = true;
+    completer
+}
+
+fn new_parameter(path: &str, value: &str, param_type: &str) -> NewParameter {
+    NewParameter {
+        path: path.to_string(),
+        value: value.to_string(),
+        param_type: param_type.to_string(),
+        key_id: None,
+        description: None,
+        tags: Vec::new(),
+    }
+}
+
+async fn cleanup(completer: &mut ParameterCompleter, prefix: &str) {
+    for path in completer.parameters_under(prefix) {
+        let _ = completer.delete_parameter(&path).await;
+    }
+}
+
+#[tokio::test]
+async fn loads_every_page() {
+    let store = tempfile::tempdir().unwrap();
+    let prefix = unique_prefix();
+    let mut completer = localstack(store.path(), &prefix, &endpoint());
+    // GetParametersByPath returns at most 10 per page.
+    for i in 0..25 {
+        let path = format!("{}/p{:02}", prefix, i);
+        completer
+            .create_parameter(&new_parameter(&path, &i.to_string(), "String"))
+            .await
+            .unwrap();
+    }
+
+    completer.load_parameters().await.unwrap();
+
+    let counters = completer.stats.snapshot();
+    assert_eq!(completer.parameters_under(&prefix).len(), 25);
+    assert_eq!(
+        completer
+            .cached_value(&format!("{}/p24", prefix))
+            .as_deref(),
+        Some("24")
+    );
+    assert!(counters.operations["GetParametersByPath"].calls >= 3);
+    cleanup(&mut completer, &prefix).await;
+}
+
+#[tokio::test]
+async fn retries_throttling_responses() {
+    let store = tempfile::tempdir().unwrap();
+    let prefix = unique_prefix();
+    let proxy = throttling_proxy(2).await;
+    let mut completer = localstack(store.path(), &prefix, &proxy);
+
+    completer
+        .create_parameter(&new_parameter(
+            &format!("{}/key", prefix),
+            "value",
+            "String",
+        ))
+        .await
+        .unwrap();
+
+    let put = &completer.stats.snapshot().operations["PutParameter"];
+    assert_eq!(put.throttled, 2);
+    assert_eq!(put.calls, 3);
+    cleanup(&mut completer, &prefix).await;
+}
+
+#[tokio::test]
+async fn secure_strings_round_trip_through_kms() {
+    let store = tempfile::tempdir().unwrap();
+    let prefix = unique_prefix();
+    let mut completer = localstack(store.path(), &prefix, &endpoint());
+
+    let kms = build_kms_client(Region::UsEast1, &completer.client_options).unwrap();
+    let key = kms
+        .create_key(CreateKeyRequest::default())
+        .await
+        .unwrap()
+        .key_metadata
+        .unwrap();
+    let alias = format!("alias/daps-e2e-{}", prefix.rsplit('/').next().unwrap());
+    kms.create_alias(CreateAliasRequest {
+        alias_name: alias.clone(),
+        target_key_id: key.key_id.clone(),
+    })
+    .await
+    .unwrap();
+
+    let path = format!("{}/db/password", prefix);
+    let mut secret = new_parameter(&path, "hunter2", "SecureString");
+    secret.key_id = Some(alias.clone());
+    completer.create_parameter(&secret).await.unwrap();
+
+    let fetched = completer.fetch_parameter(&path).await.unwrap().unwrap();
+    assert_eq!(fetched.value.as_deref(), Some("hunter2"));
+    assert_eq!(fetched.type_.as_deref(), Some("SecureString"));
+
+    let metadata = completer.describe_parameters(&path).await.unwrap();
+    assert_eq!(metadata.len(), 1);
+    assert!(metadata[0].key_id.is_some());
+
+    completer.load_vocabulary(true).await;
+    assert!(completer.vocabulary.kms_aliases.contains(&alias));
+    cleanup(&mut completer, &prefix).await;
+}
+
+/// A proxy in front of LocalStack that answers the first `count` requests
+/// with the response AWS sends when throttling, then forwards the rest.
+/// Returns its endpoint URL.
+async fn throttling_proxy(count: usize) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let upstream = endpoint()
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_string();
+    let remaining = Arc::new(AtomicUsize::new(count));
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut client, _)) = listener.accept().await else {
+                break;
+            };
+            let upstream = upstream.clone();
+            let remaining = Arc::clone(&remaining);
+            tokio::spawn(async move {
+                let request = read_request(&mut client).await;
+                let throttle = remaining
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                if throttle {
+                    let body = r#"{"__type":"ThrottlingException","message":"Rate exceeded"}"#;
+                    let response = format!(
+                        "HTTP/1.1 400 Bad Request\r\nContent-Type: application/x-amz-json-1.1\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = client.write_all(response.as_bytes()).await;
+                    return;
+                }
+                let Ok(mut server) = TcpStream::connect(&upstream).await else {
+                    return;
+                };
+                if server.write_all(&request).await.is_ok() {
+                    let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+                }
+            });
+        }
+    });
+    format!("http://{}", address)
+}
+
+/// Reads one HTTP/1.1 request: the headers and a `Content-Length` body.
+async fn read_request(stream: &mut TcpStream) -> Vec<u8> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let Ok(read) = stream.read(&mut buffer).await else {
+            return request;
+        };
+        if read == 0 {
+            return request;
+        }
+        request.extend_from_slice(&buffer[..read]);
+        let text = String::from_utf8_lossy(&request);
+        let Some(end) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let length = text[..end]
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())?
+            })
+            .unwrap_or(0);
+        if request.len() >= end + 4 + length {
+            return request;
+        }
+    }
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use common::{completer, encrypted, sample_backend};
+use daps::mock_backend::MockBackend;
+use daps::store_paths::StorePaths;
+
+/// Writes the header-less cache files older versions of daps produced, with
+/// values in plaintext.
+fn write_legacy_cache(store: &std::path::Path) {
+    let paths = StorePaths::new(store);
+    std::fs::write(paths.parameters("/app"), "/app/token:\n/app/url:\n").unwrap();
+    std::fs::write(
+        paths.values("/app"),
+        "/app/token: s3cret\n/app/url: https://example.com\n",
+    )
+    .unwrap();
+}
+
+#[tokio::test]
+async fn migrates_a_legacy_plaintext_cache() {
+    let store = tempfile::tempdir().unwrap();
+    write_legacy_cache(store.path());
+    let backend = MockBackend::new();
+    let legacy = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "key"),
+    );
+
+    // Plaintext could also be ciphertext under another passphrase, so it is
+    // only encrypted as it is when asked to.
+    let values_file = StorePaths::new(store.path()).values("/app");
+    let before = std::fs::read_to_string(&values_file).unwrap();
+    let refused = legacy.migrate_encryption(false).await.unwrap_err();
+    assert!(refused.to_string().contains("/app/token"), "{}", refused);
+    assert_eq!(std::fs::read_to_string(&values_file).unwrap(), before);
+
+    legacy.migrate_encryption(true).await.unwrap();
+
+    let values = std::fs::read_to_string(StorePaths::new(store.path()).values("/app")).unwrap();
+    assert!(!values.contains("s3cret"));
+
+    let mut migrated = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "key"),
+    );
+    migrated.offline = true;
+    migrated.load_parameters().await.unwrap();
+    assert_eq!(
+        migrated.cached_value("/app/token").as_deref(),
+        Some("s3cret")
+    );
+    assert_eq!(
+        migrated.cached_value("/app/url").as_deref(),
+        Some("https://example.com")
+    );
+    assert_eq!(backend.total_calls(), 0);
+}
+
+#[tokio::test]
+async fn rotates_the_key_of_every_cache_file() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut session = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "old"),
+    );
+    session.load_parameters().await.unwrap();
+
+    assert_eq!(session.rotate_key("new").unwrap(), 4);
+
+    // The salt changed too, so the key is derived again from the new file.
+    let mut rotated = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "new"),
+    );
+    rotated.offline = true;
+    rotated.load_parameters().await.unwrap();
+    assert_eq!(
+        rotated.cached_value("/app/db/password").as_deref(),
+        Some("hunter2")
+    );
+
+    let mut stale = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "old"),
+    );
+    stale.offline = true;
+    assert!(stale.load_parameters().await.is_err());
+}
+
+#[tokio::test]
+async fn switching_stores_keeps_the_cache_key_usable() {
+    let store = tempfile::tempdir().unwrap();
+    let other = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "key"),
+    );
+
+    // A store without a salt of its own takes the current one.
+    let fresh = StorePaths::new(other.path().join("fresh"));
+    completer.switch_store(fresh.clone()).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(fresh.salt()).unwrap(),
+        std::fs::read_to_string(StorePaths::new(store.path()).salt()).unwrap()
+    );
+    completer.load_parameters().await.unwrap();
+    assert!(fresh.values("/app").exists());
+
+    // One with its own salt was written with another key.
+    let foreign = StorePaths::new(other.path().join("foreign"));
+    daps::encryption::load_or_create_salt(&foreign.salt()).unwrap();
+    assert!(completer.switch_store(foreign).is_err());
+    assert_eq!(completer.store.root(), fresh.root());
+}
+
+#[tokio::test]
+async fn pins_are_keyed_and_kept_per_target() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(
+        store.path(),
+        &backend,
+        "/app",
+        encrypted(store.path(), "key"),
+    );
+    completer.pins.pin("/app/db/password", "hunter2");
+    completer.pins.save().unwrap();
+
+    let file = StorePaths::new(store.path()).pins();
+    let saved = std::fs::read_to_string(&file).unwrap();
+    assert!(saved.contains("hmac_sha256"));
+    // The unkeyed hash of the value would let it be brute-forced.
+    assert!(!saved.contains("f52fbd32b2b3b86ff88ef6c490628285f482af15ddcb29541f94bcf526a3f6c7"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    assert!(
+        completer
+            .pins
+            .check("/app/db/password", Some("hunter2"))
+            .is_none()
+    );
+    assert!(
+        completer
+            .pins
+            .check("/app/db/password", Some("hunter3"))
+            .is_some()
+    );
+
+    // The same path in another region is another parameter.
+    completer
+        .switch_client(rusoto_core::Region::EuWest1, None)
+        .unwrap();
+    assert!(completer.pins.entries.is_empty());
+    completer
+        .switch_client(rusoto_core::Region::UsEast1, None)
+        .unwrap();
+    assert!(completer.pins.entries.contains_key("/app/db/password"));
+}
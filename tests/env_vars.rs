@@ -0,0 +1,120 @@
+use daps::env_vars::{EnvFormat, EnvMapping, render};
+use std::collections::BTreeMap;
+
+fn values(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries
+        .iter()
+        .map(|(path, value)| (path.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn names_variables_after_the_relative_path_in_order() {
+    let values = values(&[
+        ("/dev/app/db/port", "5432"),
+        ("/dev/app/api-key", "it's secret"),
+        ("/dev/app/db/host", "db.internal"),
+        ("/dev/app/2fa", "on"),
+    ]);
+
+    let shell = render(
+        &values,
+        "/dev/app/",
+        EnvFormat::Shell,
+        &EnvMapping::default(),
+    )
+    .unwrap();
+    let docker = render(
+        &values,
+        "/dev/app/",
+        EnvFormat::Docker,
+        &EnvMapping::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        shell,
+        "export API_KEY='it'\\''s secret'\nexport DB_HOST='db.internal'\n\
+         export DB_PORT='5432'\nexport _2FA='on'\n"
+    );
+    assert_eq!(
+        docker,
+        "API_KEY=it's secret\nDB_HOST=db.internal\nDB_PORT=5432\n_2FA=on\n"
+    );
+}
+
+#[test]
+fn rejects_colliding_names_and_multiline_env_file_values() {
+    let colliding = values(&[("/app/db-host", "a"), ("/app/db_host", "b")]);
+    let err = render(
+        &colliding,
+        "/app/",
+        EnvFormat::Shell,
+        &EnvMapping::default(),
+    )
+    .unwrap_err();
+    assert!(err.contains("/app/db-host") && err.contains("/app/db_host"));
+
+    let multiline = values(&[("/app/cert", "line one\nline two")]);
+    assert_eq!(
+        render(
+            &multiline,
+            "/app/",
+            EnvFormat::Shell,
+            &EnvMapping::default()
+        )
+        .unwrap(),
+        "export CERT='line one\nline two'\n"
+    );
+    assert!(
+        render(
+            &multiline,
+            "/app/",
+            EnvFormat::Docker,
+            &EnvMapping::default()
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn mapping_rules_rename_prefix_and_keep_case() {
+    let values = values(&[
+        ("/prod/app/db/url", "postgres://db"),
+        ("/prod/app/api-key", "say \"hi\"\nbye"),
+        ("/prod/app/log_level", "info"),
+    ]);
+    let mut mapping = EnvMapping {
+        name_prefix: "APP_".to_string(),
+        ..EnvMapping::default()
+    };
+    mapping
+        .renames
+        .insert("/prod/app/db/url".to_string(), "DATABASE_URL".to_string());
+
+    assert_eq!(
+        render(&values, "/prod/app/", EnvFormat::Dotenv, &mapping).unwrap(),
+        "APP_API_KEY=\"say \\\"hi\\\"\\nbye\"\nAPP_LOG_LEVEL=\"info\"\nDATABASE_URL=\"postgres://db\"\n"
+    );
+
+    let kept = EnvMapping {
+        strip_prefix: false,
+        uppercase: false,
+        ..EnvMapping::default()
+    };
+    assert_eq!(
+        kept.name("/prod/app/log_level", "/prod/app/"),
+        "prod_app_log_level"
+    );
+
+    mapping
+        .renames
+        .insert("/prod/app/log_level".to_string(), "APP_API_KEY".to_string());
+    let err = render(&values, "/prod/app/", EnvFormat::Dotenv, &mapping).unwrap_err();
+    assert!(err.contains("/prod/app/api-key") && err.contains("/prod/app/log_level"));
+
+    mapping
+        .renames
+        .insert("/prod/app/x".to_string(), "1-bad".to_string());
+    assert!(mapping.validate().is_err());
+}
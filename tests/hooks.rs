@@ -0,0 +1,45 @@
+use daps::hooks::Hooks;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+fn hooks(entries: &[(&str, &str)]) -> Result<Hooks, String> {
+    let entries: BTreeMap<String, String> = entries
+        .iter()
+        .map(|(key, script)| (key.to_string(), script.to_string()))
+        .collect();
+    Hooks::new(entries)
+}
+
+#[test]
+fn rejects_hooks_for_unknown_stages_or_commands() {
+    assert!(hooks(&[("post_set", "true"), ("pre_mv-tree", "true")]).is_ok());
+    assert!(
+        hooks(&[("after_set", "true")])
+            .unwrap_err()
+            .contains("pre_<command>")
+    );
+    assert!(
+        hooks(&[("post_sett", "true")])
+            .unwrap_err()
+            .contains("'sett'")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_hooks_with_the_command_on_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("hook.json");
+    let script = format!("cat > '{}'; test \"$DAPS_HOOK\" = post_set", out.display());
+    let hooks = hooks(&[("post_set", script.as_str()), ("pre_delete", "exit 3")]).unwrap();
+    let payload = json!({ "command": "set", "args": "/app/feature off" });
+
+    hooks.run("post", "set", &payload).unwrap();
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(written, payload);
+
+    let err = hooks.run("pre", "delete", &payload).unwrap_err();
+    assert!(err.contains("pre_delete"), "{}", err);
+    assert!(hooks.run("pre", "set", &payload).is_ok());
+}
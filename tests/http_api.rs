@@ -0,0 +1,123 @@
+mod common;
+
+use common::{completer, plaintext, sample_backend};
+use daps::http_api::{self, HttpOptions, Mask};
+use hyper::{Body, Client, Request, StatusCode};
+use serde_json::Value;
+
+/// Starts the API over the sample store and returns its base URL; the server
+/// stops when the returned sender is dropped.
+async fn start(options: HttpOptions) -> (String, tokio::sync::oneshot::Sender<()>) {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let _store = store;
+        let shutdown = async {
+            let _ = stopped.await;
+        };
+        http_api::run(completer, listener, options, shutdown)
+            .await
+            .unwrap();
+    });
+    (url, stop)
+}
+
+async fn get(url: &str, token: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::get(url);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    let response = Client::new()
+        .request(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn lists_and_gets_from_the_cache() {
+    let (url, _stop) = start(HttpOptions::default()).await;
+
+    let (status, body) = get(&format!("{}/parameters?prefix=%2Fapp%2Fdb", url), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let paths: Vec<&str> = body["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["/app/db/host", "/app/db/password", "/app/db/port"]);
+
+    let (status, body) = get(&format!("{}/parameter/app/db/port", url), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["value"], "5432");
+
+    let (status, _) = get(&format!("{}/parameter/app/missing", url), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn requires_the_token() {
+    let options = HttpOptions {
+        token: Some("letmein".to_string()),
+        mask: Mask::None,
+    };
+    let (url, _stop) = start(options).await;
+    let parameter = format!("{}/parameter/app/feature", url);
+
+    assert_eq!(get(&parameter, None).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        get(&parameter, Some("guess")).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    let (status, body) = get(&parameter, Some("letmein")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["value"], "on");
+}
+
+#[tokio::test]
+async fn masks_secure_values() {
+    let options = HttpOptions {
+        token: None,
+        mask: Mask::Secure,
+    };
+    let (url, _stop) = start(options).await;
+
+    let (_, secret) = get(&format!("{}/parameter/app/db/password", url), None).await;
+    assert_eq!(secret["masked"], true);
+    assert!(secret.get("value").is_none());
+
+    let (_, plain) = get(&format!("{}/parameter/app/db/host", url), None).await;
+    assert_eq!(plain["value"], "db.internal");
+}
+
+#[tokio::test]
+async fn rejects_other_hosts_on_loopback() {
+    let (url, _stop) = start(HttpOptions::default()).await;
+    let port = url.rsplit(':').next().unwrap();
+    let request = |host: String| {
+        Request::get(format!("{}/parameter/app/feature", url))
+            .header("Host", host)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let rebound = Client::new()
+        .request(request(format!("attacker.example:{}", port)))
+        .await
+        .unwrap();
+    assert_eq!(rebound.status(), StatusCode::MISDIRECTED_REQUEST);
+    let local = Client::new()
+        .request(request(format!("localhost:{}", port)))
+        .await
+        .unwrap();
+    assert_eq!(local.status(), StatusCode::OK);
+}
@@ -0,0 +1,121 @@
+use daps::commands::insert::{InsertArgs, inserted_path};
+use daps::history::redact;
+use daps::utils::split_words;
+
+fn args(path: &str, value: &str, param_type: Option<&str>) -> InsertArgs {
+    InsertArgs {
+        path: path.to_string(),
+        value: value.to_string(),
+        param_type: param_type.map(str::to_string),
+    }
+}
+
+#[test]
+fn parses_the_colon_grammar() {
+    let cases = [
+        ("/p:hello", args("/p", "hello", None)),
+        (
+            "/p:hello world:String",
+            args("/p", "hello world", Some("String")),
+        ),
+        ("/p::SecureString", args("/p", "", Some("SecureString"))),
+        (
+            "/p:postgres://u:p@h:5432/db:SecureString",
+            args("/p", "postgres://u:p@h:5432/db", Some("SecureString")),
+        ),
+        (r"/p:http\://host", args("/p", "http://host", None)),
+    ];
+    for (raw, expected) in cases {
+        assert_eq!(InsertArgs::parse(raw).unwrap(), expected, "{}", raw);
+    }
+    let err = InsertArgs::parse("/p:http://host").unwrap_err();
+    assert!(err.contains("Unknown parameter type '//host'"), "{}", err);
+}
+
+#[test]
+fn parses_the_quoted_grammar() {
+    let cases = [
+        (
+            r#"/p "postgres://u:p@h:5432/db" SecureString"#,
+            args("/p", "postgres://u:p@h:5432/db", Some("SecureString")),
+        ),
+        (
+            "/p 'it is \"quoted\"'",
+            args("/p", "it is \"quoted\"", None),
+        ),
+        (
+            r#"/p "" SecureString"#,
+            args("/p", "", Some("SecureString")),
+        ),
+        (r"/p two\ words", args("/p", "two words", None)),
+    ];
+    for (raw, expected) in cases {
+        assert_eq!(InsertArgs::parse(raw).unwrap(), expected, "{}", raw);
+    }
+    assert!(InsertArgs::parse("/p").is_err());
+    assert!(InsertArgs::parse("/p two words String").is_err());
+    assert!(split_words(r#"/p "open"#).is_err());
+}
+
+#[test]
+fn history_redacts_both_grammars() {
+    assert_eq!(
+        redact("insert /p:secret:SecureString"),
+        "insert /p:<redacted>"
+    );
+    assert_eq!(
+        redact(r#"insert /p "a:b" SecureString"#),
+        "insert /p <redacted>"
+    );
+}
+
+#[test]
+fn history_redacts_every_value_taking_command() {
+    let cases = [
+        ("set hunter2", "set <redacted>"),
+        (
+            "set /app/db/password hunter2",
+            "set /app/db/password <redacted>",
+        ),
+        ("set #2 hunter2 --yes", "set #2 <redacted>"),
+        ("set /just-a-value", "set <redacted>"),
+        (
+            r#"set-many /app db/password="a b" db/port=5432 --yes"#,
+            "set-many /app db/password=<redacted> db/port=<redacted> --yes",
+        ),
+        (
+            "set-many /app --from-file pairs.env",
+            "set-many /app --from-file pairs.env",
+        ),
+        ("set-many /app k='unterminated", "set-many <redacted>"),
+        (
+            "set-binary /app/keystore key.jks",
+            "set-binary /app/keystore <redacted>",
+        ),
+        (
+            "replace /app --find old-secret --with 'new secret' --dry-run",
+            "replace /app --find <redacted> --with <redacted> --dry-run",
+        ),
+        ("get /app/db/password", "get /app/db/password"),
+        ("set", "set"),
+    ];
+    for (line, expected) in cases {
+        assert_eq!(redact(line), expected, "{}", line);
+    }
+}
+
+#[test]
+fn finds_the_inserted_path_in_every_form() {
+    let cases = [
+        ("/p:hello:String", Some("/p")),
+        ("/p hello --yes", Some("/p")),
+        (r#"/p "a b" SecureString --no-validate"#, Some("/p")),
+        ("/p --generate-uuid", Some("/p")),
+        ("/p:String --generate-hex 8", Some("/p")),
+        ("/p v --allowed-pattern ^v$", Some("/p")),
+        ("/p", None),
+    ];
+    for (raw, expected) in cases {
+        assert_eq!(inserted_path(raw).as_deref(), expected, "{}", raw);
+    }
+}
@@ -0,0 +1,32 @@
+use daps::names::check_name;
+
+#[test]
+fn accepts_names_ssm_accepts() {
+    for name in [
+        "/app/db/host",
+        "plain_name",
+        "/a.b-c_d/E9",
+        &"/x".repeat(15),
+    ] {
+        assert_eq!(check_name(name), Ok(()), "{}", name);
+    }
+}
+
+#[test]
+fn explains_why_a_name_is_rejected() {
+    let cases = [
+        ("/app/db host", "' ' is not allowed"),
+        ("/app/db/", "cannot end with '/'"),
+        ("/app//db", "a level is empty"),
+        ("app/db", "must start with '/'"),
+        (&"/x".repeat(16), "16 levels"),
+        ("/aws/service/key", "reserved"),
+        ("SSM-param", "reserved"),
+    ];
+    for (name, reason) in cases {
+        let err = check_name(name).unwrap_err();
+        assert!(err.contains(reason), "{}: {}", name, err);
+    }
+    let long = format!("/{}", "a".repeat(1011));
+    assert!(check_name(&long).unwrap_err().contains("1012 characters"));
+}
@@ -0,0 +1,1624 @@
+mod common;
+
+use common::{completer, encrypted, plaintext, sample_backend};
+use daps::commands::age::age;
+use daps::commands::compare::{Comparison, compare, compare_values};
+use daps::commands::copy::copy;
+use daps::commands::count::summarize;
+use daps::commands::deps::{references, render_resolved};
+use daps::commands::export::export;
+use daps::commands::import::{ImportOutcome, import};
+use daps::commands::insert::insert_value;
+use daps::commands::list::ListArgs;
+use daps::commands::macros::{Recording, manage_macro};
+use daps::commands::move_tree::move_tree;
+use daps::commands::notify::{ChangeEvent, diff_polls, event_pattern, poll, rule_name};
+use daps::commands::paste::{masked_preview, paste};
+use daps::commands::public::latest_ami;
+use daps::commands::redact::redact;
+use daps::commands::reload_all::reload_all;
+use daps::commands::render::render;
+use daps::commands::replace::{replace, replace_in};
+use daps::commands::search::{SortKey, parse_search_args, search};
+use daps::commands::select::select;
+use daps::commands::set::set_value;
+use daps::commands::set_many::{Outcome, SetManyArgs, read_pairs, set_many};
+use daps::commands::show::show;
+use daps::commands::size::size;
+use daps::commands::snapshot::snapshot;
+use daps::commands::status::{Status, status};
+use daps::completer::{NewParameter, REDACTED};
+use daps::cpboard::Cpboard;
+use daps::helper::ParamStoreHelper;
+use daps::macros::Macros;
+use daps::mock_backend::MockBackend;
+use daps::policy::TypePolicy;
+use daps::repl::{Session, execute, prompt, run_batch};
+use daps::snapshots::{self, Change};
+use rustyline::Context;
+use rustyline::highlight::MatchingBracketHighlighter;
+use rustyline::hint::{Hint, Hinter};
+use rustyline::history::History;
+
+#[tokio::test]
+async fn loads_the_tree_under_the_base_path() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+
+    completer.load_parameters().await.unwrap();
+
+    assert!(completer.is_parameter("/app/db/host"));
+    assert!(completer.is_parameter("/app/feature"));
+    assert!(!completer.is_parameter("/other/key"));
+    assert_eq!(
+        completer.cached_value("/app/db/port").as_deref(),
+        Some("5432")
+    );
+    assert_eq!(
+        completer.types.get("/app/db/password").map(String::as_str),
+        Some("SecureString")
+    );
+}
+
+#[tokio::test]
+async fn follows_pagination() {
+    let store = tempfile::tempdir().unwrap();
+    let names: Vec<String> = (0..25).map(|i| format!("/bulk/p{:02}", i)).collect();
+    let backend = MockBackend::with_parameters(names.iter().map(|n| (n.as_str(), "v")));
+    let mut completer = completer(store.path(), &backend, "/bulk", plaintext());
+
+    completer.load_parameters().await.unwrap();
+
+    assert!(names.iter().all(|name| completer.is_parameter(name)));
+    assert!(backend.calls("GetParametersByPath") >= 3);
+}
+
+#[tokio::test]
+async fn retries_transient_failures() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    backend.fail_next(2);
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+
+    completer.load_parameters().await.unwrap();
+
+    assert!(completer.is_parameter("/app/feature"));
+    assert_eq!(completer.stats.snapshot().retries(), 2);
+}
+
+#[tokio::test]
+async fn set_updates_the_store_and_keeps_the_type() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    completer
+        .change_value("/app/db/password", "correct horse".to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend.value("/app/db/password").as_deref(),
+        Some("correct horse")
+    );
+    assert_eq!(
+        backend.type_of("/app/db/password").as_deref(),
+        Some("SecureString")
+    );
+    assert_eq!(
+        completer.cached_value("/app/db/password").as_deref(),
+        Some("correct horse")
+    );
+}
+
+#[tokio::test]
+async fn set_targets_a_path_a_search_result_or_the_selection() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.search_result = vec!["/app/db/host".to_string(), "/app/db/port".to_string()];
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    let (path, _) = set_value(&mut helper, "/app/feature off", "/app/db/host")
+        .await
+        .unwrap();
+    assert_eq!(path, "/app/feature");
+    let (path, _) = set_value(&mut helper, "#1 6543", "/app/db/host")
+        .await
+        .unwrap();
+    assert_eq!(path, "/app/db/port");
+    let err = set_value(&mut helper, "/not/a/parameter", "/app/db/host")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Unknown parameter"), "{}", err);
+    let (path, value) = set_value(&mut helper, "/app/db/host /not/a/parameter", "")
+        .await
+        .unwrap();
+    assert_eq!(
+        (path.as_str(), value.as_str()),
+        ("/app/db/host", "/not/a/parameter")
+    );
+    assert!(
+        set_value(&mut helper, "@nope x", "/app/db/host")
+            .await
+            .is_err()
+    );
+
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("off"));
+    assert_eq!(backend.value("/app/db/port").as_deref(), Some("6543"));
+    assert_eq!(
+        backend.value("/app/db/host").as_deref(),
+        Some("/not/a/parameter")
+    );
+    assert!(set_value(&mut helper, "#5 x", "").await.is_err());
+    assert!(set_value(&mut helper, "value", "").await.is_err());
+}
+
+#[tokio::test]
+async fn insert_creates_tagged_parameters_and_never_overwrites() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    let new = NewParameter {
+        path: "/app/cache/ttl".to_string(),
+        value: "300".to_string(),
+        param_type: "String".to_string(),
+        key_id: None,
+        description: None,
+        tags: vec![("team".to_string(), "platform".to_string())],
+        allowed_pattern: None,
+        data_type: None,
+    };
+    completer.create_parameter(&new).await.unwrap();
+
+    assert_eq!(backend.value("/app/cache/ttl").as_deref(), Some("300"));
+    assert!(completer.is_parameter("/app/cache/ttl"));
+    assert_eq!(
+        completer.parameter_tags("/app/cache/ttl").await.unwrap(),
+        vec![("team".to_string(), "platform".to_string())]
+    );
+
+    let existing = NewParameter {
+        path: "/app/feature".to_string(),
+        value: "off".to_string(),
+        ..new
+    };
+    assert!(completer.create_parameter(&existing).await.is_err());
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("on"));
+}
+
+#[tokio::test]
+async fn allowed_patterns_are_checked_before_writing() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    assert!(
+        insert_value(&mut helper, r"/app/port 80a --allowed-pattern '^\d+$'")
+            .await
+            .is_err()
+    );
+    insert_value(&mut helper, r"/app/port 8080 --allowed-pattern '^\d+$'")
+        .await
+        .unwrap();
+    assert_eq!(backend.value("/app/port").as_deref(), Some("8080"));
+
+    // A new session looks the pattern up before its first write.
+    helper.completer.allowed_patterns.clear();
+    let puts = backend.calls("PutParameter");
+    let err = set_value(&mut helper, "/app/port eighty", "")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("allowed pattern"), "{}", err);
+    assert_eq!(backend.calls("PutParameter"), puts);
+    assert_eq!(
+        helper.completer.allowed_pattern("/app/port").await.unwrap(),
+        Some(r"^\d+$".to_string())
+    );
+
+    set_value(&mut helper, "/app/port 9090", "").await.unwrap();
+    assert_eq!(backend.value("/app/port").as_deref(), Some("9090"));
+}
+
+#[tokio::test]
+async fn ami_parameters_only_take_ami_ids() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    for line in [
+        "/app/ami latest --data-type aws:ec2:image",
+        "/app/ami ami-0abcdef1234567890 SecureString --data-type aws:ec2:image",
+        "/app/ami ami-0abcdef1234567890 --data-type aws:ec2:ami",
+    ] {
+        assert!(insert_value(&mut helper, line).await.is_err(), "{}", line);
+    }
+    assert_eq!(backend.calls("PutParameter"), 0);
+    insert_value(
+        &mut helper,
+        "/app/ami --data-type aws:ec2:image ami-0abcdef1234567890",
+    )
+    .await
+    .unwrap();
+
+    helper.completer.allowed_patterns.clear();
+    helper.completer.data_types.clear();
+    assert!(set_value(&mut helper, "/app/ami latest", "").await.is_err());
+    assert_eq!(
+        helper
+            .completer
+            .data_types
+            .get("/app/ami")
+            .map(String::as_str),
+        Some("aws:ec2:image")
+    );
+    set_value(&mut helper, "/app/ami ami-12345678", "")
+        .await
+        .unwrap();
+    assert_eq!(backend.value("/app/ami").as_deref(), Some("ami-12345678"));
+}
+
+#[tokio::test]
+async fn mv_keeps_type_description_and_tags() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer
+        .create_parameter(&NewParameter {
+            path: "/app/api/token".to_string(),
+            value: "s3cr3t-Token".to_string(),
+            param_type: "SecureString".to_string(),
+            key_id: None,
+            description: Some("API token".to_string()),
+            tags: vec![("team".to_string(), "platform".to_string())],
+            allowed_pattern: None,
+            data_type: None,
+        })
+        .await
+        .unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    copy(&mut helper, "/app/api/token /app/api/backup", false)
+        .await
+        .unwrap();
+    copy(&mut helper, "/app/api/token /app/api/key --yes", true)
+        .await
+        .unwrap();
+
+    assert_eq!(backend.value("/app/api/token"), None);
+    for path in ["/app/api/backup", "/app/api/key"] {
+        assert_eq!(backend.value(path).as_deref(), Some("s3cr3t-Token"));
+        assert_eq!(backend.type_of(path).as_deref(), Some("SecureString"));
+        let completer = &helper.completer;
+        assert_eq!(
+            completer.parameter_tags(path).await.unwrap(),
+            vec![("team".to_string(), "platform".to_string())]
+        );
+        let metadata = completer.describe_parameters(path).await.unwrap();
+        assert_eq!(metadata[0].description.as_deref(), Some("API token"));
+    }
+    assert!(!helper.completer.is_parameter("/app/api/token"));
+    assert!(helper.completer.is_parameter("/app/api/key"));
+    assert!(
+        copy(&mut helper, "/app/api/key /app/api/backup", true)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn mv_tree_moves_a_subtree_and_resumes() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    move_tree(&mut helper, "/app/db /app/database --dry-run")
+        .await
+        .unwrap();
+    assert_eq!(backend.calls("PutParameter"), 0);
+
+    // An earlier run copied `host`, then wrote `port` but stopped before
+    // recording it.
+    std::fs::write(
+        store.path().join("mv_tree_checkpoint.json"),
+        r#"{"command": "mv-tree /app/db/ /app/database/", "done": ["copy:host"]}"#,
+    )
+    .unwrap();
+    backend.put("/app/database/host", "db.internal", "String");
+    backend.put("/app/database/port", "5432", "String");
+    let puts = backend.calls("PutParameter");
+    move_tree(&mut helper, "/app/db /app/database --yes")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        backend.names(),
+        vec![
+            "/app/database/host",
+            "/app/database/password",
+            "/app/database/port",
+            "/app/feature",
+            "/other/key",
+        ]
+    );
+    assert_eq!(
+        backend.type_of("/app/database/password").as_deref(),
+        Some("SecureString")
+    );
+    assert_eq!(backend.calls("PutParameter"), puts + 1);
+    assert!(helper.completer.is_parameter("/app/database/password"));
+    assert!(!helper.completer.is_parameter("/app/db/host"));
+    assert!(!store.path().join("mv_tree_checkpoint.json").exists());
+}
+
+#[tokio::test]
+async fn type_policy_picks_and_enforces_types() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    let rules = [
+        ("/app/".to_string(), "String".to_string()),
+        ("/app/**/secrets/".to_string(), "SecureString".to_string()),
+    ];
+    completer.type_policy =
+        TypePolicy::new(Some("StringList".to_string()), rules.into_iter().collect()).unwrap();
+    let policy = &completer.type_policy;
+
+    assert_eq!(
+        policy
+            .resolve("/other/x", None, policy.default_type())
+            .unwrap(),
+        "StringList"
+    );
+    assert_eq!(
+        policy
+            .resolve("/app/x", None, policy.default_type())
+            .unwrap(),
+        "String"
+    );
+    assert_eq!(
+        policy
+            .resolve("/app/db/secrets/key", None, "String")
+            .unwrap(),
+        "SecureString"
+    );
+    let err = policy
+        .resolve("/app/db/secrets/key", Some("String"), "String")
+        .unwrap_err();
+    assert!(err.contains("/app/**/secrets/"), "{}", err);
+
+    let new = NewParameter {
+        path: "/app/db/secrets/key".to_string(),
+        value: "plain".to_string(),
+        param_type: "String".to_string(),
+        key_id: None,
+        description: None,
+        tags: Vec::new(),
+        allowed_pattern: None,
+        data_type: None,
+    };
+    assert!(completer.create_parameter(&new).await.is_err());
+    assert_eq!(backend.calls("PutParameter"), 0);
+    assert!(TypePolicy::new(Some("Secure".to_string()), Default::default()).is_err());
+}
+
+#[tokio::test]
+async fn search_sees_new_and_deleted_parameters() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    let mut found = completer.parameters_containing("db");
+    found.sort();
+    assert_eq!(found, ["/app/db/host", "/app/db/password", "/app/db/port"]);
+
+    completer
+        .update_all("/app/db/replica", "db-2.internal".to_string())
+        .await
+        .unwrap();
+    assert!(
+        completer
+            .parameters_containing("replica")
+            .contains(&"/app/db/replica".to_string())
+    );
+    assert!(
+        completer
+            .search_candidates("dbrep")
+            .contains(&"/app/db/replica".to_string())
+    );
+
+    completer.delete_parameter("/app/db/host").await.unwrap();
+    assert!(
+        !completer
+            .parameters_containing("host")
+            .contains(&"/app/db/host".to_string())
+    );
+}
+
+#[tokio::test]
+async fn delete_removes_the_parameter_everywhere() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    completer.delete_parameter("/app/feature").await.unwrap();
+
+    assert_eq!(backend.value("/app/feature"), None);
+    assert!(!completer.is_parameter("/app/feature"));
+    assert_eq!(completer.cached_value("/app/feature"), None);
+    assert!(completer.delete_parameter("/app/feature").await.is_err());
+}
+
+#[tokio::test]
+async fn count_summarizes_a_prefix() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    let summary = summarize(&completer, "/app/db/").await.unwrap();
+
+    assert_eq!(summary.parameters, 3);
+    assert_eq!(summary.by_type.get("SecureString"), Some(&1));
+    assert_eq!(summary.by_type.get("String"), Some(&2));
+    assert_eq!(summary.value_bytes, "db.internal5432hunter2".len());
+    assert_eq!(summary.uncached, 0);
+    assert_eq!(summary.deepest.as_deref(), Some("/app/db/host"));
+
+    let summary = summarize(&completer, "/app/").await.unwrap();
+    assert_eq!(summary.parameters, 4);
+}
+
+#[tokio::test]
+async fn remote_listing_filters_on_the_server() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let completer = completer(store.path(), &backend, "/app", plaintext());
+
+    let secrets = completer
+        .describe_filtered("/app/", &[("Type", "SecureString")])
+        .await
+        .unwrap();
+
+    let names: Vec<_> = secrets.iter().filter_map(|p| p.name.as_deref()).collect();
+    assert_eq!(names, ["/app/db/password"]);
+    assert_eq!(backend.calls("GetParametersByPath"), 0);
+    assert_eq!(backend.calls("GetParameter"), 0);
+
+    let args = ListArgs::parse("/app --remote --tier Standard").unwrap();
+    assert_eq!(args.prefix, "/app");
+    assert_eq!(args.filters, [("Tier", "Standard".to_string())]);
+    assert!(ListArgs::parse("--type SecureString").is_err());
+    assert!(ListArgs::parse("--remote --type Secret").is_err());
+}
+
+#[tokio::test]
+async fn loads_names_only_when_kms_decrypt_is_denied() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    backend.deny_decrypt();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+
+    completer.load_parameters().await.unwrap();
+
+    assert!(!completer.decrypt);
+    assert!(completer.is_parameter("/app/db/password"));
+    assert!(completer.values.is_unavailable("/app/db/password"));
+    assert_eq!(completer.cached_value("/app/db/password"), None);
+    assert_eq!(
+        completer.cached_value("/app/db/host").as_deref(),
+        Some("db.internal")
+    );
+    assert!(completer.get_set_value("/app/db/password").await.is_err());
+}
+
+#[tokio::test]
+async fn no_decrypt_decrypts_one_value_on_demand() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut first = completer(store.path(), &backend, "/app", plaintext());
+    first.decrypt = false;
+    first.load_parameters().await.unwrap();
+    assert!(first.values.is_unavailable("/app/db/password"));
+
+    let mut offline = completer(store.path(), &backend, "/app", plaintext());
+    offline.offline = true;
+    offline.load_parameters().await.unwrap();
+    assert!(offline.values.is_unavailable("/app/db/password"));
+
+    let value = first.get_set_value("/app/db/password").await.unwrap();
+    assert_eq!(value, "hunter2");
+    assert!(!first.values.is_unavailable("/app/db/password"));
+    assert_eq!(
+        first.cached_value("/app/db/password").as_deref(),
+        Some("hunter2")
+    );
+}
+
+#[tokio::test]
+async fn scope_restricts_reads_writes_and_completion() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.scope = Some("/app/db".to_string());
+
+    assert_eq!(completer.get_completions("/app/"), ["/app/db"]);
+    assert!(completer.search_candidates("feature").is_empty());
+    assert_eq!(completer.scoped("/app").unwrap(), "/app/db");
+    assert!(completer.scoped("/app/feature").is_err());
+    let summary = summarize(&completer, completer.root()).await.unwrap();
+    assert_eq!(summary.parameters, 3);
+
+    let err = completer
+        .set_parameter("/app/feature", "off".to_string(), None)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("outside the scope /app/db"),
+        "{}",
+        err
+    );
+    assert!(completer.get_set_value("/app/feature").await.is_err());
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("on"));
+
+    completer.scope = None;
+    assert!(completer.get_set_value("/app/feature").await.is_ok());
+}
+
+#[tokio::test]
+async fn redact_masks_values_and_keeps_them_off_the_command_line() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer
+        .metadata
+        .insert("selected".to_string(), "/app/db/host".to_string());
+    assert_eq!(completer.get_completions("set"), ["set db.internal"]);
+    assert_eq!(completer.get_completions("set "), ["set db.internal"]);
+    assert!(completer.get_completions("set-many").is_empty());
+    assert!(completer.get_completions("settings").is_empty());
+    assert_eq!(
+        completer.get_completions("set /app/db/port "),
+        ["set /app/db/port 5432"]
+    );
+    assert!(
+        completer
+            .get_completions("set /app/db/p")
+            .contains(&"set /app/db/port".to_string())
+    );
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    redact(&mut helper, "on").unwrap();
+    assert_eq!(helper.completer.shown("db.internal"), REDACTED);
+    assert_eq!(helper.completer.get_completions("set"), ["set "]);
+    let err = export(&mut helper, "csv /app").await.unwrap_err();
+    assert!(err.to_string().contains("redacted"), "{}", err);
+    assert!(redact(&mut helper, "maybe").is_err());
+    assert!(helper.completer.redact);
+
+    redact(&mut helper, "off").unwrap();
+    assert_eq!(helper.completer.shown("db.internal"), "db.internal");
+}
+
+#[tokio::test]
+async fn reads_public_parameters_outside_the_base_path() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let latest = "/aws/service/ami-amazon-linux-latest";
+    backend.put(
+        &format!("{}/al2023-ami-kernel-default-x86_64", latest),
+        "ami-0abc",
+        "String",
+    );
+    backend.put(
+        &format!("{}/al2023-ami-kernel-default-arm64", latest),
+        "ami-0def",
+        "String",
+    );
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.scope = Some("/app/db".to_string());
+    let helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    assert_eq!(latest_ami(&helper, "al2023").await.unwrap(), "ami-0abc");
+    assert_eq!(
+        latest_ami(&helper, "al2023 --arm64").await.unwrap(),
+        "ami-0def"
+    );
+    assert!(latest_ami(&helper, "beos").await.is_err());
+    assert!(latest_ami(&helper, "windows --arm64").await.is_err());
+
+    let listed = helper.completer.fetch_public(latest).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert!(!helper.completer.is_parameter(&listed[0].0));
+}
+
+#[tokio::test]
+async fn replace_rewrites_matching_values_under_a_prefix() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    backend.put(
+        "/app/api/db_url",
+        "postgres://db.internal:5432/app",
+        "String",
+    );
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    replace(
+        &mut helper,
+        "/app --find db.internal --with db2.internal --dry-run",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        backend.value("/app/db/host").as_deref(),
+        Some("db.internal")
+    );
+
+    replace(
+        &mut helper,
+        "/app --find db.internal --with db2.internal --yes",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        backend.value("/app/db/host").as_deref(),
+        Some("db2.internal")
+    );
+    assert_eq!(
+        backend.value("/app/api/db_url").as_deref(),
+        Some("postgres://db2.internal:5432/app")
+    );
+    assert_eq!(backend.value("/app/db/port").as_deref(), Some("5432"));
+    assert_eq!(
+        helper.completer.cached_value("/app/db/host").as_deref(),
+        Some("db2.internal")
+    );
+    assert!(replace(&mut helper, "/app --find x").await.is_err());
+
+    let regex = regex::Regex::new(r"db(\d*)\.internal").unwrap();
+    assert_eq!(
+        replace_in("db2.internal", "", "db$1.example.com", Some(&regex)).as_deref(),
+        Some("db2.example.com")
+    );
+    assert_eq!(replace_in("5432", "db", "x", None), None);
+}
+
+#[tokio::test]
+async fn paste_masks_the_preview_and_needs_a_clipboard() {
+    assert_eq!(masked_preview("sk-live-0123456789ab"), "sk-l…ab");
+    assert_eq!(masked_preview("hunter2"), "*******");
+
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    let mut cpboard = Cpboard::disabled();
+
+    let err = paste(&mut helper, &mut cpboard, "/app/db/password --yes", "")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("clipboard"), "{}", err);
+    let err = paste(&mut helper, &mut cpboard, "/app/new --yes", "")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("insert"), "{}", err);
+    assert!(paste(&mut helper, &mut cpboard, "", "").await.is_err());
+    assert_eq!(
+        backend.value("/app/db/password").as_deref(),
+        Some("hunter2")
+    );
+}
+
+#[tokio::test]
+async fn cmp_reports_whether_a_file_matches_the_stored_value() {
+    assert_eq!(
+        compare_values("a\nb", "a\nb\n", false),
+        Comparison::EqualIgnoringLineBreak
+    );
+    match compare_values("hunter2", "hunter3", true) {
+        Comparison::Different(report) => {
+            assert_eq!(report.len(), 1);
+            assert!(report[0].contains("character 7"), "{:?}", report);
+            assert!(!report[0].contains("hunter"), "{:?}", report);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    let mut cpboard = Cpboard::disabled();
+    let file = store.path().join("rotated.txt");
+    std::fs::write(&file, "db.internal\n").unwrap();
+    let args = format!("--file {}", file.display());
+
+    let path = compare(&mut helper, &mut cpboard, &args, "/app/db/host")
+        .await
+        .unwrap();
+    assert_eq!(path, "/app/db/host");
+    let err = compare(
+        &mut helper,
+        &mut cpboard,
+        &format!("/app/db/port {}", args),
+        "",
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("does not match"), "{}", err);
+    let err = compare(&mut helper, &mut cpboard, "/app/db/host", "")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("clipboard"), "{}", err);
+}
+
+#[tokio::test]
+async fn prompt_shows_the_target_and_selected_parameter() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    let region = completer.region.name().to_string();
+    assert_eq!(prompt(&completer, ""), format!("default {} >> ", region));
+
+    completer.client_options.profile = Some("prod".to_string());
+    completer.scope = Some("/app/db".to_string());
+    assert_eq!(
+        prompt(&completer, "/app/db/password"),
+        format!("prod {} (/app/db) [/app/db/password] >> ", region)
+    );
+    let long = format!("/app/{}/password", "nested/".repeat(8));
+    let shown = prompt(&completer, &long);
+    assert!(shown.contains("[…"), "{}", shown);
+    assert!(shown.ends_with("/password] >> "), "{}", shown);
+}
+
+#[tokio::test]
+async fn macros_expand_command_aliases_and_save_recordings() {
+    let store = tempfile::tempdir().unwrap();
+    let config = store.path().join("config.toml");
+    std::fs::write(&config, "# mine\nfuzzy_completion = false\n").unwrap();
+    let commands = [("rot", "set --generate 32"), ("set", "delete")]
+        .into_iter()
+        .map(|(name, line)| (name.to_string(), line.to_string()))
+        .collect();
+    let macros = Macros::new(&config, commands, Default::default());
+    assert_eq!(macros.expand("rot /app/key"), "set --generate 32 /app/key");
+    assert_eq!(macros.expand("rot"), "set --generate 32");
+    assert_eq!(macros.expand("set /app/key x"), "set /app/key x");
+
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.macros = macros;
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    let mut recording: Option<Recording> = None;
+    manage_macro(&mut helper, "record check", &mut recording).unwrap();
+    assert!(manage_macro(&mut helper, "record other", &mut recording).is_err());
+    let lines = &mut recording.as_mut().unwrap().lines;
+    lines.push("get /app/db/host".to_string());
+    lines.push("count /app".to_string());
+    manage_macro(&mut helper, "stop", &mut recording).unwrap();
+    assert!(recording.is_none());
+
+    let saved = std::fs::read_to_string(&config).unwrap();
+    assert!(saved.starts_with("# mine"), "{}", saved);
+    assert!(saved.contains("[macros]"), "{}", saved);
+    assert!(
+        saved.contains("check = [\"get /app/db/host\", \"count /app\"]"),
+        "{}",
+        saved
+    );
+    let played = manage_macro(&mut helper, "play check", &mut recording)
+        .unwrap()
+        .unwrap();
+    assert_eq!(played, ["get /app/db/host", "count /app"]);
+    assert!(manage_macro(&mut helper, "play missing", &mut recording).is_err());
+}
+
+#[tokio::test]
+async fn notify_builds_rules_and_sees_changes_between_polls() {
+    assert_eq!(rule_name("/prod/app db/"), "daps-prod-app-db");
+    assert_eq!(rule_name("/"), "daps-all");
+    assert_eq!(rule_name(&"/x".repeat(50)).len(), 64);
+    assert_eq!(
+        event_pattern("/prod/")["detail"]["name"][0]["prefix"],
+        "/prod/"
+    );
+
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let completer = completer(store.path(), &backend, "/app", plaintext());
+    let helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    let before = poll(&helper, "/app").await.unwrap();
+    assert_eq!(before.len(), 4);
+
+    backend.put("/app/db/port", "6432", "String");
+    backend.put("/app/new", "x", "String");
+    let after = poll(&helper, "/app").await.unwrap();
+    let events = diff_polls(&before, &after);
+    assert_eq!(events.len(), 2, "{:?}", events);
+    assert!(
+        matches!(&events[0], ChangeEvent::Updated(path, 1, seen) if path == "/app/db/port" && seen.version == 2)
+    );
+    assert!(matches!(&events[1], ChangeEvent::Created(path, _) if path == "/app/new"));
+    assert_eq!(
+        diff_polls(&after, &before).last(),
+        Some(&ChangeEvent::Deleted("/app/new".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn render_resolved_substitutes_references_and_detects_cycles() {
+    assert_eq!(
+        references("${/a} and ${@db} and ${/a} but not $/b or ${x}"),
+        ["/a", "@db"]
+    );
+
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    backend.put(
+        "/app/url",
+        "postgres://${/app/db/host}:${/app/db/port}/x",
+        "String",
+    );
+    backend.put("/app/loop/a", "${/app/loop/b}", "String");
+    backend.put("/app/loop/b", "b${/app/loop/a}", "String");
+    backend.put("/app/broken", "${/app/nope}", "String");
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    let (path, value) = render_resolved(&mut helper, "", "/app/url").await.unwrap();
+    assert_eq!(path, "/app/url");
+    assert_eq!(value, "postgres://db.internal:5432/x");
+    let err = render_resolved(&mut helper, "/app/loop/a", "")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Reference cycle: /app/loop/a -> /app/loop/b -> /app/loop/a"
+    );
+    let err = render_resolved(&mut helper, "/app/broken", "")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("/app/nope"), "{}", err);
+}
+
+#[tokio::test]
+async fn snapshots_restore_changed_deleted_and_extra_parameters() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let encryption = encrypted(store.path(), "snapshot key");
+    let mut completer = completer(store.path(), &backend, "/app", encryption);
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    snapshot(&mut helper, "create before /app").await.unwrap();
+    let file = store.path().join("snapshots").join("before.json");
+    let saved = std::fs::read_to_string(&file).unwrap();
+    assert!(saved.contains("/app/db/host") && !saved.contains("hunter2"));
+
+    backend.put("/app/db/port", "6543", "String");
+    backend.put("/app/extra", "new", "String");
+    helper
+        .completer
+        .delete_parameter("/app/feature")
+        .await
+        .unwrap();
+
+    snapshot(&mut helper, "restore before --dry-run")
+        .await
+        .unwrap();
+    assert_eq!(backend.value("/app/db/port").as_deref(), Some("6543"));
+
+    snapshot(&mut helper, "restore before --delete-extra --yes")
+        .await
+        .unwrap();
+    assert_eq!(backend.value("/app/db/port").as_deref(), Some("5432"));
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("on"));
+    assert_eq!(backend.value("/app/extra"), None);
+    assert_eq!(
+        backend.type_of("/app/db/password").as_deref(),
+        Some("SecureString")
+    );
+
+    let before = [("/a", "1"), ("/b", "2")]
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .into();
+    let now = [("/b", "3"), ("/c", "4")]
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .into();
+    assert_eq!(
+        snapshots::plan(&before, &now, false),
+        [
+            ("/a".to_string(), Change::Create),
+            ("/b".to_string(), Change::Update)
+        ]
+    );
+    assert_eq!(snapshots::plan(&before, &now, true).len(), 3);
+    assert!(snapshots::validate_name("../x").is_err());
+}
+
+#[tokio::test]
+async fn automatic_snapshots_record_what_a_session_changes() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.auto_snapshots.enabled = true;
+    completer.auto_snapshots.keep = 2;
+    let dir = store.path().join("snapshots");
+    let old = |created_at| snapshots::Snapshot {
+        prefix: "/".to_string(),
+        region: "us-east-1".to_string(),
+        profile: None,
+        created_at,
+        encrypted: false,
+        parameters: Default::default(),
+        auto: true,
+        absent: Vec::new(),
+    };
+    snapshots::save(&dir, "auto-old", &old(1)).unwrap();
+    snapshots::save(&dir, "auto-recent", &old(u64::MAX / 2)).unwrap();
+    let mut manual = old(1);
+    manual.auto = false;
+    snapshots::save(&dir, "manual", &manual).unwrap();
+
+    completer
+        .change_value("/app/db/port", "6543".to_string())
+        .await
+        .unwrap();
+    completer
+        .change_value("/app/db/port", "7654".to_string())
+        .await
+        .unwrap();
+    completer
+        .set_parameter("/app/new", "x".to_string(), Some("String".to_string()))
+        .await
+        .unwrap();
+    completer.delete_parameter("/app/feature").await.unwrap();
+
+    let names = snapshots::list(&dir);
+    assert_eq!(names.len(), 3, "{:?}", names);
+    assert!(names.contains(&"manual".to_string()) && names.contains(&"auto-recent".to_string()));
+    let name = names
+        .iter()
+        .find(|name| name.starts_with("auto-2"))
+        .unwrap();
+    let taken = snapshots::load(&dir, name).unwrap();
+    assert_eq!(taken.parameters["/app/db/port"].value, "5432");
+    assert_eq!(taken.absent, ["/app/new"]);
+
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    helper.completer.auto_snapshots.enabled = false;
+    snapshot(&mut helper, &format!("restore {} --yes", name))
+        .await
+        .unwrap();
+    assert_eq!(backend.value("/app/db/port").as_deref(), Some("5432"));
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("on"));
+    assert_eq!(backend.value("/app/new"), None);
+    assert_eq!(
+        backend.value("/app/db/host").as_deref(),
+        Some("db.internal")
+    );
+}
+
+#[tokio::test]
+async fn set_many_creates_and_updates_after_one_preview() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    let file = store.path().join("pairs.env");
+    std::fs::write(&file, "# cache\ncache/ttl=60 cache/mode=\"lru only\"\n").unwrap();
+    let args = format!(
+        "/app feature=off db/port=5432 --from-file {} --dry-run",
+        file.display()
+    );
+    let planned = set_many(&mut helper, &args).await.unwrap();
+    assert_eq!(
+        planned,
+        [
+            ("/app/feature".to_string(), Outcome::Updated),
+            ("/app/db/port".to_string(), Outcome::Unchanged),
+            ("/app/cache/ttl".to_string(), Outcome::Created),
+            ("/app/cache/mode".to_string(), Outcome::Created),
+        ]
+    );
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("on"));
+
+    set_many(&mut helper, &args.replace(" --dry-run", " --yes"))
+        .await
+        .unwrap();
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("off"));
+    assert_eq!(
+        backend.value("/app/cache/mode").as_deref(),
+        Some("lru only")
+    );
+    assert!(helper.completer.is_parameter("/app/cache/ttl"));
+
+    assert!(set_many(&mut helper, "/app a=1 a=2 --yes").await.is_err());
+    assert!(SetManyArgs::parse("/app /abs=1").is_err());
+    assert!(SetManyArgs::parse("/app a=1 --type Number").is_err());
+    assert!(read_pairs("novalue").is_err());
+}
+
+#[tokio::test]
+async fn import_skips_entries_done_before_an_interruption() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    let file = store.path().join("params.json");
+    std::fs::write(
+        &file,
+        r#"{"/app/feature": "off", "/app/db/port": "6543", "/app/new": "1"}"#,
+    )
+    .unwrap();
+    // An earlier run imported `/app/db/port` before it was stopped.
+    let checkpoint = store.path().join("import_checkpoint.json");
+    std::fs::write(
+        &checkpoint,
+        format!(
+            r#"{{"command": "import {} --on-conflict Overwrite", "done": ["/app/db/port"]}}"#,
+            file.display()
+        ),
+    )
+    .unwrap();
+
+    let report = import(
+        &mut helper,
+        &format!("{} --on-conflict overwrite", file.display()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        report[0],
+        (
+            "/app/db/port".to_string(),
+            ImportOutcome::Skipped("imported before resume")
+        )
+    );
+    assert_eq!(backend.value("/app/db/port").as_deref(), Some("5432"));
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("off"));
+    assert_eq!(backend.value("/app/new").as_deref(), Some("1"));
+    assert!(!checkpoint.exists());
+
+    // A checkpoint of another import refuses to start a new one.
+    std::fs::write(
+        &checkpoint,
+        r#"{"command": "import other.json", "done": []}"#,
+    )
+    .unwrap();
+    assert!(
+        import(&mut helper, &file.display().to_string())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn show_prints_the_selected_or_named_value() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.value_width = 8;
+    assert_eq!(completer.clipped("db.internal"), "db.inte…");
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    assert_eq!(
+        show(&mut helper, "", "/app/db/host").await.unwrap(),
+        "/app/db/host"
+    );
+    assert_eq!(
+        show(&mut helper, "/app/feature | trim", "").await.unwrap(),
+        "/app/feature"
+    );
+    assert!(show(&mut helper, "", "").await.is_err());
+    assert!(show(&mut helper, "/app/missing", "").await.is_err());
+    assert!(
+        show(&mut helper, "/app/db/port | json | b64d", "")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn sel_takes_an_index_or_a_full_or_partial_path() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.search_result = vec!["/app/feature".to_string()];
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    assert_eq!(select(&mut helper, "0").unwrap(), "/app/feature");
+    assert!(select(&mut helper, "1").is_err());
+    assert_eq!(select(&mut helper, "/app/db/port").unwrap(), "/app/db/port");
+    assert_eq!(select(&mut helper, "db/host").unwrap(), "/app/db/host");
+    assert_eq!(helper.completer.metadata["selected"], "/app/db/host");
+    let ambiguous = select(&mut helper, "db").unwrap_err().to_string();
+    assert!(ambiguous.contains("/app/db/host"), "{}", ambiguous);
+    assert!(select(&mut helper, "missing").is_err());
+    helper.completer.recent.touch("/app/db/port");
+    assert_eq!(select(&mut helper, "-1").unwrap(), "/app/db/port");
+    assert_eq!(helper.completer.metadata["selected"], "/app/db/port");
+
+    assert_eq!(
+        helper.completer.get_completions("sel /app/f"),
+        ["sel /app/feature"]
+    );
+    assert_eq!(
+        helper.completer.get_completions("sel pass"),
+        ["sel /app/db/password"]
+    );
+}
+
+#[tokio::test]
+async fn sel_and_hints_stay_in_the_scope() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.get_set_value("/app/feature").await.unwrap();
+    completer.get_set_value("/app/db/port").await.unwrap();
+    completer.search_result = vec!["/app/feature".to_string()];
+    completer.recent.touch("/app/feature");
+    completer.scope = Some("/app/db".to_string());
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    assert!(select(&mut helper, "0").is_err());
+    assert!(select(&mut helper, "-1").is_err());
+    assert!(!helper.completer.metadata.contains_key("selected"));
+
+    let history = History::new();
+    let ctx = Context::new(&history);
+    assert!(helper.hint("/app/f", 6, &ctx).is_none());
+    let hint = helper.hint("/app/db/po", 10, &ctx).unwrap();
+    assert_eq!(hint.display(), "rt = 5432");
+    helper.completer.redact = true;
+    let hint = helper.hint("/app/db/po", 10, &ctx).unwrap();
+    assert_eq!(hint.display(), format!("rt = {}", REDACTED));
+}
+
+#[tokio::test]
+async fn reload_all_reports_the_values_that_changed() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.search_result = vec!["/app/db/host".to_string(), "/app/db/port".to_string()];
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+    backend.put("/app/db/host", "db2.internal", "String");
+    let calls = backend.calls("GetParameters");
+
+    let reloaded = reload_all(&mut helper, "").await.unwrap();
+    assert_eq!(reloaded.changed, ["/app/db/host"]);
+    assert_eq!(reloaded.unchanged, ["/app/db/port"]);
+    assert!(reloaded.missing.is_empty());
+    assert_eq!(backend.calls("GetParameters"), calls + 1);
+    assert_eq!(
+        helper.completer.cached_value("/app/db/host").as_deref(),
+        Some("db2.internal")
+    );
+
+    let reloaded = reload_all(&mut helper, "/app/feature /app/gone")
+        .await
+        .unwrap();
+    assert_eq!(reloaded.unchanged, ["/app/feature"]);
+    assert_eq!(reloaded.missing, ["/app/gone"]);
+}
+
+#[tokio::test]
+async fn only_sel_changes_the_selection_and_status_reports_it() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut rl = rustyline::Editor::<ParamStoreHelper>::new().unwrap();
+    rl.set_helper(Some(ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    }));
+    let mut session = Session::new(Cpboard::disabled());
+
+    execute(&mut rl, &mut session, "sel db/host").await.unwrap();
+    execute(&mut rl, &mut session, "/app/feature")
+        .await
+        .unwrap();
+    execute(&mut rl, &mut session, "get /app/db/port")
+        .await
+        .unwrap();
+    assert_eq!(session.selected, "/app/db/host");
+    let helper = rl.helper_mut().unwrap();
+    assert_eq!(helper.completer.metadata["selected"], "/app/db/host");
+
+    helper.completer.offline = true;
+    helper
+        .completer
+        .queue_change("/app/feature", "off", None)
+        .unwrap();
+    let report = status(helper, &session.selected);
+    assert_eq!(report.selected.as_deref(), Some("/app/db/host"));
+    // Loaded from AWS just now.
+    assert!(report.fetched_at.is_some());
+    assert_eq!(report.pending, ["/app/feature"]);
+    assert_eq!(
+        status(helper, ""),
+        Status {
+            pending: vec!["/app/feature".to_string()],
+            ..Status::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn search_results_can_be_sorted() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    let mut helper = ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    };
+
+    search(&mut helper, "db --sort name").await.unwrap();
+    assert_eq!(
+        helper.completer.search_result,
+        ["/app/db/host", "/app/db/password", "/app/db/port"]
+    );
+    search(&mut helper, "db --sort length").await.unwrap();
+    assert_eq!(helper.completer.search_result[0], "/app/db/host");
+    assert_eq!(helper.completer.search_result[2], "/app/db/port");
+
+    assert_eq!(
+        parse_search_args("db host --sort modified").unwrap(),
+        ("db host".to_string(), SortKey::Modified)
+    );
+    assert!(parse_search_args("--sort name").is_err());
+    assert!(parse_search_args("db --sort size").is_err());
+}
+
+#[tokio::test]
+async fn batch_history_holds_the_expanded_command() {
+    let store = tempfile::tempdir().unwrap();
+    let config = store.path().join("config.toml");
+    let commands = [("put".to_string(), "set".to_string())]
+        .into_iter()
+        .collect();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    completer.macros = Macros::new(&config, commands, Default::default());
+    let mut rl = rustyline::Editor::<ParamStoreHelper>::new().unwrap();
+    rl.set_helper(Some(ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    }));
+
+    let input = "put /app/feature off\n".as_bytes();
+    run_batch(&mut rl, Cpboard::disabled(), input, "script", false)
+        .await
+        .unwrap();
+    assert_eq!(
+        rl.history().last().map(String::as_str),
+        Some("set /app/feature off")
+    );
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("off"));
+}
+
+/// A helper over the sample store plus a plain value that needs quoting.
+async fn export_helper(store: &std::path::Path, backend: &MockBackend) -> ParamStoreHelper {
+    backend.put("/app/motd", "say \"hi\", ${USER}\n", "String");
+    let mut completer = completer(store, backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+    ParamStoreHelper {
+        completer,
+        highlighter: MatchingBracketHighlighter::new(),
+        commands: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn exports_quote_values_for_kubernetes_and_terraform() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut helper = export_helper(store.path(), &backend).await;
+    let file = store.path().join("out");
+    let out = file.to_str().unwrap();
+
+    export(
+        &mut helper,
+        &format!(
+            "k8s-configmap /app --name cfg --namespace ns --output {}",
+            out
+        ),
+    )
+    .await
+    .unwrap();
+    let configmap = std::fs::read_to_string(&file).unwrap();
+    assert!(configmap.contains("kind: ConfigMap\nmetadata:\n  name: cfg\n  namespace: ns\n"));
+    assert!(
+        configmap.contains("  db_host: \"db.internal\"\n"),
+        "{}",
+        configmap
+    );
+    assert!(
+        configmap.contains(r#"  motd: "say \"hi\", ${USER}\n""#),
+        "{}",
+        configmap
+    );
+    assert!(!configmap.contains("password"), "{}", configmap);
+
+    export(
+        &mut helper,
+        &format!("k8s-secret /app --name creds --output {}", out),
+    )
+    .await
+    .unwrap();
+    let secret = std::fs::read_to_string(&file).unwrap();
+    assert!(secret.contains("type: Opaque\n"));
+    assert!(
+        secret.contains("  db_password: aHVudGVyMg==\n"),
+        "{}",
+        secret
+    );
+    let invalid = export(
+        &mut helper,
+        &format!("k8s-secret /app --name Bad_Name --output {}", out),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        invalid.to_string().contains("Invalid Kubernetes name"),
+        "{}",
+        invalid
+    );
+
+    export(&mut helper, &format!("terraform /app --output {}", out))
+        .await
+        .unwrap();
+    let terraform = std::fs::read_to_string(&file).unwrap();
+    assert!(
+        terraform.contains(r#"  value = "say \"hi\", $${USER}\n""#),
+        "{}",
+        terraform
+    );
+    assert!(terraform.contains("resource \"aws_ssm_parameter\" \"db_host\" {\n  name = \"/app/db/host\"\n  type = \"String\"\n"));
+    assert!(terraform.contains("  value = var.db_password\n"));
+    assert!(terraform.contains("variable \"db_password\" {"));
+    assert!(!terraform.contains("hunter2"));
+    assert!(terraform.contains("# terraform import aws_ssm_parameter.db_host /app/db/host\n"));
+}
+
+#[tokio::test]
+async fn csv_export_reads_back_unchanged() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut helper = export_helper(store.path(), &backend).await;
+    let file = store.path().join("params.csv");
+    let out = file.to_str().unwrap();
+
+    export(&mut helper, &format!("csv /app --output {}", out))
+        .await
+        .unwrap();
+    let mut reader = csv::Reader::from_path(&file).unwrap();
+    assert_eq!(
+        reader.headers().unwrap(),
+        vec!["name", "type", "value", "description", "tags"]
+    );
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    let motd = rows.iter().find(|row| &row[0] == "/app/motd").unwrap();
+    assert_eq!((&motd[1], &motd[2]), ("String", "say \"hi\", ${USER}\n"));
+    let password = rows
+        .iter()
+        .find(|row| &row[0] == "/app/db/password")
+        .unwrap();
+    assert_eq!((&password[1], &password[2]), ("SecureString", "hunter2"));
+
+    let report = import(&mut helper, &format!("csv {} --dry-run", out))
+        .await
+        .unwrap();
+    assert_eq!(report.len(), rows.len());
+    assert!(
+        report
+            .iter()
+            .all(|(_, outcome)| *outcome == ImportOutcome::Unchanged),
+        "{:?}",
+        report
+    );
+}
+
+#[tokio::test]
+async fn render_fills_placeholders_defaults_fields_and_names() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    backend.put("/app/json", r#"{"user": "bob"}"#, "String");
+    let mut helper = export_helper(store.path(), &backend).await;
+    let template = store.path().join("app.conf.tmpl");
+    let file = store.path().join("app.conf");
+    let out = file.to_str().unwrap();
+
+    std::fs::write(
+        &template,
+        "host={{/app/db/host}}:{{ /app/db/port }}\n\
+         user={{/app/json#user}}\n\
+         region={{/app/region | default \"eu-west-1\"}}\n\
+         var={{/app/db/host | env}}\n\
+         other={{ .Values.name }}\n",
+    )
+    .unwrap();
+    render(
+        &mut helper,
+        &format!("{} --output {}", template.display(), out),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file).unwrap(),
+        "host=db.internal:5432\nuser=bob\nregion=eu-west-1\nvar=APP_DB_HOST\nother={{ .Values.name }}\n"
+    );
+
+    std::fs::write(&template, "{{/app/missing}} {{/app/gone}}").unwrap();
+    let err = render(&mut helper, &template.display().to_string())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("/app/gone, /app/missing"),
+        "{}",
+        err
+    );
+    std::fs::write(&template, "{{/app/db/host | upper}}").unwrap();
+    assert!(
+        render(&mut helper, &template.display().to_string())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn age_and_size_report_online_and_from_the_cache() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut helper = export_helper(store.path(), &backend).await;
+    helper
+        .completer
+        .get_set_value("/app/db/host")
+        .await
+        .unwrap();
+
+    age(&helper, "").await.unwrap();
+    assert_eq!(backend.calls("DescribeParameters"), 1);
+    size(&helper, "/app");
+
+    helper.completer.offline = true;
+    age(&helper, "/app/db").await.unwrap();
+    assert_eq!(backend.calls("DescribeParameters"), 1);
+}
+
+#[tokio::test]
+async fn listed_values_are_clipped_to_the_configured_width() {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.value_width = 8;
+    assert_eq!(completer.clipped("0123456789"), "0123456…");
+    assert_eq!(completer.clipped("short"), "short");
+    completer.value_width = 0;
+    assert_eq!(completer.clipped("0123456789"), "0123456789");
+    completer.redact = true;
+    assert_eq!(completer.clipped("0123456789"), REDACTED);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{completer, plaintext, sample_backend};
+use daps::mock_backend::MockBackend;
+use daps::serve::serve_lines;
+use serde_json::{Value, json};
+
+/// Sends `requests` one per line and returns the response lines.
+async fn exchange(requests: &[Value]) -> (Vec<Value>, MockBackend) {
+    let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+    exchange_raw(&input).await
+}
+
+async fn exchange_raw(input: &str) -> (Vec<Value>, MockBackend) {
+    let store = tempfile::tempdir().unwrap();
+    let backend = sample_backend();
+    let mut completer = completer(store.path(), &backend, "/app", plaintext());
+    completer.load_parameters().await.unwrap();
+
+    let mut output = Vec::new();
+    serve_lines(&mut completer, input.as_bytes(), &mut output)
+        .await
+        .unwrap();
+    let responses = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    (responses, backend)
+}
+
+#[tokio::test]
+async fn answers_list_get_and_search() {
+    let (responses, backend) = exchange(&[
+        json!({ "id": 1, "method": "list", "params": { "prefix": "/app/db" } }),
+        json!({ "id": 2, "method": "get", "params": { "path": "/app/db/password" } }),
+        json!({ "id": 3, "method": "search", "params": { "term": "feat" } }),
+    ])
+    .await;
+
+    let listed: Vec<&str> = responses[0]["result"]["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(listed, ["/app/db/host", "/app/db/password", "/app/db/port"]);
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["result"]["value"], "hunter2");
+    assert_eq!(responses[1]["result"]["type"], "SecureString");
+    assert_eq!(responses[2]["result"]["results"], json!(["/app/feature"]));
+    // Reads are served from the cache loaded at startup.
+    assert_eq!(backend.calls("GetParameter"), 0);
+}
+
+#[tokio::test]
+async fn set_updates_or_creates_with_a_type() {
+    let (responses, backend) = exchange(&[
+        json!({ "id": 1, "method": "set", "params": { "path": "/app/feature", "value": "off" } }),
+        json!({ "id": 2, "method": "set", "params": { "path": "/app/new", "value": "1" } }),
+        json!({ "id": 3, "method": "set", "params": { "path": "/app/new", "value": "1", "type": "String" } }),
+    ])
+    .await;
+
+    assert_eq!(responses[0]["result"]["created"], false);
+    assert_eq!(backend.value("/app/feature").as_deref(), Some("off"));
+    assert_eq!(responses[1]["error"]["code"], -32602);
+    assert_eq!(responses[2]["result"]["created"], true);
+    assert_eq!(backend.value("/app/new").as_deref(), Some("1"));
+}
+
+#[tokio::test]
+async fn reports_bad_requests_and_keeps_going() {
+    let input = concat!(
+        "not json\n",
+        "{\"id\": 7, \"method\": \"drop\"}\n",
+        "{\"id\": 8, \"method\": \"get\", \"params\": {\"path\": \"/app/missing\"}}\n",
+    );
+    let (responses, _) = exchange_raw(input).await;
+
+    assert_eq!(responses[0]["error"]["code"], -32700);
+    assert_eq!(responses[1]["id"], 7);
+    assert_eq!(responses[1]["error"]["code"], -32601);
+    assert_eq!(responses[2]["error"]["code"], -32000);
+}
@@ -0,0 +1,202 @@
+use daps::chunking::{CHUNK_SIZE, MAX_CHUNKS};
+use daps::commands::size::{check_value_size, size_warning};
+use daps::display_filter::{self, Filter};
+use daps::generate::Generator;
+use daps::lint::{LintConfig, Severity};
+use daps::policy::SecretPolicy;
+use daps::utils::{split_change, truncate_value};
+use rusoto_ssm::ParameterMetadata;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[test]
+fn split_change_isolates_the_differing_middle() {
+    assert_eq!(
+        split_change("db.internal:5432", "db.internal:5433"),
+        ("db.internal:543", "2", "3", "")
+    );
+    assert_eq!(
+        split_change("https://api", "https://api/v2"),
+        ("https://api", "", "/v2", "")
+    );
+    assert_eq!(split_change("héllo", "hallo"), ("h", "é", "a", "llo"));
+    assert_eq!(split_change("aaa", "aa"), ("aa", "a", "", ""));
+    assert_eq!(split_change("same", "same"), ("same", "", "", ""));
+}
+
+#[test]
+fn display_filters_transform_values_in_order() {
+    let (path, filters) = display_filter::split("/app/config | b64d | json").unwrap();
+    assert_eq!(path, "/app/config");
+    assert_eq!(filters, [Filter::Base64Decode, Filter::Json]);
+    assert_eq!(
+        display_filter::apply("eyJhIjogMX0=\n", &filters).unwrap(),
+        "{\n  \"a\": 1\n}"
+    );
+
+    let (_, filters) = display_filter::split("/app/hosts | lines | trim").unwrap();
+    assert_eq!(
+        display_filter::apply(" a,b,c ", &filters).unwrap(),
+        "a\nb\nc"
+    );
+    assert!(display_filter::apply("not base64!", &[Filter::Base64Decode]).is_err());
+    assert!(display_filter::apply("{", &[Filter::Json]).is_err());
+    assert!(display_filter::split("/app | upper").is_err());
+    assert!(display_filter::split("/app |").is_err());
+}
+
+#[test]
+fn long_values_are_cut_with_an_ellipsis() {
+    assert_eq!(truncate_value("short", 10), "short");
+    assert_eq!(truncate_value("0123456789abc", 10), "012345678…");
+    assert_eq!(truncate_value("héllo wörld", 6), "héllo…");
+    assert_eq!(truncate_value("line one\nline two", 40), "line one…");
+    assert_eq!(truncate_value("0123456789abc", 0), "0123456789abc");
+}
+
+fn metadata(name: &str, param_type: &str, description: &str, age_days: f64) -> ParameterMetadata {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64();
+    ParameterMetadata {
+        name: Some(name.to_string()),
+        type_: Some(param_type.to_string()),
+        description: Some(description.to_string()),
+        last_modified_date: Some(now - age_days * 86_400.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn lint_reports_each_rule_most_severe_first() {
+    let parameters = [
+        metadata("/app/ok", "String", "fine", 1.0),
+        metadata("/app/db/password", "String", "", 1.0),
+        metadata("/app/DB_Host", "String", "host", 1.0),
+        metadata("/a/b/c/d/e/f/g", "String", "deep", 1.0),
+        metadata("/app/api-token", "SecureString", "token", 200.0),
+        metadata("/app/new-token", "SecureString", "token", 10.0),
+    ];
+    let findings = LintConfig::default().check(&parameters);
+    let found: Vec<(&str, &str, Severity)> = findings
+        .iter()
+        .map(|f| (f.path.as_str(), f.rule, f.severity))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("/app/db/password", "secure-type", Severity::Error),
+            ("/a/b/c/d/e/f/g", "max-depth", Severity::Warning),
+            ("/app/DB_Host", "naming", Severity::Warning),
+            ("/app/api-token", "rotation", Severity::Warning),
+            ("/app/db/password", "description", Severity::Info),
+        ]
+    );
+
+    let quiet = LintConfig {
+        disabled: vec!["description".into(), "naming".into()],
+        max_depth: 10,
+        ..Default::default()
+    };
+    let rules: Vec<&str> = quiet.check(&parameters).iter().map(|f| f.rule).collect();
+    assert_eq!(rules, ["secure-type", "rotation"]);
+}
+
+#[test]
+fn generators_parse_their_flags_and_make_fresh_values() {
+    assert_eq!(Generator::parse("plain value").unwrap(), None);
+
+    let digits = Generator::parse("--generate 16 --charset digits")
+        .unwrap()
+        .unwrap();
+    let value = digits.generate();
+    assert_eq!(value.len(), 16);
+    assert!(value.chars().all(|c| c.is_ascii_digit()), "{}", value);
+    assert_ne!(digits.generate(), value);
+
+    let default = Generator::parse("--generate").unwrap().unwrap().generate();
+    assert_eq!(default.len(), 32);
+    assert!(default.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    let uuid = Generator::parse("--generate-uuid")
+        .unwrap()
+        .unwrap()
+        .generate();
+    let groups: Vec<usize> = uuid.split('-').map(str::len).collect();
+    assert_eq!(groups, [8, 4, 4, 4, 12]);
+    assert_eq!(uuid.chars().nth(14), Some('4'));
+
+    let hex = Generator::parse("--generate-hex 8")
+        .unwrap()
+        .unwrap()
+        .generate();
+    assert_eq!(hex.len(), 16);
+    assert!(hex.chars().all(|c| c.is_ascii_hexdigit()));
+    let base64 = Generator::parse("--generate-base64 3")
+        .unwrap()
+        .unwrap()
+        .generate();
+    assert_eq!(base64.len(), 4);
+
+    for invalid in [
+        "--generate 0",
+        "--generate 5000",
+        "--generate-uuid 4",
+        "--generate 8 extra",
+        "--generate --charset",
+        "--generate-words",
+    ] {
+        assert!(Generator::parse(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn secret_policy_lists_every_broken_rule() {
+    let policy = SecretPolicy::default();
+    assert!(policy.applies_to("/app/DB/Password"));
+    assert!(!policy.applies_to("/app/db/host"));
+    assert!(policy.check("/app/db/host", "x").is_ok());
+    assert!(
+        policy
+            .check("/app/db/password", "Str0ngEnoughValue")
+            .is_ok()
+    );
+
+    let err = policy.check("/app/db/password", "ChangeMe").unwrap_err();
+    assert!(err.contains("deny list"), "{}", err);
+    assert!(err.contains("at least 12"), "{}", err);
+    assert!(err.contains("a digit"), "{}", err);
+    assert!(err.contains("--no-validate"), "{}", err);
+
+    let symbols = SecretPolicy {
+        require: vec!["symbol".into()],
+        ..Default::default()
+    };
+    assert!(symbols.check("/app/secret", "nosymbolshere").is_err());
+    assert!(symbols.check("/app/secret", "has-a-symbol!").is_ok());
+    let unknown = SecretPolicy {
+        require: vec!["emoji".into()],
+        ..Default::default()
+    };
+    assert!(unknown.check("/app/secret", "anything").is_err());
+    let disabled = SecretPolicy {
+        enabled: false,
+        ..Default::default()
+    };
+    assert!(disabled.check("/app/secret", "changeme").is_ok());
+}
+
+#[test]
+fn size_warnings_follow_the_tier_limits() {
+    assert_eq!(size_warning(100), None);
+    assert!(size_warning(3900).unwrap().contains("close to the 4 KB"));
+    assert!(size_warning(5000).unwrap().contains("Advanced tier"));
+    assert!(size_warning(9000).unwrap().contains("over the 8 KB"));
+
+    let huge = "x".repeat(9000);
+    assert!(check_value_size("/app/big", &huge, false).is_err());
+    assert!(check_value_size("/app/big", &huge, true).is_ok());
+    assert!(check_value_size("/app/big", "small", false).is_ok());
+    let too_many = "x".repeat(CHUNK_SIZE * MAX_CHUNKS + 1);
+    assert!(check_value_size("/app/big", &too_many, true).is_err());
+}
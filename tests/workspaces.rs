@@ -0,0 +1,36 @@
+use daps::config::Config;
+use daps::workspaces::{find, validate};
+
+const CONFIG: &str = r#"
+[workspaces.prod]
+profile = "prod-admin"
+region = "eu-west-1"
+base_paths = ["/prod/app/", "/prod/shared/"]
+store_dir = ".daps-prod"
+
+[workspaces.dev]
+base_paths = ["/dev/"]
+"#;
+
+#[test]
+fn workspaces_pick_a_configured_base_path() {
+    let config: Config = toml::from_str(CONFIG).unwrap();
+    validate(&config.workspaces).unwrap();
+
+    let prod = find(&config.workspaces, "prod").unwrap();
+    assert_eq!(prod.profile.as_deref(), Some("prod-admin"));
+    assert_eq!(prod.pick_base_path("prod", None).unwrap(), "/prod/app/");
+    assert_eq!(
+        prod.pick_base_path("prod", Some("/prod/shared")).unwrap(),
+        "/prod/shared/"
+    );
+    assert!(prod.pick_base_path("prod", Some("/dev/")).is_err());
+
+    let dev = find(&config.workspaces, "dev").unwrap();
+    assert!(dev.profile.is_none() && dev.store_dir.is_none());
+    let err = find(&config.workspaces, "stage").unwrap_err();
+    assert!(err.contains("dev, prod"));
+
+    let broken: Config = toml::from_str("[workspaces.x]\nbase_paths = [\"app\"]\n").unwrap();
+    assert!(validate(&broken.workspaces).is_err());
+}